@@ -678,6 +678,23 @@ impl<'a> TypeEncoder<'a> {
                 state.cur.encodable.ty().defined_type().borrow(ty);
                 index
             }
+            wasmparser::types::ComponentDefinedType::Future(ty) => {
+                let ty = ty.map(|ty| self.component_val_type(state, ty));
+                let index = state.cur.encodable.type_count();
+                state.cur.encodable.ty().defined_type().future(ty);
+                index
+            }
+            wasmparser::types::ComponentDefinedType::Stream(ty) => {
+                let ty = ty.map(|ty| self.component_val_type(state, ty));
+                let index = state.cur.encodable.type_count();
+                state.cur.encodable.ty().defined_type().stream(ty);
+                index
+            }
+            wasmparser::types::ComponentDefinedType::ErrorContext => {
+                let index = state.cur.encodable.type_count();
+                state.cur.encodable.ty().defined_type().error_context();
+                index
+            }
         }
     }
 
@@ -1226,10 +1243,16 @@ impl DependencyRegistrar<'_, '_> {
         match &self.types[ty] {
             types::ComponentDefinedType::Primitive(_)
             | types::ComponentDefinedType::Enum(_)
-            | types::ComponentDefinedType::Flags(_) => {}
+            | types::ComponentDefinedType::Flags(_)
+            | types::ComponentDefinedType::ErrorContext => {}
             types::ComponentDefinedType::List(t) | types::ComponentDefinedType::Option(t) => {
                 self.val_type(*t)
             }
+            types::ComponentDefinedType::Future(t) | types::ComponentDefinedType::Stream(t) => {
+                if let Some(t) = t {
+                    self.val_type(*t);
+                }
+            }
             types::ComponentDefinedType::Own(r) | types::ComponentDefinedType::Borrow(r) => {
                 self.ty(ComponentAnyTypeId::Resource(*r))
             }
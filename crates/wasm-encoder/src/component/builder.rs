@@ -372,6 +372,90 @@ impl ComponentBuilder {
         inc(&mut self.core_funcs)
     }
 
+    /// Declares a new `task.backpressure` intrinsic.
+    pub fn task_backpressure(&mut self) -> u32 {
+        self.canonical_functions().task_backpressure();
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `task.return` intrinsic.
+    pub fn task_return<O>(&mut self, result: Option<ComponentValType>, options: O) -> u32
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.canonical_functions().task_return(result, options);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `stream.new` intrinsic.
+    pub fn stream_new(&mut self, ty: u32) -> u32 {
+        self.canonical_functions().stream_new(ty);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `stream.read` intrinsic.
+    pub fn stream_read<O>(&mut self, ty: u32, options: O) -> u32
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.canonical_functions().stream_read(ty, options);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `stream.write` intrinsic.
+    pub fn stream_write<O>(&mut self, ty: u32, options: O) -> u32
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.canonical_functions().stream_write(ty, options);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `future.new` intrinsic.
+    pub fn future_new(&mut self, ty: u32) -> u32 {
+        self.canonical_functions().future_new(ty);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `future.read` intrinsic.
+    pub fn future_read<O>(&mut self, ty: u32, options: O) -> u32
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.canonical_functions().future_read(ty, options);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `future.write` intrinsic.
+    pub fn future_write<O>(&mut self, ty: u32, options: O) -> u32
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.canonical_functions().future_write(ty, options);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `error-context.new` intrinsic.
+    pub fn error_context_new<O>(&mut self, options: O) -> u32
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.canonical_functions().error_context_new(options);
+        inc(&mut self.core_funcs)
+    }
+
+    /// Declares a new `error-context.drop` intrinsic.
+    pub fn error_context_drop(&mut self) -> u32 {
+        self.canonical_functions().error_context_drop();
+        inc(&mut self.core_funcs)
+    }
+
     /// Adds a new custom section to this component.
     pub fn custom_section(&mut self, section: &CustomSection<'_>) {
         self.flush();
@@ -1,4 +1,4 @@
-use crate::{encode_section, ComponentSection, ComponentSectionId, Encode};
+use crate::{encode_section, ComponentSection, ComponentSectionId, ComponentValType, Encode};
 
 /// Represents options for canonical function definitions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +21,14 @@ pub enum CanonicalOption {
     /// The post-return function to use if the lifting of a function requires
     /// cleanup after the function returns.
     PostReturn(u32),
+    /// The function is lifted or lowered with the asynchronous ABI.
+    Async,
+    /// The callback function to invoke with events for an asynchronously
+    /// lifted function.
+    ///
+    /// The value is an index to a core function of type
+    /// `(func (param i32 i32 i32 i32) (result i32))`.
+    Callback(u32),
 }
 
 impl Encode for CanonicalOption {
@@ -41,6 +49,11 @@ impl Encode for CanonicalOption {
                 sink.push(0x05);
                 idx.encode(sink);
             }
+            Self::Async => sink.push(0x06),
+            Self::Callback(idx) => {
+                sink.push(0x07);
+                idx.encode(sink);
+            }
         }
     }
 }
@@ -144,6 +157,240 @@ impl CanonicalFunctionSection {
         self.num_added += 1;
         self
     }
+
+    /// Defines a function which enables or disables backpressure for the
+    /// current component instance.
+    pub fn task_backpressure(&mut self) -> &mut Self {
+        self.bytes.push(0x08);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which returns the `result` of the current
+    /// asynchronous task.
+    pub fn task_return<O>(&mut self, result: Option<ComponentValType>, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.bytes.push(0x09);
+        match result {
+            Some(ty) => {
+                self.bytes.push(0x00);
+                ty.encode(&mut self.bytes);
+            }
+            None => {
+                self.bytes.push(0x01);
+                self.bytes.push(0x00);
+            }
+        }
+        self.encode_options(options);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which waits for at least one outstanding subtask or
+    /// stream event to be ready, writing the event payload to `memory`.
+    pub fn task_wait(&mut self, async_: bool, memory: u32) -> &mut Self {
+        self.bytes.push(0x0a);
+        self.bytes.push(async_ as u8);
+        memory.encode(&mut self.bytes);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which checks, without blocking, whether any
+    /// outstanding subtask or stream event is ready.
+    pub fn task_poll(&mut self, async_: bool, memory: u32) -> &mut Self {
+        self.bytes.push(0x0b);
+        self.bytes.push(async_ as u8);
+        memory.encode(&mut self.bytes);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which yields control to other tasks.
+    pub fn task_yield(&mut self, async_: bool) -> &mut Self {
+        self.bytes.push(0x0c);
+        self.bytes.push(async_ as u8);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which drops a completed subtask handle.
+    pub fn subtask_drop(&mut self) -> &mut Self {
+        self.bytes.push(0x0d);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which creates a new stream of the type `ty`.
+    pub fn stream_new(&mut self, ty: u32) -> &mut Self {
+        self.type_intrinsic(0x0e, ty)
+    }
+
+    /// Defines a function which reads values from a stream of the type `ty`.
+    pub fn stream_read<O>(&mut self, ty: u32, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.type_options_intrinsic(0x0f, ty, options)
+    }
+
+    /// Defines a function which writes values to a stream of the type `ty`.
+    pub fn stream_write<O>(&mut self, ty: u32, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.type_options_intrinsic(0x10, ty, options)
+    }
+
+    /// Defines a function which cancels an in-progress read from a stream of
+    /// the type `ty`.
+    pub fn stream_cancel_read(&mut self, ty: u32, async_: bool) -> &mut Self {
+        self.type_async_intrinsic(0x11, ty, async_)
+    }
+
+    /// Defines a function which cancels an in-progress write to a stream of
+    /// the type `ty`.
+    pub fn stream_cancel_write(&mut self, ty: u32, async_: bool) -> &mut Self {
+        self.type_async_intrinsic(0x12, ty, async_)
+    }
+
+    /// Defines a function which closes the readable end of a stream of the
+    /// type `ty`.
+    pub fn stream_close_readable(&mut self, ty: u32) -> &mut Self {
+        self.type_intrinsic(0x13, ty)
+    }
+
+    /// Defines a function which closes the writable end of a stream of the
+    /// type `ty`.
+    pub fn stream_close_writable(&mut self, ty: u32) -> &mut Self {
+        self.type_intrinsic(0x14, ty)
+    }
+
+    /// Defines a function which creates a new future of the type `ty`.
+    pub fn future_new(&mut self, ty: u32) -> &mut Self {
+        self.type_intrinsic(0x15, ty)
+    }
+
+    /// Defines a function which reads the value from a future of the type
+    /// `ty`.
+    pub fn future_read<O>(&mut self, ty: u32, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.type_options_intrinsic(0x16, ty, options)
+    }
+
+    /// Defines a function which writes the value to a future of the type
+    /// `ty`.
+    pub fn future_write<O>(&mut self, ty: u32, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.type_options_intrinsic(0x17, ty, options)
+    }
+
+    /// Defines a function which cancels an in-progress read from a future of
+    /// the type `ty`.
+    pub fn future_cancel_read(&mut self, ty: u32, async_: bool) -> &mut Self {
+        self.type_async_intrinsic(0x18, ty, async_)
+    }
+
+    /// Defines a function which cancels an in-progress write to a future of
+    /// the type `ty`.
+    pub fn future_cancel_write(&mut self, ty: u32, async_: bool) -> &mut Self {
+        self.type_async_intrinsic(0x19, ty, async_)
+    }
+
+    /// Defines a function which closes the readable end of a future of the
+    /// type `ty`.
+    pub fn future_close_readable(&mut self, ty: u32) -> &mut Self {
+        self.type_intrinsic(0x1a, ty)
+    }
+
+    /// Defines a function which closes the writable end of a future of the
+    /// type `ty`.
+    pub fn future_close_writable(&mut self, ty: u32) -> &mut Self {
+        self.type_intrinsic(0x1b, ty)
+    }
+
+    /// Defines a function which creates a new `error-context` from a debug
+    /// message.
+    pub fn error_context_new<O>(&mut self, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.bytes.push(0x1c);
+        self.encode_options(options);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which retrieves the debug message of an
+    /// `error-context`.
+    pub fn error_context_debug_message<O>(&mut self, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.bytes.push(0x1d);
+        self.encode_options(options);
+        self.num_added += 1;
+        self
+    }
+
+    /// Defines a function which drops an `error-context` handle.
+    pub fn error_context_drop(&mut self) -> &mut Self {
+        self.bytes.push(0x1e);
+        self.num_added += 1;
+        self
+    }
+
+    fn type_intrinsic(&mut self, opcode: u8, ty: u32) -> &mut Self {
+        self.bytes.push(opcode);
+        ty.encode(&mut self.bytes);
+        self.num_added += 1;
+        self
+    }
+
+    fn type_async_intrinsic(&mut self, opcode: u8, ty: u32, async_: bool) -> &mut Self {
+        self.bytes.push(opcode);
+        ty.encode(&mut self.bytes);
+        self.bytes.push(async_ as u8);
+        self.num_added += 1;
+        self
+    }
+
+    fn type_options_intrinsic<O>(&mut self, opcode: u8, ty: u32, options: O) -> &mut Self
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        self.bytes.push(opcode);
+        ty.encode(&mut self.bytes);
+        self.encode_options(options);
+        self.num_added += 1;
+        self
+    }
+
+    fn encode_options<O>(&mut self, options: O)
+    where
+        O: IntoIterator<Item = CanonicalOption>,
+        O::IntoIter: ExactSizeIterator,
+    {
+        let options = options.into_iter();
+        options.len().encode(&mut self.bytes);
+        for option in options {
+            option.encode(&mut self.bytes);
+        }
+    }
 }
 
 impl Encode for CanonicalFunctionSection {
@@ -146,6 +146,14 @@ define_wasm_features! {
         pub component_model_values: COMPONENT_MODEL_VALUES(1 << 21) = false;
         /// Support for the nested namespaces and projects in component model names.
         pub component_model_nested_names: COMPONENT_MODEL_NESTED_NAMES(1 << 22) = false;
+        /// Support for async lifting and lowering, `future`, `stream` and
+        /// `error-context` types, and the task built-ins in the component
+        /// model proposal.
+        pub component_model_async: COMPONENT_MODEL_ASYNC(1 << 23) = false;
+        /// Support for lifting and lowering functions with a 64-bit `memory`
+        /// in the component model, where pointers and lengths are `i64`
+        /// values.
+        pub component_model_memory64: COMPONENT_MODEL_MEMORY64(1 << 24) = false;
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::limits::MAX_WASM_CANONICAL_OPTIONS;
 use crate::prelude::*;
-use crate::{BinaryReader, FromReader, Result, SectionLimited};
+use crate::{BinaryReader, ComponentValType, FromReader, Result, SectionLimited};
 
 /// Represents options for component functions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +23,14 @@ pub enum CanonicalOption {
     /// The post-return function to use if the lifting of a function requires
     /// cleanup after the function returns.
     PostReturn(u32),
+    /// The function is lifted or lowered with the asynchronous ABI.
+    Async,
+    /// The callback function to invoke with events for an asynchronously
+    /// lifted function.
+    ///
+    /// The value is an index to a core function of type
+    /// `(func (param i32 i32 i32 i32) (result i32))`.
+    Callback(u32),
 }
 
 /// Represents a canonical function in a WebAssembly component.
@@ -60,6 +68,139 @@ pub enum CanonicalFunction {
         /// The type index of the resource that's being accessed.
         resource: u32,
     },
+    /// A function which enables or disables backpressure for the current
+    /// component instance.
+    TaskBackpressure,
+    /// A function which returns the result of the current asynchronous task.
+    TaskReturn {
+        /// The result type of the task, if any.
+        result: Option<ComponentValType>,
+        /// The canonical options used to lift the result.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which waits for at least one outstanding subtask or stream
+    /// event to be ready.
+    TaskWait {
+        /// Whether other tasks may run while this task waits.
+        async_: bool,
+        /// The memory the event payload is written to.
+        memory: u32,
+    },
+    /// A function which checks, without blocking, whether any outstanding
+    /// subtask or stream event is ready.
+    TaskPoll {
+        /// Whether other tasks may run during the poll.
+        async_: bool,
+        /// The memory the event payload is written to.
+        memory: u32,
+    },
+    /// A function which yields control to other tasks.
+    TaskYield {
+        /// Whether other tasks may run while this task yields.
+        async_: bool,
+    },
+    /// A function which drops a completed subtask handle.
+    SubtaskDrop,
+    /// A function which creates a new stream with a readable and a writable
+    /// end.
+    StreamNew {
+        /// The type index of the stream type.
+        ty: u32,
+    },
+    /// A function which reads values from the readable end of a stream.
+    StreamRead {
+        /// The type index of the stream type.
+        ty: u32,
+        /// The canonical options used to lower the values read.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which writes values to the writable end of a stream.
+    StreamWrite {
+        /// The type index of the stream type.
+        ty: u32,
+        /// The canonical options used to lift the values written.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which cancels an in-progress read from a stream.
+    StreamCancelRead {
+        /// The type index of the stream type.
+        ty: u32,
+        /// Whether the cancellation may complete asynchronously.
+        async_: bool,
+    },
+    /// A function which cancels an in-progress write to a stream.
+    StreamCancelWrite {
+        /// The type index of the stream type.
+        ty: u32,
+        /// Whether the cancellation may complete asynchronously.
+        async_: bool,
+    },
+    /// A function which closes the readable end of a stream.
+    StreamCloseReadable {
+        /// The type index of the stream type.
+        ty: u32,
+    },
+    /// A function which closes the writable end of a stream.
+    StreamCloseWritable {
+        /// The type index of the stream type.
+        ty: u32,
+    },
+    /// A function which creates a new future with a readable and a writable
+    /// end.
+    FutureNew {
+        /// The type index of the future type.
+        ty: u32,
+    },
+    /// A function which reads the value from the readable end of a future.
+    FutureRead {
+        /// The type index of the future type.
+        ty: u32,
+        /// The canonical options used to lower the value read.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which writes the value to the writable end of a future.
+    FutureWrite {
+        /// The type index of the future type.
+        ty: u32,
+        /// The canonical options used to lift the value written.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which cancels an in-progress read from a future.
+    FutureCancelRead {
+        /// The type index of the future type.
+        ty: u32,
+        /// Whether the cancellation may complete asynchronously.
+        async_: bool,
+    },
+    /// A function which cancels an in-progress write to a future.
+    FutureCancelWrite {
+        /// The type index of the future type.
+        ty: u32,
+        /// Whether the cancellation may complete asynchronously.
+        async_: bool,
+    },
+    /// A function which closes the readable end of a future.
+    FutureCloseReadable {
+        /// The type index of the future type.
+        ty: u32,
+    },
+    /// A function which closes the writable end of a future.
+    FutureCloseWritable {
+        /// The type index of the future type.
+        ty: u32,
+    },
+    /// A function which creates a new `error-context` from a debug message.
+    ErrorContextNew {
+        /// The canonical options used to lift the debug message.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which retrieves the debug message of an `error-context`.
+    ErrorContextDebugMessage {
+        /// The canonical options used to lower the debug message.
+        options: Box<[CanonicalOption]>,
+    },
+    /// A function which drops an `error-context` handle.
+    ErrorContextDrop,
 }
 
 /// A reader for the canonical section of a WebAssembly component.
@@ -101,11 +242,97 @@ impl<'a> FromReader<'a> for CanonicalFunction {
             0x04 => CanonicalFunction::ResourceRep {
                 resource: reader.read()?,
             },
+            0x08 => CanonicalFunction::TaskBackpressure,
+            0x09 => {
+                let result = match reader.read_u8()? {
+                    0x00 => Some(reader.read()?),
+                    0x01 => match reader.read_u8()? {
+                        0x00 => None,
+                        x => return reader.invalid_leading_byte(x, "task.return result"),
+                    },
+                    x => return reader.invalid_leading_byte(x, "task.return result"),
+                };
+                CanonicalFunction::TaskReturn {
+                    result,
+                    options: read_options(reader)?,
+                }
+            }
+            0x0a => CanonicalFunction::TaskWait {
+                async_: read_async(reader)?,
+                memory: reader.read()?,
+            },
+            0x0b => CanonicalFunction::TaskPoll {
+                async_: read_async(reader)?,
+                memory: reader.read()?,
+            },
+            0x0c => CanonicalFunction::TaskYield {
+                async_: read_async(reader)?,
+            },
+            0x0d => CanonicalFunction::SubtaskDrop,
+            0x0e => CanonicalFunction::StreamNew { ty: reader.read()? },
+            0x0f => CanonicalFunction::StreamRead {
+                ty: reader.read()?,
+                options: read_options(reader)?,
+            },
+            0x10 => CanonicalFunction::StreamWrite {
+                ty: reader.read()?,
+                options: read_options(reader)?,
+            },
+            0x11 => CanonicalFunction::StreamCancelRead {
+                ty: reader.read()?,
+                async_: read_async(reader)?,
+            },
+            0x12 => CanonicalFunction::StreamCancelWrite {
+                ty: reader.read()?,
+                async_: read_async(reader)?,
+            },
+            0x13 => CanonicalFunction::StreamCloseReadable { ty: reader.read()? },
+            0x14 => CanonicalFunction::StreamCloseWritable { ty: reader.read()? },
+            0x15 => CanonicalFunction::FutureNew { ty: reader.read()? },
+            0x16 => CanonicalFunction::FutureRead {
+                ty: reader.read()?,
+                options: read_options(reader)?,
+            },
+            0x17 => CanonicalFunction::FutureWrite {
+                ty: reader.read()?,
+                options: read_options(reader)?,
+            },
+            0x18 => CanonicalFunction::FutureCancelRead {
+                ty: reader.read()?,
+                async_: read_async(reader)?,
+            },
+            0x19 => CanonicalFunction::FutureCancelWrite {
+                ty: reader.read()?,
+                async_: read_async(reader)?,
+            },
+            0x1a => CanonicalFunction::FutureCloseReadable { ty: reader.read()? },
+            0x1b => CanonicalFunction::FutureCloseWritable { ty: reader.read()? },
+            0x1c => CanonicalFunction::ErrorContextNew {
+                options: read_options(reader)?,
+            },
+            0x1d => CanonicalFunction::ErrorContextDebugMessage {
+                options: read_options(reader)?,
+            },
+            0x1e => CanonicalFunction::ErrorContextDrop,
             x => return reader.invalid_leading_byte(x, "canonical function"),
         })
     }
 }
 
+fn read_options(reader: &mut BinaryReader<'_>) -> Result<Box<[CanonicalOption]>> {
+    reader
+        .read_iter(MAX_WASM_CANONICAL_OPTIONS, "canonical options")?
+        .collect()
+}
+
+fn read_async(reader: &mut BinaryReader<'_>) -> Result<bool> {
+    match reader.read_u8()? {
+        0x00 => Ok(false),
+        0x01 => Ok(true),
+        x => reader.invalid_leading_byte(x, "async flag"),
+    }
+}
+
 impl<'a> FromReader<'a> for CanonicalOption {
     fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
         Ok(match reader.read_u8()? {
@@ -115,6 +342,8 @@ impl<'a> FromReader<'a> for CanonicalOption {
             0x03 => CanonicalOption::Memory(reader.read_var_u32()?),
             0x04 => CanonicalOption::Realloc(reader.read_var_u32()?),
             0x05 => CanonicalOption::PostReturn(reader.read_var_u32()?),
+            0x06 => CanonicalOption::Async,
+            0x07 => CanonicalOption::Callback(reader.read_var_u32()?),
             x => return reader.invalid_leading_byte(x, "canonical option"),
         })
     }
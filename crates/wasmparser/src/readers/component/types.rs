@@ -493,6 +493,12 @@ pub enum ComponentDefinedType<'a> {
     Own(u32),
     /// A borrowed handle to a resource.
     Borrow(u32),
+    /// A future type with the given payload type, if any.
+    Future(Option<ComponentValType>),
+    /// A stream type with the given element type, if any.
+    Stream(Option<ComponentValType>),
+    /// The `error-context` type.
+    ErrorContext,
 }
 
 impl<'a> ComponentDefinedType<'a> {
@@ -532,6 +538,9 @@ impl<'a> ComponentDefinedType<'a> {
             },
             0x69 => ComponentDefinedType::Own(reader.read()?),
             0x68 => ComponentDefinedType::Borrow(reader.read()?),
+            0x66 => ComponentDefinedType::Stream(reader.read()?),
+            0x65 => ComponentDefinedType::Future(reader.read()?),
+            0x64 => ComponentDefinedType::ErrorContext,
             x => return reader.invalid_leading_byte(x, "component defined type"),
         })
     }
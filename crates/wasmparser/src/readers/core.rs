@@ -1,5 +1,6 @@
 mod branch_hinting;
 mod code;
+mod code_metadata;
 mod coredumps;
 mod custom;
 mod data;
@@ -22,6 +23,7 @@ mod types;
 
 pub use self::branch_hinting::*;
 pub use self::code::*;
+pub use self::code_metadata::*;
 pub use self::coredumps::*;
 pub use self::custom::*;
 pub use self::data::*;
@@ -0,0 +1,62 @@
+use crate::{BinaryReader, FromReader, Result, SectionLimited};
+
+/// A reader for a `metadata.code.*` custom section, as defined by the
+/// [code metadata] proposal.
+///
+/// All code metadata sections share the same structure: a list of functions
+/// each with a list of annotations attached to instructions within that
+/// function's body. The payload of each annotation is opaque at this level and
+/// is interpreted according to the name of the custom section. Note that the
+/// `metadata.code.branch_hint` section has a dedicated reader in
+/// [`BranchHintSectionReader`](crate::BranchHintSectionReader).
+///
+/// [code metadata]: https://github.com/WebAssembly/tool-conventions/blob/main/CodeMetadata.md
+pub type CodeMetadataSectionReader<'a> = SectionLimited<'a, CodeMetadataFunction<'a>>;
+
+/// Code metadata for a single function.
+///
+/// Produced from [`CodeMetadataSectionReader`].
+#[derive(Debug, Clone)]
+pub struct CodeMetadataFunction<'a> {
+    /// The function that this metadata applies to.
+    pub func: u32,
+    /// The metadata annotations available for this function.
+    pub annotations: SectionLimited<'a, CodeMetadata<'a>>,
+}
+
+impl<'a> FromReader<'a> for CodeMetadataFunction<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let func = reader.read_var_u32()?;
+        // FIXME(#188) ideally wouldn't have to do skips here
+        let annotations = reader.skip(|reader| {
+            let items_count = reader.read_var_u32()?;
+            for _ in 0..items_count {
+                reader.read::<CodeMetadata>()?;
+            }
+            Ok(())
+        })?;
+        Ok(CodeMetadataFunction {
+            func,
+            annotations: SectionLimited::new(annotations)?,
+        })
+    }
+}
+
+/// A single code metadata annotation attached to an instruction.
+#[derive(Debug, Copy, Clone)]
+pub struct CodeMetadata<'a> {
+    /// The byte offset, from the start of the function's body, of where the
+    /// annotated instruction lives.
+    pub func_offset: u32,
+    /// The raw payload of this annotation.
+    pub data: &'a [u8],
+}
+
+impl<'a> FromReader<'a> for CodeMetadata<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let func_offset = reader.read_var_u32()?;
+        let len = reader.read_var_u32()?;
+        let data = reader.read_bytes(len as usize)?;
+        Ok(CodeMetadata { func_offset, data })
+    }
+}
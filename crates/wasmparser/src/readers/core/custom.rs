@@ -61,6 +61,12 @@ impl<'a> CustomSectionReader<'a> {
                     Err(_) => KnownCustom::Unknown,
                 }
             }
+            s if s.starts_with("metadata.code.") => {
+                match crate::CodeMetadataSectionReader::new(self.reader.shrink()) {
+                    Ok(s) => KnownCustom::CodeMetadata(s),
+                    Err(_) => KnownCustom::Unknown,
+                }
+            }
             "producers" => match crate::ProducersSectionReader::new(self.reader.shrink()) {
                 Ok(s) => KnownCustom::Producers(s),
                 Err(_) => KnownCustom::Unknown,
@@ -105,6 +111,7 @@ pub enum KnownCustom<'a> {
     Name(crate::NameSectionReader<'a>),
     ComponentName(crate::ComponentNameSectionReader<'a>),
     BranchHints(crate::BranchHintSectionReader<'a>),
+    CodeMetadata(crate::CodeMetadataSectionReader<'a>),
     Producers(crate::ProducersSectionReader<'a>),
     Dylink0(crate::Dylink0SectionReader<'a>),
     CoreDump(crate::CoreDumpSection<'a>),
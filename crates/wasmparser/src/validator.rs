@@ -15,8 +15,8 @@
 
 use crate::prelude::*;
 use crate::{
-    limits::*, AbstractHeapType, BinaryReaderError, Encoding, FromReader, FunctionBody, HeapType,
-    Parser, Payload, RefType, Result, SectionLimited, ValType, WasmFeatures,
+    limits::*, AbstractHeapType, BinaryReaderError, Encoding, FromReader, FuncType, FunctionBody,
+    HeapType, Parser, Payload, RefType, Result, SectionLimited, ValType, WasmFeatures,
     WASM_COMPONENT_VERSION, WASM_MODULE_VERSION,
 };
 use ::core::mem;
@@ -1192,8 +1192,9 @@ impl Validator {
                 current.funcs.reserve(count as usize);
                 Ok(())
             },
-            |components, types, _, func, offset| {
+            |components, types, features, func, offset| {
                 let current = components.last_mut().unwrap();
+                let i32 = ValType::I32;
                 match func {
                     crate::CanonicalFunction::Lift {
                         core_func_index,
@@ -1204,12 +1205,19 @@ impl Validator {
                         type_index,
                         options.into_vec(),
                         types,
+                        features,
                         offset,
                     ),
                     crate::CanonicalFunction::Lower {
                         func_index,
                         options,
-                    } => current.lower_function(func_index, options.into_vec(), types, offset),
+                    } => current.lower_function(
+                        func_index,
+                        options.into_vec(),
+                        types,
+                        features,
+                        offset,
+                    ),
                     crate::CanonicalFunction::ResourceNew { resource } => {
                         current.resource_new(resource, types, offset)
                     }
@@ -1219,6 +1227,132 @@ impl Validator {
                     crate::CanonicalFunction::ResourceRep { resource } => {
                         current.resource_rep(resource, types, offset)
                     }
+                    crate::CanonicalFunction::TaskBackpressure => {
+                        current.task_backpressure(types, features, offset)
+                    }
+                    crate::CanonicalFunction::TaskReturn { result, options } => {
+                        current.task_return(result, options.into_vec(), types, features, offset)
+                    }
+                    crate::CanonicalFunction::TaskWait { async_: _, memory }
+                    | crate::CanonicalFunction::TaskPoll { async_: _, memory } => {
+                        current.task_wait(memory, types, features, offset)
+                    }
+                    crate::CanonicalFunction::TaskYield { async_: _ } => {
+                        current.task_yield(types, features, offset)
+                    }
+                    crate::CanonicalFunction::SubtaskDrop => {
+                        current.subtask_drop(types, features, offset)
+                    }
+                    crate::CanonicalFunction::StreamNew { ty } => current.stream_or_future(
+                        "stream.new",
+                        ty,
+                        None,
+                        FuncType::new([], [ValType::I64]),
+                        types,
+                        features,
+                        offset,
+                    ),
+                    crate::CanonicalFunction::StreamRead { ty, options } => current
+                        .stream_or_future(
+                            "stream.read",
+                            ty,
+                            Some((options.into_vec(), true)),
+                            FuncType::new([i32, i32, i32], [i32]),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::StreamWrite { ty, options } => current
+                        .stream_or_future(
+                            "stream.write",
+                            ty,
+                            Some((options.into_vec(), false)),
+                            FuncType::new([i32, i32, i32], [i32]),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::StreamCancelRead { ty, async_: _ }
+                    | crate::CanonicalFunction::StreamCancelWrite { ty, async_: _ } => current
+                        .stream_or_future(
+                            "stream.cancel",
+                            ty,
+                            None,
+                            FuncType::new([i32], [i32]),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::StreamCloseReadable { ty }
+                    | crate::CanonicalFunction::StreamCloseWritable { ty } => current
+                        .stream_or_future(
+                            "stream.close",
+                            ty,
+                            None,
+                            FuncType::new([i32], []),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::FutureNew { ty } => current.stream_or_future(
+                        "future.new",
+                        ty,
+                        None,
+                        FuncType::new([], [ValType::I64]),
+                        types,
+                        features,
+                        offset,
+                    ),
+                    crate::CanonicalFunction::FutureRead { ty, options } => current
+                        .stream_or_future(
+                            "future.read",
+                            ty,
+                            Some((options.into_vec(), true)),
+                            FuncType::new([i32, i32], [i32]),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::FutureWrite { ty, options } => current
+                        .stream_or_future(
+                            "future.write",
+                            ty,
+                            Some((options.into_vec(), false)),
+                            FuncType::new([i32, i32], [i32]),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::FutureCancelRead { ty, async_: _ }
+                    | crate::CanonicalFunction::FutureCancelWrite { ty, async_: _ } => current
+                        .stream_or_future(
+                            "future.cancel",
+                            ty,
+                            None,
+                            FuncType::new([i32], [i32]),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::FutureCloseReadable { ty }
+                    | crate::CanonicalFunction::FutureCloseWritable { ty } => current
+                        .stream_or_future(
+                            "future.close",
+                            ty,
+                            None,
+                            FuncType::new([i32], []),
+                            types,
+                            features,
+                            offset,
+                        ),
+                    crate::CanonicalFunction::ErrorContextNew { options } => {
+                        current.error_context_new(options.into_vec(), types, features, offset)
+                    }
+                    crate::CanonicalFunction::ErrorContextDebugMessage { options } => current
+                        .error_context_debug_message(options.into_vec(), types, features, offset),
+                    crate::CanonicalFunction::ErrorContextDrop => {
+                        current.error_context_drop(types, features, offset)
+                    }
                 }
             },
         )
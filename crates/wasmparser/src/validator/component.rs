@@ -348,7 +348,7 @@ impl ComponentState {
 
         let id = match ty {
             crate::ComponentType::Defined(ty) => {
-                let ty = current(components).create_defined_type(ty, types, offset)?;
+                let ty = current(components).create_defined_type(ty, types, features, offset)?;
                 types.push(ty).into()
             }
             crate::ComponentType::Func(ty) => {
@@ -727,7 +727,8 @@ impl ComponentState {
             // named.
             ComponentDefinedType::Primitive(_)
             | ComponentDefinedType::Flags(_)
-            | ComponentDefinedType::Enum(_) => true,
+            | ComponentDefinedType::Enum(_)
+            | ComponentDefinedType::ErrorContext => true,
 
             // Referenced types of all these aggregates must all be
             // named.
@@ -754,6 +755,10 @@ impl ComponentState {
             ComponentDefinedType::List(ty) | ComponentDefinedType::Option(ty) => {
                 types.type_named_valtype(ty, set)
             }
+            ComponentDefinedType::Future(ty) | ComponentDefinedType::Stream(ty) => ty
+                .as_ref()
+                .map(|t| types.type_named_valtype(t, set))
+                .unwrap_or(true),
 
             // The resource referred to by own/borrow must be named.
             ComponentDefinedType::Own(id) | ComponentDefinedType::Borrow(id) => {
@@ -954,15 +959,31 @@ impl ComponentState {
         type_index: u32,
         options: Vec<CanonicalOption>,
         types: &TypeList,
+        features: &WasmFeatures,
         offset: usize,
     ) -> Result<()> {
         let ty = self.function_type_at(type_index, types, offset)?;
         let core_ty = types[self.core_function_at(core_func_index, offset)?].unwrap_func();
+        let memory64 = self.options_memory64(&options, features, offset)?;
 
         // Lifting a function is for an export, so match the expected canonical ABI
         // export signature
-        let info = ty.lower(types, false);
-        self.check_options(Some(core_ty), &info, &options, types, offset)?;
+        let mut info = if options.contains(&CanonicalOption::Async) {
+            ty.lower_async(types, false, memory64)
+        } else {
+            ty.lower(types, false, memory64)
+        };
+        self.check_options(Some(core_ty), &info, &options, types, features, offset)?;
+
+        // An asynchronous lift with a callback returns a status code to the
+        // caller rather than the function's results.
+        if options
+            .iter()
+            .any(|option| matches!(option, CanonicalOption::Callback(_)))
+        {
+            info.results = LoweringInfo::default().results;
+            assert!(info.results.push(ValType::I32));
+        }
 
         if core_ty.params() != info.params.as_slice() {
             bail!(
@@ -995,15 +1016,21 @@ impl ComponentState {
         func_index: u32,
         options: Vec<CanonicalOption>,
         types: &mut TypeAlloc,
+        features: &WasmFeatures,
         offset: usize,
     ) -> Result<()> {
         let ty = &types[self.function_at(func_index, offset)?];
+        let memory64 = self.options_memory64(&options, features, offset)?;
 
         // Lowering a function is for an import, so use a function type that matches
         // the expected canonical ABI import signature.
-        let info = ty.lower(types, true);
+        let info = if options.contains(&CanonicalOption::Async) {
+            ty.lower_async(types, true, memory64)
+        } else {
+            ty.lower(types, true, memory64)
+        };
 
-        self.check_options(None, &info, &options, types, offset)?;
+        self.check_options(None, &info, &options, types, features, offset)?;
 
         let lowered_ty = SubType {
             is_final: true,
@@ -1076,6 +1103,185 @@ impl ComponentState {
         Ok(())
     }
 
+    pub fn task_backpressure(
+        &mut self,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`task.backpressure`", offset)?;
+        self.push_core_func(FuncType::new([ValType::I32], []), types, offset);
+        Ok(())
+    }
+
+    pub fn task_return(
+        &mut self,
+        result: Option<crate::ComponentValType>,
+        options: Vec<CanonicalOption>,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`task.return`", offset)?;
+        check_no_async_option(&options, "task.return", offset)?;
+        let result = result
+            .map(|ty| self.create_component_val_type(ty, offset))
+            .transpose()?;
+        let memory64 = self.options_memory64(&options, features, offset)?;
+        let info = LoweringInfo::value_params(result.as_ref(), types, memory64);
+        self.check_options(None, &info, &options, types, features, offset)?;
+        self.push_core_func(info.into_func_type(), types, offset);
+        Ok(())
+    }
+
+    pub fn task_wait(
+        &mut self,
+        memory: u32,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`task.wait` and `task.poll`", offset)?;
+        self.memory_at(memory, offset)?;
+        self.push_core_func(FuncType::new([ValType::I32], [ValType::I32]), types, offset);
+        Ok(())
+    }
+
+    pub fn task_yield(
+        &mut self,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`task.yield`", offset)?;
+        self.push_core_func(FuncType::new([], []), types, offset);
+        Ok(())
+    }
+
+    pub fn subtask_drop(
+        &mut self,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`subtask.drop`", offset)?;
+        self.push_core_func(FuncType::new([ValType::I32], []), types, offset);
+        Ok(())
+    }
+
+    /// Validates one of the `stream.*` or `future.*` built-ins named `name`
+    /// which operates on the type at index `ty`.
+    ///
+    /// When `options` is present the built-in transfers values through linear
+    /// memory and `lower` indicates whether those values are lowered into
+    /// memory (a read) or lifted out of it (a write).
+    pub fn stream_or_future(
+        &mut self,
+        name: &str,
+        ty: u32,
+        options: Option<(Vec<CanonicalOption>, bool)>,
+        core_ty: FuncType,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "stream and future built-ins", offset)?;
+        let id = self.defined_type_at(ty, offset)?;
+        let payload = match (&types[id], name.starts_with("stream.")) {
+            (ComponentDefinedType::Stream(payload), true)
+            | (ComponentDefinedType::Future(payload), false) => *payload,
+            _ => {
+                let expected = if name.starts_with("stream.") {
+                    "stream"
+                } else {
+                    "future"
+                };
+                bail!(offset, "`{name}` requires a {expected} type")
+            }
+        };
+        if let Some((options, lower)) = options {
+            let info = LoweringInfo {
+                requires_memory: true,
+                requires_realloc: lower
+                    && payload.map(|ty| ty.contains_ptr(types)).unwrap_or(false),
+                ..LoweringInfo::default()
+            };
+            self.check_options(None, &info, &options, types, features, offset)?;
+        }
+        self.push_core_func(core_ty, types, offset);
+        Ok(())
+    }
+
+    pub fn error_context_new(
+        &mut self,
+        options: Vec<CanonicalOption>,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`error-context.new`", offset)?;
+        check_no_async_option(&options, "error-context.new", offset)?;
+        let info = LoweringInfo {
+            requires_memory: true,
+            ..LoweringInfo::default()
+        };
+        self.check_options(None, &info, &options, types, features, offset)?;
+        self.push_core_func(
+            FuncType::new([ValType::I32, ValType::I32], [ValType::I32]),
+            types,
+            offset,
+        );
+        Ok(())
+    }
+
+    pub fn error_context_debug_message(
+        &mut self,
+        options: Vec<CanonicalOption>,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`error-context.debug-message`", offset)?;
+        check_no_async_option(&options, "error-context.debug-message", offset)?;
+        let info = LoweringInfo {
+            requires_memory: true,
+            requires_realloc: true,
+            ..LoweringInfo::default()
+        };
+        self.check_options(None, &info, &options, types, features, offset)?;
+        self.push_core_func(
+            FuncType::new([ValType::I32, ValType::I32], []),
+            types,
+            offset,
+        );
+        Ok(())
+    }
+
+    pub fn error_context_drop(
+        &mut self,
+        types: &mut TypeAlloc,
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<()> {
+        check_async_support(features, "`error-context.drop`", offset)?;
+        self.push_core_func(FuncType::new([ValType::I32], []), types, offset);
+        Ok(())
+    }
+
+    /// Defines a new core function of type `ty` produced by a canonical
+    /// built-in.
+    fn push_core_func(&mut self, ty: FuncType, types: &mut TypeAlloc, offset: usize) {
+        let core_ty = SubType {
+            is_final: true,
+            supertype_idx: None,
+            composite_type: CompositeType::Func(ty),
+        };
+        let (_is_new, group_id) =
+            types.intern_canonical_rec_group(RecGroup::implicit(offset, core_ty));
+        let id = types[group_id].start;
+        self.core_funcs.push(id);
+    }
+
     fn check_local_resource(&self, idx: u32, types: &TypeList, offset: usize) -> Result<ValType> {
         let resource = self.resource_at(idx, types, offset)?;
         match self
@@ -1242,12 +1448,36 @@ impl ComponentState {
         Ok(())
     }
 
+    /// Returns whether the `memory` option within `options`, if any, refers
+    /// to a 64-bit memory, in which case pointers and lengths are lowered to
+    /// `i64` values.
+    ///
+    /// This is always `false` unless the `component-model-memory64` feature
+    /// is enabled.
+    fn options_memory64(
+        &self,
+        options: &[CanonicalOption],
+        features: &WasmFeatures,
+        offset: usize,
+    ) -> Result<bool> {
+        if !features.component_model_memory64() {
+            return Ok(false);
+        }
+        for option in options {
+            if let CanonicalOption::Memory(idx) = option {
+                return Ok(self.memory_at(*idx, offset)?.memory64);
+            }
+        }
+        Ok(false)
+    }
+
     fn check_options(
         &self,
         core_ty: Option<&FuncType>,
         info: &LoweringInfo,
         options: &[CanonicalOption],
         types: &TypeList,
+        features: &WasmFeatures,
         offset: usize,
     ) -> Result<()> {
         fn display(option: CanonicalOption) -> &'static str {
@@ -1258,6 +1488,8 @@ impl ComponentState {
                 CanonicalOption::Memory(_) => "memory",
                 CanonicalOption::Realloc(_) => "realloc",
                 CanonicalOption::PostReturn(_) => "post-return",
+                CanonicalOption::Async => "async",
+                CanonicalOption::Callback(_) => "callback",
             }
         }
 
@@ -1265,6 +1497,8 @@ impl ComponentState {
         let mut memory = None;
         let mut realloc = None;
         let mut post_return = None;
+        let mut async_ = false;
+        let mut callback = None;
 
         for option in options {
             match option {
@@ -1298,16 +1532,7 @@ impl ComponentState {
                 CanonicalOption::Realloc(idx) => {
                     realloc = match realloc {
                         None => {
-                            let ty = types[self.core_function_at(*idx, offset)?].unwrap_func();
-                            if ty.params()
-                                != [ValType::I32, ValType::I32, ValType::I32, ValType::I32]
-                                || ty.results() != [ValType::I32]
-                            {
-                                return Err(BinaryReaderError::new(
-                                    "canonical option `realloc` uses a core function with an incorrect signature",
-                                    offset,
-                                ));
-                            }
+                            self.core_function_at(*idx, offset)?;
                             Some(*idx)
                         }
                         Some(_) => {
@@ -1346,6 +1571,76 @@ impl ComponentState {
                         }
                     }
                 }
+                CanonicalOption::Async => {
+                    check_async_support(features, "canonical option `async`", offset)?;
+                    if async_ {
+                        bail!(
+                            offset,
+                            "canonical option `async` is specified more than once"
+                        );
+                    }
+                    async_ = true;
+                }
+                CanonicalOption::Callback(idx) => {
+                    if core_ty.is_none() {
+                        bail!(
+                            offset,
+                            "canonical option `callback` cannot be specified for lowerings"
+                        );
+                    }
+                    if callback.is_some() {
+                        bail!(
+                            offset,
+                            "canonical option `callback` is specified more than once"
+                        );
+                    }
+                    let ty = types[self.core_function_at(*idx, offset)?].unwrap_func();
+                    if ty.params() != [ValType::I32, ValType::I32, ValType::I32, ValType::I32]
+                        || ty.results() != [ValType::I32]
+                    {
+                        bail!(
+                            offset,
+                            "canonical option `callback` uses a core function with an incorrect signature"
+                        );
+                    }
+                    callback = Some(*idx);
+                }
+            }
+        }
+
+        if callback.is_some() && !async_ {
+            bail!(
+                offset,
+                "canonical option `callback` requires the `async` option"
+            );
+        }
+
+        if async_ && post_return.is_some() {
+            bail!(
+                offset,
+                "canonical option `post-return` cannot be used with the `async` option"
+            );
+        }
+
+        // The pointers and sizes passed to `realloc` are the same width as
+        // the addresses of the memory it allocates within, but the alignment
+        // is always an `i32` as in the canonical ABI.
+        if let Some(idx) = realloc {
+            let ptr = match memory {
+                Some(memory)
+                    if features.component_model_memory64()
+                        && self.memory_at(memory, offset)?.memory64 =>
+                {
+                    ValType::I64
+                }
+                _ => ValType::I32,
+            };
+            let ty = types[self.core_function_at(idx, offset)?].unwrap_func();
+            if ty.params() != [ptr, ptr, ValType::I32, ptr] || ty.results() != [ptr] {
+                return Err(BinaryReaderError::new(
+                    "canonical option `realloc` uses a core function with an incorrect signature",
+                    offset,
+                ));
             }
         }
 
@@ -2512,6 +2807,7 @@ impl ComponentState {
         &self,
         ty: crate::ComponentDefinedType,
         types: &TypeList,
+        features: &WasmFeatures,
         offset: usize,
     ) -> Result<ComponentDefinedType> {
         match ty {
@@ -2551,6 +2847,24 @@ impl ComponentState {
             crate::ComponentDefinedType::Borrow(idx) => Ok(ComponentDefinedType::Borrow(
                 self.resource_at(idx, types, offset)?,
             )),
+            crate::ComponentDefinedType::Future(ty) => {
+                check_async_support(features, "future types", offset)?;
+                Ok(ComponentDefinedType::Future(
+                    ty.map(|ty| self.create_component_val_type(ty, offset))
+                        .transpose()?,
+                ))
+            }
+            crate::ComponentDefinedType::Stream(ty) => {
+                check_async_support(features, "stream types", offset)?;
+                Ok(ComponentDefinedType::Stream(
+                    ty.map(|ty| self.create_component_val_type(ty, offset))
+                        .transpose()?,
+                ))
+            }
+            crate::ComponentDefinedType::ErrorContext => {
+                check_async_support(features, "`error-context` types", offset)?;
+                Ok(ComponentDefinedType::ErrorContext)
+            }
         }
     }
 
@@ -3234,3 +3548,23 @@ mod append_only {
         }
     }
 }
+
+fn check_async_support(features: &WasmFeatures, desc: &str, offset: usize) -> Result<()> {
+    if !features.component_model_async() {
+        bail!(
+            offset,
+            "the component model async feature is required for {desc}"
+        );
+    }
+    Ok(())
+}
+
+fn check_no_async_option(options: &[CanonicalOption], name: &str, offset: usize) -> Result<()> {
+    if options.contains(&CanonicalOption::Async) {
+        bail!(
+            offset,
+            "canonical option `async` cannot be used with `{name}`"
+        );
+    }
+    Ok(())
+}
@@ -32,6 +32,13 @@ const MAX_FLAT_FUNC_PARAMS: usize = 16;
 /// additional pointer parameter (imports) or return a single pointer value (exports).
 const MAX_FLAT_FUNC_RESULTS: usize = 1;
 
+/// The maximum number of parameters an asynchronously lowered function can
+/// receive by value.
+///
+/// Functions that exceed this limit will instead pass parameters indirectly from
+/// linear memory via a single pointer parameter.
+const MAX_FLAT_ASYNC_PARAMS: usize = 4;
+
 /// The maximum lowered types, including a possible type for a return pointer parameter.
 const MAX_LOWERED_TYPES: usize = MAX_FLAT_FUNC_PARAMS + 1;
 
@@ -40,15 +47,18 @@ pub(crate) struct LoweredTypes {
     types: [ValType; MAX_LOWERED_TYPES],
     len: usize,
     max: usize,
+    /// The type of pointers and lengths, which is `i64` for 64-bit memories.
+    ptr: ValType,
 }
 
 impl LoweredTypes {
-    fn new(max: usize) -> Self {
+    fn new(max: usize, ptr: ValType) -> Self {
         assert!(max <= MAX_LOWERED_TYPES);
         Self {
             types: [ValType::I32; MAX_LOWERED_TYPES],
             len: 0,
             max,
+            ptr,
         }
     }
 
@@ -68,7 +78,7 @@ impl LoweredTypes {
         }
     }
 
-    fn push(&mut self, ty: ValType) -> bool {
+    pub(crate) fn push(&mut self, ty: ValType) -> bool {
         if self.maxed() {
             return false;
         }
@@ -78,6 +88,11 @@ impl LoweredTypes {
         true
     }
 
+    /// Pushes a pointer or a length into linear memory.
+    pub(crate) fn push_ptr(&mut self) -> bool {
+        self.push(self.ptr)
+    }
+
     fn clear(&mut self) {
         self.len = 0;
     }
@@ -100,6 +115,37 @@ pub(crate) struct LoweringInfo {
 }
 
 impl LoweringInfo {
+    /// Creates an empty lowering where pointers into linear memory are `i64`
+    /// values if `memory64` is set, and `i32` values otherwise.
+    pub(crate) fn new(memory64: bool) -> Self {
+        let ptr = if memory64 { ValType::I64 } else { ValType::I32 };
+        Self {
+            params: LoweredTypes::new(MAX_FLAT_FUNC_PARAMS, ptr),
+            results: LoweredTypes::new(MAX_FLAT_FUNC_RESULTS, ptr),
+            requires_memory: false,
+            requires_realloc: false,
+        }
+    }
+
+    /// Lowers the optional value `ty` as the core parameters of a built-in,
+    /// such as `task.return`, which lifts a single value out of core wasm.
+    pub(crate) fn value_params(
+        ty: Option<&ComponentValType>,
+        types: &TypeList,
+        memory64: bool,
+    ) -> Self {
+        let mut info = LoweringInfo::new(memory64);
+        if let Some(ty) = ty {
+            info.requires_memory = ty.contains_ptr(types);
+            if !ty.push_wasm_types(types, &mut info.params) {
+                info.params.clear();
+                assert!(info.params.push_ptr());
+                info.requires_memory = true;
+            }
+        }
+        info
+    }
+
     pub(crate) fn into_func_type(self) -> FuncType {
         FuncType::new(
             self.params.as_slice().iter().copied(),
@@ -110,12 +156,7 @@ impl LoweringInfo {
 
 impl Default for LoweringInfo {
     fn default() -> Self {
-        Self {
-            params: LoweredTypes::new(MAX_FLAT_FUNC_PARAMS),
-            results: LoweredTypes::new(MAX_FLAT_FUNC_RESULTS),
-            requires_memory: false,
-            requires_realloc: false,
-        }
+        LoweringInfo::new(false)
     }
 }
 
@@ -132,9 +173,7 @@ fn push_primitive_wasm_types(ty: &PrimitiveValType, lowered_types: &mut LoweredT
         PrimitiveValType::S64 | PrimitiveValType::U64 => lowered_types.push(ValType::I64),
         PrimitiveValType::F32 => lowered_types.push(ValType::F32),
         PrimitiveValType::F64 => lowered_types.push(ValType::F64),
-        PrimitiveValType::String => {
-            lowered_types.push(ValType::I32) && lowered_types.push(ValType::I32)
-        }
+        PrimitiveValType::String => lowered_types.push_ptr() && lowered_types.push_ptr(),
     }
 }
 
@@ -1182,8 +1221,11 @@ impl TypeData for ComponentFuncType {
 impl ComponentFuncType {
     /// Lowers the component function type to core parameter and result types for the
     /// canonical ABI.
-    pub(crate) fn lower(&self, types: &TypeList, is_lower: bool) -> LoweringInfo {
-        let mut info = LoweringInfo::default();
+    ///
+    /// Pointers and lengths are `i64` values when `memory64` is set, as the
+    /// memory used by the lowering is a 64-bit memory.
+    pub(crate) fn lower(&self, types: &TypeList, is_lower: bool, memory64: bool) -> LoweringInfo {
+        let mut info = LoweringInfo::new(memory64);
 
         for (_, ty) in self.params.iter() {
             // Check to see if `ty` has a pointer somewhere in it, needed for
@@ -1207,7 +1249,7 @@ impl ComponentFuncType {
                 // Function will have a single pointer parameter to pass the arguments
                 // via linear memory
                 info.params.clear();
-                assert!(info.params.push(ValType::I32));
+                assert!(info.params.push_ptr());
                 info.requires_memory = true;
 
                 // We need realloc as well when lifting a function
@@ -1233,9 +1275,9 @@ impl ComponentFuncType {
                 info.results.clear();
                 if is_lower {
                     info.params.max = MAX_LOWERED_TYPES;
-                    assert!(info.params.push(ValType::I32));
+                    assert!(info.params.push_ptr());
                 } else {
-                    assert!(info.results.push(ValType::I32));
+                    assert!(info.results.push_ptr());
                 }
                 info.requires_memory = true;
                 break;
@@ -1247,6 +1289,67 @@ impl ComponentFuncType {
 
         info
     }
+
+    /// Lowers the component function type to core parameter and result types
+    /// for the asynchronous canonical ABI.
+    ///
+    /// Results of asynchronously lifted functions are delivered with
+    /// `task.return` rather than returned, so no result types are produced
+    /// for lifts. Asynchronously lowered functions receive a pointer for
+    /// their results, if any, and always return an `i32` status.
+    pub(crate) fn lower_async(
+        &self,
+        types: &TypeList,
+        is_lower: bool,
+        memory64: bool,
+    ) -> LoweringInfo {
+        if !is_lower {
+            let mut info = LoweringInfo::new(memory64);
+            for (_, ty) in self.params.iter() {
+                if !info.requires_realloc {
+                    info.requires_realloc = ty.contains_ptr(types);
+                }
+                if !ty.push_wasm_types(types, &mut info.params) {
+                    info.params.clear();
+                    assert!(info.params.push_ptr());
+                    info.requires_memory = true;
+                    info.requires_realloc = true;
+                    break;
+                }
+            }
+            info.requires_memory |= info.requires_realloc;
+            return info;
+        }
+
+        let mut info = LoweringInfo::new(memory64);
+        info.params.max = MAX_FLAT_ASYNC_PARAMS;
+        for (_, ty) in self.params.iter() {
+            if !info.requires_memory {
+                info.requires_memory = ty.contains_ptr(types);
+            }
+            if !ty.push_wasm_types(types, &mut info.params) {
+                info.params.clear();
+                assert!(info.params.push_ptr());
+                info.requires_memory = true;
+                break;
+            }
+        }
+
+        if !self.results.is_empty() {
+            info.params.max = MAX_LOWERED_TYPES;
+            assert!(info.params.push_ptr());
+            info.requires_memory = true;
+            for (_, ty) in self.results.iter() {
+                if !info.requires_realloc {
+                    info.requires_realloc = ty.contains_ptr(types);
+                }
+            }
+        }
+        assert!(info.results.push(ValType::I32));
+
+        info.requires_memory |= info.requires_realloc;
+        info
+    }
 }
 
 /// Represents a variant case.
@@ -1315,6 +1418,12 @@ pub enum ComponentDefinedType {
     Own(AliasableResourceId),
     /// The type is a borrowed handle to the specified resource.
     Borrow(AliasableResourceId),
+    /// The type is a future with the given payload type, if any.
+    Future(Option<ComponentValType>),
+    /// The type is a stream with the given element type, if any.
+    Stream(Option<ComponentValType>),
+    /// The type is an `error-context`.
+    ErrorContext,
 }
 
 impl TypeData for ComponentDefinedType {
@@ -1322,8 +1431,15 @@ impl TypeData for ComponentDefinedType {
 
     fn type_info(&self, types: &TypeList) -> TypeInfo {
         match self {
-            Self::Primitive(_) | Self::Flags(_) | Self::Enum(_) | Self::Own(_) => TypeInfo::new(),
+            Self::Primitive(_)
+            | Self::Flags(_)
+            | Self::Enum(_)
+            | Self::Own(_)
+            | Self::ErrorContext => TypeInfo::new(),
             Self::Borrow(_) => TypeInfo::borrow(),
+            Self::Future(ty) | Self::Stream(ty) => {
+                ty.map(|ty| ty.type_info(types)).unwrap_or(TypeInfo::new())
+            }
             Self::Record(r) => r.info,
             Self::Variant(v) => v.info,
             Self::Tuple(t) => t.info,
@@ -1350,7 +1466,13 @@ impl ComponentDefinedType {
                 .any(|case| case.ty.map(|ty| ty.contains_ptr(types)).unwrap_or(false)),
             Self::List(_) => true,
             Self::Tuple(t) => t.types.iter().any(|ty| ty.contains_ptr(types)),
-            Self::Flags(_) | Self::Enum(_) | Self::Own(_) | Self::Borrow(_) => false,
+            Self::Flags(_)
+            | Self::Enum(_)
+            | Self::Own(_)
+            | Self::Borrow(_)
+            | Self::Future(_)
+            | Self::Stream(_)
+            | Self::ErrorContext => false,
             Self::Option(ty) => ty.contains_ptr(types),
             Self::Result { ok, err } => {
                 ok.map(|ty| ty.contains_ptr(types)).unwrap_or(false)
@@ -1371,7 +1493,7 @@ impl ComponentDefinedType {
                 types,
                 lowered_types,
             ),
-            Self::List(_) => lowered_types.push(ValType::I32) && lowered_types.push(ValType::I32),
+            Self::List(_) => lowered_types.push_ptr() && lowered_types.push_ptr(),
             Self::Tuple(t) => t
                 .types
                 .iter()
@@ -1379,7 +1501,12 @@ impl ComponentDefinedType {
             Self::Flags(names) => {
                 (0..(names.len() + 31) / 32).all(|_| lowered_types.push(ValType::I32))
             }
-            Self::Enum(_) | Self::Own(_) | Self::Borrow(_) => lowered_types.push(ValType::I32),
+            Self::Enum(_)
+            | Self::Own(_)
+            | Self::Borrow(_)
+            | Self::Future(_)
+            | Self::Stream(_)
+            | Self::ErrorContext => lowered_types.push(ValType::I32),
             Self::Option(ty) => {
                 Self::push_variant_wasm_types([ty].into_iter(), types, lowered_types)
             }
@@ -1402,7 +1529,7 @@ impl ComponentDefinedType {
         let start = lowered_types.len();
 
         for ty in cases {
-            let mut temp = LoweredTypes::new(lowered_types.max);
+            let mut temp = LoweredTypes::new(lowered_types.max, lowered_types.ptr);
 
             if !ty.push_wasm_types(types, &mut temp) {
                 return false;
@@ -1447,6 +1574,9 @@ impl ComponentDefinedType {
             ComponentDefinedType::Result { .. } => "result",
             ComponentDefinedType::Own(_) => "own",
             ComponentDefinedType::Borrow(_) => "borrow",
+            ComponentDefinedType::Future(_) => "future",
+            ComponentDefinedType::Stream(_) => "stream",
+            ComponentDefinedType::ErrorContext => "error-context",
         }
     }
 }
@@ -3208,6 +3338,12 @@ impl TypeAlloc {
             ComponentDefinedType::Own(id) | ComponentDefinedType::Borrow(id) => {
                 set.insert(id.resource());
             }
+            ComponentDefinedType::Future(ty) | ComponentDefinedType::Stream(ty) => {
+                if let Some(ty) = ty {
+                    self.free_variables_valtype(ty, set);
+                }
+            }
+            ComponentDefinedType::ErrorContext => {}
         }
     }
 
@@ -3308,7 +3444,7 @@ impl TypeAlloc {
         let ty = &self[id];
         match ty {
             // Primitives are always considered named
-            ComponentDefinedType::Primitive(_) => true,
+            ComponentDefinedType::Primitive(_) | ComponentDefinedType::ErrorContext => true,
 
             // These structures are never allowed to be anonymous, so they
             // themselves must be named.
@@ -3334,6 +3470,10 @@ impl TypeAlloc {
             ComponentDefinedType::List(ty) | ComponentDefinedType::Option(ty) => {
                 self.type_named_valtype(ty, set)
             }
+            ComponentDefinedType::Future(ty) | ComponentDefinedType::Stream(ty) => ty
+                .as_ref()
+                .map(|t| self.type_named_valtype(t, set))
+                .unwrap_or(true),
 
             // own/borrow themselves don't have to be named, but the resource
             // they refer to must be named.
@@ -3522,6 +3662,12 @@ where
             ComponentDefinedType::Own(id) | ComponentDefinedType::Borrow(id) => {
                 any_changed |= self.remap_resource_id(id, map);
             }
+            ComponentDefinedType::Future(ty) | ComponentDefinedType::Stream(ty) => {
+                if let Some(ty) = ty {
+                    any_changed |= self.remap_valtype(ty, map);
+                }
+            }
+            ComponentDefinedType::ErrorContext => {}
         }
         self.insert_if_any_changed(map, any_changed, id, tmp)
     }
@@ -4461,6 +4607,18 @@ impl<'a> SubtypeCx<'a> {
             }
             (Own(_), b) => bail!(offset, "expected {}, found own", b.desc()),
             (Borrow(_), b) => bail!(offset, "expected {}, found borrow", b.desc()),
+            (Future(a), Future(b)) | (Stream(a), Stream(b)) => match (a, b) {
+                (None, None) => Ok(()),
+                (Some(a), Some(b)) => self
+                    .component_val_type(a, b, offset)
+                    .with_context(|| "type mismatch in payload type"),
+                (None, Some(_)) => bail!(offset, "expected payload type, but found none"),
+                (Some(_), None) => bail!(offset, "expected payload type to not be present"),
+            },
+            (Future(_), b) => bail!(offset, "expected {}, found future", b.desc()),
+            (Stream(_), b) => bail!(offset, "expected {}, found stream", b.desc()),
+            (ErrorContext, ErrorContext) => Ok(()),
+            (ErrorContext, b) => bail!(offset, "expected {}, found error-context", b.desc()),
         }
     }
 
@@ -52,6 +52,7 @@ pub struct Config {
     print_offsets: bool,
     print_skeleton: bool,
     name_unnamed: bool,
+    source_annotations: Option<Box<dyn Fn(usize) -> Option<String> + Send + Sync>>,
 }
 
 /// This structure is the actual structure that prints WebAssembly binaries.
@@ -62,6 +63,18 @@ struct Printer<'cfg, 'env> {
     line: usize,
     group_lines: Vec<usize>,
     code_section_hints: Vec<(u32, Vec<(usize, BranchHint)>)>,
+    code_section_metadata: HashMap<u32, Vec<CodeMetadataAnnotation>>,
+    scopes: u32,
+    last_source_annotation: Option<String>,
+}
+
+/// A `metadata.code.*` annotation, other than a branch hint, which is printed
+/// inline with the instructions of a function body.
+struct CodeMetadataAnnotation {
+    offset: usize,
+    func_offset: u32,
+    name: String,
+    data: Vec<u8>,
 }
 
 #[derive(Default)]
@@ -98,20 +111,32 @@ struct CoreState {
 /// The type parameter `K` is a static description/namespace for what kind of
 /// item is contained within this map. That's used by some helper methods to
 /// synthesize reasonable names automatically.
+///
+/// The `scope` identifies the module or component that this map belongs to and
+/// is used to describe items passed to [`Print::start_definition`] and
+/// [`Print::start_reference`].
 struct NamingMap<T, K> {
     index_to_name: HashMap<T, Naming>,
+    scope: u32,
     _marker: marker::PhantomData<K>,
 }
 
-impl<T, K> Default for NamingMap<T, K> {
-    fn default() -> NamingMap<T, K> {
+impl<T, K> NamingMap<T, K> {
+    fn new(scope: u32) -> NamingMap<T, K> {
         NamingMap {
             index_to_name: HashMap::new(),
+            scope,
             _marker: marker::PhantomData,
         }
     }
 }
 
+impl<T, K> Default for NamingMap<T, K> {
+    fn default() -> NamingMap<T, K> {
+        NamingMap::new(0)
+    }
+}
+
 #[derive(Default)]
 struct ComponentState {
     types: u32,
@@ -135,12 +160,34 @@ struct State {
 }
 
 impl State {
-    fn new(encoding: Encoding) -> Self {
+    fn new(encoding: Encoding, scope: u32) -> Self {
         Self {
             encoding,
             name: None,
-            core: CoreState::default(),
-            component: ComponentState::default(),
+            core: CoreState {
+                func_names: NamingMap::new(scope),
+                local_names: NamingMap::new(scope),
+                label_names: NamingMap::new(scope),
+                type_names: NamingMap::new(scope),
+                field_names: NamingMap::new(scope),
+                tag_names: NamingMap::new(scope),
+                table_names: NamingMap::new(scope),
+                memory_names: NamingMap::new(scope),
+                global_names: NamingMap::new(scope),
+                element_names: NamingMap::new(scope),
+                data_names: NamingMap::new(scope),
+                module_names: NamingMap::new(scope),
+                instance_names: NamingMap::new(scope),
+                ..CoreState::default()
+            },
+            component: ComponentState {
+                type_names: NamingMap::new(scope),
+                func_names: NamingMap::new(scope),
+                component_names: NamingMap::new(scope),
+                instance_names: NamingMap::new(scope),
+                value_names: NamingMap::new(scope),
+                ..ComponentState::default()
+            },
             custom_section_place: None,
         }
     }
@@ -190,6 +237,21 @@ impl Config {
         self.name_unnamed = enable;
     }
 
+    /// Annotate lines of the output with comments describing the source
+    /// location they originated from.
+    ///
+    /// The `annotate` function is invoked with the binary offset of each line
+    /// printed and returns the annotation for that offset, if any, such as a
+    /// `file:line` location from DWARF debugging information. Whenever the
+    /// annotation differs from the previous one it's printed as a `;;` comment
+    /// before the line, with one comment per line of the annotation.
+    pub fn source_annotations(
+        &mut self,
+        annotate: impl Fn(usize) -> Option<String> + Send + Sync + 'static,
+    ) {
+        self.source_annotations = Some(Box::new(annotate));
+    }
+
     /// Prints a WebAssembly binary into a `String`
     ///
     /// This function takes an entire `wasm` binary blob and will print it to
@@ -199,9 +261,12 @@ impl Config {
             config: self,
             result,
             code_section_hints: Vec::new(),
+            code_section_metadata: HashMap::new(),
             group_lines: Vec::new(),
             line: 0,
             nesting: 0,
+            scopes: 0,
+            last_source_annotation: None,
         }
         .print_contents(wasm)
     }
@@ -258,6 +323,14 @@ impl Config {
 }
 
 impl Printer<'_, '_> {
+    /// Creates the state for a new module or component, assigning it a fresh
+    /// scope.
+    fn new_state(&mut self, encoding: Encoding) -> State {
+        let state = State::new(encoding, self.scopes);
+        self.scopes += 1;
+        state
+    }
+
     fn read_names_and_code<'a>(
         &mut self,
         mut bytes: &'a [u8],
@@ -265,6 +338,7 @@ impl Printer<'_, '_> {
         state: &mut State,
         code: &mut Vec<FunctionBody<'a>>,
     ) -> Result<()> {
+        self.code_section_metadata.clear();
         loop {
             let payload = match parser.parse(bytes, true)? {
                 Chunk::NeedMoreData(_) => unreachable!(),
@@ -315,6 +389,9 @@ impl Printer<'_, '_> {
                         KnownCustom::BranchHints(reader) => {
                             drop(self.register_branch_hint_section(reader));
                         }
+                        KnownCustom::CodeMetadata(reader) => {
+                            drop(self.register_code_metadata_section(c.name(), reader));
+                        }
                         _ => {}
                     }
                 }
@@ -374,7 +451,7 @@ impl Printer<'_, '_> {
 
                     match encoding {
                         Encoding::Module => {
-                            states.push(State::new(Encoding::Module));
+                            states.push(self.new_state(Encoding::Module));
                             states.last_mut().unwrap().custom_section_place = Some("before first");
                             if states.len() > 1 {
                                 self.start_group("core module")?;
@@ -389,7 +466,7 @@ impl Printer<'_, '_> {
                             }
                         }
                         Encoding::Component => {
-                            states.push(State::new(Encoding::Component));
+                            states.push(self.new_state(Encoding::Component));
                             self.start_group("component")?;
 
                             if states.len() > 1 {
@@ -929,7 +1006,13 @@ impl Printer<'_, '_> {
         ty_field_idx: Option<(u32, u32)>,
     ) -> Result<u32> {
         self.result.write_str(" ")?;
-        if let Some(idxs @ (_, field_idx)) = ty_field_idx {
+        if let Some(idxs @ (ty_idx, field_idx)) = ty_field_idx {
+            self.result.start_definition(&Item {
+                scope: state.core.field_names.scope,
+                kind: NameField::desc(),
+                parent: Some(ty_idx),
+                index: field_idx,
+            })?;
             match state.core.field_names.index_to_name.get(&idxs) {
                 Some(name) => {
                     name.write_identifier(self)?;
@@ -938,6 +1021,7 @@ impl Printer<'_, '_> {
                 None if self.config.name_unnamed => write!(self.result, "$#field{field_idx} ")?,
                 None => {}
             }
+            self.result.end_item()?;
         }
         if ty.mutable {
             self.result.write_str("(mut ")?;
@@ -1258,6 +1342,9 @@ impl Printer<'_, '_> {
             let mut body = body.get_binary_reader();
             let offset = body.original_position();
             let ty = ty?;
+            // Each function starts with its own source annotation, even if
+            // it's the same as the one the previous function ended with.
+            self.last_source_annotation = None;
             self.newline(offset)?;
             self.start_group("func ")?;
             let func_idx = state.core.funcs;
@@ -1276,11 +1363,15 @@ impl Printer<'_, '_> {
                 }
                 _ => Vec::new(),
             };
+            let metadata = self
+                .code_section_metadata
+                .remove(&func_idx)
+                .unwrap_or_default();
 
             if self.config.print_skeleton {
                 self.result.write_str(" ...")?;
             } else {
-                self.print_func_body(state, func_idx, params, &mut body, &hints)?;
+                self.print_func_body(state, func_idx, params, &mut body, &hints, &metadata)?;
             }
 
             self.end_group()?;
@@ -1297,6 +1388,7 @@ impl Printer<'_, '_> {
         params: u32,
         body: &mut BinaryReader<'_>,
         mut branch_hints: &[(usize, BranchHint)],
+        mut metadata: &[CodeMetadataAnnotation],
     ) -> Result<()> {
         let mut first = true;
         let mut local_idx = 0;
@@ -1347,6 +1439,24 @@ impl Printer<'_, '_> {
                 }
             }
 
+            // Other code metadata is sorted the same way, but there may be
+            // multiple annotations for a single instruction.
+            while let Some((m, rest)) = metadata.split_first() {
+                if m.func_offset != (body.original_position() - func_start) as u32 {
+                    break;
+                }
+                metadata = rest;
+                let printer = &mut *op_printer.printer;
+                printer.newline(m.offset)?;
+                printer.result.start_comment()?;
+                write!(printer.result, "(@{} ", m.name)?;
+                printer.result.reset_color()?;
+                printer.print_bytes(&m.data)?;
+                printer.result.start_comment()?;
+                printer.result.write_str(")")?;
+                printer.result.reset_color()?;
+            }
+
             op_printer.op_offset = body.original_position();
             body.visit_operator(&mut op_printer)??;
         }
@@ -1372,8 +1482,41 @@ impl Printer<'_, '_> {
         self.print_newline(None)
     }
 
+    fn print_source_annotation(&mut self, offset: usize) -> Result<()> {
+        let annotation = match &self.config.source_annotations {
+            Some(annotate) => annotate(offset),
+            None => return Ok(()),
+        };
+        let annotation = match annotation {
+            Some(annotation) => annotation,
+            None => return Ok(()),
+        };
+        if self.last_source_annotation.as_ref() == Some(&annotation) {
+            return Ok(());
+        }
+        for line in annotation.lines() {
+            self.result.start_line(None);
+            if self.config.print_offsets {
+                self.result.write_str("           ")?;
+            }
+            for _ in 0..self.nesting.min(MAX_NESTING_TO_PRINT) {
+                self.result.write_str("  ")?;
+            }
+            self.result.start_comment()?;
+            write!(self.result, ";; {line}")?;
+            self.result.reset_color()?;
+            self.result.newline()?;
+            self.line += 1;
+        }
+        self.last_source_annotation = Some(annotation);
+        Ok(())
+    }
+
     fn print_newline(&mut self, offset: Option<usize>) -> Result<()> {
         self.result.newline()?;
+        if let Some(offset) = offset {
+            self.print_source_annotation(offset)?;
+        }
         self.result.start_line(offset);
 
         if self.config.print_offsets {
@@ -1456,21 +1599,31 @@ impl Printer<'_, '_> {
     where
         K: NamingNamespace,
     {
-        self._print_idx(&names.index_to_name, idx, K::desc())
-    }
-
-    fn _print_idx(&mut self, names: &HashMap<u32, Naming>, idx: u32, desc: &str) -> Result<()> {
+        let desc = K::desc();
+        self.result.start_reference(&Item {
+            scope: names.scope,
+            kind: desc,
+            parent: None,
+            index: idx,
+        })?;
         self.result.start_name()?;
-        match names.get(&idx) {
+        match names.index_to_name.get(&idx) {
             Some(name) => name.write_identifier(self)?,
             None if self.config.name_unnamed => write!(self.result, "$#{desc}{idx}")?,
             None => write!(self.result, "{idx}")?,
         }
         self.result.reset_color()?;
+        self.result.end_item()?;
         Ok(())
     }
 
     fn print_local_idx(&mut self, state: &State, func: u32, idx: u32) -> Result<()> {
+        self.result.start_reference(&Item {
+            scope: state.core.local_names.scope,
+            kind: NameLocal::desc(),
+            parent: Some(func),
+            index: idx,
+        })?;
         self.result.start_name()?;
         match state.core.local_names.index_to_name.get(&(func, idx)) {
             Some(name) => name.write_identifier(self)?,
@@ -1478,10 +1631,17 @@ impl Printer<'_, '_> {
             None => write!(self.result, "{}", idx)?,
         }
         self.result.reset_color()?;
+        self.result.end_item()?;
         Ok(())
     }
 
     fn print_field_idx(&mut self, state: &State, ty: u32, idx: u32) -> Result<()> {
+        self.result.start_reference(&Item {
+            scope: state.core.field_names.scope,
+            kind: NameField::desc(),
+            parent: Some(ty),
+            index: idx,
+        })?;
         self.result.start_name()?;
         match state.core.field_names.index_to_name.get(&(ty, idx)) {
             Some(name) => name.write_identifier(self)?,
@@ -1489,6 +1649,7 @@ impl Printer<'_, '_> {
             None => write!(self.result, "{}", idx)?,
         }
         self.result.reset_color()?;
+        self.result.end_item()?;
         Ok(())
     }
 
@@ -1496,17 +1657,15 @@ impl Printer<'_, '_> {
     where
         K: NamingNamespace,
     {
-        self._print_name(&names.index_to_name, cur_idx, K::desc())
-    }
-
-    fn _print_name(
-        &mut self,
-        names: &HashMap<u32, Naming>,
-        cur_idx: u32,
-        desc: &str,
-    ) -> Result<()> {
+        let desc = K::desc();
+        self.result.start_definition(&Item {
+            scope: names.scope,
+            kind: desc,
+            parent: None,
+            index: cur_idx,
+        })?;
         self.result.start_name()?;
-        match names.get(&cur_idx) {
+        match names.index_to_name.get(&cur_idx) {
             Some(name) => {
                 name.write(self)?;
                 self.result.write_str(" ")?;
@@ -1518,6 +1677,7 @@ impl Printer<'_, '_> {
         }
         write!(self.result, "(;{cur_idx};)")?;
         self.result.reset_color()?;
+        self.result.end_item()?;
         Ok(())
     }
 
@@ -1766,6 +1926,21 @@ impl Printer<'_, '_> {
         Ok(())
     }
 
+    fn print_payload_type(
+        &mut self,
+        state: &State,
+        name: &str,
+        ty: Option<ComponentValType>,
+    ) -> Result<()> {
+        self.start_group(name)?;
+        if let Some(ty) = ty {
+            self.result.write_str(" ")?;
+            self.print_component_val_type(state, &ty)?;
+        }
+        self.end_group()?;
+        Ok(())
+    }
+
     fn print_defined_type(&mut self, state: &State, ty: &ComponentDefinedType) -> Result<()> {
         match ty {
             ComponentDefinedType::Primitive(ty) => self.print_primitive_val_type(ty)?,
@@ -1787,6 +1962,9 @@ impl Printer<'_, '_> {
                 self.print_idx(&state.component.type_names, *idx)?;
                 self.end_group()?;
             }
+            ComponentDefinedType::Stream(ty) => self.print_payload_type(state, "stream", *ty)?,
+            ComponentDefinedType::Future(ty) => self.print_payload_type(state, "future", *ty)?,
+            ComponentDefinedType::ErrorContext => self.result.write_str("error-context")?,
         }
 
         Ok(())
@@ -1804,7 +1982,7 @@ impl Printer<'_, '_> {
         states: &mut Vec<State>,
         decls: Vec<ModuleTypeDeclaration>,
     ) -> Result<()> {
-        states.push(State::new(Encoding::Module));
+        states.push(self.new_state(Encoding::Module));
         self.newline_unknown_pos()?;
         self.start_group("module")?;
         for decl in decls {
@@ -1838,7 +2016,7 @@ impl Printer<'_, '_> {
         states: &mut Vec<State>,
         decls: Vec<ComponentTypeDeclaration<'a>>,
     ) -> Result<()> {
-        states.push(State::new(Encoding::Component));
+        states.push(self.new_state(Encoding::Component));
         self.newline_unknown_pos()?;
         self.start_group("component")?;
         for decl in decls {
@@ -1873,7 +2051,7 @@ impl Printer<'_, '_> {
         states: &mut Vec<State>,
         decls: Vec<InstanceTypeDeclaration<'a>>,
     ) -> Result<()> {
-        states.push(State::new(Encoding::Component));
+        states.push(self.new_state(Encoding::Component));
         self.newline_unknown_pos()?;
         self.start_group("instance")?;
         for decl in decls {
@@ -2299,6 +2477,12 @@ impl Printer<'_, '_> {
                     self.print_idx(&state.core.func_names, *idx)?;
                     self.end_group()?;
                 }
+                CanonicalOption::Async => self.result.write_str("async")?,
+                CanonicalOption::Callback(idx) => {
+                    self.start_group("callback ")?;
+                    self.print_idx(&state.core.func_names, *idx)?;
+                    self.end_group()?;
+                }
             }
         }
         Ok(())
@@ -2380,12 +2564,167 @@ impl Printer<'_, '_> {
                     self.end_group()?;
                     state.core.funcs += 1;
                 }
+                CanonicalFunction::TaskBackpressure => {
+                    self.print_intrinsic(state, "task.backpressure", |_, _| Ok(()))?;
+                }
+                CanonicalFunction::TaskReturn { result, options } => {
+                    self.print_intrinsic(state, "task.return", |me, state| {
+                        if let Some(ty) = result {
+                            me.result.write_str(" ")?;
+                            me.start_group("result ")?;
+                            me.print_component_val_type(state, &ty)?;
+                            me.end_group()?;
+                        }
+                        me.print_canonical_options(state, &options)
+                    })?;
+                }
+                CanonicalFunction::TaskWait { async_, memory } => {
+                    self.print_intrinsic(state, "task.wait", |me, state| {
+                        me.print_task_memory(state, async_, memory)
+                    })?;
+                }
+                CanonicalFunction::TaskPoll { async_, memory } => {
+                    self.print_intrinsic(state, "task.poll", |me, state| {
+                        me.print_task_memory(state, async_, memory)
+                    })?;
+                }
+                CanonicalFunction::TaskYield { async_ } => {
+                    self.print_intrinsic(state, "task.yield", |me, _| {
+                        if async_ {
+                            me.result.write_str(" async")?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                CanonicalFunction::SubtaskDrop => {
+                    self.print_intrinsic(state, "subtask.drop", |_, _| Ok(()))?;
+                }
+                CanonicalFunction::StreamNew { ty } => {
+                    self.print_type_intrinsic(state, "stream.new", ty, None, None)?;
+                }
+                CanonicalFunction::StreamRead { ty, options } => {
+                    self.print_type_intrinsic(state, "stream.read", ty, None, Some(&options))?;
+                }
+                CanonicalFunction::StreamWrite { ty, options } => {
+                    self.print_type_intrinsic(state, "stream.write", ty, None, Some(&options))?;
+                }
+                CanonicalFunction::StreamCancelRead { ty, async_ } => {
+                    self.print_type_intrinsic(state, "stream.cancel-read", ty, Some(async_), None)?;
+                }
+                CanonicalFunction::StreamCancelWrite { ty, async_ } => {
+                    self.print_type_intrinsic(
+                        state,
+                        "stream.cancel-write",
+                        ty,
+                        Some(async_),
+                        None,
+                    )?;
+                }
+                CanonicalFunction::StreamCloseReadable { ty } => {
+                    self.print_type_intrinsic(state, "stream.close-readable", ty, None, None)?;
+                }
+                CanonicalFunction::StreamCloseWritable { ty } => {
+                    self.print_type_intrinsic(state, "stream.close-writable", ty, None, None)?;
+                }
+                CanonicalFunction::FutureNew { ty } => {
+                    self.print_type_intrinsic(state, "future.new", ty, None, None)?;
+                }
+                CanonicalFunction::FutureRead { ty, options } => {
+                    self.print_type_intrinsic(state, "future.read", ty, None, Some(&options))?;
+                }
+                CanonicalFunction::FutureWrite { ty, options } => {
+                    self.print_type_intrinsic(state, "future.write", ty, None, Some(&options))?;
+                }
+                CanonicalFunction::FutureCancelRead { ty, async_ } => {
+                    self.print_type_intrinsic(state, "future.cancel-read", ty, Some(async_), None)?;
+                }
+                CanonicalFunction::FutureCancelWrite { ty, async_ } => {
+                    self.print_type_intrinsic(
+                        state,
+                        "future.cancel-write",
+                        ty,
+                        Some(async_),
+                        None,
+                    )?;
+                }
+                CanonicalFunction::FutureCloseReadable { ty } => {
+                    self.print_type_intrinsic(state, "future.close-readable", ty, None, None)?;
+                }
+                CanonicalFunction::FutureCloseWritable { ty } => {
+                    self.print_type_intrinsic(state, "future.close-writable", ty, None, None)?;
+                }
+                CanonicalFunction::ErrorContextNew { options } => {
+                    self.print_intrinsic(state, "error-context.new", |me, state| {
+                        me.print_canonical_options(state, &options)
+                    })?;
+                }
+                CanonicalFunction::ErrorContextDebugMessage { options } => {
+                    self.print_intrinsic(state, "error-context.debug-message", |me, state| {
+                        me.print_canonical_options(state, &options)
+                    })?;
+                }
+                CanonicalFunction::ErrorContextDrop => {
+                    self.print_intrinsic(state, "error-context.drop", |_, _| Ok(()))?;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Prints a `(core func (canon $name ...))` definition for one of the
+    /// component model async intrinsics, using `body` to print everything
+    /// after the intrinsic's name.
+    fn print_intrinsic(
+        &mut self,
+        state: &mut State,
+        name: &str,
+        body: impl FnOnce(&mut Self, &State) -> Result<()>,
+    ) -> Result<()> {
+        self.start_group("core func ")?;
+        self.print_name(&state.core.func_names, state.core.funcs)?;
+        self.result.write_str(" ")?;
+        self.start_group("canon ")?;
+        self.result.write_str(name)?;
+        body(self, state)?;
+        self.end_group()?;
+        self.end_group()?;
+        state.core.funcs += 1;
+        Ok(())
+    }
+
+    fn print_type_intrinsic(
+        &mut self,
+        state: &mut State,
+        name: &str,
+        ty: u32,
+        async_: Option<bool>,
+        options: Option<&[CanonicalOption]>,
+    ) -> Result<()> {
+        self.print_intrinsic(state, name, |me, state| {
+            me.result.write_str(" ")?;
+            me.print_idx(&state.component.type_names, ty)?;
+            if async_ == Some(true) {
+                me.result.write_str(" async")?;
+            }
+            if let Some(options) = options {
+                me.print_canonical_options(state, options)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn print_task_memory(&mut self, state: &State, async_: bool, memory: u32) -> Result<()> {
+        if async_ {
+            self.result.write_str(" async")?;
+        }
+        self.result.write_str(" ")?;
+        self.start_group("memory ")?;
+        self.print_idx(&state.core.memory_names, memory)?;
+        self.end_group()?;
+        Ok(())
+    }
+
     fn print_instances(&mut self, state: &mut State, parser: InstanceSectionReader) -> Result<()> {
         for instance in parser.into_iter_with_offsets() {
             let (offset, instance) = instance?;
@@ -2711,9 +3050,10 @@ impl Printer<'_, '_> {
 
             // These are parsed during `read_names_and_code` and are part of
             // printing elsewhere, so don't print them.
-            KnownCustom::Name(_) | KnownCustom::ComponentName(_) | KnownCustom::BranchHints(_) => {
-                Ok(())
-            }
+            KnownCustom::Name(_)
+            | KnownCustom::ComponentName(_)
+            | KnownCustom::BranchHints(_)
+            | KnownCustom::CodeMetadata(_) => Ok(()),
 
             // Custom sections without a text format at this time and unknown
             // custom sections get a `@custom` annotation printed.
@@ -2876,6 +3216,36 @@ impl Printer<'_, '_> {
         self.code_section_hints.reverse();
         Ok(())
     }
+
+    fn register_code_metadata_section(
+        &mut self,
+        name: &str,
+        section: CodeMetadataSectionReader<'_>,
+    ) -> Result<()> {
+        for func in section {
+            let func = func?;
+            if self.code_section_metadata.len() >= MAX_WASM_FUNCTIONS as usize {
+                bail!("found too many code metadata annotations");
+            }
+            if func.annotations.count() >= MAX_WASM_FUNCTION_SIZE {
+                bail!("found too many code metadata annotations");
+            }
+            let list = self.code_section_metadata.entry(func.func).or_default();
+            for item in func.annotations.into_iter_with_offsets() {
+                let (offset, item) = item?;
+                list.push(CodeMetadataAnnotation {
+                    offset,
+                    func_offset: item.func_offset,
+                    name: name.to_string(),
+                    data: item.data.to_vec(),
+                });
+            }
+            // Keep annotations sorted by their offset in the function while
+            // preserving the relative order of sections for equal offsets.
+            list.sort_by_key(|m| m.func_offset);
+        }
+        Ok(())
+    }
 }
 
 struct NamedLocalPrinter {
@@ -2925,7 +3295,17 @@ impl NamedLocalPrinter {
             self.in_group = true;
         }
 
-        // Print the optional name if given...
+        // Print the optional name if given. Parameters of function types which
+        // aren't the type of a function don't have an item to define.
+        let item = Item {
+            scope: state.core.local_names.scope,
+            kind: NameLocal::desc(),
+            parent: Some(func),
+            index: local,
+        };
+        if func != u32::MAX {
+            dst.result.start_definition(&item)?;
+        }
         match name {
             Some(name) => {
                 name.write(dst)?;
@@ -2940,6 +3320,9 @@ impl NamedLocalPrinter {
                 self.end_group_after_local = false;
             }
         }
+        if func != u32::MAX {
+            dst.result.end_item()?;
+        }
         Ok(())
     }
 
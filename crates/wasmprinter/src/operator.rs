@@ -1,4 +1,4 @@
-use super::{Print, Printer, State};
+use super::{Item, NameLabel, NamingNamespace, Print, Printer, State};
 use anyhow::{anyhow, bail, Result};
 use wasmparser::{BlockType, BrTable, Catch, MemArg, Ordering, RefType, TryTable, VisitOperator};
 
@@ -83,10 +83,13 @@ impl<'printer, 'state, 'a, 'b> PrintOperator<'printer, 'state, 'a, 'b> {
 
     fn blockty_without_label_comment(&mut self, ty: BlockType) -> Result<bool> {
         let key = (self.state.core.funcs, self.label);
+        let item = self.label_item(self.label);
         let has_name = match self.state.core.label_names.index_to_name.get(&key) {
             Some(name) => {
                 write!(self.printer.result, " ")?;
+                self.printer.result.start_definition(&item)?;
                 name.write(self.printer)?;
+                self.printer.result.end_item()?;
                 true
             }
             None if self.printer.config.name_unnamed => {
@@ -94,7 +97,9 @@ impl<'printer, 'state, 'a, 'b> PrintOperator<'printer, 'state, 'a, 'b> {
                 // already pushed onto our stack when the instruction was
                 // entered so its own label is one less.
                 let depth = self.cur_depth() - 1;
+                self.printer.result.start_definition(&item)?;
                 write!(self.result(), " $#label{depth}")?;
+                self.printer.result.end_item()?;
                 true
             }
             None => false,
@@ -119,16 +124,29 @@ impl<'printer, 'state, 'a, 'b> PrintOperator<'printer, 'state, 'a, 'b> {
     fn maybe_blockty_label_comment(&mut self, has_name: bool) -> Result<()> {
         if !has_name {
             let depth = self.cur_depth();
+            let item = self.label_item(self.label);
             self.push_str(" ")?;
+            self.result().start_definition(&item)?;
             self.result().start_comment()?;
             write!(self.result(), ";; label = @{}", depth)?;
             self.result().reset_color()?;
+            self.result().end_item()?;
         }
 
         self.label += 1;
         Ok(())
     }
 
+    /// Describes the `label`th label of the current function.
+    fn label_item(&self, label: u32) -> Item {
+        Item {
+            scope: self.state.core.label_names.scope,
+            kind: NameLabel::desc(),
+            parent: Some(self.state.core.funcs),
+            index: label,
+        }
+    }
+
     fn cur_depth(&self) -> u32 {
         self.printer.nesting - self.nesting_start
     }
@@ -148,13 +166,13 @@ impl<'printer, 'state, 'a, 'b> PrintOperator<'printer, 'state, 'a, 'b> {
             // names don't account for the function name so offset by one more
             // here.
             Some(i) => {
-                let name = i
+                let label_idx = i
                     .checked_sub(1)
-                    .and_then(|idx| self.label_indices.get(idx as usize).copied())
-                    .and_then(|label_idx| {
-                        let key = (self.state.core.funcs, label_idx);
-                        self.state.core.label_names.index_to_name.get(&key)
-                    });
+                    .and_then(|idx| self.label_indices.get(idx as usize).copied());
+                let name = label_idx.and_then(|label_idx| {
+                    let key = (self.state.core.funcs, label_idx);
+                    self.state.core.label_names.index_to_name.get(&key)
+                });
 
                 // This is a bit tricky, but if there's a shallower label than
                 // this target which shares the same name then we can't print
@@ -173,6 +191,10 @@ impl<'printer, 'state, 'a, 'b> PrintOperator<'printer, 'state, 'a, 'b> {
                         false
                     });
 
+                if let Some(label_idx) = label_idx {
+                    let item = self.label_item(label_idx);
+                    self.printer.result.start_reference(&item)?;
+                }
                 match name {
                     // Only print the name if one is found and there's also no
                     // name conflict.
@@ -210,6 +232,9 @@ impl<'printer, 'state, 'a, 'b> PrintOperator<'printer, 'state, 'a, 'b> {
                         }
                     }
                 }
+                if label_idx.is_some() {
+                    self.result().end_item()?;
+                }
             }
 
             // This branch is out of range. Print the raw integer and then leave
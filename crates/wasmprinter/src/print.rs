@@ -12,6 +12,8 @@ use termcolor::{Color, ColorSpec};
 ///
 /// * For users of [`std::io::Write`] use [`PrintIoWrite`].
 /// * For users of [`std::fmt::Write`] use [`PrintFmtWrite`].
+/// * For colored terminal output use [`PrintTermcolor`].
+/// * For hyperlinked HTML output use [`PrintHtml`].
 pub trait Print {
     /// Writes the given string `s` in its entirety.
     ///
@@ -110,6 +112,48 @@ pub trait Print {
     fn reset_color(&mut self) -> io::Result<()> {
         Ok(())
     }
+
+    /// Indicates that the definition of `item`, its name and index, is about
+    /// to be printed.
+    ///
+    /// This is paired with a call to [`Print::end_item`] once the definition
+    /// has been printed.
+    fn start_definition(&mut self, item: &Item) -> io::Result<()> {
+        let _ = item;
+        Ok(())
+    }
+
+    /// Indicates that a reference to `item`, such as the function of a `call`
+    /// instruction, is about to be printed.
+    ///
+    /// This is paired with a call to [`Print::end_item`] once the reference
+    /// has been printed.
+    fn start_reference(&mut self, item: &Item) -> io::Result<()> {
+        let _ = item;
+        Ok(())
+    }
+
+    /// Indicates that the definition or reference started by the previous
+    /// [`Print::start_definition`] or [`Print::start_reference`] is finished.
+    fn end_item(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An item in an index space of a module or component, passed to
+/// [`Print::start_definition`] and [`Print::start_reference`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// Identifier of the module or component whose index space this item is
+    /// in, unique within one printed binary.
+    pub scope: u32,
+    /// The index space of this item, for example `"func"` or `"type"`.
+    pub kind: &'static str,
+    /// For items nested within another item, the index of that item: the
+    /// function of a local or label, or the struct type of a field.
+    pub parent: Option<u32>,
+    /// The index of this item within its index space.
+    pub index: u32,
 }
 
 /// An adapter between the [`std::io::Write`] trait and [`Print`].
@@ -188,3 +232,222 @@ where
         self.0.reset()
     }
 }
+
+/// A [`Print`] implementation which renders HTML.
+///
+/// The output is a standalone HTML document where references to items such as
+/// functions, globals, types, and labels link to their definitions and each
+/// line is prefixed with its binary offset. Long literals, such as the
+/// contents of data segments, are collapsed by default.
+///
+/// [`PrintHtml::finish`] must be called once printing is done to close the
+/// document.
+pub struct PrintHtml<T> {
+    dst: T,
+    started: bool,
+    pending_line: Option<Option<usize>>,
+    color: bool,
+    literal: Option<String>,
+    items: Vec<&'static str>,
+}
+
+/// Literals longer than this many characters are collapsed.
+const HTML_COLLAPSE_LITERAL: usize = 128;
+
+impl<T> PrintHtml<T>
+where
+    T: io::Write,
+{
+    /// Creates a new printer which writes HTML into `dst`.
+    pub fn new(dst: T) -> PrintHtml<T> {
+        PrintHtml {
+            dst,
+            started: false,
+            pending_line: None,
+            color: false,
+            literal: None,
+            items: Vec::new(),
+        }
+    }
+
+    /// Finishes the HTML document, returning the underlying writer.
+    pub fn finish(mut self) -> io::Result<T> {
+        self.pending_line = None;
+        self.flush()?;
+        while let Some(tag) = self.items.pop() {
+            write!(self.dst, "</{tag}>")?;
+        }
+        self.dst.write_all(b"</pre>\n</body>\n</html>\n")?;
+        Ok(self.dst)
+    }
+
+    /// Writes any deferred output: the document header, a buffered literal,
+    /// and the offset gutter of the current line.
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            self.dst.write_all(HTML_HEADER.as_bytes())?;
+        }
+        if let Some(literal) = self.literal.take() {
+            self.write_literal(&literal)?;
+        }
+        if let Some(offset) = self.pending_line.take() {
+            match offset {
+                Some(offset) => write!(self.dst, "<span class=\"offset\">{offset:#x}</span>")?,
+                None => self.dst.write_all(b"<span class=\"offset\"></span>")?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_literal(&mut self, literal: &str) -> io::Result<()> {
+        let len = literal.chars().count();
+        if len <= HTML_COLLAPSE_LITERAL {
+            return self.write_escaped(literal);
+        }
+        let summary = match literal.char_indices().nth(HTML_COLLAPSE_LITERAL / 2) {
+            Some((i, _)) => &literal[..i],
+            None => literal,
+        };
+        self.dst.write_all(b"<details><summary>")?;
+        self.write_escaped(summary)?;
+        write!(self.dst, "&hellip; ({len} characters)</summary>")?;
+        self.write_escaped(literal)?;
+        self.dst.write_all(b"</details>")
+    }
+
+    fn write_escaped(&mut self, s: &str) -> io::Result<()> {
+        let mut rest = s;
+        while let Some(i) = rest.find(|c| matches!(c, '&' | '<' | '>' | '"')) {
+            self.dst.write_all(rest[..i].as_bytes())?;
+            let escaped = match rest.as_bytes()[i] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                _ => "&quot;",
+            };
+            self.dst.write_all(escaped.as_bytes())?;
+            rest = &rest[i + 1..];
+        }
+        self.dst.write_all(rest.as_bytes())
+    }
+
+    fn start_color(&mut self, class: &str) -> io::Result<()> {
+        self.reset_color()?;
+        self.flush()?;
+        write!(self.dst, "<span class=\"{class}\">")?;
+        self.color = true;
+        Ok(())
+    }
+
+    fn start_item(&mut self, tag: &'static str, attr: &str, item: &Item) -> io::Result<()> {
+        self.reset_color()?;
+        self.flush()?;
+        write!(self.dst, "<{tag} {attr}=\"")?;
+        if attr == "href" {
+            self.dst.write_all(b"#")?;
+        }
+        write!(self.dst, "{}-{}", item.scope, item.kind)?;
+        if let Some(parent) = item.parent {
+            write!(self.dst, "-{parent}")?;
+        }
+        write!(self.dst, "-{}\">", item.index)?;
+        self.items.push(tag);
+        Ok(())
+    }
+}
+
+impl<T> Print for PrintHtml<T>
+where
+    T: io::Write,
+{
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        if let Some(literal) = &mut self.literal {
+            literal.push_str(s);
+            return Ok(());
+        }
+        self.flush()?;
+        self.write_escaped(s)
+    }
+
+    fn newline(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.dst.write_all(b"\n")
+    }
+
+    fn start_line(&mut self, binary_offset: Option<usize>) {
+        self.pending_line = Some(binary_offset);
+    }
+
+    fn start_literal(&mut self) -> io::Result<()> {
+        self.start_color("literal")?;
+        self.literal = Some(String::new());
+        Ok(())
+    }
+
+    fn start_name(&mut self) -> io::Result<()> {
+        self.start_color("name")
+    }
+
+    fn start_keyword(&mut self) -> io::Result<()> {
+        self.start_color("keyword")
+    }
+
+    fn start_type(&mut self) -> io::Result<()> {
+        self.start_color("type")
+    }
+
+    fn start_comment(&mut self) -> io::Result<()> {
+        self.start_color("comment")
+    }
+
+    fn reset_color(&mut self) -> io::Result<()> {
+        if self.color {
+            self.flush()?;
+            self.dst.write_all(b"</span>")?;
+            self.color = false;
+        }
+        Ok(())
+    }
+
+    fn start_definition(&mut self, item: &Item) -> io::Result<()> {
+        self.start_item("span", "id", item)
+    }
+
+    fn start_reference(&mut self, item: &Item) -> io::Result<()> {
+        self.start_item("a", "href", item)
+    }
+
+    fn end_item(&mut self) -> io::Result<()> {
+        self.reset_color()?;
+        self.flush()?;
+        if let Some(tag) = self.items.pop() {
+            write!(self.dst, "</{tag}>")?;
+        }
+        Ok(())
+    }
+}
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+pre { line-height: 1.3; }
+.offset { display: inline-block; width: 8ch; margin-right: 2ch; color: #999; text-align: right; user-select: none; }
+.keyword { color: #a0522d; font-weight: bold; }
+.type { color: #228b22; font-weight: bold; }
+.name { color: #8b008b; }
+.literal { color: #b22222; }
+.comment { color: #008b8b; }
+a { color: inherit; text-decoration: none; }
+a:hover { text-decoration: underline; }
+:target { background-color: #ffff99; }
+details { display: inline; }
+details[open] { white-space: pre-wrap; word-break: break-all; }
+summary { display: inline; cursor: pointer; }
+</style>
+</head>
+<body>
+<pre>
+"#;
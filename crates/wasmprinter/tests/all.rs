@@ -46,3 +46,40 @@ fn offsets_and_lines_smoke_test() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn config_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<wasmprinter::Config>();
+}
+
+#[test]
+fn source_annotations_restart_at_each_function() {
+    let bytes = wat::parse_str(
+        r#"
+            (module
+                (func i32.const 0 drop)
+                (func i32.const 1 drop))
+        "#,
+    )
+    .unwrap();
+
+    let mut config = wasmprinter::Config::new();
+    config.source_annotations(|_| Some("a.c:1".to_string()));
+    let mut wat = String::new();
+    config
+        .print(&bytes, &mut wasmprinter::PrintFmtWrite(&mut wat))
+        .unwrap();
+
+    let lines = wat.lines().map(|l| l.trim()).collect::<Vec<_>>();
+    let funcs = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with("(func"))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(funcs.len(), 2);
+    for i in funcs {
+        assert_eq!(lines[i - 1], ";; a.c:1", "{wat}");
+    }
+}
@@ -260,10 +260,12 @@ impl<'a> Expander<'a> {
             CanonicalFuncKind::Lift { ty, .. } => {
                 self.expand_component_type_use(ty);
             }
-            CanonicalFuncKind::Lower(_)
-            | CanonicalFuncKind::ResourceNew(_)
-            | CanonicalFuncKind::ResourceRep(_)
-            | CanonicalFuncKind::ResourceDrop(_) => {}
+            CanonicalFuncKind::TaskReturn(info) => {
+                if let Some(ty) = &mut info.result {
+                    self.expand_component_val_ty(ty);
+                }
+            }
+            _ => {}
         }
     }
 
@@ -305,6 +307,12 @@ impl<'a> Expander<'a> {
                 name: func.name,
                 kind: CanonicalFuncKind::ResourceRep(mem::take(info)),
             })),
+            CoreFuncKind::Async(kind) => Some(ComponentField::CanonicalFunc(CanonicalFunc {
+                span: func.span,
+                id: func.id,
+                name: func.name,
+                kind: mem::replace(kind, CanonicalFuncKind::TaskBackpressure),
+            })),
         }
     }
 
@@ -567,7 +575,19 @@ impl<'a> Expander<'a> {
                     self.expand_component_val_ty(ty);
                 }
             }
-            ComponentDefinedType::Own(_) | ComponentDefinedType::Borrow(_) => {}
+            ComponentDefinedType::Stream(s) => {
+                if let Some(ty) = &mut s.element {
+                    self.expand_component_val_ty(ty);
+                }
+            }
+            ComponentDefinedType::Future(f) => {
+                if let Some(ty) = &mut f.element {
+                    self.expand_component_val_ty(ty);
+                }
+            }
+            ComponentDefinedType::Own(_)
+            | ComponentDefinedType::Borrow(_)
+            | ComponentDefinedType::ErrorContext => {}
         }
     }
 
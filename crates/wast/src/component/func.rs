@@ -51,6 +51,11 @@ pub enum CoreFuncKind<'a> {
     ResourceNew(CanonResourceNew<'a>),
     ResourceDrop(CanonResourceDrop<'a>),
     ResourceRep(CanonResourceRep<'a>),
+    /// The core function is one of the component model async intrinsics,
+    /// such as `task.return` or `stream.read`.
+    ///
+    /// The core function is actually a member of the canon section.
+    Async(CanonicalFuncKind<'a>),
 }
 
 impl<'a> Parse<'a> for CoreFuncKind<'a> {
@@ -73,6 +78,8 @@ impl<'a> Parse<'a> for CoreFuncKind<'a> {
                 Ok(CoreFuncKind::ResourceDrop(parser.parse()?))
             } else if l.peek::<kw::resource_rep>()? {
                 Ok(CoreFuncKind::ResourceRep(parser.parse()?))
+            } else if let Some(kind) = CanonicalFuncKind::parse_async_intrinsic(parser)? {
+                Ok(CoreFuncKind::Async(kind))
             } else {
                 Err(l.error())
             }
@@ -211,6 +218,8 @@ impl<'a> Parse<'a> for CanonicalFunc<'a> {
             Self::parse_core_func(span, parser, CanonicalFuncKind::ResourceDrop)
         } else if parser.peek::<kw::resource_rep>()? {
             Self::parse_core_func(span, parser, CanonicalFuncKind::ResourceRep)
+        } else if let Some(kind) = CanonicalFuncKind::parse_async_intrinsic(parser)? {
+            Self::finish_core_func(span, parser, kind)
         } else {
             Err(parser.error("expected `canon lift` or `canon lower`"))
         }
@@ -227,6 +236,14 @@ impl<'a> CanonicalFunc<'a> {
         T: Parse<'a>,
     {
         let info = parser.parse()?;
+        Self::finish_core_func(span, parser, variant(info))
+    }
+
+    fn finish_core_func(
+        span: Span,
+        parser: Parser<'a>,
+        kind: CanonicalFuncKind<'a>,
+    ) -> Result<Self> {
         let (id, name) = parser.parens(|parser| {
             parser.parse::<kw::core>()?;
             parser.parse::<kw::func>()?;
@@ -239,7 +256,7 @@ impl<'a> CanonicalFunc<'a> {
             span,
             id,
             name,
-            kind: variant(info),
+            kind,
         })
     }
 }
@@ -261,6 +278,73 @@ pub enum CanonicalFuncKind<'a> {
     ResourceNew(CanonResourceNew<'a>),
     ResourceDrop(CanonResourceDrop<'a>),
     ResourceRep(CanonResourceRep<'a>),
+
+    TaskBackpressure,
+    TaskReturn(CanonTaskReturn<'a>),
+    TaskWait(CanonTaskWait<'a>),
+    TaskPoll(CanonTaskWait<'a>),
+    TaskYield(CanonAsync),
+    SubtaskDrop,
+    StreamNew(CanonType<'a>),
+    StreamRead(CanonTypeOpts<'a>),
+    StreamWrite(CanonTypeOpts<'a>),
+    StreamCancelRead(CanonTypeAsync<'a>),
+    StreamCancelWrite(CanonTypeAsync<'a>),
+    StreamCloseReadable(CanonType<'a>),
+    StreamCloseWritable(CanonType<'a>),
+    FutureNew(CanonType<'a>),
+    FutureRead(CanonTypeOpts<'a>),
+    FutureWrite(CanonTypeOpts<'a>),
+    FutureCancelRead(CanonTypeAsync<'a>),
+    FutureCancelWrite(CanonTypeAsync<'a>),
+    FutureCloseReadable(CanonType<'a>),
+    FutureCloseWritable(CanonType<'a>),
+    ErrorContextNew(CanonOpts<'a>),
+    ErrorContextDebugMessage(CanonOpts<'a>),
+    ErrorContextDrop,
+}
+
+impl<'a> CanonicalFuncKind<'a> {
+    /// Parses one of the component model async intrinsics, including its
+    /// leading keyword, returning `None` if the next token isn't one.
+    fn parse_async_intrinsic(parser: Parser<'a>) -> Result<Option<Self>> {
+        macro_rules! intrinsics {
+            ($($kw:ident => $variant:expr,)*) => {
+                $(
+                    if parser.peek::<kw::$kw>()? {
+                        parser.parse::<kw::$kw>()?;
+                        return Ok(Some($variant));
+                    }
+                )*
+            };
+        }
+        intrinsics! {
+            task_backpressure => Self::TaskBackpressure,
+            task_return => Self::TaskReturn(parser.parse()?),
+            task_wait => Self::TaskWait(parser.parse()?),
+            task_poll => Self::TaskPoll(parser.parse()?),
+            task_yield => Self::TaskYield(parser.parse()?),
+            subtask_drop => Self::SubtaskDrop,
+            stream_new => Self::StreamNew(parser.parse()?),
+            stream_read => Self::StreamRead(parser.parse()?),
+            stream_write => Self::StreamWrite(parser.parse()?),
+            stream_cancel_read => Self::StreamCancelRead(parser.parse()?),
+            stream_cancel_write => Self::StreamCancelWrite(parser.parse()?),
+            stream_close_readable => Self::StreamCloseReadable(parser.parse()?),
+            stream_close_writable => Self::StreamCloseWritable(parser.parse()?),
+            future_new => Self::FutureNew(parser.parse()?),
+            future_read => Self::FutureRead(parser.parse()?),
+            future_write => Self::FutureWrite(parser.parse()?),
+            future_cancel_read => Self::FutureCancelRead(parser.parse()?),
+            future_cancel_write => Self::FutureCancelWrite(parser.parse()?),
+            future_close_readable => Self::FutureCloseReadable(parser.parse()?),
+            future_close_writable => Self::FutureCloseWritable(parser.parse()?),
+            error_context_new => Self::ErrorContextNew(parser.parse()?),
+            error_context_debug_message => Self::ErrorContextDebugMessage(parser.parse()?),
+            error_context_drop => Self::ErrorContextDrop,
+        }
+        Ok(None)
+    }
 }
 
 /// Information relating to lifting a core function.
@@ -409,6 +493,138 @@ impl Default for CanonResourceRep<'_> {
     }
 }
 
+/// Information relating to the `task.return` intrinsic.
+#[derive(Debug)]
+pub struct CanonTaskReturn<'a> {
+    /// The type of the result of the task, if any.
+    pub result: Option<ComponentValType<'a>>,
+    /// The canonical options used to lift the result.
+    pub opts: Vec<CanonOpt<'a>>,
+}
+
+impl<'a> Parse<'a> for CanonTaskReturn<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let result = if parser.peek2::<kw::result>()? {
+            Some(parser.parens(|parser| {
+                parser.parse::<kw::result>()?;
+                parser.parse()
+            })?)
+        } else {
+            None
+        };
+        Ok(Self {
+            result,
+            opts: parser.parse()?,
+        })
+    }
+}
+
+/// Information relating to the `task.wait` and `task.poll` intrinsics.
+#[derive(Debug)]
+pub struct CanonTaskWait<'a> {
+    /// Whether other tasks may run while this one waits.
+    pub async_: bool,
+    /// The memory the event payload is written to.
+    pub memory: CoreItemRef<'a, kw::memory>,
+}
+
+impl<'a> Parse<'a> for CanonTaskWait<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let async_ = parser.parse::<Option<kw::r#async>>()?.is_some();
+        let memory = parser.parens(|parser| {
+            let span = parser.parse::<kw::memory>()?.0;
+            parse_trailing_item_ref(kw::memory(span), parser)
+        })?;
+        Ok(Self { async_, memory })
+    }
+}
+
+/// Information relating to intrinsics which only take an `async` flag, such
+/// as `task.yield`.
+#[derive(Debug)]
+pub struct CanonAsync {
+    /// Whether other tasks may run during this intrinsic.
+    pub async_: bool,
+}
+
+impl<'a> Parse<'a> for CanonAsync {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(Self {
+            async_: parser.parse::<Option<kw::r#async>>()?.is_some(),
+        })
+    }
+}
+
+/// Information relating to `stream` and `future` intrinsics which only
+/// reference the type they operate on, such as `stream.new`.
+#[derive(Debug)]
+pub struct CanonType<'a> {
+    /// The `stream` or `future` type this intrinsic operates on.
+    pub ty: Index<'a>,
+}
+
+impl<'a> Parse<'a> for CanonType<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(Self {
+            ty: parser.parse()?,
+        })
+    }
+}
+
+/// Information relating to `stream` and `future` intrinsics which transfer
+/// values through linear memory, such as `stream.read`.
+#[derive(Debug)]
+pub struct CanonTypeOpts<'a> {
+    /// The `stream` or `future` type this intrinsic operates on.
+    pub ty: Index<'a>,
+    /// The canonical options used to transfer values.
+    pub opts: Vec<CanonOpt<'a>>,
+}
+
+impl<'a> Parse<'a> for CanonTypeOpts<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(Self {
+            ty: parser.parse()?,
+            opts: parser.parse()?,
+        })
+    }
+}
+
+/// Information relating to the `stream` and `future` cancellation
+/// intrinsics, such as `stream.cancel-read`.
+#[derive(Debug)]
+pub struct CanonTypeAsync<'a> {
+    /// The `stream` or `future` type this intrinsic operates on.
+    pub ty: Index<'a>,
+    /// Whether the cancellation may complete asynchronously.
+    pub async_: bool,
+}
+
+impl<'a> Parse<'a> for CanonTypeAsync<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(Self {
+            ty: parser.parse()?,
+            async_: parser.parse::<Option<kw::r#async>>()?.is_some(),
+        })
+    }
+}
+
+/// Information relating to intrinsics which only take canonical options,
+/// such as `error-context.new`.
+#[derive(Debug)]
+pub struct CanonOpts<'a> {
+    /// The canonical options for the intrinsic.
+    pub opts: Vec<CanonOpt<'a>>,
+}
+
+impl<'a> Parse<'a> for CanonOpts<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(Self {
+            opts: parser.parse()?,
+        })
+    }
+}
+
 #[derive(Debug)]
 /// Canonical ABI options.
 pub enum CanonOpt<'a> {
@@ -424,6 +640,11 @@ pub enum CanonOpt<'a> {
     Realloc(CoreItemRef<'a, kw::func>),
     /// Call the specified function after the lifted function has returned.
     PostReturn(CoreItemRef<'a, kw::func>),
+    /// Use the asynchronous ABI.
+    Async,
+    /// Call the specified function with events for an asynchronously lifted
+    /// function.
+    Callback(CoreItemRef<'a, kw::func>),
 }
 
 impl<'a> Parse<'a> for CanonOpt<'a> {
@@ -438,6 +659,9 @@ impl<'a> Parse<'a> for CanonOpt<'a> {
         } else if l.peek::<kw::string_latin1_utf16>()? {
             parser.parse::<kw::string_latin1_utf16>()?;
             Ok(Self::StringLatin1Utf16)
+        } else if l.peek::<kw::r#async>()? {
+            parser.parse::<kw::r#async>()?;
+            Ok(Self::Async)
         } else if l.peek::<LParen>()? {
             parser.parens(|parser| {
                 let mut l = parser.lookahead1();
@@ -457,6 +681,11 @@ impl<'a> Parse<'a> for CanonOpt<'a> {
                     Ok(CanonOpt::PostReturn(
                         parser.parse::<IndexOrCoreRef<'_, _>>()?.0,
                     ))
+                } else if l.peek::<kw::callback>()? {
+                    parser.parse::<kw::callback>()?;
+                    Ok(CanonOpt::Callback(
+                        parser.parse::<IndexOrCoreRef<'_, _>>()?.0,
+                    ))
                 } else {
                     Err(l.error())
                 }
@@ -370,13 +370,54 @@ impl<'a> Resolver<'a> {
             CanonicalFuncKind::ResourceDrop(info) => {
                 return self.resolve_ns(&mut info.ty, Ns::Type)
             }
+            CanonicalFuncKind::TaskReturn(info) => {
+                if let Some(ty) = &mut info.result {
+                    self.component_val_type(ty)?;
+                }
+                &mut info.opts
+            }
+            CanonicalFuncKind::TaskWait(info) | CanonicalFuncKind::TaskPoll(info) => {
+                return self.core_item_ref(&mut info.memory)
+            }
+            CanonicalFuncKind::StreamNew(info)
+            | CanonicalFuncKind::StreamCloseReadable(info)
+            | CanonicalFuncKind::StreamCloseWritable(info)
+            | CanonicalFuncKind::FutureNew(info)
+            | CanonicalFuncKind::FutureCloseReadable(info)
+            | CanonicalFuncKind::FutureCloseWritable(info) => {
+                return self.resolve_ns(&mut info.ty, Ns::Type)
+            }
+            CanonicalFuncKind::StreamCancelRead(info)
+            | CanonicalFuncKind::StreamCancelWrite(info)
+            | CanonicalFuncKind::FutureCancelRead(info)
+            | CanonicalFuncKind::FutureCancelWrite(info) => {
+                return self.resolve_ns(&mut info.ty, Ns::Type)
+            }
+            CanonicalFuncKind::StreamRead(info)
+            | CanonicalFuncKind::StreamWrite(info)
+            | CanonicalFuncKind::FutureRead(info)
+            | CanonicalFuncKind::FutureWrite(info) => {
+                self.resolve_ns(&mut info.ty, Ns::Type)?;
+                &mut info.opts
+            }
+            CanonicalFuncKind::ErrorContextNew(info)
+            | CanonicalFuncKind::ErrorContextDebugMessage(info) => &mut info.opts,
+            CanonicalFuncKind::TaskBackpressure
+            | CanonicalFuncKind::TaskYield(_)
+            | CanonicalFuncKind::SubtaskDrop
+            | CanonicalFuncKind::ErrorContextDrop => return Ok(()),
         };
 
         for opt in opts {
             match opt {
-                CanonOpt::StringUtf8 | CanonOpt::StringUtf16 | CanonOpt::StringLatin1Utf16 => {}
+                CanonOpt::StringUtf8
+                | CanonOpt::StringUtf16
+                | CanonOpt::StringLatin1Utf16
+                | CanonOpt::Async => {}
                 CanonOpt::Memory(r) => self.core_item_ref(r)?,
-                CanonOpt::Realloc(r) | CanonOpt::PostReturn(r) => self.core_item_ref(r)?,
+                CanonOpt::Realloc(r) | CanonOpt::PostReturn(r) | CanonOpt::Callback(r) => {
+                    self.core_item_ref(r)?
+                }
             }
         }
 
@@ -461,6 +502,17 @@ impl<'a> Resolver<'a> {
             ComponentDefinedType::Own(t) | ComponentDefinedType::Borrow(t) => {
                 self.resolve_ns(t, Ns::Type)?;
             }
+            ComponentDefinedType::Stream(s) => {
+                if let Some(ty) = &mut s.element {
+                    self.component_val_type(ty)?;
+                }
+            }
+            ComponentDefinedType::Future(f) => {
+                if let Some(ty) = &mut f.element {
+                    self.component_val_type(ty)?;
+                }
+            }
+            ComponentDefinedType::ErrorContext => {}
         }
         Ok(())
     }
@@ -798,12 +850,7 @@ impl<'a> ComponentState<'a> {
             ComponentField::Type(t) => self.types.register(t.id, "type")?,
             ComponentField::CanonicalFunc(f) => match &f.kind {
                 CanonicalFuncKind::Lift { .. } => self.funcs.register(f.id, "func")?,
-                CanonicalFuncKind::Lower(_)
-                | CanonicalFuncKind::ResourceNew(_)
-                | CanonicalFuncKind::ResourceRep(_)
-                | CanonicalFuncKind::ResourceDrop(_) => {
-                    self.core_funcs.register(f.id, "core func")?
-                }
+                _ => self.core_funcs.register(f.id, "core func")?,
             },
             ComponentField::CoreFunc(_) | ComponentField::Func(_) => {
                 unreachable!("should be expanded already")
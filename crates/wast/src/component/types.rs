@@ -209,10 +209,8 @@ impl<'a> Parse<'a> for TypeDef<'a> {
                 }
             })
         } else {
-            // Only primitive types have no parens
-            Ok(Self::Defined(ComponentDefinedType::Primitive(
-                parser.parse()?,
-            )))
+            // Only primitive types and `error-context` have no parens
+            Ok(Self::Defined(ComponentDefinedType::parse_bare(parser)?))
         }
     }
 }
@@ -364,7 +362,7 @@ impl<'a> Parse<'a> for InlineComponentValType<'a> {
                 )?))
             })
         } else {
-            Ok(Self(ComponentDefinedType::Primitive(parser.parse()?)))
+            Ok(Self(ComponentDefinedType::parse_bare(parser)?))
         }
     }
 }
@@ -384,9 +382,21 @@ pub enum ComponentDefinedType<'a> {
     Result(ResultType<'a>),
     Own(Index<'a>),
     Borrow(Index<'a>),
+    Stream(Stream<'a>),
+    Future(Future<'a>),
+    ErrorContext,
 }
 
 impl<'a> ComponentDefinedType<'a> {
+    fn parse_bare(parser: Parser<'a>) -> Result<Self> {
+        if parser.peek::<kw::error_context>()? {
+            parser.parse::<kw::error_context>()?;
+            Ok(Self::ErrorContext)
+        } else {
+            Ok(Self::Primitive(parser.parse()?))
+        }
+    }
+
     fn parse_non_primitive(parser: Parser<'a>, mut l: Lookahead1<'a>) -> Result<Self> {
         parser.depth_check()?;
         if l.peek::<kw::record>()? {
@@ -411,6 +421,10 @@ impl<'a> ComponentDefinedType<'a> {
         } else if l.peek::<kw::borrow>()? {
             parser.parse::<kw::borrow>()?;
             Ok(Self::Borrow(parser.parse()?))
+        } else if l.peek::<kw::stream>()? {
+            Ok(Self::Stream(parser.parse()?))
+        } else if l.peek::<kw::future>()? {
+            Ok(Self::Future(parser.parse()?))
         } else {
             Err(l.error())
         }
@@ -425,7 +439,7 @@ impl Default for ComponentDefinedType<'_> {
 
 impl Peek for ComponentDefinedType<'_> {
     fn peek(cursor: crate::parser::Cursor<'_>) -> Result<bool> {
-        if PrimitiveValType::peek(cursor)? {
+        if PrimitiveValType::peek(cursor)? || kw::error_context::peek(cursor)? {
             return Ok(true);
         }
 
@@ -442,6 +456,8 @@ impl Peek for ComponentDefinedType<'_> {
                     | Some(("result", _))
                     | Some(("own", _))
                     | Some(("borrow", _))
+                    | Some(("stream", _))
+                    | Some(("future", _))
             ),
             None => false,
         })
@@ -650,6 +666,38 @@ impl<'a> Parse<'a> for OptionType<'a> {
     }
 }
 
+/// A stream type.
+#[derive(Debug)]
+pub struct Stream<'a> {
+    /// The type of the elements of the stream, if any.
+    pub element: Option<Box<ComponentValType<'a>>>,
+}
+
+impl<'a> Parse<'a> for Stream<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parse::<kw::stream>()?;
+        Ok(Self {
+            element: parser.parse::<Option<ComponentValType>>()?.map(Box::new),
+        })
+    }
+}
+
+/// A future type.
+#[derive(Debug)]
+pub struct Future<'a> {
+    /// The type of the value the future resolves to, if any.
+    pub element: Option<Box<ComponentValType<'a>>>,
+}
+
+impl<'a> Parse<'a> for Future<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parse::<kw::future>()?;
+        Ok(Self {
+            element: parser.parse::<Option<ComponentValType>>()?.map(Box::new),
+        })
+    }
+}
+
 /// A result type.
 #[derive(Debug)]
 pub struct ResultType<'a> {
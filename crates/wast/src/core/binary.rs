@@ -123,18 +123,20 @@ impl Encoder<'_> {
     }
 
     /// Encodes the code section of a wasm module module while additionally
-    /// handling the branch hinting proposal.
+    /// handling the branch hinting and code metadata proposals.
     ///
-    /// The branch hinting proposal requires to encode the offsets of the
-    /// instructions relative from the beginning of the function. Here we encode
-    /// each instruction and we save its offset. If needed, we use this
-    /// information to build the branch hint section and insert it before the
-    /// code section.
+    /// These proposals require to encode the offsets of the instructions
+    /// relative from the beginning of the function. Here we encode each
+    /// instruction and we save its offset. If needed, we use this information
+    /// to build the branch hint section, and one section per other
+    /// `metadata.code.*` annotation name, and insert them before the code
+    /// section.
     fn code_section<'a>(&'a mut self, list: &[&'a Func<'_>], imports: &[&Import<'_>]) {
         self.custom_sections(CustomPlace::Before(CustomPlaceAnchor::Code));
 
         if !list.is_empty() {
             let mut branch_hints = Vec::new();
+            let mut code_metadata: Vec<(&str, Vec<FunctionCodeMetadata<'_>>)> = Vec::new();
             let mut code_section = Vec::new();
 
             list.len().encode(&mut code_section);
@@ -143,10 +145,26 @@ impl Encoder<'_> {
                 .filter(|i| matches!(i.item.kind, ItemKind::Func(..)))
                 .count() as u32;
             for func in list.iter() {
-                let hints = func.encode(&mut code_section);
+                let (hints, metadata) = func.encode(&mut code_section);
                 if !hints.is_empty() {
                     branch_hints.push(FunctionBranchHints { func_index, hints });
                 }
+                for (name, entry) in metadata {
+                    let funcs = match code_metadata.iter_mut().find(|(n, _)| *n == name) {
+                        Some((_, funcs)) => funcs,
+                        None => {
+                            code_metadata.push((name, Vec::new()));
+                            &mut code_metadata.last_mut().unwrap().1
+                        }
+                    };
+                    match funcs.last_mut() {
+                        Some(f) if f.func_index == func_index => f.entries.push(entry),
+                        _ => funcs.push(FunctionCodeMetadata {
+                            func_index,
+                            entries: vec![entry],
+                        }),
+                    }
+                }
                 func_index += 1;
             }
 
@@ -156,6 +174,12 @@ impl Encoder<'_> {
                 self.section(0, &("metadata.code.branch_hint", branch_hints));
             }
 
+            // Similarly all other code metadata sections go before the Code
+            // section, in order of their first use.
+            for (name, funcs) in code_metadata {
+                self.section(0, &(name, funcs));
+            }
+
             // Finally, insert the Code section from the tmp buffer
             self.wasm.push(10);
             code_section.encode(&mut self.wasm);
@@ -653,9 +677,12 @@ impl Encode for Data<'_> {
 }
 
 impl Func<'_> {
-    /// Encodes the function into `e` while returning all branch hints with
-    /// known relative offsets after encoding.
-    fn encode(&self, e: &mut Vec<u8>) -> Vec<BranchHint> {
+    /// Encodes the function into `e` while returning all branch hints and
+    /// code metadata with known relative offsets after encoding.
+    fn encode<'a>(
+        &'a self,
+        e: &mut Vec<u8>,
+    ) -> (Vec<BranchHint>, Vec<(&'a str, CodeMetadataEntry<'a>)>) {
         assert!(self.exports.names.is_empty());
         let (expr, locals) = match &self.kind {
             FuncKind::Inline { expression, locals } => (expression, locals),
@@ -667,10 +694,10 @@ impl Func<'_> {
         // encodes its length first then the body.
         let mut tmp = Vec::new();
         locals.encode(&mut tmp);
-        let branch_hints = expr.encode(&mut tmp, 0);
+        let hints = expr.encode(&mut tmp, 0);
         tmp.encode(e);
 
-        branch_hints
+        hints
     }
 }
 
@@ -692,23 +719,39 @@ impl Encode for Box<[Local<'_>]> {
 
 // Encode the expression and store the offset from the beginning
 // for each instruction.
-impl Expression<'_> {
-    fn encode(&self, e: &mut Vec<u8>, relative_start: usize) -> Vec<BranchHint> {
+impl<'a> Expression<'a> {
+    fn encode(
+        &self,
+        e: &mut Vec<u8>,
+        relative_start: usize,
+    ) -> (Vec<BranchHint>, Vec<(&'a str, CodeMetadataEntry<'_>)>) {
         let mut hints = Vec::with_capacity(self.branch_hints.len());
         let mut next_hint = self.branch_hints.iter().peekable();
+        let mut metadata = Vec::with_capacity(self.code_metadata.len());
+        let mut next_metadata = self.code_metadata.iter().peekable();
 
         for (i, instr) in self.instrs.iter().enumerate() {
+            let func_offset = u32::try_from(e.len() - relative_start).unwrap();
             if let Some(hint) = next_hint.next_if(|h| h.instr_index == i) {
                 hints.push(BranchHint {
-                    branch_func_offset: u32::try_from(e.len() - relative_start).unwrap(),
+                    branch_func_offset: func_offset,
                     branch_hint_value: hint.value,
                 });
             }
+            while let Some(m) = next_metadata.next_if(|m| m.instr_index == i) {
+                metadata.push((
+                    m.name,
+                    CodeMetadataEntry {
+                        func_offset,
+                        data: &m.data,
+                    },
+                ));
+            }
             instr.encode(e);
         }
         e.push(0x0b);
 
-        hints
+        (hints, metadata)
     }
 }
 
@@ -1243,6 +1286,33 @@ impl Encode for BranchHint {
     }
 }
 
+struct FunctionCodeMetadata<'a> {
+    func_index: u32,
+    entries: Vec<CodeMetadataEntry<'a>>,
+}
+
+struct CodeMetadataEntry<'a> {
+    func_offset: u32,
+    data: &'a [&'a [u8]],
+}
+
+impl Encode for FunctionCodeMetadata<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.func_index.encode(e);
+        self.entries.encode(e);
+    }
+}
+
+impl Encode for CodeMetadataEntry<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.func_offset.encode(e);
+        self.data.iter().map(|l| l.len()).sum::<usize>().encode(e);
+        for val in self.data.iter() {
+            e.extend_from_slice(val);
+        }
+    }
+}
+
 impl Encode for Tag<'_> {
     fn encode(&self, e: &mut Vec<u8>) {
         self.ty.encode(e);
@@ -2,7 +2,7 @@ use crate::annotation;
 use crate::core::*;
 use crate::encode::Encode;
 use crate::kw;
-use crate::parser::{Cursor, Parse, Parser, Result};
+use crate::parser::{Cursor, Parse, Parser, Peek, Result};
 use crate::token::*;
 use std::mem;
 
@@ -16,6 +16,7 @@ use std::mem;
 pub struct Expression<'a> {
     pub instrs: Box<[Instruction<'a>]>,
     pub branch_hints: Vec<BranchHint>,
+    pub code_metadata: Vec<CodeMetadata<'a>>,
 }
 
 /// A `@metadata.code.branch_hint` in the code, associated with a If or BrIf
@@ -31,6 +32,23 @@ pub struct BranchHint {
     pub value: u32,
 }
 
+/// A `@metadata.code.*` annotation in the code, other than a branch hint,
+/// which attaches an opaque payload to the instruction that follows it.
+///
+/// Each distinct annotation name is encoded into its own custom section of the
+/// same name, recording the offset of the annotated instruction relative to the
+/// start of the function body.
+#[derive(Debug)]
+pub struct CodeMetadata<'a> {
+    /// Index of instructions in `instrs` field of `Expression` that this
+    /// annotation applies to.
+    pub instr_index: usize,
+    /// The full name of this annotation, such as `metadata.code.foo`.
+    pub name: &'a str,
+    /// The payload of this annotation, as a list of strings to concatenate.
+    pub data: Vec<&'a [u8]>,
+}
+
 impl<'a> Parse<'a> for Expression<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let mut exprs = ExpressionParser::default();
@@ -38,6 +56,7 @@ impl<'a> Parse<'a> for Expression<'a> {
         Ok(Expression {
             instrs: exprs.instrs.into(),
             branch_hints: exprs.branch_hints,
+            code_metadata: exprs.code_metadata,
         })
     }
 }
@@ -64,6 +83,7 @@ impl<'a> Expression<'a> {
         Ok(Expression {
             instrs: exprs.instrs.into(),
             branch_hints: exprs.branch_hints,
+            code_metadata: exprs.code_metadata,
         })
     }
 }
@@ -88,6 +108,10 @@ struct ExpressionParser<'a> {
     /// Will be used later to collect the offsets in the final binary.
     /// <(index of branch instructions, BranchHintAnnotation)>
     branch_hints: Vec<BranchHint>,
+
+    /// Related to the code metadata proposal, all `@metadata.code.*`
+    /// annotations other than branch hints.
+    code_metadata: Vec<CodeMetadata<'a>>,
 }
 
 enum Paren {
@@ -114,6 +138,9 @@ enum Level<'a> {
 
     /// This means we are finishing the parsing of a branch hint annotation.
     BranchHint,
+
+    /// This means we are finishing the parsing of a code metadata annotation.
+    CodeMetadata,
 }
 
 /// Possible states of "what is currently being parsed?" in an `if` expression.
@@ -178,6 +205,13 @@ impl<'a> ExpressionParser<'a> {
                         continue;
                     }
 
+                    // Handle the case of any other code metadata annotation
+                    if parser.peek::<CodeMetadataAnnotation>()? {
+                        self.parse_code_metadata(parser)?;
+                        self.stack.push(Level::CodeMetadata);
+                        continue;
+                    }
+
                     match parser.parse()? {
                         // If block/loop show up then we just need to be sure to
                         // push an `end` instruction whenever the `)` token is
@@ -210,6 +244,7 @@ impl<'a> ExpressionParser<'a> {
                     Level::EndWith(i) => self.instrs.push(i),
                     Level::IfArm => {}
                     Level::BranchHint => {}
+                    Level::CodeMetadata => {}
 
                     // If an `if` statement hasn't parsed the clause or `then`
                     // block, then that's an error because there weren't enough
@@ -337,6 +372,39 @@ impl<'a> ExpressionParser<'a> {
         });
         Ok(())
     }
+
+    fn parse_code_metadata(&mut self, parser: Parser<'a>) -> Result<()> {
+        let name = parser.step(|c| match c.annotation()? {
+            Some((name, rest)) => Ok((name, rest)),
+            None => Err(c.error("expected a code metadata annotation")),
+        })?;
+        let mut data = Vec::new();
+        while !parser.is_empty() {
+            data.push(parser.parse()?);
+        }
+        self.code_metadata.push(CodeMetadata {
+            instr_index: self.instrs.len(),
+            name,
+            data,
+        });
+        Ok(())
+    }
+}
+
+/// Helper to peek for any `@metadata.code.*` annotation.
+struct CodeMetadataAnnotation;
+
+impl Peek for CodeMetadataAnnotation {
+    fn peek(cursor: Cursor<'_>) -> Result<bool> {
+        Ok(match cursor.annotation()? {
+            Some((name, _rest)) => name.starts_with("metadata.code."),
+            None => false,
+        })
+    }
+
+    fn display() -> &'static str {
+        "`@metadata.code.*`"
+    }
 }
 
 // TODO: document this obscenity
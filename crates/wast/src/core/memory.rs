@@ -169,6 +169,7 @@ impl<'a> Parse<'a> for Data<'a> {
                         return Ok(Expression {
                             instrs: [insn].into(),
                             branch_hints: Vec::new(),
+                            code_metadata: Vec::new(),
                         });
                     }
 
@@ -189,6 +190,7 @@ impl<'a> Parse<'a> for Data<'a> {
                     Ok(Expression {
                         instrs: instrs.into(),
                         branch_hints: Vec::new(),
+                        code_metadata: Vec::new(),
                     })
                 }
             })?;
@@ -115,6 +115,7 @@ impl<'a> Parse<'a> for Module<'a> {
         let _r = parser.register_annotation("name");
         let _r = parser.register_annotation("dylink.0");
         let _r = parser.register_annotation("metadata.code.branch_hint");
+        let _r = parser.register_annotation("metadata.code.*");
 
         let span = parser.parse::<kw::module>()?.0;
         let id = parser.parse()?;
@@ -78,6 +78,7 @@ pub fn run(fields: &mut Vec<ModuleField>) {
                                         Instruction::I32Const(0)
                                     }]),
                                     branch_hints: Vec::new(),
+                                    code_metadata: Vec::new(),
                                 },
                             },
                             data,
@@ -147,6 +148,7 @@ pub fn run(fields: &mut Vec<ModuleField>) {
                                         Instruction::I32Const(0)
                                     }]),
                                     branch_hints: Vec::new(),
+                                    code_metadata: Vec::new(),
                                 },
                             },
                             payload,
@@ -267,6 +267,7 @@ impl<'a> ElemPayload<'a> {
                     let expr = Expression {
                         instrs: [Instruction::RefFunc(func)].into(),
                         branch_hints: Vec::new(),
+                        code_metadata: Vec::new(),
                     };
                     exprs.push(expr);
                 }
@@ -427,12 +427,9 @@ impl ParseBuffer<'_> {
                 TokenKind::LParen => {
                     if let Some(annotation) = self.lexer.annotation(pos)? {
                         let text = annotation.annotation(self.lexer.input())?;
-                        match self.known_annotations.borrow().get(&text[..]) {
-                            Some(0) | None => {
-                                self.skip_annotation(&mut pos)?;
-                                continue;
-                            }
-                            Some(_) => {}
+                        if !self.is_known_annotation(&text) {
+                            self.skip_annotation(&mut pos)?;
+                            continue;
                         }
                     }
                     break token;
@@ -443,6 +440,25 @@ impl ParseBuffer<'_> {
         Ok(Some(token))
     }
 
+    /// Returns whether `text` names an annotation which is currently
+    /// registered, either exactly or through a wildcard registration such as
+    /// `metadata.code.*`.
+    fn is_known_annotation(&self, text: &str) -> bool {
+        let annotations = self.known_annotations.borrow();
+        if let Some(n) = annotations.get(text) {
+            if *n > 0 {
+                return true;
+            }
+        }
+        annotations.iter().any(|(name, n)| {
+            *n > 0
+                && match name.strip_suffix('*') {
+                    Some(prefix) => text.starts_with(prefix),
+                    None => false,
+                }
+        })
+    }
+
     fn skip_annotation(&self, pos: &mut usize) -> Result<()> {
         let mut depth = 1;
         let span = Span { offset: *pos };
@@ -836,6 +852,10 @@ impl<'a> Parser<'a> {
     /// with the name `annotation` will be parse of the token stream and not
     /// implicitly skipped.
     ///
+    /// Annotation names ending in `*` are treated as a wildcard, so for
+    /// example registering `metadata.code.*` enables parsing of all
+    /// annotations whose name starts with `metadata.code.`.
+    ///
     /// # Skipping annotations
     ///
     /// The behavior of skipping unknown/unregistered annotations can be
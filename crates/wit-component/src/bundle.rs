@@ -0,0 +1,411 @@
+use anyhow::{bail, Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
+use wasm_encoder::{Component, RawSection};
+use wasmparser::{
+    Chunk, ComponentAlias, ComponentExternalKind, ComponentInstance, ComponentOuterAliasKind,
+    ComponentTypeRef, Encoding, ExternalKind, Instance, Parser, Payload,
+};
+
+/// A description of a component which has been split into its parts by
+/// [`unbundle`], and which can be reassembled with [`bundle`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BundleManifest {
+    /// The sections of the component, in order.
+    pub sections: Vec<BundleSection>,
+    /// The core instances created by the component, describing which module
+    /// each instantiates and with which arguments.
+    ///
+    /// This is informational only and is not used by [`bundle`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub core_instances: Vec<BundleInstance>,
+    /// The component instances created by the component, describing which
+    /// nested component each instantiates and with which arguments.
+    ///
+    /// This is informational only and is not used by [`bundle`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<BundleInstance>,
+}
+
+/// A single section of a component within a [`BundleManifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum BundleSection {
+    /// A core module section whose contents live in `file`.
+    CoreModule {
+        /// The file name of the core module.
+        file: String,
+    },
+    /// A nested component section whose contents live in `file`.
+    Component {
+        /// The file name of the nested component.
+        file: String,
+    },
+    /// Any other section, stored inline.
+    Raw {
+        /// The section id.
+        id: u8,
+        /// The hex-encoded contents of the section.
+        data: String,
+    },
+}
+
+/// An instance created by a component, as listed in a [`BundleManifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BundleInstance {
+    /// The index of this instance in its index space.
+    pub index: u32,
+    /// The file of the module or component that is instantiated, or a
+    /// description of its index if it was not defined by the component
+    /// itself. This is `None` for instances created from a bag of exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instantiate: Option<String>,
+    /// The arguments supplied to the instantiation, as `name: kind index`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// The items of an instance created from a bag of exports, as
+    /// `name: kind index`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exports: Vec<String>,
+}
+
+/// A component split into its parts by [`unbundle`].
+#[derive(Debug, Clone)]
+pub struct Unbundled {
+    /// The core modules and nested components extracted from the component,
+    /// in the order they appear.
+    pub items: Vec<UnbundledItem>,
+    /// The manifest describing how to reassemble the component.
+    pub manifest: BundleManifest,
+}
+
+/// A core module or nested component extracted by [`unbundle`].
+#[derive(Debug, Clone)]
+pub struct UnbundledItem {
+    /// The file name this item is referred to by in the manifest.
+    pub file: String,
+    /// The binary contents of the item.
+    pub bytes: Vec<u8>,
+}
+
+/// Splits the component `bytes` into its core modules, its nested components
+/// and a [`BundleManifest`] describing the rest of the component.
+///
+/// Each extracted item is given a file name derived from the name recorded in
+/// its name section, if any. Modules synthesized by this crate are named after
+/// their role, for example `shim.wasm` or `adapter-wasi_snapshot_preview1.wasm`,
+/// and an unnamed first core module is named `main.wasm`.
+///
+/// Passing the result to [`bundle`] produces a byte-for-byte copy of `bytes`.
+pub fn unbundle(bytes: &[u8]) -> Result<Unbundled> {
+    let mut state = UnbundleState::default();
+    let mut parser = Parser::new(0);
+    let mut offset = 0;
+    loop {
+        let (payload, consumed) = match parser.parse(&bytes[offset..], true)? {
+            Chunk::NeedMoreData(_) => unreachable!(),
+            Chunk::Parsed { payload, consumed } => (payload, consumed),
+        };
+        offset += consumed;
+        match payload {
+            Payload::Version { encoding, .. } => {
+                if encoding != Encoding::Component {
+                    bail!("input is not a component");
+                }
+            }
+            Payload::ModuleSection {
+                unchecked_range, ..
+            } => {
+                let file = state.push_item(&bytes[unchecked_range.clone()], true);
+                state.modules.push(Some(file.clone()));
+                state.sections.push(BundleSection::CoreModule { file });
+                offset = unchecked_range.end;
+            }
+            Payload::ComponentSection {
+                unchecked_range, ..
+            } => {
+                let file = state.push_item(&bytes[unchecked_range.clone()], false);
+                state.components.push(Some(file.clone()));
+                state.sections.push(BundleSection::Component { file });
+                offset = unchecked_range.end;
+            }
+            Payload::End(_) => break,
+            payload => {
+                state.track(&payload)?;
+                if let Some((id, range)) = payload.as_section() {
+                    state.sections.push(BundleSection::Raw {
+                        id,
+                        data: to_hex(&bytes[range]),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(Unbundled {
+        items: state.items,
+        manifest: BundleManifest {
+            sections: state.sections,
+            core_instances: state.core_instances,
+            instances: state.instances,
+        },
+    })
+}
+
+/// Reassembles a component from a `manifest` produced by [`unbundle`].
+///
+/// The contents of each core module and nested component referenced by the
+/// manifest are loaded with `read`, which is given the file name recorded in
+/// the manifest.
+pub fn bundle(
+    manifest: &BundleManifest,
+    mut read: impl FnMut(&str) -> Result<Vec<u8>>,
+) -> Result<Vec<u8>> {
+    let mut component = Component::new();
+    for section in manifest.sections.iter() {
+        let (id, data) = match section {
+            BundleSection::CoreModule { file } => (
+                wasm_encoder::ComponentSectionId::CoreModule as u8,
+                read(file).with_context(|| format!("failed to read core module `{file}`"))?,
+            ),
+            BundleSection::Component { file } => (
+                wasm_encoder::ComponentSectionId::Component as u8,
+                read(file).with_context(|| format!("failed to read component `{file}`"))?,
+            ),
+            BundleSection::Raw { id, data } => (
+                *id,
+                from_hex(data).with_context(|| format!("invalid data for section {id}"))?,
+            ),
+        };
+        component.section(&RawSection { id, data: &data });
+    }
+    Ok(component.finish())
+}
+
+#[derive(Default)]
+struct UnbundleState {
+    items: Vec<UnbundledItem>,
+    files: HashSet<String>,
+    sections: Vec<BundleSection>,
+    core_instances: Vec<BundleInstance>,
+    instances: Vec<BundleInstance>,
+
+    /// The core module index space, with the file of each module defined in
+    /// this component.
+    modules: Vec<Option<String>>,
+    /// The component index space, with the file of each nested component
+    /// defined in this component.
+    components: Vec<Option<String>>,
+    /// The number of component instances in the index space so far.
+    instance_count: u32,
+}
+
+impl UnbundleState {
+    fn push_item(&mut self, bytes: &[u8], module: bool) -> String {
+        let index = if module {
+            self.modules.len()
+        } else {
+            self.components.len()
+        };
+        let name = wasm_metadata::Metadata::from_binary(bytes)
+            .ok()
+            .and_then(|metadata| match metadata {
+                wasm_metadata::Metadata::Module { name, .. }
+                | wasm_metadata::Metadata::Component { name, .. } => name,
+            })
+            .map(|name| sanitize(name.strip_prefix("wit-component:").unwrap_or(&name)))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(
+                || match (module, self.modules.iter().all(|m| m.is_none())) {
+                    (true, true) => "main".to_string(),
+                    (true, false) => format!("module{index}"),
+                    (false, _) => format!("component{index}"),
+                },
+            );
+        // Suffix the name with the item's index upon a collision, and then
+        // with successive numbers if that's taken as well.
+        let mut file = format!("{name}.wasm");
+        let mut suffix = index;
+        while !self.files.insert(file.clone()) {
+            file = format!("{name}-{suffix}.wasm");
+            suffix += 1;
+        }
+        self.items.push(UnbundledItem {
+            file: file.clone(),
+            bytes: bytes.to_vec(),
+        });
+        file
+    }
+
+    /// Updates the index spaces tracked for the manifest's instance listing.
+    fn track(&mut self, payload: &Payload<'_>) -> Result<()> {
+        match payload {
+            Payload::InstanceSection(s) => {
+                for instance in s.clone() {
+                    let index = u32::try_from(self.core_instances.len()).unwrap();
+                    self.core_instances.push(match instance? {
+                        Instance::Instantiate { module_index, args } => BundleInstance {
+                            index,
+                            instantiate: Some(describe(&self.modules, "module", module_index)),
+                            args: args
+                                .iter()
+                                .map(|arg| format!("{}: instance {}", arg.name, arg.index))
+                                .collect(),
+                            exports: Vec::new(),
+                        },
+                        Instance::FromExports(exports) => BundleInstance {
+                            index,
+                            instantiate: None,
+                            args: Vec::new(),
+                            exports: exports
+                                .iter()
+                                .map(|e| format!("{}: {} {}", e.name, core_kind(e.kind), e.index))
+                                .collect(),
+                        },
+                    });
+                }
+            }
+            Payload::ComponentInstanceSection(s) => {
+                for instance in s.clone() {
+                    let index = self.instance_count;
+                    self.instance_count += 1;
+                    self.instances.push(match instance? {
+                        ComponentInstance::Instantiate {
+                            component_index,
+                            args,
+                        } => BundleInstance {
+                            index,
+                            instantiate: Some(describe(
+                                &self.components,
+                                "component",
+                                component_index,
+                            )),
+                            args: args
+                                .iter()
+                                .map(|a| format!("{}: {} {}", a.name, a.kind.desc(), a.index))
+                                .collect(),
+                            exports: Vec::new(),
+                        },
+                        ComponentInstance::FromExports(exports) => BundleInstance {
+                            index,
+                            instantiate: None,
+                            args: Vec::new(),
+                            exports: exports
+                                .iter()
+                                .map(|e| format!("{}: {} {}", e.name.0, e.kind.desc(), e.index))
+                                .collect(),
+                        },
+                    });
+                }
+            }
+            Payload::ComponentImportSection(s) => {
+                for import in s.clone() {
+                    match import?.ty {
+                        ComponentTypeRef::Module(_) => self.modules.push(None),
+                        ComponentTypeRef::Component(_) => self.components.push(None),
+                        ComponentTypeRef::Instance(_) => self.instance_count += 1,
+                        _ => {}
+                    }
+                }
+            }
+            Payload::ComponentExportSection(s) => {
+                for export in s.clone() {
+                    let export = export?;
+                    match export.kind {
+                        ComponentExternalKind::Module => {
+                            let file = self.modules.get(export.index as usize).cloned();
+                            self.modules.push(file.flatten());
+                        }
+                        ComponentExternalKind::Component => {
+                            let file = self.components.get(export.index as usize).cloned();
+                            self.components.push(file.flatten());
+                        }
+                        ComponentExternalKind::Instance => self.instance_count += 1,
+                        _ => {}
+                    }
+                }
+            }
+            Payload::ComponentAliasSection(s) => {
+                for alias in s.clone() {
+                    match alias? {
+                        ComponentAlias::InstanceExport {
+                            kind: ComponentExternalKind::Module,
+                            ..
+                        }
+                        | ComponentAlias::Outer {
+                            kind: ComponentOuterAliasKind::CoreModule,
+                            ..
+                        } => self.modules.push(None),
+                        ComponentAlias::InstanceExport {
+                            kind: ComponentExternalKind::Component,
+                            ..
+                        }
+                        | ComponentAlias::Outer {
+                            kind: ComponentOuterAliasKind::Component,
+                            ..
+                        } => self.components.push(None),
+                        ComponentAlias::InstanceExport {
+                            kind: ComponentExternalKind::Instance,
+                            ..
+                        } => self.instance_count += 1,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Describes the item at `index` in `space`, preferring the file it was
+/// extracted to.
+fn describe(space: &[Option<String>], kind: &str, index: u32) -> String {
+    match space.get(index as usize) {
+        Some(Some(file)) => file.clone(),
+        _ => format!("{kind} {index}"),
+    }
+}
+
+fn core_kind(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func => "func",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
+
+/// Replaces characters which aren't suitable for a file name with `-`.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut ret = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        ret.push_str(&format!("{byte:02x}"));
+    }
+    ret
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        bail!("hex string has an odd length or non-ascii characters");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16)
+                .with_context(|| format!("invalid hex byte `{}`", &s[i..i + 2]))
+        })
+        .collect()
+}
@@ -0,0 +1,985 @@
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use wit_parser::*;
+
+/// The format of the pages produced by [`DocsGenerator`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DocsFormat {
+    /// CommonMark-flavored Markdown, using inline HTML for anchors.
+    #[default]
+    Markdown,
+    /// Standalone HTML pages.
+    Html,
+}
+
+impl DocsFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+        }
+    }
+}
+
+/// A single page of documentation produced by [`DocsGenerator`].
+#[derive(Debug, Clone)]
+pub struct DocsPage {
+    /// The path of this page relative to the root of the documentation, using
+    /// `/` as a separator.
+    pub path: String,
+    /// The contents of this page.
+    pub contents: String,
+}
+
+/// A generator of browsable reference documentation for WIT packages.
+///
+/// Documentation is generated for every package in a [`Resolve`], including
+/// dependencies, so references to types defined in other packages can be
+/// linked. The generated pages are:
+///
+/// * `index.*` - a list of all packages.
+/// * `{namespace}/{name}[@{version}]/index.*` - one page per package listing
+///   its interfaces and worlds.
+/// * `{namespace}/{name}[@{version}]/interface.{name}.*` - one page per
+///   interface.
+/// * `{namespace}/{name}[@{version}]/world.{name}.*` - one page per world,
+///   including any interfaces defined inline within the world.
+///
+/// Each page renders doc comments, `@since`, `@unstable` and `@deprecated`
+/// stability attributes, and the canonical ABI size and alignment of types for
+/// 32-bit memories.
+#[derive(Default)]
+pub struct DocsGenerator {
+    format: DocsFormat,
+}
+
+impl DocsGenerator {
+    /// Creates a new generator which produces pages in `format`.
+    pub fn new(format: DocsFormat) -> DocsGenerator {
+        DocsGenerator { format }
+    }
+
+    /// Generates documentation for all packages in `resolve`.
+    pub fn generate(&self, resolve: &Resolve) -> Vec<DocsPage> {
+        let mut sizes = SizeAlign::default();
+        sizes.fill(resolve);
+
+        let mut anonymous = HashMap::new();
+        for (id, world) in resolve.worlds.iter() {
+            for (prefix, items) in [("import", &world.imports), ("export", &world.exports)] {
+                for (key, item) in items {
+                    if let (WorldKey::Name(name), WorldItem::Interface { id: iface, .. }) =
+                        (key, item)
+                    {
+                        anonymous.insert(*iface, (id, format!("{prefix}-{name}")));
+                    }
+                }
+            }
+        }
+
+        let mut generator = Generator {
+            resolve,
+            format: self.format,
+            sizes,
+            anonymous,
+            page: Page::Index,
+            out: String::new(),
+            pages: Vec::new(),
+        };
+        generator.index();
+        for (id, pkg) in resolve.packages.iter() {
+            generator.package(id);
+            for (_, iface) in pkg.interfaces.iter() {
+                generator.interface_page(*iface);
+            }
+            for (_, world) in pkg.worlds.iter() {
+                generator.world(*world);
+            }
+        }
+        generator.pages
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Page {
+    Index,
+    Package(PackageId),
+    Interface(InterfaceId),
+    World(WorldId),
+}
+
+struct Generator<'a> {
+    resolve: &'a Resolve,
+    format: DocsFormat,
+    sizes: SizeAlign,
+    /// Interfaces defined inline within a world, mapped to the world they're
+    /// documented in and the prefix of their anchors.
+    anonymous: HashMap<InterfaceId, (WorldId, String)>,
+    page: Page,
+    out: String,
+    pages: Vec<DocsPage>,
+}
+
+impl Generator<'_> {
+    fn index(&mut self) {
+        self.start(Page::Index, "WIT packages");
+        self.heading(1, None, "WIT packages");
+        let items = self
+            .resolve
+            .packages
+            .iter()
+            .map(|(id, pkg)| {
+                let link = self.link(&self.code(&pkg.name.to_string()), Page::Package(id), None);
+                self.with_summary(link, &pkg.docs)
+            })
+            .collect();
+        self.list(items);
+        self.finish();
+    }
+
+    fn package(&mut self, id: PackageId) {
+        let pkg = &self.resolve.packages[id];
+        let title = format!("Package {}", pkg.name);
+        self.start(Page::Package(id), &title);
+        let heading = format!("Package {}", self.code(&pkg.name.to_string()));
+        self.heading(1, None, &heading);
+        self.docs(&pkg.docs);
+
+        for (title, items) in [
+            (
+                "Interfaces",
+                pkg.interfaces
+                    .values()
+                    .map(|i| {
+                        let iface = &self.resolve.interfaces[*i];
+                        let name = self.code(iface.name.as_deref().unwrap_or(""));
+                        let link = self.link(&name, Page::Interface(*i), None);
+                        (link, &iface.stability, &iface.docs)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "Worlds",
+                pkg.worlds
+                    .values()
+                    .map(|w| {
+                        let world = &self.resolve.worlds[*w];
+                        let link = self.link(&self.code(&world.name), Page::World(*w), None);
+                        (link, &world.stability, &world.docs)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            self.heading(2, None, title);
+            let items = items
+                .into_iter()
+                .map(|(link, stability, docs)| {
+                    let item = match self.badge(stability) {
+                        Some(badge) => format!("{link} {badge}"),
+                        None => link,
+                    };
+                    self.with_summary(item, docs)
+                })
+                .collect();
+            self.list(items);
+        }
+        self.finish();
+    }
+
+    fn interface_page(&mut self, id: InterfaceId) {
+        let iface = &self.resolve.interfaces[id];
+        let name = self.resolve.id_of(id).unwrap();
+        self.start(Page::Interface(id), &format!("Interface {name}"));
+        let heading = format!("Interface {}", self.code(&name));
+        self.heading(1, None, &heading);
+        self.package_link(iface.package.unwrap(), &iface.stability);
+        self.docs(&iface.docs);
+        self.interface_contents(id, "", 2);
+        self.finish();
+    }
+
+    fn world(&mut self, id: WorldId) {
+        let world = &self.resolve.worlds[id];
+        let pkg = world.package.unwrap();
+        let name = self.resolve.id_of_name(pkg, &world.name);
+        self.start(Page::World(id), &format!("World {name}"));
+        let heading = format!("World {}", self.code(&name));
+        self.heading(1, None, &heading);
+        self.package_link(pkg, &world.stability);
+        self.docs(&world.docs);
+
+        let types = world
+            .imports
+            .values()
+            .chain(world.exports.values())
+            .filter_map(|item| match item {
+                WorldItem::Type(id) => Some(*id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        self.uses(&types, 2);
+
+        for (prefix, title, items) in [
+            ("import", "Imports", &world.imports),
+            ("export", "Exports", &world.exports),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            self.heading(2, None, title);
+            let items = items
+                .iter()
+                .map(|(key, item)| {
+                    let name = self.resolve.name_world_key(key);
+                    let (desc, link, stability) = match item {
+                        WorldItem::Interface {
+                            id: iface,
+                            stability,
+                        } => {
+                            let link = match key {
+                                WorldKey::Name(n) => {
+                                    let anchor = format!("{prefix}-{n}");
+                                    self.link(&self.code(&name), Page::World(id), Some(&anchor))
+                                }
+                                WorldKey::Interface(_) => {
+                                    self.link(&self.code(&name), Page::Interface(*iface), None)
+                                }
+                            };
+                            ("interface", link, stability)
+                        }
+                        WorldItem::Function(f) => {
+                            let anchor = format!("{prefix}-{}", func_anchor(self.resolve, f));
+                            let link = self.link(&self.code(&name), self.page, Some(&anchor));
+                            ("function", link, &f.stability)
+                        }
+                        WorldItem::Type(t) => {
+                            let link = self.type_link(*t, &self.code(&name));
+                            ("type", link, &self.resolve.types[*t].stability)
+                        }
+                    };
+                    match self.badge(stability) {
+                        Some(badge) => format!("{desc} {link} {badge}"),
+                        None => format!("{desc} {link}"),
+                    }
+                })
+                .collect();
+            self.list(items);
+        }
+
+        let defined = types
+            .iter()
+            .copied()
+            .filter(|t| self.use_source(*t).is_none())
+            .collect::<Vec<_>>();
+        if !defined.is_empty() {
+            self.heading(2, None, "Types");
+            for ty in defined {
+                self.type_def(ty, "", 3);
+            }
+        }
+
+        let funcs = [("import", &world.imports), ("export", &world.exports)]
+            .into_iter()
+            .flat_map(|(prefix, items)| {
+                items.values().filter_map(move |item| match item {
+                    WorldItem::Function(f) => Some((prefix, f)),
+                    _ => None,
+                })
+            })
+            .collect::<Vec<_>>();
+        if !funcs.is_empty() {
+            self.heading(2, None, "Functions");
+            for (prefix, func) in funcs {
+                self.function(func, &format!("{prefix}-"), 3);
+            }
+        }
+
+        for (prefix, items) in [("import", &world.imports), ("export", &world.exports)] {
+            for (key, item) in items {
+                let (WorldKey::Name(name), WorldItem::Interface { id, stability }) = (key, item)
+                else {
+                    continue;
+                };
+                let anchor = format!("{prefix}-{name}");
+                let heading = format!("{prefix} interface {}", self.code(name));
+                self.heading(2, Some(&anchor), &heading);
+                if let Some(badge) = self.badge(stability) {
+                    self.para(&badge);
+                }
+                self.docs(&self.resolve.interfaces[*id].docs);
+                self.interface_contents(*id, &format!("{anchor}-"), 3);
+            }
+        }
+
+        self.finish();
+    }
+
+    /// Renders the types and functions of the interface `id` with headings at
+    /// `level` and anchors prefixed with `prefix`.
+    fn interface_contents(&mut self, id: InterfaceId, prefix: &str, level: usize) {
+        let iface = &self.resolve.interfaces[id];
+        let types = iface.types.values().copied().collect::<Vec<_>>();
+        self.uses(&types, level);
+
+        let defined = types
+            .iter()
+            .copied()
+            .filter(|t| self.use_source(*t).is_none())
+            .collect::<Vec<_>>();
+        if !defined.is_empty() {
+            self.heading(level, None, "Types");
+            for ty in defined {
+                self.type_def(ty, prefix, level + 1);
+            }
+        }
+
+        let funcs = iface
+            .functions
+            .values()
+            .filter(|f| matches!(f.kind, FunctionKind::Freestanding))
+            .collect::<Vec<_>>();
+        if !funcs.is_empty() {
+            self.heading(level, None, "Functions");
+            for func in funcs {
+                self.function(func, prefix, level + 1);
+            }
+        }
+    }
+
+    /// Renders the list of types brought into scope with `use` from other
+    /// interfaces, grouped by the interface they're defined in.
+    fn uses(&mut self, types: &[TypeId], level: usize) {
+        let mut uses = IndexMap::<InterfaceId, Vec<String>>::new();
+        for ty in types {
+            let Some(source) = self.use_source(*ty) else {
+                continue;
+            };
+            let TypeOwner::Interface(iface) = self.resolve.types[source].owner else {
+                continue;
+            };
+            let name = self.resolve.types[*ty].name.as_deref().unwrap();
+            let link = self.type_link(source, &self.code(name));
+            uses.entry(iface).or_default().push(link);
+        }
+        if uses.is_empty() {
+            return;
+        }
+        self.heading(level, None, "Uses");
+        let items = uses
+            .into_iter()
+            .map(|(iface, types)| {
+                let link = self.interface_link(iface);
+                format!("{link}: {}", types.join(", "))
+            })
+            .collect();
+        self.list(items);
+    }
+
+    /// Returns the type that `id` was imported from with `use`, if any.
+    fn use_source(&self, id: TypeId) -> Option<TypeId> {
+        let ty = &self.resolve.types[id];
+        match ty.kind {
+            TypeDefKind::Type(Type::Id(other)) if ty.name.is_some() => {
+                let other_def = &self.resolve.types[other];
+                if other_def.name.is_some() && other_def.owner != ty.owner {
+                    Some(other)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn type_def(&mut self, id: TypeId, prefix: &str, level: usize) {
+        let ty = &self.resolve.types[id];
+        let name = ty.name.as_deref().unwrap();
+        let anchor = format!("{prefix}type-{name}");
+        let kind = match &ty.kind {
+            TypeDefKind::Record(_)
+            | TypeDefKind::Variant(_)
+            | TypeDefKind::Enum(_)
+            | TypeDefKind::Flags(_)
+            | TypeDefKind::Resource => ty.kind.as_str(),
+            _ => "type",
+        };
+        let heading = format!("{kind} {}", self.code(name));
+        self.heading(level, Some(&anchor), &heading);
+        if let Some(badge) = self.badge(&ty.stability) {
+            self.para(&badge);
+        }
+        self.docs(&ty.docs);
+
+        if !matches!(ty.kind, TypeDefKind::Resource) {
+            let size = self.sizes.size(&Type::Id(id));
+            let align = self.sizes.align(&Type::Id(id));
+            self.para(&self.text(&format!(
+                "Canonical ABI (32-bit memory): size {size}, alignment {align}"
+            )));
+        }
+
+        match &ty.kind {
+            TypeDefKind::Record(r) => {
+                let offsets = self.sizes.field_offsets(r.fields.iter().map(|f| &f.ty));
+                let rows = r
+                    .fields
+                    .iter()
+                    .zip(offsets)
+                    .map(|(field, (offset, _))| {
+                        vec![
+                            self.code(&field.name),
+                            self.ty(field.ty),
+                            offset.to_string(),
+                            self.summary(&field.docs),
+                        ]
+                    })
+                    .collect();
+                self.table(&["Field", "Type", "Offset", "Description"], rows);
+            }
+            TypeDefKind::Variant(v) => {
+                let offset = self
+                    .sizes
+                    .payload_offset(v.tag(), v.cases.iter().map(|c| c.ty.as_ref()));
+                self.para(&self.text(&format!("Payload offset: {offset}")));
+                let rows = v
+                    .cases
+                    .iter()
+                    .map(|case| {
+                        vec![
+                            self.code(&case.name),
+                            case.ty.map(|t| self.ty(t)).unwrap_or_default(),
+                            self.summary(&case.docs),
+                        ]
+                    })
+                    .collect();
+                self.table(&["Case", "Payload", "Description"], rows);
+            }
+            TypeDefKind::Enum(e) => {
+                let rows = e
+                    .cases
+                    .iter()
+                    .map(|case| vec![self.code(&case.name), self.summary(&case.docs)])
+                    .collect();
+                self.table(&["Case", "Description"], rows);
+            }
+            TypeDefKind::Flags(f) => {
+                let rows = f
+                    .flags
+                    .iter()
+                    .map(|flag| vec![self.code(&flag.name), self.summary(&flag.docs)])
+                    .collect();
+                self.table(&["Flag", "Description"], rows);
+            }
+            TypeDefKind::Resource => {
+                let funcs = match ty.owner {
+                    TypeOwner::Interface(i) => self.resolve.interfaces[i]
+                        .functions
+                        .values()
+                        .filter(|f| match f.kind {
+                            FunctionKind::Method(r)
+                            | FunctionKind::Static(r)
+                            | FunctionKind::Constructor(r) => r == id,
+                            FunctionKind::Freestanding => false,
+                        })
+                        .collect::<Vec<_>>(),
+                    _ => Vec::new(),
+                };
+                for func in funcs {
+                    self.function(func, prefix, level + 1);
+                }
+            }
+            TypeDefKind::Unknown => {}
+            _ => {
+                let def = format!("{} {}", self.text("Definition:"), self.def(id));
+                self.para(&def);
+            }
+        }
+    }
+
+    fn function(&mut self, func: &Function, prefix: &str, level: usize) {
+        let anchor = format!("{prefix}{}", func_anchor(self.resolve, func));
+        let heading = match func.kind {
+            FunctionKind::Freestanding => format!("func {}", self.code(&func.name)),
+            FunctionKind::Method(_) => format!("method {}", self.code(func.item_name())),
+            FunctionKind::Static(_) => format!("static {}", self.code(func.item_name())),
+            FunctionKind::Constructor(_) => "constructor".to_string(),
+        };
+        self.heading(level, Some(&anchor), &heading);
+        if let Some(badge) = self.badge(&func.stability) {
+            self.para(&badge);
+        }
+
+        let mut sig = String::new();
+        sig.push_str(&self.text(match func.kind {
+            FunctionKind::Constructor(_) => "constructor(",
+            _ => "func(",
+        }));
+        let params = match func.kind {
+            FunctionKind::Method(_) => &func.params[1..],
+            _ => &func.params[..],
+        };
+        let params = params
+            .iter()
+            .map(|(name, ty)| format!("{}{}", self.text(&format!("{name}: ")), self.ty(*ty)))
+            .collect::<Vec<_>>();
+        sig.push_str(&params.join(&self.text(", ")));
+        sig.push_str(&self.text(")"));
+        if !matches!(func.kind, FunctionKind::Constructor(_)) {
+            match &func.results {
+                Results::Anon(ty) => {
+                    sig.push_str(&self.text(" -> "));
+                    sig.push_str(&self.ty(*ty));
+                }
+                Results::Named(results) if results.is_empty() => {}
+                Results::Named(results) => {
+                    let results = results
+                        .iter()
+                        .map(|(name, ty)| {
+                            format!("{}{}", self.text(&format!("{name}: ")), self.ty(*ty))
+                        })
+                        .collect::<Vec<_>>();
+                    sig.push_str(&self.text(" -> ("));
+                    sig.push_str(&results.join(&self.text(", ")));
+                    sig.push_str(&self.text(")"));
+                }
+            }
+        }
+        match self.format {
+            DocsFormat::Markdown => self.para(&sig),
+            DocsFormat::Html => self.para(&format!("<code>{sig}</code>")),
+        }
+        self.docs(&func.docs);
+    }
+
+    /// Renders a reference to `ty`, linking to named types.
+    fn ty(&self, ty: Type) -> String {
+        let name = match ty {
+            Type::Bool => "bool",
+            Type::U8 => "u8",
+            Type::U16 => "u16",
+            Type::U32 => "u32",
+            Type::U64 => "u64",
+            Type::S8 => "s8",
+            Type::S16 => "s16",
+            Type::S32 => "s32",
+            Type::S64 => "s64",
+            Type::F32 => "f32",
+            Type::F64 => "f64",
+            Type::Char => "char",
+            Type::String => "string",
+            Type::ErrorContext => "error-context",
+            Type::Id(id) => {
+                return match &self.resolve.types[id].name {
+                    Some(name) => self.type_link(id, &self.text(name)),
+                    None => self.def(id),
+                };
+            }
+        };
+        self.text(name)
+    }
+
+    /// Renders the structure of the type `id`, ignoring its name.
+    fn def(&self, id: TypeId) -> String {
+        let opt = |ty: Option<Type>| match ty {
+            Some(ty) => self.ty(ty),
+            None => self.text("_"),
+        };
+        let generic = |name: &str, args: &[String]| {
+            format!(
+                "{}{}{}",
+                self.text(&format!("{name}<")),
+                args.join(&self.text(", ")),
+                self.text(">")
+            )
+        };
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(ty) => self.ty(*ty),
+            TypeDefKind::List(ty) => generic("list", &[self.ty(*ty)]),
+            TypeDefKind::Option(ty) => generic("option", &[self.ty(*ty)]),
+            TypeDefKind::Tuple(t) => {
+                let types = t.types.iter().map(|t| self.ty(*t)).collect::<Vec<_>>();
+                generic("tuple", &types)
+            }
+            TypeDefKind::Result(r) => match (r.ok, r.err) {
+                (None, None) => self.text("result"),
+                (Some(ok), None) => generic("result", &[self.ty(ok)]),
+                (ok, err) => generic("result", &[opt(ok), opt(err)]),
+            },
+            TypeDefKind::Handle(Handle::Own(r)) => self.ty(Type::Id(*r)),
+            TypeDefKind::Handle(Handle::Borrow(r)) => generic("borrow", &[self.ty(Type::Id(*r))]),
+            TypeDefKind::Future(None) => self.text("future"),
+            TypeDefKind::Future(Some(ty)) => generic("future", &[self.ty(*ty)]),
+            TypeDefKind::Stream(s) => match (s.element, s.end) {
+                (None, None) => self.text("stream"),
+                (Some(element), None) => generic("stream", &[self.ty(element)]),
+                (element, end) => generic("stream", &[opt(element), opt(end)]),
+            },
+            kind => self.text(kind.as_str()),
+        }
+    }
+
+    /// Returns the page and anchor documenting the named type `id`.
+    fn type_location(&self, id: TypeId) -> (Page, String) {
+        let ty = &self.resolve.types[id];
+        let name = ty.name.as_deref().unwrap_or("");
+        match ty.owner {
+            TypeOwner::Interface(i) => match self.anonymous.get(&i) {
+                Some((world, prefix)) => (Page::World(*world), format!("{prefix}-type-{name}")),
+                None => (Page::Interface(i), format!("type-{name}")),
+            },
+            TypeOwner::World(w) => (Page::World(w), format!("type-{name}")),
+            TypeOwner::None => (self.page, format!("type-{name}")),
+        }
+    }
+
+    fn type_link(&self, mut id: TypeId, contents: &str) -> String {
+        // Types brought into scope with `use` are documented where they're
+        // originally defined.
+        while let Some(source) = self.use_source(id) {
+            id = source;
+        }
+        let (page, anchor) = self.type_location(id);
+        self.link(contents, page, Some(&anchor))
+    }
+
+    fn interface_link(&self, id: InterfaceId) -> String {
+        match self.anonymous.get(&id) {
+            Some((world, anchor)) => {
+                let name = self.code(&self.resolve.worlds[*world].name);
+                self.link(&name, Page::World(*world), Some(anchor))
+            }
+            None => {
+                let name = self.code(&self.resolve.id_of(id).unwrap_or_default());
+                self.link(&name, Page::Interface(id), None)
+            }
+        }
+    }
+
+    fn package_link(&mut self, pkg: PackageId, stability: &Stability) {
+        let name = self.resolve.packages[pkg].name.to_string();
+        let mut line = format!(
+            "{} {}",
+            self.text("Package:"),
+            self.link(&self.code(&name), Page::Package(pkg), None)
+        );
+        if let Some(badge) = self.badge(stability) {
+            line.push(' ');
+            line.push_str(&badge);
+        }
+        self.para(&line);
+    }
+
+    fn path(&self, page: Page) -> String {
+        let ext = self.format.extension();
+        let pkg_dir = |pkg: PackageId| {
+            let name = &self.resolve.packages[pkg].name;
+            match &name.version {
+                Some(version) => format!("{}/{}@{version}", name.namespace, name.name),
+                None => format!("{}/{}", name.namespace, name.name),
+            }
+        };
+        match page {
+            Page::Index => format!("index.{ext}"),
+            Page::Package(pkg) => format!("{}/index.{ext}", pkg_dir(pkg)),
+            Page::Interface(id) => {
+                if let Some((world, _)) = self.anonymous.get(&id) {
+                    return self.path(Page::World(*world));
+                }
+                let iface = &self.resolve.interfaces[id];
+                format!(
+                    "{}/interface.{}.{ext}",
+                    pkg_dir(iface.package.unwrap()),
+                    iface.name.as_deref().unwrap()
+                )
+            }
+            Page::World(id) => {
+                let world = &self.resolve.worlds[id];
+                format!(
+                    "{}/world.{}.{ext}",
+                    pkg_dir(world.package.unwrap()),
+                    world.name
+                )
+            }
+        }
+    }
+
+    fn link(&self, contents: &str, page: Page, anchor: Option<&str>) -> String {
+        let mut href = String::new();
+        let target = self.path(page);
+        if target != self.path(self.page) {
+            // All pages other than the index are two directories deep.
+            if self.page != Page::Index {
+                href.push_str("../../");
+            }
+            href.push_str(&target);
+        }
+        if let Some(anchor) = anchor {
+            href.push('#');
+            href.push_str(anchor);
+        }
+        match self.format {
+            DocsFormat::Markdown => format!("[{contents}]({href})"),
+            DocsFormat::Html => format!("<a href=\"{}\">{contents}</a>", escape_html(&href)),
+        }
+    }
+
+    fn badge(&self, stability: &Stability) -> Option<String> {
+        let (mut class, mut text) = match stability {
+            Stability::Unknown => return None,
+            Stability::Stable {
+                since,
+                feature: None,
+                ..
+            } => ("stable", format!("@since(version = {since})")),
+            Stability::Stable {
+                since,
+                feature: Some(feature),
+                ..
+            } => (
+                "stable",
+                format!("@since(version = {since}, feature = {feature})"),
+            ),
+            Stability::Unstable { feature, .. } => {
+                ("unstable", format!("@unstable(feature = {feature})"))
+            }
+        };
+        if let Some(version) = stability.deprecated() {
+            class = "deprecated";
+            text.push_str(&format!(" @deprecated(version = {version})"));
+        }
+        Some(match self.format {
+            DocsFormat::Markdown => format!("`{text}`"),
+            DocsFormat::Html => format!(
+                "<span class=\"badge {class}\">{}</span>",
+                escape_html(&text)
+            ),
+        })
+    }
+
+    /// Escapes `s` as inline text.
+    fn text(&self, s: &str) -> String {
+        match self.format {
+            DocsFormat::Markdown => {
+                let mut ret = String::new();
+                for c in s.chars() {
+                    if "\\`*_[]<#|".contains(c) {
+                        ret.push('\\');
+                    }
+                    ret.push(c);
+                }
+                ret
+            }
+            DocsFormat::Html => escape_html(s),
+        }
+    }
+
+    fn code(&self, s: &str) -> String {
+        match self.format {
+            DocsFormat::Markdown => format!("`{s}`"),
+            DocsFormat::Html => format!("<code>{}</code>", escape_html(s)),
+        }
+    }
+
+    /// Returns the first paragraph of `docs` on a single line.
+    fn summary(&self, docs: &Docs) -> String {
+        let contents = docs.contents.as_deref().unwrap_or("");
+        let summary = contents
+            .trim()
+            .split("\n\n")
+            .next()
+            .unwrap_or("")
+            .lines()
+            .map(|l| l.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.format {
+            DocsFormat::Markdown => summary.replace('|', "\\|"),
+            DocsFormat::Html => escape_html(&summary),
+        }
+    }
+
+    fn with_summary(&self, item: String, docs: &Docs) -> String {
+        let summary = self.summary(docs);
+        if summary.is_empty() {
+            item
+        } else {
+            format!("{item} - {summary}")
+        }
+    }
+
+    fn start(&mut self, page: Page, title: &str) {
+        self.page = page;
+        self.out.clear();
+        if self.format == DocsFormat::Html {
+            self.out
+                .push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+            self.out
+                .push_str(&format!("<title>{}</title>\n", escape_html(title)));
+            self.out.push_str(HTML_STYLE);
+            self.out.push_str("</head>\n<body>\n");
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.format == DocsFormat::Html {
+            self.out.push_str("</body>\n</html>\n");
+        }
+        let contents = std::mem::take(&mut self.out);
+        let contents = match self.format {
+            DocsFormat::Markdown => format!("{}\n", contents.trim_end()),
+            DocsFormat::Html => contents,
+        };
+        self.pages.push(DocsPage {
+            path: self.path(self.page),
+            contents,
+        });
+    }
+
+    fn heading(&mut self, level: usize, anchor: Option<&str>, contents: &str) {
+        match self.format {
+            DocsFormat::Markdown => {
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+                if let Some(anchor) = anchor {
+                    self.out.push_str(&format!("<a id=\"{anchor}\"></a>"));
+                }
+                self.out.push_str(contents);
+                self.out.push_str("\n\n");
+            }
+            DocsFormat::Html => {
+                self.out.push_str(&format!("<h{level}"));
+                if let Some(anchor) = anchor {
+                    self.out
+                        .push_str(&format!(" id=\"{}\"", escape_html(anchor)));
+                }
+                self.out.push_str(&format!(">{contents}</h{level}>\n"));
+            }
+        }
+    }
+
+    fn para(&mut self, contents: &str) {
+        match self.format {
+            DocsFormat::Markdown => {
+                self.out.push_str(contents);
+                self.out.push_str("\n\n");
+            }
+            DocsFormat::Html => self.out.push_str(&format!("<p>{contents}</p>\n")),
+        }
+    }
+
+    /// Renders doc comments, which are assumed to already be Markdown.
+    fn docs(&mut self, docs: &Docs) {
+        let Some(contents) = docs.contents.as_deref() else {
+            return;
+        };
+        let contents = contents.trim();
+        if contents.is_empty() {
+            return;
+        }
+        match self.format {
+            DocsFormat::Markdown => {
+                self.out.push_str(contents);
+                self.out.push_str("\n\n");
+            }
+            DocsFormat::Html => {
+                for paragraph in contents.split("\n\n") {
+                    let paragraph = escape_html(paragraph.trim()).replace('\n', "<br>\n");
+                    self.out.push_str(&format!("<p>{paragraph}</p>\n"));
+                }
+            }
+        }
+    }
+
+    fn list(&mut self, items: Vec<String>) {
+        if items.is_empty() {
+            return;
+        }
+        match self.format {
+            DocsFormat::Markdown => {
+                for item in items {
+                    self.out.push_str(&format!("- {item}\n"));
+                }
+                self.out.push('\n');
+            }
+            DocsFormat::Html => {
+                self.out.push_str("<ul>\n");
+                for item in items {
+                    self.out.push_str(&format!("<li>{item}</li>\n"));
+                }
+                self.out.push_str("</ul>\n");
+            }
+        }
+    }
+
+    fn table(&mut self, headers: &[&str], rows: Vec<Vec<String>>) {
+        if rows.is_empty() {
+            return;
+        }
+        match self.format {
+            DocsFormat::Markdown => {
+                self.out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                let rule = headers.iter().map(|_| "---").collect::<Vec<_>>();
+                self.out.push_str(&format!("| {} |\n", rule.join(" | ")));
+                for row in rows {
+                    self.out.push_str(&format!("| {} |\n", row.join(" | ")));
+                }
+                self.out.push('\n');
+            }
+            DocsFormat::Html => {
+                self.out.push_str("<table>\n<tr>");
+                for header in headers {
+                    self.out.push_str(&format!("<th>{header}</th>"));
+                }
+                self.out.push_str("</tr>\n");
+                for row in rows {
+                    self.out.push_str("<tr>");
+                    for cell in row {
+                        self.out.push_str(&format!("<td>{cell}</td>"));
+                    }
+                    self.out.push_str("</tr>\n");
+                }
+                self.out.push_str("</table>\n");
+            }
+        }
+    }
+}
+
+/// Returns the anchor, without any prefix, of the documentation for `func`.
+fn func_anchor(resolve: &Resolve, func: &Function) -> String {
+    let resource = |id: TypeId| resolve.types[id].name.clone().unwrap_or_default();
+    match func.kind {
+        FunctionKind::Freestanding => format!("func-{}", func.name),
+        FunctionKind::Method(id) => format!("method-{}-{}", resource(id), func.item_name()),
+        FunctionKind::Static(id) => format!("static-{}-{}", resource(id), func.item_name()),
+        FunctionKind::Constructor(id) => format!("constructor-{}", resource(id)),
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '&' => ret.push_str("&amp;"),
+            '"' => ret.push_str("&quot;"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+const HTML_STYLE: &str = "<style>
+body { font-family: sans-serif; max-width: 60em; margin: auto; }
+code { background: #f4f4f4; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }
+.badge { font-size: 0.8em; border-radius: 0.3em; padding: 0 0.3em; }
+.badge.stable { background: #dfd; }
+.badge.unstable { background: #fdd; }
+.badge.deprecated { background: #ffd; }
+</style>
+";
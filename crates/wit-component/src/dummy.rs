@@ -1,12 +1,53 @@
+use std::collections::HashMap;
 use wit_parser::abi::{AbiVariant, WasmType};
 use wit_parser::{Function, Resolve, TypeDefKind, TypeId, WorldId, WorldItem};
 
+#[cfg(feature = "dummy-component")]
+mod component;
+
+#[cfg(feature = "dummy-component")]
+pub use component::{DummyBehavior, DummyComponent};
+
 /// Generate a dummy implementation core Wasm module for a given WIT document
 pub fn dummy_module(resolve: &Resolve, world: WorldId) -> Vec<u8> {
+    dummy_module_with(resolve, world, &DummyOptions::default())
+}
+
+/// Customizations of the module generated by [`dummy_module_with`].
+#[derive(Default)]
+pub(crate) struct DummyOptions {
+    /// Whether to leave out all imports, generating a module which only
+    /// depends on the types of the world's imports.
+    pub skip_imports: bool,
+    /// The bodies of exported functions, keyed by their core export name,
+    /// which are otherwise `unreachable`.
+    pub bodies: HashMap<String, String>,
+    /// The initial contents of linear memory, starting at address 0.
+    ///
+    /// When set a bump allocator placed after this data is used for
+    /// `cabi_realloc` rather than one which traps.
+    pub data: Option<Vec<u8>>,
+}
+
+fn resource_new_id(interface: &str, resource: &str) -> String {
+    format!("$resource-new:{interface}/{resource}")
+}
+
+pub(crate) fn dummy_module_with(
+    resolve: &Resolve,
+    world: WorldId,
+    options: &DummyOptions,
+) -> Vec<u8> {
     let world = &resolve.worlds[world];
     let mut wat = String::new();
     wat.push_str("(module\n");
-    for (name, import) in world.imports.iter() {
+    let no_imports = Default::default();
+    let imports = if options.skip_imports {
+        &no_imports
+    } else {
+        &world.imports
+    };
+    for (name, import) in imports.iter() {
         match import {
             WorldItem::Function(func) => {
                 let sig = resolve.wasm_signature(AbiVariant::GuestImport, func);
@@ -38,21 +79,26 @@ pub fn dummy_module(resolve: &Resolve, world: WorldId) -> Vec<u8> {
 
     // Import any resource-related functions for exports.
     for (name, export) in world.exports.iter() {
+        if options.skip_imports {
+            break;
+        }
         let export = match export {
             WorldItem::Interface { id, .. } => *id,
             _ => continue,
         };
-        let module = format!("[export]{}", resolve.name_world_key(name));
+        let interface = resolve.name_world_key(name);
+        let module = format!("[export]{interface}");
         for (name, ty) in resolve.interfaces[export].types.iter() {
             let ty = &resolve.types[*ty];
             match ty.kind {
                 TypeDefKind::Resource => {}
                 _ => continue,
             }
+            let new = resource_new_id(&interface, name);
             wat.push_str(&format!(
                 "\
 (import \"{module}\" \"[resource-drop]{name}\" (func (param i32)))
-(import \"{module}\" \"[resource-new]{name}\" (func (param i32) (result i32)))
+(import \"{module}\" \"[resource-new]{name}\" (func {new} (param i32) (result i32)))
 (import \"{module}\" \"[resource-rep]{name}\" (func (param i32) (result i32)))
                 "
             ));
@@ -62,13 +108,13 @@ pub fn dummy_module(resolve: &Resolve, world: WorldId) -> Vec<u8> {
     for (name, export) in world.exports.iter() {
         match export {
             WorldItem::Function(func) => {
-                push_func(&mut wat, &func.name, resolve, func);
+                push_func(&mut wat, &func.name, resolve, func, options);
             }
             WorldItem::Interface { id: export, .. } => {
                 let name = resolve.name_world_key(name);
                 for (_, func) in resolve.interfaces[*export].functions.iter() {
                     let name = func.core_export_name(Some(&name));
-                    push_func(&mut wat, &name, resolve, func);
+                    push_func(&mut wat, &name, resolve, func, options);
                 }
 
                 // Feign destructors for any resource that this interface
@@ -88,10 +134,15 @@ pub fn dummy_module(resolve: &Resolve, world: WorldId) -> Vec<u8> {
         }
     }
 
-    wat.push_str("(memory (export \"memory\") 0)\n");
-    wat.push_str(
-        "(func (export \"cabi_realloc\") (param i32 i32 i32 i32) (result i32) unreachable)\n",
-    );
+    match &options.data {
+        Some(data) => push_memory_with_data(&mut wat, data),
+        None => {
+            wat.push_str("(memory (export \"memory\") 0)\n");
+            wat.push_str(
+                "(func (export \"cabi_realloc\") (param i32 i32 i32 i32) (result i32) unreachable)\n",
+            );
+        }
+    }
     wat.push_str(")\n");
 
     return wat::parse_str(&wat).unwrap();
@@ -107,12 +158,67 @@ pub fn dummy_module(resolve: &Resolve, world: WorldId) -> Vec<u8> {
         wat.push_str(" (func (param i32)))\n");
     }
 
-    fn push_func(wat: &mut String, name: &str, resolve: &Resolve, func: &Function) {
+    fn push_func(
+        wat: &mut String,
+        name: &str,
+        resolve: &Resolve,
+        func: &Function,
+        options: &DummyOptions,
+    ) {
         let sig = resolve.wasm_signature(AbiVariant::GuestExport, func);
         wat.push_str(&format!("(func (export \"{name}\")"));
         push_tys(wat, "param", &sig.params);
         push_tys(wat, "result", &sig.results);
-        wat.push_str(" unreachable)\n");
+        match options.bodies.get(name) {
+            Some(body) => wat.push_str(&format!(" {body})\n")),
+            None => wat.push_str(" unreachable)\n"),
+        }
+    }
+
+    fn push_memory_with_data(wat: &mut String, data: &[u8]) {
+        // The heap starts after the data, leaving address 0 unused.
+        let heap = (data.len().max(1) + 7) & !7;
+        let pages = (heap + 0xffff) / 0x10000;
+        wat.push_str(&format!("(memory (export \"memory\") {pages})\n"));
+        if !data.is_empty() {
+            wat.push_str("(data (i32.const 0) \"");
+            for byte in data {
+                wat.push_str(&format!("\\{byte:02x}"));
+            }
+            wat.push_str("\")\n");
+        }
+
+        // A bump allocator which never frees memory, growing memory as
+        // necessary and copying the old contents on reallocation.
+        wat.push_str(&format!(
+            "\
+(global $heap (mut i32) (i32.const {heap}))
+(func (export \"cabi_realloc\") (param $old_ptr i32) (param $old_size i32)
+  (param $align i32) (param $new_size i32) (result i32)
+  (local $ret i32)
+  (local $end i32)
+  (local.set $ret
+    (i32.and
+      (i32.add (global.get $heap) (i32.sub (local.get $align) (i32.const 1)))
+      (i32.sub (i32.const 0) (local.get $align))))
+  (local.set $end (i32.add (local.get $ret) (local.get $new_size)))
+  (if (i32.gt_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+    (then
+      (if (i32.eq
+            (memory.grow
+              (i32.shr_u
+                (i32.add
+                  (i32.sub (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+                  (i32.const 0xffff))
+                (i32.const 16)))
+            (i32.const -1))
+        (then unreachable))))
+  (global.set $heap (local.get $end))
+  (if (local.get $old_ptr)
+    (then (memory.copy (local.get $ret) (local.get $old_ptr) (local.get $old_size))))
+  (local.get $ret))
+"
+        ));
     }
 
     fn push_tys(dst: &mut String, desc: &str, params: &[WasmType]) {
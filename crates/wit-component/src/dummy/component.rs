@@ -0,0 +1,151 @@
+use super::{dummy_module_with, resource_new_id, DummyOptions};
+use crate::result_data::ResultData;
+use crate::{embed_component_metadata, ComponentEncoder, StringEncoding};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::str::FromStr;
+use wit_parser::{Resolve, TypeDefKind, TypeId, WorldId, WorldItem};
+
+/// The behavior of an exported function of a component generated by
+/// [`DummyComponent`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DummyBehavior {
+    /// The function traps when called.
+    #[default]
+    Trap,
+    /// The function returns zero values: numbers are zero, strings and lists
+    /// are empty, options are `none`, and the first case is used for
+    /// variants, enums and results.
+    ///
+    /// Handles to resources exported by the world refer to new resources.
+    /// Functions returning handles to imported resources trap instead, unless
+    /// this behavior was requested for them specifically in which case an
+    /// error is returned.
+    Zero,
+    /// The function returns the given value, written in the WebAssembly Value
+    /// Encoding (WAVE).
+    ///
+    /// A function with a single result takes a value of that type, a
+    /// function with several results takes a tuple of them, and a function
+    /// without results takes `()`.
+    Value(String),
+}
+
+impl FromStr for DummyBehavior {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "trap" => Ok(DummyBehavior::Trap),
+            "zero" => Ok(DummyBehavior::Zero),
+            _ => bail!("unknown behavior `{s}`, expected `trap` or `zero`"),
+        }
+    }
+}
+
+/// Generates a complete component implementing a WIT world, for example to
+/// use as a test double when developing a host.
+///
+/// Unlike [`dummy_module`](crate::dummy_module) the result is a component rather than a core
+/// module, and the behavior of each exported function can be configured.
+/// Resources exported by the world can be created with their constructors,
+/// or any other function configured to return them, and can be dropped.
+#[derive(Default)]
+pub struct DummyComponent {
+    default: DummyBehavior,
+    exports: IndexMap<String, DummyBehavior>,
+    validate: bool,
+}
+
+impl DummyComponent {
+    /// Sets the behavior of exported functions which aren't configured with
+    /// [`DummyComponent::export`], which is to trap by default.
+    pub fn behavior(mut self, behavior: DummyBehavior) -> Self {
+        self.default = behavior;
+        self
+    }
+
+    /// Sets the behavior of the exported function `func`.
+    ///
+    /// The function is named as it is exported from a core module, for
+    /// example `run` for a function exported by the world itself or
+    /// `wasi:cli/run@0.2.0#run` for a function of an exported interface.
+    pub fn export(mut self, func: impl Into<String>, behavior: DummyBehavior) -> Self {
+        self.exports.insert(func.into(), behavior);
+        self
+    }
+
+    /// Sets whether the generated component is validated.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Generates a component which targets `world`.
+    pub fn encode(&self, resolve: &Resolve, world: WorldId) -> Result<Vec<u8>> {
+        let mut funcs = Vec::new();
+        for (name, export) in resolve.worlds[world].exports.iter() {
+            match export {
+                WorldItem::Function(func) => funcs.push((func.name.clone(), func)),
+                WorldItem::Interface { id, .. } => {
+                    let name = resolve.name_world_key(name);
+                    for (_, func) in resolve.interfaces[*id].functions.iter() {
+                        funcs.push((func.core_export_name(Some(&name)).into_owned(), func));
+                    }
+                }
+                WorldItem::Type(_) => {}
+            }
+        }
+
+        let mut exports = self.exports.clone();
+        let mut data = ResultData::new(resolve, exported_resources(resolve, world));
+        let mut options = DummyOptions::default();
+        for (name, func) in funcs {
+            let explicit = exports.shift_remove(&name);
+            let body = match explicit.as_ref().unwrap_or(&self.default) {
+                DummyBehavior::Trap => continue,
+                DummyBehavior::Zero => match data.zero_body(func) {
+                    Ok(body) => body,
+                    Err(_) if explicit.is_none() => continue,
+                    Err(e) => {
+                        return Err(e.context(format!("cannot return zero from function `{name}`")))
+                    }
+                },
+                DummyBehavior::Value(value) => data
+                    .value_body(func, value)
+                    .with_context(|| format!("invalid result for function `{name}`"))?,
+            };
+            options.bodies.insert(name, body);
+        }
+        if let Some((func, _)) = exports.first() {
+            bail!("world does not export a function named `{func}`");
+        }
+        options.data = Some(data.into_bytes());
+
+        let mut module = dummy_module_with(resolve, world, &options);
+        embed_component_metadata(&mut module, resolve, world, StringEncoding::UTF8)?;
+        ComponentEncoder::default()
+            .module(&module)?
+            .validate(self.validate)
+            .encode()
+    }
+}
+
+/// Returns the `$id` which [`dummy_module_with`] gives the `[resource-new]`
+/// import of each resource exported by `world`.
+fn exported_resources(resolve: &Resolve, world: WorldId) -> HashMap<TypeId, String> {
+    let mut resources = HashMap::new();
+    for (name, export) in resolve.worlds[world].exports.iter() {
+        let WorldItem::Interface { id, .. } = export else {
+            continue;
+        };
+        let interface = resolve.name_world_key(name);
+        for (name, ty) in resolve.interfaces[*id].types.iter() {
+            if let TypeDefKind::Resource = resolve.types[*ty].kind {
+                resources.insert(*ty, resource_new_id(&interface, name));
+            }
+        }
+    }
+    resources
+}
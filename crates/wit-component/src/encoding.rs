@@ -74,8 +74,8 @@
 use crate::encoding::world::WorldAdapter;
 use crate::metadata::{self, Bindgen, ModuleMetadata};
 use crate::validation::{
-    ResourceInfo, ValidatedModule, BARE_FUNC_MODULE_NAME, MAIN_MODULE_IMPORT_NAME,
-    POST_RETURN_PREFIX,
+    wasm_sig_to_func_type, ResourceInfo, ValidatedModule, BARE_FUNC_MODULE_NAME,
+    MAIN_MODULE_IMPORT_NAME, POST_RETURN_PREFIX,
 };
 use crate::StringEncoding;
 use anyhow::{anyhow, bail, Context, Result};
@@ -100,18 +100,6 @@ use types::{InstanceTypeEncoder, RootTypeEncoder, ValtypeEncoder};
 mod world;
 use world::{ComponentWorld, ImportedInterface, Lowering};
 
-fn to_val_type(ty: &WasmType) -> ValType {
-    match ty {
-        WasmType::I32 => ValType::I32,
-        WasmType::I64 => ValType::I64,
-        WasmType::F32 => ValType::F32,
-        WasmType::F64 => ValType::F64,
-        WasmType::Pointer => ValType::I32,
-        WasmType::PointerOrI64 => ValType::I64,
-        WasmType::Length => ValType::I32,
-    }
-}
-
 bitflags::bitflags! {
     /// Options in the `canon lower` or `canon lift` required for a particular
     /// function.
@@ -312,8 +300,9 @@ impl TypeContents {
                 TypeDefKind::Enum(_) => Self::empty(),
                 TypeDefKind::List(t) => Self::for_type(resolve, t) | Self::LIST,
                 TypeDefKind::Type(t) => Self::for_type(resolve, t),
-                TypeDefKind::Future(_) => todo!("encoding for future"),
-                TypeDefKind::Stream(_) => todo!("encoding for stream"),
+                // Futures and streams are passed as handles so their payloads
+                // don't contribute to what's needed to lift or lower them.
+                TypeDefKind::Future(_) | TypeDefKind::Stream(_) => Self::empty(),
                 TypeDefKind::Unknown => unreachable!(),
             },
             Type::String => Self::STRING,
@@ -1240,14 +1229,17 @@ impl<'a> EncodingState<'a> {
         let mut elements = ElementSection::new();
         let mut func_indexes = Vec::new();
         let mut func_names = NameMap::new();
+        let memory64 = self.info.info.memory64;
 
         for (i, (sig, shim)) in signatures.iter().zip(&ret.list).enumerate() {
             let i = i as u32;
             let type_index = *sigs.entry(sig).or_insert_with(|| {
                 let index = types.len();
+                let ty = wasm_sig_to_func_type(sig.clone(), memory64);
+                let to_val_type = |ty: &wasmparser::ValType| ValType::try_from(*ty).unwrap();
                 types.function(
-                    sig.params.iter().map(to_val_type),
-                    sig.results.iter().map(to_val_type),
+                    ty.params().iter().map(to_val_type),
+                    ty.results().iter().map(to_val_type),
                 );
                 index
             });
@@ -1935,6 +1927,25 @@ impl ComponentEncoder {
         Ok(self)
     }
 
+    /// Provides the WIT packages that component type information was
+    /// generated from, such as those passed to
+    /// [`embed_component_metadata`](crate::embed_component_metadata).
+    ///
+    /// Metadata embedded in a module doesn't record where its items were
+    /// defined, so this is used to point errors about the module, such as a
+    /// missing export of an interface function, at the WIT source of the item
+    /// in question. Functions of the world itself can't be located this way
+    /// since the world is synthesized when the metadata is decoded. The
+    /// packages in `resolve` are merged with those of the metadata and so
+    /// must agree with them.
+    pub fn wit_source(mut self, resolve: &Resolve) -> Result<Self> {
+        self.metadata
+            .resolve
+            .merge(resolve.clone())
+            .context("failed to merge WIT source with component type information")?;
+        Ok(self)
+    }
+
     /// Sets whether or not the encoder will validate its output.
     pub fn validate(mut self, validate: bool) -> Self {
         self.validate = validate;
@@ -2073,6 +2084,22 @@ impl ComponentEncoder {
         self
     }
 
+    /// Explains why each adapter used by the component imports what it does.
+    ///
+    /// Adapters are reduced to only the exports that the main module imports
+    /// from them or that implement the world's exports, and then to the
+    /// imports that those exports transitively call. The returned reports
+    /// list, for each adapter, the exports that were kept and the chain of
+    /// calls from each of them to each import that was retained.
+    pub fn explain_adapter_imports(&self) -> Result<Vec<crate::AdapterImports>> {
+        if self.module.is_empty() {
+            bail!("a module is required when encoding a component");
+        }
+
+        let world = ComponentWorld::new(self).context("failed to decode world from module")?;
+        world.explain_adapter_imports()
+    }
+
     /// Encode the component and return the bytes.
     pub fn encode(&self) -> Result<Vec<u8>> {
         if self.module.is_empty() {
@@ -2114,9 +2141,7 @@ impl ComponentEncoder {
         let bytes = state.component.finish();
 
         if self.validate {
-            let mut validator = Validator::new_with_features(
-                WasmFeatures::default() | WasmFeatures::COMPONENT_MODEL,
-            );
+            let mut validator = Validator::new_with_features(component_features());
 
             validator
                 .validate_all(&bytes)
@@ -2127,6 +2152,17 @@ impl ComponentEncoder {
     }
 }
 
+/// Returns the features enabled when validating components, both those
+/// produced by [`ComponentEncoder`] and those checked against a world with
+/// [`targets`](crate::targets).
+pub(crate) fn component_features() -> WasmFeatures {
+    WasmFeatures::default()
+        | WasmFeatures::COMPONENT_MODEL
+        | WasmFeatures::COMPONENT_MODEL_ASYNC
+        | WasmFeatures::COMPONENT_MODEL_MEMORY64
+        | WasmFeatures::MEMORY64
+}
+
 #[cfg(all(test, feature = "dummy-module"))]
 mod test {
     use super::*;
@@ -1,5 +1,5 @@
 use super::EncodingState;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::collections::HashMap;
 use wasm_encoder::*;
 use wit_parser::{
@@ -121,6 +121,11 @@ pub trait ValtypeEncoder<'a> {
             Type::F64 => ComponentValType::Primitive(PrimitiveValType::F64),
             Type::Char => ComponentValType::Primitive(PrimitiveValType::Char),
             Type::String => ComponentValType::Primitive(PrimitiveValType::String),
+            Type::ErrorContext => {
+                let (index, encoder) = self.defined_type();
+                encoder.error_context();
+                ComponentValType::Type(index)
+            }
             Type::Id(id) => {
                 // If this id has already been prior defined into this section
                 // refer to that definition.
@@ -153,8 +158,21 @@ pub trait ValtypeEncoder<'a> {
                         ComponentValType::Type(index)
                     }
                     TypeDefKind::Type(ty) => self.encode_valtype(resolve, ty)?,
-                    TypeDefKind::Future(_) => todo!("encoding for future type"),
-                    TypeDefKind::Stream(_) => todo!("encoding for stream type"),
+                    TypeDefKind::Future(ty) => {
+                        let ty = self.encode_optional_valtype(resolve, ty.as_ref())?;
+                        let (index, encoder) = self.defined_type();
+                        encoder.future(ty);
+                        ComponentValType::Type(index)
+                    }
+                    TypeDefKind::Stream(s) => {
+                        if s.end.is_some() {
+                            bail!("stream types with an `end` type cannot be encoded");
+                        }
+                        let ty = self.encode_optional_valtype(resolve, s.element.as_ref())?;
+                        let (index, encoder) = self.defined_type();
+                        encoder.stream(ty);
+                        ComponentValType::Type(index)
+                    }
                     TypeDefKind::Unknown => unreachable!(),
                     TypeDefKind::Resource => {
                         let name = ty.name.as_ref().expect("resources must be named");
@@ -1,7 +1,7 @@
 use super::{Adapter, ComponentEncoder, LibraryInfo, RequiredOptions};
 use crate::validation::{
-    validate_adapter_module, validate_module, RequiredImports, ValidatedAdapter, ValidatedModule,
-    BARE_FUNC_MODULE_NAME, RESOURCE_DROP,
+    validate_adapter_module, validate_module, wasm_sig_to_func_type, RequiredImports,
+    ValidatedAdapter, ValidatedModule, BARE_FUNC_MODULE_NAME, RESOURCE_DROP,
 };
 use anyhow::{Context, Result};
 use indexmap::{IndexMap, IndexSet};
@@ -10,7 +10,7 @@ use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use wasmparser::FuncType;
 use wit_parser::{
-    abi::{AbiVariant, WasmSignature, WasmType},
+    abi::{AbiVariant, WasmSignature},
     Function, InterfaceId, LiveTypes, Resolve, TypeDefKind, TypeId, TypeOwner, WorldId, WorldItem,
     WorldKey,
 };
@@ -175,6 +175,37 @@ impl<'a> ComponentWorld<'a> {
         Ok(())
     }
 
+    /// Explains why each adapter used by the component retains the imports
+    /// that it does after being reduced to the exports required of it.
+    pub fn explain_adapter_imports(&self) -> Result<Vec<crate::AdapterImports>> {
+        let resolve = &self.encoder.metadata.resolve;
+        let world = self.encoder.metadata.world;
+        let mut reports = Vec::new();
+        for (name, adapter) in self.encoder.adapters.iter() {
+            if adapter.library_info.is_some() || !self.adapters.contains_key(name.as_str()) {
+                continue;
+            }
+            let required = self
+                .required_adapter_exports(
+                    resolve,
+                    world,
+                    &adapter.required_exports,
+                    self.info.adapters_required.get(name.as_str()),
+                )
+                .into_iter()
+                .map(|(name, (_, func))| {
+                    let required_by = match func {
+                        Some(_) => crate::RequiredBy::WorldExport,
+                        None => crate::RequiredBy::MainModule,
+                    };
+                    (name, required_by)
+                })
+                .collect();
+            reports.push(crate::gc::explain(name, &adapter.wasm, &required)?);
+        }
+        Ok(reports)
+    }
+
     /// Returns the set of functions required to be exported from an adapter,
     /// either because they're exported from the adapter's world or because
     /// they're required as an import to the main module.
@@ -185,8 +216,7 @@ impl<'a> ComponentWorld<'a> {
         required_exports: &IndexSet<WorldKey>,
         required_by_import: Option<&IndexMap<&str, FuncType>>,
     ) -> IndexMap<String, (FuncType, Option<&'r Function>)> {
-        use wasmparser::ValType;
-
+        let memory64 = self.info.memory64;
         let mut required = IndexMap::new();
         if let Some(imports) = required_by_import {
             for (name, ty) in imports {
@@ -198,13 +228,7 @@ impl<'a> ComponentWorld<'a> {
             let ty = resolve.wasm_signature(AbiVariant::GuestExport, func);
             let prev = required.insert(
                 name.into_owned(),
-                (
-                    wasmparser::FuncType::new(
-                        ty.params.iter().map(to_valty),
-                        ty.results.iter().map(to_valty),
-                    ),
-                    Some(func),
-                ),
+                (wasm_sig_to_func_type(ty, memory64), Some(func)),
             );
             assert!(prev.is_none());
         };
@@ -220,19 +244,7 @@ impl<'a> ComponentWorld<'a> {
                 WorldItem::Type(_) => {}
             }
         }
-        return required;
-
-        fn to_valty(ty: &WasmType) -> ValType {
-            match ty {
-                WasmType::I32 => ValType::I32,
-                WasmType::I64 => ValType::I64,
-                WasmType::F32 => ValType::F32,
-                WasmType::F64 => ValType::F64,
-                WasmType::Pointer => ValType::I32,
-                WasmType::PointerOrI64 => ValType::I64,
-                WasmType::Length => ValType::I32,
-            }
-        }
+        required
     }
 
     /// Fills out the `import_map` field of `self` by determining the live
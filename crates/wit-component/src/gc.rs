@@ -1,6 +1,7 @@
 use self::bitvec::BitVec;
 use anyhow::{bail, Result};
 use indexmap::{IndexMap, IndexSet};
+use serde_derive::Serialize;
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
@@ -22,6 +23,92 @@ pub fn run<T>(
     required: &IndexMap<String, T>,
     main_module_realloc: Option<&str>,
 ) -> Result<Vec<u8>> {
+    let mut module = reduce(wasm, required)?;
+    module.encode(main_module_realloc)
+}
+
+/// Explains why the imports of the adapter module `wasm` named `adapter` are
+/// retained by [`run`] when it's reduced to the exports `required`.
+///
+/// Each retained import lists a chain of calls reaching it from each export
+/// kept, where the shortest such chain is reported for each export.
+pub fn explain(
+    adapter: &str,
+    wasm: &[u8],
+    required: &IndexMap<String, RequiredBy>,
+) -> Result<AdapterImports> {
+    let module = reduce(wasm, required)?;
+    let func_name = |idx: u32| match &module.funcs[idx as usize].def {
+        Definition::Import(m, n) => format!("{m}::{n}"),
+        Definition::Local(_) => match module.func_names.get(&idx) {
+            Some(name) => name.to_string(),
+            None => format!("func[{idx}]"),
+        },
+    };
+
+    let mut exports = Vec::new();
+    let mut imports = IndexMap::<u32, Vec<ImportChain>>::new();
+    for (name, export) in module.exports.iter() {
+        let required_by = match required.get(*name) {
+            Some(required_by) => *required_by,
+            None => RequiredBy::Adapter,
+        };
+        exports.push(RequiredExport {
+            name: name.to_string(),
+            required_by,
+        });
+        if export.kind != ExternalKind::Func {
+            continue;
+        }
+
+        // Breadth-first search of the call graph from this export, recording
+        // the first caller of each function to find the shortest chains.
+        let mut callers = HashMap::new();
+        let mut queue = std::collections::VecDeque::from([export.index]);
+        let mut seen = HashSet::from([export.index]);
+        while let Some(func) = queue.pop_front() {
+            if let Definition::Import(..) = module.funcs[func as usize].def {
+                let mut chain = vec![func];
+                while let Some(caller) = callers.get(chain.last().unwrap()) {
+                    chain.push(*caller);
+                }
+                chain.reverse();
+                imports.entry(func).or_default().push(ImportChain {
+                    export: name.to_string(),
+                    calls: chain.into_iter().map(func_name).collect(),
+                });
+                continue;
+            }
+            for callee in module.calls.get(&func).into_iter().flatten() {
+                if seen.insert(*callee) {
+                    callers.insert(*callee, func);
+                    queue.push_back(*callee);
+                }
+            }
+        }
+    }
+
+    let mut retained = Vec::new();
+    for (i, func) in module.live_funcs() {
+        if let Definition::Import(m, n) = func.def {
+            retained.push(RetainedImport {
+                module: m.to_string(),
+                name: n.to_string(),
+                chains: imports.swap_remove(&i).unwrap_or_default(),
+            });
+        }
+    }
+
+    Ok(AdapterImports {
+        adapter: adapter.to_string(),
+        exports,
+        imports: retained,
+    })
+}
+
+/// Parses `wasm` and calculates which of its items are live after removing
+/// all exports other than those `required`.
+fn reduce<'a, T>(wasm: &'a [u8], required: &IndexMap<String, T>) -> Result<Module<'a>> {
     assert!(!required.is_empty());
 
     let mut module = Module::default();
@@ -52,7 +139,68 @@ pub fn run<T>(
     }
     assert!(!module.exports.is_empty());
     module.liveness()?;
-    module.encode(main_module_realloc)
+    Ok(module)
+}
+
+/// A report of why an adapter module retains each of its imports after being
+/// reduced to the exports the component requires of it, as returned by
+/// [`ComponentEncoder::explain_adapter_imports`](crate::ComponentEncoder::explain_adapter_imports).
+#[derive(Debug, Clone, Serialize)]
+pub struct AdapterImports {
+    /// The name of the adapter, such as `wasi_snapshot_preview1`.
+    pub adapter: String,
+    /// The exports of the adapter which are kept.
+    pub exports: Vec<RequiredExport>,
+    /// The imports of the adapter which are retained.
+    pub imports: Vec<RetainedImport>,
+}
+
+/// An export of an adapter which is kept, as listed in [`AdapterImports`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RequiredExport {
+    /// The name of the export.
+    pub name: String,
+    /// Why the export is kept.
+    pub required_by: RequiredBy,
+}
+
+/// The reason an adapter export is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RequiredBy {
+    /// The main module imports the function from the adapter.
+    MainModule,
+    /// The adapter implements a function exported by the component's world.
+    WorldExport,
+    /// The adapter itself needs the export, such as `cabi_realloc` or the
+    /// `cabi_post_*` function of another export.
+    Adapter,
+}
+
+/// An import of an adapter which is retained, as listed in
+/// [`AdapterImports`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RetainedImport {
+    /// The module the import is from, such as `wasi:filesystem/types@0.2.0`.
+    pub module: String,
+    /// The name of the import.
+    pub name: String,
+    /// How the import is reached from each kept export which reaches it.
+    ///
+    /// This is empty for imports which are only referenced from elsewhere,
+    /// such as from the initializer of a global.
+    pub chains: Vec<ImportChain>,
+}
+
+/// A chain of calls from a kept adapter export to a retained import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportChain {
+    /// The name of the export the chain starts at.
+    pub export: String,
+    /// The functions called, starting with the exported function and ending
+    /// with the import, named by the adapter's `name` section where possible.
+    pub calls: Vec<String>,
 }
 
 fn always_keep(name: &str) -> bool {
@@ -64,53 +212,71 @@ fn always_keep(name: &str) -> bool {
     }
 }
 
+/// Returns the instruction which pushes the address or size `value` for a
+/// memory which is 64-bit if `memory64` is set.
+fn ptr_const(memory64: bool, value: i32) -> Instruction<'static> {
+    if memory64 {
+        Instruction::I64Const(value.into())
+    } else {
+        Instruction::I32Const(value)
+    }
+}
+
 /// This function generates a Wasm function body which implements `cabi_realloc` in terms of `memory.grow`.  It
 /// only accepts new, page-sized allocations.
-fn realloc_via_memory_grow() -> wasm_encoder::Function {
+///
+/// If `memory64` is set the pointers and sizes involved are all `i64` values to match a 64-bit memory,
+/// while the alignment remains an `i32`.
+fn realloc_via_memory_grow(memory64: bool) -> wasm_encoder::Function {
     use wasm_encoder::Instruction::*;
 
-    let mut func = wasm_encoder::Function::new([(1, wasm_encoder::ValType::I32)]);
+    let (ptr, ne, eq, shl) = if memory64 {
+        (wasm_encoder::ValType::I64, I64Ne, I64Eq, I64Shl)
+    } else {
+        (wasm_encoder::ValType::I32, I32Ne, I32Eq, I32Shl)
+    };
+    let mut func = wasm_encoder::Function::new([(1, ptr)]);
 
     // Assert `old_ptr` is null.
-    func.instruction(&I32Const(0));
+    func.instruction(&ptr_const(memory64, 0));
     func.instruction(&LocalGet(0));
-    func.instruction(&I32Ne);
+    func.instruction(&ne);
     func.instruction(&If(wasm_encoder::BlockType::Empty));
     func.instruction(&Unreachable);
     func.instruction(&End);
 
     // Assert `old_len` is zero.
-    func.instruction(&I32Const(0));
+    func.instruction(&ptr_const(memory64, 0));
     func.instruction(&LocalGet(1));
-    func.instruction(&I32Ne);
+    func.instruction(&ne);
     func.instruction(&If(wasm_encoder::BlockType::Empty));
     func.instruction(&Unreachable);
     func.instruction(&End);
 
     // Assert `new_len` is equal to the page size (which is the only value we currently support)
     // Note: we could easily support arbitrary multiples of PAGE_SIZE here if the need arises.
-    func.instruction(&I32Const(PAGE_SIZE));
+    func.instruction(&ptr_const(memory64, PAGE_SIZE));
     func.instruction(&LocalGet(3));
-    func.instruction(&I32Ne);
+    func.instruction(&ne);
     func.instruction(&If(wasm_encoder::BlockType::Empty));
     func.instruction(&Unreachable);
     func.instruction(&End);
 
     // Grow the memory by 1 page.
-    func.instruction(&I32Const(1));
+    func.instruction(&ptr_const(memory64, 1));
     func.instruction(&MemoryGrow(0));
     func.instruction(&LocalTee(4));
 
     // Test if the return value of the growth was -1 and, if so, trap due to a failed allocation.
-    func.instruction(&I32Const(-1));
-    func.instruction(&I32Eq);
+    func.instruction(&ptr_const(memory64, -1));
+    func.instruction(&eq);
     func.instruction(&If(wasm_encoder::BlockType::Empty));
     func.instruction(&Unreachable);
     func.instruction(&End);
 
     func.instruction(&LocalGet(4));
-    func.instruction(&I32Const(16));
-    func.instruction(&I32Shl);
+    func.instruction(&ptr_const(memory64, 16));
+    func.instruction(&shl);
     func.instruction(&End);
 
     func
@@ -128,6 +294,7 @@ fn allocate_stack_via_realloc(
     realloc_index: u32,
     sp: u32,
     allocation_state: Option<u32>,
+    memory64: bool,
 ) -> wasm_encoder::Function {
     use wasm_encoder::Instruction::*;
 
@@ -145,13 +312,13 @@ fn allocate_stack_via_realloc(
         // assume it defaults to zero anyway, in which case setting it would be redundant.
     }
 
-    func.instruction(&I32Const(0));
-    func.instruction(&I32Const(0));
+    func.instruction(&ptr_const(memory64, 0));
+    func.instruction(&ptr_const(memory64, 0));
     func.instruction(&I32Const(8));
-    func.instruction(&I32Const(PAGE_SIZE));
+    func.instruction(&ptr_const(memory64, PAGE_SIZE));
     func.instruction(&Call(realloc_index));
-    func.instruction(&I32Const(PAGE_SIZE));
-    func.instruction(&I32Add);
+    func.instruction(&ptr_const(memory64, PAGE_SIZE));
+    func.instruction(if memory64 { &I64Add } else { &I32Add });
     func.instruction(&GlobalSet(sp));
 
     if let Some(allocation_state) = allocation_state {
@@ -201,6 +368,11 @@ struct Module<'a> {
     // a new index that has been discovered to be live and the function is what
     // walks the item's definition to find other items that it references.
     worklist: Vec<(u32, WorklistFunc<'a>)>,
+
+    // The function whose body is being walked during the `liveness` pass, if
+    // any, and the functions each live function was found to reference.
+    current_func: Option<u32>,
+    calls: HashMap<u32, IndexSet<u32>>,
 }
 
 struct Table<'a> {
@@ -233,7 +405,8 @@ enum Definition<'a, T> {
 impl<'a> Module<'a> {
     fn parse(&mut self, wasm: &'a [u8]) -> Result<()> {
         let mut next_code_index = 0;
-        let mut validator = Validator::new();
+        let mut validator =
+            Validator::new_with_features(WasmFeatures::default() | WasmFeatures::MEMORY64);
         for payload in Parser::new(0).parse_all(wasm) {
             let payload = payload?;
             validator.payload(&payload)?;
@@ -420,11 +593,14 @@ impl<'a> Module<'a> {
     }
 
     fn func(&mut self, func: u32) {
+        if let Some(caller) = self.current_func {
+            self.calls.entry(caller).or_default().insert(func);
+        }
         if !self.live_funcs.insert(func) {
             return;
         }
-        self.worklist.push((func, |me, func| {
-            let func = me.funcs[func as usize].clone();
+        self.worklist.push((func, |me, idx| {
+            let func = me.funcs[idx as usize].clone();
             me.ty(func.ty);
             let mut body = match &func.def {
                 Definition::Import(..) => return Ok(()),
@@ -435,7 +611,10 @@ impl<'a> Module<'a> {
                 body.read_var_u32()?;
                 body.read::<ValType>()?;
             }
-            me.operators(body)
+            me.current_func = Some(idx);
+            let result = me.operators(body);
+            me.current_func = None;
+            result
         }));
     }
 
@@ -657,17 +836,20 @@ impl<'a> Module<'a> {
             }
         }
 
+        // Pointers and sizes passed to `cabi_realloc` and stored in the stack
+        // pointer match the width of addresses in the imported memory.
+        let memory64 = self.live_memories().any(|(_, mem)| mem.ty.memory64);
+        let ptr = if memory64 { ValType::I64 } else { ValType::I32 };
+
         let add_realloc_type = |types: &mut wasm_encoder::TypeSection| {
             let type_index = types.len();
-            types.function(
-                [
-                    wasm_encoder::ValType::I32,
-                    wasm_encoder::ValType::I32,
-                    wasm_encoder::ValType::I32,
-                    wasm_encoder::ValType::I32,
-                ],
-                [wasm_encoder::ValType::I32],
-            );
+            let ptr = if memory64 {
+                wasm_encoder::ValType::I64
+            } else {
+                wasm_encoder::ValType::I32
+            };
+            // The alignment is an `i32` even when pointers are 64-bit.
+            types.function([ptr, ptr, wasm_encoder::ValType::I32, ptr], [ptr]);
             type_index
         };
 
@@ -677,8 +859,8 @@ impl<'a> Module<'a> {
             type_index
         };
 
-        let sp = self.find_mut_i32_global("__stack_pointer")?;
-        let allocation_state = self.find_mut_i32_global("allocation_state")?;
+        let sp = self.find_mut_global("__stack_pointer", ptr)?;
+        let allocation_state = self.find_mut_global("allocation_state", ValType::I32)?;
 
         let mut func_names = Vec::new();
 
@@ -708,7 +890,7 @@ impl<'a> Module<'a> {
                     // exporting it.  In this case, we need to define a local function it can call instead.
                     realloc_index = Some(num_func_imports + funcs.len());
                     funcs.function(ty);
-                    code.function(&realloc_via_memory_grow());
+                    code.function(&realloc_via_memory_grow(memory64));
                 }
                 Definition::Local(_) => {
                     funcs.function(ty);
@@ -770,6 +952,7 @@ impl<'a> Module<'a> {
                 realloc_index.unwrap(),
                 sp.unwrap(),
                 allocation_state,
+                memory64,
             ));
         }
 
@@ -778,7 +961,7 @@ impl<'a> Module<'a> {
             // allocation because we have no way to short-circuit reentrance, so we'll use `memory.grow` instead.
             realloc_index = Some(num_func_imports + funcs.len());
             funcs.function(add_realloc_type(&mut types));
-            code.function(&realloc_via_memory_grow());
+            code.function(&realloc_via_memory_grow(memory64));
         }
 
         // Inject a start function to initialize the stack pointer which will be local to this module. This only
@@ -810,6 +993,7 @@ impl<'a> Module<'a> {
                     realloc_index.unwrap(),
                     sp,
                     allocation_state,
+                    memory64,
                 ));
 
                 start = Some(wasm_encoder::StartSection { function_index });
@@ -945,14 +1129,11 @@ impl<'a> Module<'a> {
         Ok(ret.finish())
     }
 
-    fn find_mut_i32_global(&self, name: &str) -> Result<Option<u32>> {
+    fn find_mut_global(&self, name: &str, ty: ValType) -> Result<Option<u32>> {
         let matches = &self
             .live_globals()
             .filter_map(|(i, g)| {
-                if g.ty.mutable
-                    && g.ty.content_type == ValType::I32
-                    && *self.global_names.get(&i)? == name
-                {
+                if g.ty.mutable && g.ty.content_type == ty && *self.global_names.get(&i)? == name {
                     Some(i)
                 } else {
                     None
@@ -964,7 +1145,7 @@ impl<'a> Module<'a> {
             [] => Ok(None),
             [i] => Ok(Some(*i)),
             _ => bail!(
-                "found {} mutable i32 globals with name {name}",
+                "found {} mutable {ty} globals with name {name}",
                 matches.len()
             ),
         }
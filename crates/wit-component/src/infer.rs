@@ -0,0 +1,281 @@
+use crate::validation::{
+    is_canonical_function, validate_func_sig, wasm_sig_to_func_type, BARE_FUNC_MODULE_NAME,
+    RESOURCE_DROP,
+};
+use anyhow::{bail, Context, Result};
+use indexmap::{IndexMap, IndexSet};
+use std::fmt::Write;
+use wasmparser::names::{ComponentName, ComponentNameKind, KebabStr};
+use wasmparser::{
+    types::Types, Encoding, ExternalKind, FuncType, Parser, Payload, TypeRef, ValType,
+    ValidPayload, Validator, WasmFeatures,
+};
+use wit_parser::{abi::AbiVariant, InterfaceId, PackageName, Resolve, TypeDefKind, WorldId};
+
+/// Infers a WIT world for a core wasm `module` which has no `component-type`
+/// custom sections describing one.
+///
+/// The imports and exports of `module` must follow the naming conventions of
+/// the canonical ABI, as generated by `wit-bindgen`:
+///
+/// * Imports from a module named after an interface, such as
+///   `wasi:cli/environment@0.2.0`, import that interface. The interface must
+///   be defined by one of the packages already in `resolve`, for example
+///   those loaded with [`Resolve::push_deps_dir`].
+/// * Exports named `interface#func` export the interface `interface`, which
+///   must similarly be defined in `resolve`.
+/// * Imports from the `$root` module, and exports with plain WIT names such as
+///   `run`, become functions of the world. As no types are known for these
+///   their core signatures may only use `i32`, `i64`, `f32`, and `f64`,
+///   which become `s32`, `s64`, `f32`, and `f64`, and may have at most one
+///   result.
+///
+/// Imports from other modules, such as `wasi_snapshot_preview1`, are left to
+/// be satisfied by an adapter. Exports whose names aren't valid WIT names,
+/// such as `_initialize`, and canonical ABI exports such as `cabi_realloc` are
+/// ignored.
+///
+/// The core signature of every function matched against an interface is
+/// checked against the signature the canonical ABI expects.
+///
+/// The inferred world is named `root` and is added to `resolve` in a new
+/// package named `root:component`, which can be printed for review. The world
+/// can be embedded into `module` with
+/// [`embed_component_metadata`](crate::embed_component_metadata) to then
+/// create a component from it.
+pub fn infer_world(resolve: &mut Resolve, module: &[u8]) -> Result<WorldId> {
+    let module = CoreModule::parse(module)?;
+
+    let mut imports = IndexSet::new();
+    let mut exports = IndexSet::new();
+    for (module_name, funcs) in module.imports.iter() {
+        if *module_name == BARE_FUNC_MODULE_NAME {
+            for (name, ty) in funcs {
+                imports.insert(synthesize_func(name, ty).with_context(|| {
+                    format!("failed to infer a WIT function for import `{module_name}::{name}`")
+                })?);
+            }
+            continue;
+        }
+        if module_name.starts_with("[export]") {
+            continue;
+        }
+        let Some(interface) = lookup_interface(resolve, module_name)? else {
+            continue;
+        };
+        for (name, ty) in funcs {
+            check_import(resolve, interface, name, ty, module.memory64)
+                .with_context(|| format!("failed to match import `{module_name}::{name}`"))?;
+        }
+        imports.insert(format!("{};", interface_name(resolve, interface)));
+    }
+
+    for (name, ty) in module.exports.iter() {
+        if is_canonical_function(name) {
+            continue;
+        }
+        match name.split_once('#') {
+            Some((interface, func)) => {
+                let id = lookup_interface(resolve, interface)?
+                    .with_context(|| format!("export `{name}` does not name an interface"))?;
+                if !func.starts_with("[dtor]") {
+                    check_export(resolve, id, func, ty, module.memory64)
+                        .with_context(|| format!("failed to match export `{name}`"))?;
+                }
+                exports.insert(format!("{};", interface_name(resolve, id)));
+            }
+            None if KebabStr::new(*name).is_some() => {
+                exports.insert(synthesize_func(name, ty).with_context(|| {
+                    format!("failed to infer a WIT function for export `{name}`")
+                })?);
+            }
+            None => {}
+        }
+    }
+
+    let mut wit = "package root:component;\n\nworld root {\n".to_string();
+    for import in imports.iter() {
+        writeln!(wit, "  import {import}").unwrap();
+    }
+    for export in exports.iter() {
+        writeln!(wit, "  export {export}").unwrap();
+    }
+    wit.push_str("}\n");
+    log::debug!("inferred WIT:\n{wit}");
+
+    let pkgs = resolve
+        .push_str("inferred.wit", &wit)
+        .context("failed to resolve the inferred world")?;
+    resolve.select_world(&pkgs, Some("root"))
+}
+
+/// The function imports and exports of a core wasm module.
+struct CoreModule<'a> {
+    imports: IndexMap<&'a str, IndexMap<&'a str, FuncType>>,
+    exports: IndexMap<&'a str, FuncType>,
+    memory64: bool,
+}
+
+impl<'a> CoreModule<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<CoreModule<'a>> {
+        let mut validator =
+            Validator::new_with_features(WasmFeatures::default() | WasmFeatures::MEMORY64);
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+        let mut memory = None;
+        let mut types: Option<Types> = None;
+        for payload in Parser::new(0).parse_all(bytes) {
+            let payload = payload?;
+            if let ValidPayload::End(tys) = validator.payload(&payload)? {
+                types = Some(tys);
+                break;
+            }
+            match payload {
+                Payload::Version { encoding, .. } if encoding != Encoding::Module => {
+                    bail!("data is not a WebAssembly module");
+                }
+                Payload::ImportSection(s) => {
+                    for import in s {
+                        let import = import?;
+                        match import.ty {
+                            TypeRef::Func(ty) => imports.push((import.module, import.name, ty)),
+                            _ => bail!("module is only allowed to import functions"),
+                        }
+                    }
+                }
+                Payload::ExportSection(s) => {
+                    for export in s {
+                        let export = export?;
+                        match export.kind {
+                            ExternalKind::Func => exports.push((export.name, export.index)),
+                            ExternalKind::Memory if export.name == "memory" => {
+                                memory = Some(export.index)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Payload::CustomSection(s) if s.name().starts_with("component-type") => {
+                    bail!(
+                        "module already contains component metadata in the `{}` custom section",
+                        s.name()
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let types = types.unwrap();
+        let mut ret = CoreModule {
+            imports: IndexMap::new(),
+            exports: IndexMap::new(),
+            memory64: memory.map_or(false, |m| types.memory_at(m).memory64),
+        };
+        for (module, name, ty) in imports {
+            let ty = types[types.core_type_at(ty).unwrap_sub()].unwrap_func();
+            ret.imports
+                .entry(module)
+                .or_default()
+                .insert(name, ty.clone());
+        }
+        for (name, index) in exports {
+            let ty = types[types.core_function_at(index)].unwrap_func();
+            ret.exports.insert(name, ty.clone());
+        }
+        Ok(ret)
+    }
+}
+
+/// Returns the interface named `name` within the packages of `resolve`, or
+/// `None` if `name` isn't the name of an interface.
+fn lookup_interface(resolve: &Resolve, name: &str) -> Result<Option<InterfaceId>> {
+    let name = ComponentName::new(name, 0);
+    let name = match name.as_ref().map(|n| n.kind()) {
+        Ok(ComponentNameKind::Interface(name)) => name,
+        _ => return Ok(None),
+    };
+    let pkg = PackageName {
+        namespace: name.namespace().to_string(),
+        name: name.package().to_string(),
+        version: name.version(),
+    };
+    match resolve.package_names.get(&pkg).and_then(|p| {
+        resolve.packages[*p]
+            .interfaces
+            .get(name.interface().as_str())
+    }) {
+        Some(id) => Ok(Some(*id)),
+        None => bail!("no WIT interface named `{}` was found", name.as_str()),
+    }
+}
+
+fn interface_name(resolve: &Resolve, id: InterfaceId) -> String {
+    resolve.id_of(id).unwrap()
+}
+
+fn check_import(
+    resolve: &Resolve,
+    interface: InterfaceId,
+    name: &str,
+    ty: &FuncType,
+    memory64: bool,
+) -> Result<()> {
+    let interface = &resolve.interfaces[interface];
+    if let Some(resource) = name.strip_prefix(RESOURCE_DROP) {
+        match interface.types.get(resource) {
+            Some(id) if matches!(resolve.types[*id].kind, TypeDefKind::Resource) => {
+                let expected = FuncType::new([ValType::I32], []);
+                return validate_func_sig(name, None, &expected, ty);
+            }
+            _ => bail!("interface has no resource named `{resource}`"),
+        }
+    }
+    let Some(func) = interface.functions.get(name) else {
+        bail!("interface has no function named `{name}`");
+    };
+    let sig = resolve.wasm_signature(AbiVariant::GuestImport, func);
+    validate_func_sig(name, None, &wasm_sig_to_func_type(sig, memory64), ty)
+}
+
+fn check_export(
+    resolve: &Resolve,
+    interface: InterfaceId,
+    name: &str,
+    ty: &FuncType,
+    memory64: bool,
+) -> Result<()> {
+    let Some(func) = resolve.interfaces[interface].functions.get(name) else {
+        bail!("interface has no function named `{name}`");
+    };
+    let sig = resolve.wasm_signature(AbiVariant::GuestExport, func);
+    validate_func_sig(name, None, &wasm_sig_to_func_type(sig, memory64), ty)
+}
+
+/// Returns a WIT function named `name` whose core signature is `ty`.
+fn synthesize_func(name: &str, ty: &FuncType) -> Result<String> {
+    if KebabStr::new(name).is_none() {
+        bail!("`{name}` is not a valid WIT name");
+    }
+    let scalar = |ty: &ValType| match ty {
+        ValType::I32 => Ok("s32"),
+        ValType::I64 => Ok("s64"),
+        ValType::F32 => Ok("f32"),
+        ValType::F64 => Ok("f64"),
+        _ => bail!("core type `{ty}` has no corresponding WIT type"),
+    };
+    let params = ty
+        .params()
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| Ok(format!("arg{i}: {}", scalar(ty)?)))
+        .collect::<Result<Vec<_>>>()?;
+    if params.len() > 16 {
+        bail!("functions with more than 16 parameters cannot be inferred");
+    }
+    let result = match ty.results() {
+        [] => String::new(),
+        [ty] => format!(" -> {}", scalar(ty)?),
+        _ => bail!("functions with multiple results cannot be inferred"),
+    };
+    Ok(format!("{name}: func({}){result};", params.join(", ")))
+}
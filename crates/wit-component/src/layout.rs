@@ -0,0 +1,348 @@
+use std::fmt::Write;
+use wit_parser::abi::{AbiVariant, WasmType};
+use wit_parser::*;
+
+/// Renders a report of the canonical ABI memory layout of the types and
+/// functions used by `world`.
+///
+/// Every record, variant, tuple, flags and enum type defined in the world, or
+/// in an interface it imports or exports, is listed with its size and
+/// alignment for both 32-bit and 64-bit memories. Records and tuples list the
+/// byte range of each field along with any padding, variants and enums list
+/// their discriminant and payload ranges, and flags list the bit assigned to
+/// each flag.
+///
+/// Each function is additionally listed with its flattened core wasm
+/// signature, as produced by [`Resolve::wasm_signature`], for the direction
+/// in which the world uses it.
+///
+/// This is intended to help authors of guests in languages without bindings
+/// generators check that hand-written definitions match the canonical ABI.
+pub fn print_layout(resolve: &Resolve, world: WorldId) -> String {
+    let mut wasm32 = SizeAlign::new(AddressSize::Wasm32);
+    wasm32.fill(resolve);
+    let mut wasm64 = SizeAlign::new(AddressSize::Wasm64);
+    wasm64.fill(resolve);
+    let mut printer = LayoutPrinter {
+        resolve,
+        sizes: [("wasm32", wasm32), ("wasm64", wasm64)],
+        out: String::new(),
+    };
+    printer.world(world);
+    printer.out
+}
+
+struct LayoutPrinter<'a> {
+    resolve: &'a Resolve,
+    sizes: [(&'static str, SizeAlign); 2],
+    out: String,
+}
+
+impl LayoutPrinter<'_> {
+    fn world(&mut self, id: WorldId) {
+        let world = &self.resolve.worlds[id];
+        match world.package {
+            Some(pkg) => {
+                let pkg = &self.resolve.packages[pkg].name;
+                writeln!(self.out, "world {}/{}", pkg, world.name).unwrap();
+            }
+            None => writeln!(self.out, "world {}", world.name).unwrap(),
+        }
+
+        for (direction, items, variant) in [
+            ("import", &world.imports, AbiVariant::GuestImport),
+            ("export", &world.exports, AbiVariant::GuestExport),
+        ] {
+            for (key, item) in items {
+                match item {
+                    WorldItem::Interface { id, .. } => {
+                        let name = match key {
+                            WorldKey::Name(name) => name.clone(),
+                            WorldKey::Interface(_) => self.resolve.id_of(*id).unwrap(),
+                        };
+                        writeln!(self.out, "\n{direction} interface {name}").unwrap();
+                        let iface = &self.resolve.interfaces[*id];
+                        for (_, ty) in iface.types.iter() {
+                            self.type_def(*ty);
+                        }
+                        for (_, func) in iface.functions.iter() {
+                            self.function(func, variant);
+                        }
+                    }
+                    WorldItem::Function(func) => {
+                        writeln!(self.out, "\n{direction} function").unwrap();
+                        self.function(func, variant);
+                    }
+                    WorldItem::Type(ty) => {
+                        if self.is_reported(*ty) {
+                            writeln!(self.out, "\n{direction} type").unwrap();
+                            self.type_def(*ty);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_reported(&self, id: TypeId) -> bool {
+        matches!(
+            self.resolve.types[id].kind,
+            TypeDefKind::Record(_)
+                | TypeDefKind::Tuple(_)
+                | TypeDefKind::Variant(_)
+                | TypeDefKind::Enum(_)
+                | TypeDefKind::Flags(_)
+        )
+    }
+
+    fn type_def(&mut self, id: TypeId) {
+        if !self.is_reported(id) {
+            return;
+        }
+        let resolve = self.resolve;
+        let ty = &resolve.types[id];
+        let name = ty.name.as_deref().unwrap_or("<anonymous>");
+        writeln!(self.out, "  {} {name}", ty.kind.as_str()).unwrap();
+
+        for (abi, sizes) in self.sizes.iter() {
+            let size = sizes.size(&Type::Id(id));
+            let align = sizes.align(&Type::Id(id));
+            let mut rows = Vec::new();
+            let summary = match &ty.kind {
+                TypeDefKind::Record(r) => {
+                    let fields = r
+                        .fields
+                        .iter()
+                        .map(|f| (f.name.clone(), f.ty))
+                        .collect::<Vec<_>>();
+                    self.fields(sizes, &fields, size, &mut rows);
+                    String::new()
+                }
+                TypeDefKind::Tuple(t) => {
+                    let fields = t
+                        .types
+                        .iter()
+                        .enumerate()
+                        .map(|(i, ty)| (i.to_string(), *ty))
+                        .collect::<Vec<_>>();
+                    self.fields(sizes, &fields, size, &mut rows);
+                    String::new()
+                }
+                TypeDefKind::Variant(v) => {
+                    let cases = v
+                        .cases
+                        .iter()
+                        .map(|c| (c.name.as_str(), c.ty))
+                        .collect::<Vec<_>>();
+                    self.cases(sizes, v.tag(), &cases, size, &mut rows);
+                    format!(", discriminant {}", int_name(v.tag()))
+                }
+                TypeDefKind::Enum(e) => {
+                    let cases = e
+                        .cases
+                        .iter()
+                        .map(|c| (c.name.as_str(), None))
+                        .collect::<Vec<_>>();
+                    self.cases(sizes, e.tag(), &cases, size, &mut rows);
+                    format!(", discriminant {}", int_name(e.tag()))
+                }
+                TypeDefKind::Flags(f) => {
+                    for (i, flag) in f.flags.iter().enumerate() {
+                        rows.push((format!("bit {i}"), flag.name.clone()));
+                    }
+                    match f.repr() {
+                        FlagsRepr::U8 => ", representation u8".to_string(),
+                        FlagsRepr::U16 => ", representation u16".to_string(),
+                        FlagsRepr::U32(n) => format!(", representation {n} x u32"),
+                    }
+                }
+                _ => unreachable!(),
+            };
+            writeln!(self.out, "    {abi}: size {size}, align {align}{summary}").unwrap();
+            for (range, what) in rows {
+                writeln!(self.out, "      {range:<10}{what}").unwrap();
+            }
+        }
+    }
+
+    /// Lists the byte range of each of `fields`, laid out as a record of
+    /// `size` bytes, along with any padding between or after them.
+    fn fields(
+        &self,
+        sizes: &SizeAlign,
+        fields: &[(String, Type)],
+        size: usize,
+        rows: &mut Vec<(String, String)>,
+    ) {
+        let mut end = 0;
+        let offsets = sizes.field_offsets(fields.iter().map(|(_, ty)| ty));
+        for ((name, _), (offset, ty)) in fields.iter().zip(offsets) {
+            push_padding(rows, end, offset);
+            end = offset + sizes.size(ty);
+            rows.push((
+                format!("{offset}..{end}"),
+                format!("{name}: {}", self.ty_name(*ty)),
+            ));
+        }
+        push_padding(rows, end, size);
+    }
+
+    /// Lists the discriminant of a variant of `size` bytes along with the
+    /// byte range of each case's payload.
+    fn cases(
+        &self,
+        sizes: &SizeAlign,
+        tag: Int,
+        cases: &[(&str, Option<Type>)],
+        size: usize,
+        rows: &mut Vec<(String, String)>,
+    ) {
+        let tag_size = int_size(tag);
+        rows.push((format!("0..{tag_size}"), "discriminant".to_string()));
+        let payload = sizes.payload_offset(tag, cases.iter().map(|(_, ty)| ty.as_ref()));
+        push_padding(rows, tag_size, payload);
+        let mut end = payload;
+        for (i, (name, ty)) in cases.iter().enumerate() {
+            match ty {
+                Some(ty) => {
+                    let case_end = payload + sizes.size(ty);
+                    end = end.max(case_end);
+                    rows.push((
+                        format!("{payload}..{case_end}"),
+                        format!("case {i} {name}: {}", self.ty_name(*ty)),
+                    ));
+                }
+                None => rows.push((String::new(), format!("case {i} {name}"))),
+            }
+        }
+        push_padding(rows, end, size);
+    }
+
+    fn function(&mut self, func: &Function, variant: AbiVariant) {
+        let sig = self.resolve.wasm_signature(variant, func);
+        let list = |types: &[WasmType]| {
+            types
+                .iter()
+                .map(|t| wasm_type_name(*t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut notes = Vec::new();
+        if sig.indirect_params {
+            notes.push("params passed indirectly");
+        }
+        if sig.retptr {
+            notes.push("results returned through a pointer");
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!("  ; {}", notes.join(", "))
+        };
+        writeln!(
+            self.out,
+            "  func {}: ({}) -> ({}){notes}",
+            func.name,
+            list(&sig.params),
+            list(&sig.results),
+        )
+        .unwrap();
+    }
+
+    fn ty_name(&self, ty: Type) -> String {
+        let name = match ty {
+            Type::Bool => "bool",
+            Type::U8 => "u8",
+            Type::U16 => "u16",
+            Type::U32 => "u32",
+            Type::U64 => "u64",
+            Type::S8 => "s8",
+            Type::S16 => "s16",
+            Type::S32 => "s32",
+            Type::S64 => "s64",
+            Type::F32 => "f32",
+            Type::F64 => "f64",
+            Type::Char => "char",
+            Type::String => "string",
+            Type::ErrorContext => "error-context",
+            Type::Id(id) => {
+                let ty = &self.resolve.types[id];
+                if let Some(name) = &ty.name {
+                    return name.clone();
+                }
+                let opt = |ty: Option<Type>| match ty {
+                    Some(ty) => self.ty_name(ty),
+                    None => "_".to_string(),
+                };
+                return match &ty.kind {
+                    TypeDefKind::Type(ty) => self.ty_name(*ty),
+                    TypeDefKind::List(ty) => format!("list<{}>", self.ty_name(*ty)),
+                    TypeDefKind::Option(ty) => format!("option<{}>", self.ty_name(*ty)),
+                    TypeDefKind::Tuple(t) => {
+                        let types = t.types.iter().map(|t| self.ty_name(*t));
+                        format!("tuple<{}>", types.collect::<Vec<_>>().join(", "))
+                    }
+                    TypeDefKind::Result(r) => match (r.ok, r.err) {
+                        (None, None) => "result".to_string(),
+                        (Some(ok), None) => format!("result<{}>", self.ty_name(ok)),
+                        (ok, err) => format!("result<{}, {}>", opt(ok), opt(err)),
+                    },
+                    TypeDefKind::Handle(Handle::Own(r)) => self.ty_name(Type::Id(*r)),
+                    TypeDefKind::Handle(Handle::Borrow(r)) => {
+                        format!("borrow<{}>", self.ty_name(Type::Id(*r)))
+                    }
+                    TypeDefKind::Future(None) => "future".to_string(),
+                    TypeDefKind::Future(Some(ty)) => format!("future<{}>", self.ty_name(*ty)),
+                    TypeDefKind::Stream(s) => match (s.element, s.end) {
+                        (None, None) => "stream".to_string(),
+                        (Some(element), None) => format!("stream<{}>", self.ty_name(element)),
+                        (element, end) => format!("stream<{}, {}>", opt(element), opt(end)),
+                    },
+                    TypeDefKind::Record(_)
+                    | TypeDefKind::Resource
+                    | TypeDefKind::Flags(_)
+                    | TypeDefKind::Variant(_)
+                    | TypeDefKind::Enum(_)
+                    | TypeDefKind::Unknown => ty.kind.as_str().to_string(),
+                };
+            }
+        };
+        name.to_string()
+    }
+}
+
+fn push_padding(rows: &mut Vec<(String, String)>, start: usize, end: usize) {
+    if end > start {
+        rows.push((format!("{start}..{end}"), "(padding)".to_string()));
+    }
+}
+
+fn int_size(int: Int) -> usize {
+    match int {
+        Int::U8 => 1,
+        Int::U16 => 2,
+        Int::U32 => 4,
+        Int::U64 => 8,
+    }
+}
+
+fn int_name(int: Int) -> &'static str {
+    match int {
+        Int::U8 => "u8",
+        Int::U16 => "u16",
+        Int::U32 => "u32",
+        Int::U64 => "u64",
+    }
+}
+
+fn wasm_type_name(ty: WasmType) -> &'static str {
+    match ty {
+        WasmType::I32 => "i32",
+        WasmType::I64 => "i64",
+        WasmType::F32 => "f32",
+        WasmType::F64 => "f64",
+        WasmType::Pointer => "pointer",
+        WasmType::PointerOrI64 => "pointer-or-i64",
+        WasmType::Length => "length",
+    }
+}
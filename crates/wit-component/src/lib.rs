@@ -9,31 +9,50 @@ use anyhow::{bail, Result};
 use wasm_encoder::{CanonicalOption, Encode, Section};
 use wit_parser::{Resolve, WorldId};
 
+mod bundle;
+mod docs;
 mod encoding;
 mod gc;
+mod infer;
+mod layout;
 mod linking;
 mod printing;
 mod targets;
 mod validation;
 
+pub use bundle::*;
+pub use docs::*;
 pub use encoding::{encode, ComponentEncoder};
+pub use gc::{AdapterImports, ImportChain, RequiredBy, RequiredExport, RetainedImport};
+pub use infer::*;
+pub use layout::*;
 pub use linking::Linker;
 pub use printing::*;
 pub use targets::*;
-pub use wit_parser::decoding::{decode, decode_reader, DecodedWasm};
+pub use wit_parser::decoding::{decode, decode_reader, decode_with_features, DecodedWasm};
 
 pub mod metadata;
 
 #[cfg(feature = "dummy-module")]
 pub use dummy::dummy_module;
+#[cfg(feature = "dummy-component")]
+pub use dummy::{DummyBehavior, DummyComponent};
 #[cfg(feature = "dummy-module")]
 mod dummy;
 
+#[cfg(any(feature = "dummy-component", feature = "stub"))]
+mod result_data;
+
 #[cfg(feature = "semver-check")]
 mod semver_check;
 #[cfg(feature = "semver-check")]
 pub use semver_check::*;
 
+#[cfg(feature = "stub")]
+mod stub;
+#[cfg(feature = "stub")]
+pub use stub::*;
+
 /// Supported string encoding formats.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum StringEncoding {
@@ -88,11 +107,15 @@ pub(crate) fn base_producers() -> wasm_metadata::Producers {
 }
 
 /// Embed component metadata in a buffer of bytes that contains a Wasm module
+///
+/// The `encoding` may be a single [`StringEncoding`] or a
+/// [`metadata::StringEncodings`] with different encodings for some imports
+/// and exports.
 pub fn embed_component_metadata(
     bytes: &mut Vec<u8>,
     wit_resolver: &Resolve,
     world: WorldId,
-    encoding: StringEncoding,
+    encoding: impl Into<metadata::StringEncodings>,
 ) -> Result<()> {
     let encoded = metadata::encode(&wit_resolver, world, encoding, None)?;
 
@@ -113,6 +136,7 @@ mod tests {
     use wit_parser::Resolve;
 
     use super::{embed_component_metadata, StringEncoding};
+    use crate::metadata::StringEncodings;
 
     const MODULE_WAT: &str = r#"
 (module
@@ -173,4 +197,64 @@ world test-world {}
 
         Ok(())
     }
+
+    #[test]
+    fn component_metadata_string_encodings() -> Result<()> {
+        const WIT: &str = r#"
+package test:foo;
+
+interface host {
+  log: func(msg: string);
+  name: func() -> string;
+}
+
+world c-lib {
+  import host;
+  export c-greet: func(name: string);
+}
+
+world rust {
+  import env: func(key: string) -> string;
+  export rust-greet: func(name: string);
+}
+"#;
+        let mut resolve = Resolve::default();
+        let pkgs = resolve.push_str("in-code.wit", WIT)?;
+        let c_lib = resolve.select_world(&pkgs, Some("c-lib"))?;
+        let rust = resolve.select_world(&pkgs, Some("rust"))?;
+
+        // A module mixing a C library using UTF-16, except for one function,
+        // with Rust code using UTF-8.
+        let mut bytes = wat::parse_str(MODULE_WAT)?;
+        let mut c_encodings = StringEncodings::from(StringEncoding::UTF16);
+        c_encodings.imports.insert(
+            "test:foo/host#name".to_string(),
+            StringEncoding::CompactUTF16,
+        );
+        embed_component_metadata(&mut bytes, &resolve, c_lib, c_encodings)?;
+        embed_component_metadata(&mut bytes, &resolve, rust, StringEncoding::UTF8)?;
+
+        let (_, bindgen) = crate::metadata::decode(&bytes)?;
+        let imports = &bindgen.metadata.import_encodings;
+        let import = |module: &str, name: &str| imports[&(module.to_string(), name.to_string())];
+        assert_eq!(import("test:foo/host", "log"), StringEncoding::UTF16);
+        assert_eq!(
+            import("test:foo/host", "name"),
+            StringEncoding::CompactUTF16
+        );
+        assert_eq!(import("$root", "env"), StringEncoding::UTF8);
+        let exports = &bindgen.metadata.export_encodings;
+        assert_eq!(exports["c-greet"], StringEncoding::UTF16);
+        assert_eq!(exports["rust-greet"], StringEncoding::UTF8);
+
+        // Items not in the world are rejected.
+        let mut encodings = StringEncodings::default();
+        encodings
+            .exports
+            .insert("missing".to_string(), StringEncoding::UTF16);
+        let err = embed_component_metadata(&mut bytes, &resolve, rust, encodings).unwrap_err();
+        assert!(err.to_string().contains("no export of that name"), "{err}");
+
+        Ok(())
+    }
 }
@@ -32,6 +32,7 @@ use {
         fmt::Debug,
         hash::Hash,
         iter,
+        path::PathBuf,
     },
     wasm_encoder::{
         CodeSection, ConstExpr, DataSection, ElementSection, Elements, EntityType, ExportKind,
@@ -1186,6 +1187,70 @@ fn find_reachable<'a>(
         .collect()
 }
 
+/// Verify that every library named in a `needed` entry is among those being linked
+fn check_needed_libraries(metadata: &[Metadata]) -> Result<()> {
+    let names = metadata
+        .iter()
+        .map(|metadata| metadata.name)
+        .collect_unique::<HashSet<_>>();
+
+    let missing = metadata
+        .iter()
+        .filter_map(|metadata| {
+            let missing = metadata
+                .needed_libs
+                .iter()
+                .copied()
+                .filter(|name| !names.contains(*name))
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() {
+                None
+            } else {
+                Some((metadata.name, missing))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        bail!(
+            "missing libraries:\n{}",
+            missing
+                .iter()
+                .map(|(needed_by, missing)| format!("\t{needed_by} needs {}", missing.join(", ")))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Verify that the `missing` symbols, if any, may be satisfied by generating trapping stubs
+///
+/// Stubs are only generated for functions, and only if either `stub_missing_functions` is set or all of the
+/// imports are weak.
+fn check_missing_symbols(missing: &[(&str, Export)], stub_missing_functions: bool) -> Result<()> {
+    if missing
+        .iter()
+        .all(|(_, export)| matches!(&export.key.ty, Type::Function(_)))
+        && (stub_missing_functions
+            || missing
+                .iter()
+                .all(|(_, export)| export.flags.contains(SymbolFlags::BINDING_WEAK)))
+    {
+        return Ok(());
+    }
+    bail!(
+        "unresolved symbol(s):\n{}",
+        missing
+            .iter()
+            .filter(|(_, export)| !export.flags.contains(SymbolFlags::BINDING_WEAK))
+            .map(|(importer, export)| { format!("\t{importer} needs {}", export.key) })
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
+
 /// Builder type for composing dynamic library modules into a component
 #[derive(Default)]
 pub struct Linker {
@@ -1210,6 +1275,37 @@ pub struct Linker {
     ///
     /// If `None`, use `DEFAULT_STACK_SIZE_BYTES`.
     stack_size: Option<u32>,
+
+    /// Directories to search for libraries which are needed by other libraries but weren't added with
+    /// [`Linker::library`]
+    library_paths: Vec<PathBuf>,
+}
+
+/// A library to be linked, as reported by [`Linker::load_order`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedLibrary {
+    /// The name of the library, e.g. `libc.so`
+    pub name: String,
+
+    /// The file the library was read from if it was found in a library search path, or `None` if it was added
+    /// with [`Linker::library`]
+    pub path: Option<PathBuf>,
+
+    /// The libraries which list this one in the `needed` entries of their `dylink.0` section
+    pub needed_by: Vec<String>,
+}
+
+/// Returns the name of the library `name` without its version if it has one,
+/// for example `libfoo.so` for `libfoo.so.1`.
+fn versioned_base(name: &str) -> Option<&str> {
+    let i = name.find(".so.")?;
+    Some(&name[..i + 3])
+}
+
+/// Returns whether `name` is a single file name rather than a path, for example
+/// `libfoo.so` but not `../libfoo.so` or `lib/libfoo.so`.
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
 }
 
 impl Linker {
@@ -1257,13 +1353,98 @@ impl Linker {
         self
     }
 
-    /// Encode the component and return the bytes
-    pub fn encode(mut self) -> Result<Vec<u8>> {
+    /// Add a directory to search for libraries which are needed by other libraries but weren't added with
+    /// [`Linker::library`]
+    ///
+    /// Like `ld.so`, each name listed in the `needed` entries of a library's `dylink.0` section is looked up as a
+    /// file of that name in each directory in the order they were added, and the first match is used.  Libraries
+    /// found this way are searched for their own needed libraries in turn and are not `dlopen`-able.  It's an
+    /// error for a library found this way to be a different version of one already being linked, for example
+    /// `libc.so.7` when `libc.so.6` was added, or for a needed name to be a path rather than a plain file name,
+    /// for example `../libfoo.so`.
+    pub fn library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_paths.push(path.into());
+        self
+    }
+
+    /// Resolve the libraries to be linked without encoding a component
+    ///
+    /// This locates needed libraries in the library search paths, checks that none of them is a different version
+    /// of a library already being linked and that every symbol can be resolved, and then returns all the libraries in breadth-first
+    /// order of their `needed` entries, starting from those added with [`Linker::library`].
+    pub fn load_order(&self) -> Result<Vec<LoadedLibrary>> {
+        let mut libraries = self
+            .libraries
+            .iter()
+            .map(|(name, module, dl_openable)| (name.clone(), module.clone(), *dl_openable))
+            .collect::<Vec<_>>();
         if self.use_built_in_libdl {
-            self.use_built_in_libdl = false;
-            self = self.library("libdl.so", include_bytes!("../libdl.so"), false)?;
+            libraries.push((
+                "libdl.so".into(),
+                include_bytes!("../libdl.so").to_vec(),
+                false,
+            ));
+        }
+        let adapter_names = self.adapter_names()?;
+        let found = self.find_needed_libraries(&libraries, &adapter_names)?;
+        let paths = found
+            .iter()
+            .map(|(name, _, path)| (name.clone(), path.clone()))
+            .collect::<HashMap<_, _>>();
+        libraries.extend(
+            found
+                .into_iter()
+                .map(|(name, module, _)| (name, module, false)),
+        );
+
+        let metadata = libraries
+            .iter()
+            .map(|(name, module, dl_openable)| {
+                Metadata::try_new(name, *dl_openable, module, &adapter_names)
+                    .with_context(|| format!("failed to extract linking metadata from {name}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        check_needed_libraries(&metadata)?;
+        let exporters = resolve_exporters(&metadata)?;
+        let (_, missing, _) = resolve_symbols(&metadata, &exporters);
+        check_missing_symbols(&missing, self.stub_missing_functions)?;
+
+        let by_name = metadata
+            .iter()
+            .map(|metadata| (metadata.name, metadata))
+            .collect::<HashMap<_, _>>();
+        let mut order = IndexMap::<&str, Vec<String>>::new();
+        let mut queue = metadata
+            .iter()
+            .take(self.libraries.len())
+            .map(|metadata| metadata.name)
+            .collect::<std::collections::VecDeque<_>>();
+        for name in queue.iter() {
+            order.insert(name, Vec::new());
+        }
+        while let Some(name) = queue.pop_front() {
+            for &needed in by_name[name].needed_libs.iter() {
+                if !order.contains_key(needed) {
+                    queue.push_back(needed);
+                }
+                order.entry(needed).or_default().push(name.to_string());
+            }
+        }
+        for metadata in metadata.iter() {
+            order.entry(metadata.name).or_default();
         }
 
+        Ok(order
+            .into_iter()
+            .map(|(name, needed_by)| LoadedLibrary {
+                name: name.to_string(),
+                path: paths.get(name).cloned(),
+                needed_by,
+            })
+            .collect())
+    }
+
+    fn adapter_names(&self) -> Result<HashSet<&str>> {
         let adapter_names = self
             .adapters
             .iter()
@@ -1273,6 +1454,100 @@ impl Linker {
         if adapter_names.len() != self.adapters.len() {
             bail!("duplicate adapter name");
         }
+        Ok(adapter_names)
+    }
+
+    /// Find the libraries which `libraries` transitively need but which aren't among them in the library search
+    /// paths, returning the name, contents, and path of each
+    fn find_needed_libraries(
+        &self,
+        libraries: &[(String, Vec<u8>, bool)],
+        adapter_names: &HashSet<&str>,
+    ) -> Result<Vec<(String, Vec<u8>, PathBuf)>> {
+        let mut found = Vec::new();
+        let mut names = libraries
+            .iter()
+            .map(|(name, ..)| name.clone())
+            .collect::<HashSet<_>>();
+        let mut needed = Vec::new();
+        for (name, module, dl_openable) in libraries {
+            let metadata = Metadata::try_new(name, *dl_openable, module, adapter_names)
+                .with_context(|| format!("failed to extract linking metadata from {name}"))?;
+            needed.extend(metadata.needed_libs.iter().map(|s| s.to_string()));
+        }
+        let mut needed = needed
+            .into_iter()
+            .collect::<std::collections::VecDeque<_>>();
+
+        if !self.library_paths.is_empty() {
+            while let Some(name) = needed.pop_front() {
+                if names.contains(&name) {
+                    continue;
+                }
+                // Needed names come from the `dylink.0` sections of the
+                // libraries themselves, so make sure they can't refer to files
+                // outside of the search path directories.
+                if !is_plain_file_name(&name) {
+                    bail!("invalid needed library name `{name}`: expected a plain file name");
+                }
+                let Some(path) = self
+                    .library_paths
+                    .iter()
+                    .map(|dir| dir.join(&name))
+                    .find(|path| path.is_file())
+                else {
+                    // Reported as a missing library later on.
+                    continue;
+                };
+                let module = std::fs::read(&path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                let metadata = Metadata::try_new(&name, false, &module, adapter_names)
+                    .with_context(|| format!("failed to extract linking metadata from {name}"))?;
+                needed.extend(metadata.needed_libs.iter().map(|s| s.to_string()));
+                names.insert(name.clone());
+                found.push((name, module, path));
+            }
+        }
+
+        // Refuse to load a library from the search path when a different
+        // version of it is already being linked, e.g. both `libfoo.so.1` and
+        // `libfoo.so.2`. Names without a version such as `libfoo.so` don't
+        // conflict with anything.
+        let mut versions = libraries
+            .iter()
+            .filter_map(|(name, ..)| Some((versioned_base(name)?, name.as_str())))
+            .collect::<HashMap<_, _>>();
+        for (name, ..) in found.iter() {
+            let Some(base) = versioned_base(name) else {
+                continue;
+            };
+            match versions.insert(base, name) {
+                Some(prev) if prev != name => {
+                    bail!("conflicting versions of library {base}: {prev}, {name}")
+                }
+                _ => {}
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Encode the component and return the bytes
+    pub fn encode(mut self) -> Result<Vec<u8>> {
+        if self.use_built_in_libdl {
+            self.use_built_in_libdl = false;
+            self = self.library("libdl.so", include_bytes!("../libdl.so"), false)?;
+        }
+
+        let adapter_names = self.adapter_names()?;
+
+        let found = self.find_needed_libraries(&self.libraries, &adapter_names)?;
+        if !found.is_empty() {
+            for (name, module, _) in found {
+                self.libraries.push((name, module, false));
+            }
+            return self.encode();
+        }
 
         let metadata = self
             .libraries
@@ -1283,45 +1558,7 @@ impl Linker {
             })
             .collect::<Result<Vec<_>>>()?;
 
-        {
-            let names = self
-                .libraries
-                .iter()
-                .map(|(name, ..)| name.as_str())
-                .collect_unique::<HashSet<_>>();
-
-            let missing = metadata
-                .iter()
-                .filter_map(|metadata| {
-                    let missing = metadata
-                        .needed_libs
-                        .iter()
-                        .copied()
-                        .filter(|name| !names.contains(*name))
-                        .collect::<Vec<_>>();
-
-                    if missing.is_empty() {
-                        None
-                    } else {
-                        Some((metadata.name, missing))
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            if !missing.is_empty() {
-                bail!(
-                    "missing libraries:\n{}",
-                    missing
-                        .iter()
-                        .map(|(needed_by, missing)| format!(
-                            "\t{needed_by} needs {}",
-                            missing.join(", ")
-                        ))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                );
-            }
-        }
+        check_needed_libraries(&metadata)?;
 
         let exporters = resolve_exporters(&metadata)?;
 
@@ -1338,32 +1575,14 @@ impl Linker {
         let (exporters, missing, _) = resolve_symbols(&metadata, &exporters);
 
         if !missing.is_empty() {
-            if missing
-                .iter()
-                .all(|(_, export)| matches!(&export.key.ty, Type::Function(_)))
-                && (self.stub_missing_functions
-                    || missing
-                        .iter()
-                        .all(|(_, export)| export.flags.contains(SymbolFlags::BINDING_WEAK)))
-            {
-                self.stub_missing_functions = false;
-                self.libraries.push((
-                    "wit-component:stubs".into(),
-                    make_stubs_module(&missing),
-                    false,
-                ));
-                return self.encode();
-            } else {
-                bail!(
-                    "unresolved symbol(s):\n{}",
-                    missing
-                        .iter()
-                        .filter(|(_, export)| !export.flags.contains(SymbolFlags::BINDING_WEAK))
-                        .map(|(importer, export)| { format!("\t{importer} needs {}", export.key) })
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                );
-            }
+            check_missing_symbols(&missing, self.stub_missing_functions)?;
+            self.stub_missing_functions = false;
+            self.libraries.push((
+                "wit-component:stubs".into(),
+                make_stubs_module(&missing),
+                false,
+            ));
+            return self.encode();
         }
 
         let dependencies = find_dependencies(&metadata, &exporters)?;
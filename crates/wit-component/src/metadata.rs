@@ -31,8 +31,14 @@
 //!   part of the bindings process. The mapping is defined by
 //!   `encode_string_encoding`.
 //!
+//! If some imports or exports use a different string encoding than the rest,
+//! then the first byte is instead `OVERRIDES_VERSION` and the two bytes are
+//! followed by a vector of overrides. Each override is a byte which is 0x00
+//! for an import and 0x01 for an export, the name of the item as a string, and
+//! the byte of its string encoding.
+//!
 //! This means that the top-level `encode` function takes a `Resolve`, a
-//! `WorldId`, and a `StringEncoding`. Note that the top-level `decode` function
+//! `WorldId`, and a set of `StringEncodings`. Note that the top-level `decode` function
 //! is slightly difference because it's taking all custom sections in a core
 //! wasm binary, possibly from multiple invocations of bindgen, and unioning
 //! them all together. This means that the output is a `Bindgen` which
@@ -47,15 +53,91 @@ use anyhow::{bail, Context, Result};
 use indexmap::IndexMap;
 use std::borrow::Cow;
 use wasm_encoder::{
-    ComponentBuilder, ComponentExportKind, ComponentType, ComponentTypeRef, CustomSection,
+    ComponentBuilder, ComponentExportKind, ComponentType, ComponentTypeRef, CustomSection, Encode,
 };
 use wasm_metadata::Producers;
 use wasmparser::{BinaryReader, Encoding, Parser, Payload, WasmFeatures};
 use wit_parser::{Package, PackageName, Resolve, World, WorldId, WorldItem};
 
 const CURRENT_VERSION: u8 = 0x04;
+const OVERRIDES_VERSION: u8 = 0x05;
 const CUSTOM_SECTION_NAME: &str = "wit-component-encoding";
 
+/// The string encodings used by the imports and exports of a world bound in a
+/// core wasm module.
+///
+/// Every import and export uses the `default` encoding unless it's listed in
+/// `imports` or `exports`. Items are named by the name of an interface or a
+/// function in the world, for example `wasi:cli/environment@0.2.0`, or by an
+/// interface and one of its functions joined with `#`, for example
+/// `wasi:cli/environment@0.2.0#get-arguments`. The most specific name wins.
+///
+/// This enables a module mixing languages, such as a C library using UTF-16
+/// linked with Rust using UTF-8, to describe each of its bindings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringEncodings {
+    /// The encoding used by items which aren't listed otherwise.
+    pub default: StringEncoding,
+    /// Encodings of specific imports.
+    pub imports: IndexMap<String, StringEncoding>,
+    /// Encodings of specific exports.
+    pub exports: IndexMap<String, StringEncoding>,
+}
+
+impl StringEncodings {
+    fn get(
+        &self,
+        map: &IndexMap<String, StringEncoding>,
+        name: &str,
+        func: Option<&str>,
+    ) -> StringEncoding {
+        func.and_then(|func| map.get(&format!("{name}#{func}")))
+            .or_else(|| map.get(name))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Returns an error if any item listed isn't imported or exported by
+    /// `world`.
+    fn validate(&self, resolve: &Resolve, world: WorldId) -> Result<()> {
+        let world = &resolve.worlds[world];
+        for (desc, items, map) in [
+            ("import", &world.imports, &self.imports),
+            ("export", &world.exports, &self.exports),
+        ] {
+            let mut names = Vec::new();
+            for (key, item) in items.iter() {
+                let name = resolve.name_world_key(key);
+                match item {
+                    WorldItem::Function(_) => names.push(name),
+                    WorldItem::Interface { id, .. } => {
+                        for func in resolve.interfaces[*id].functions.keys() {
+                            names.push(format!("{name}#{func}"));
+                        }
+                        names.push(name);
+                    }
+                    WorldItem::Type(_) => {}
+                }
+            }
+            for name in map.keys() {
+                if !names.contains(name) {
+                    bail!("string encoding specified for `{name}` but the world has no {desc} of that name");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<StringEncoding> for StringEncodings {
+    fn from(default: StringEncoding) -> StringEncodings {
+        StringEncodings {
+            default,
+            ..Default::default()
+        }
+    }
+}
+
 /// The result of decoding binding information from a WebAssembly binary.
 ///
 /// This structure is returned by [`decode`] and represents the interface of a
@@ -168,12 +250,17 @@ pub fn decode(wasm: &[u8]) -> Result<(Vec<u8>, Bindgen)> {
 /// into the final core wasm binary. The core wasm binary is later fed
 /// through `wit-component` to produce the actual component where this returned
 /// section will be decoded.
+///
+/// The `string_encodings` may be a single [`StringEncoding`] used by all
+/// imports and exports or [`StringEncodings`] listing the encoding of each.
 pub fn encode(
     resolve: &Resolve,
     world: WorldId,
-    string_encoding: StringEncoding,
+    string_encodings: impl Into<StringEncodings>,
     extra_producers: Option<&Producers>,
 ) -> Result<Vec<u8>> {
+    let string_encodings = string_encodings.into();
+    string_encodings.validate(resolve, world)?;
     let ty = crate::encoding::encode_world(resolve, world)?;
 
     let world = &resolve.worlds[world];
@@ -186,10 +273,9 @@ pub fn encode(
 
     let mut builder = ComponentBuilder::default();
 
-    let string_encoding = encode_string_encoding(string_encoding);
     builder.custom_section(&CustomSection {
         name: CUSTOM_SECTION_NAME.into(),
-        data: Cow::Borrowed(&[CURRENT_VERSION, string_encoding]),
+        data: Cow::Owned(encode_string_encodings(&string_encodings)),
     });
 
     let ty = builder.type_component(&outer_ty);
@@ -203,7 +289,47 @@ pub fn encode(
     Ok(builder.finish())
 }
 
-fn decode_custom_section(wasm: &[u8]) -> Result<(Resolve, WorldId, StringEncoding)> {
+fn encode_string_encodings(encodings: &StringEncodings) -> Vec<u8> {
+    let default = encode_string_encoding(encodings.default);
+    if encodings.imports.is_empty() && encodings.exports.is_empty() {
+        return vec![CURRENT_VERSION, default];
+    }
+    let mut data = vec![OVERRIDES_VERSION, default];
+    let overrides = encodings
+        .imports
+        .iter()
+        .map(|item| (0x00, item))
+        .chain(encodings.exports.iter().map(|item| (0x01, item)));
+    let len = u32::try_from(encodings.imports.len() + encodings.exports.len()).unwrap();
+    len.encode(&mut data);
+    for (kind, (name, encoding)) in overrides {
+        data.push(kind);
+        name.as_str().encode(&mut data);
+        data.push(encode_string_encoding(*encoding));
+    }
+    data
+}
+
+fn decode_string_encodings(data: &[u8]) -> Result<StringEncodings> {
+    let mut reader = BinaryReader::new(data, 0, WasmFeatures::all());
+    reader.read_u8()?;
+    let mut encodings = StringEncodings::from(decode_string_encoding(reader.read_u8()?)?);
+    for _ in 0..reader.read_var_u32()? {
+        let map = match reader.read_u8()? {
+            0x00 => &mut encodings.imports,
+            0x01 => &mut encodings.exports,
+            byte => bail!("invalid string encoding override kind {byte:#x}"),
+        };
+        let name = reader.read_string()?.to_string();
+        map.insert(name, decode_string_encoding(reader.read_u8()?)?);
+    }
+    if !reader.eof() {
+        bail!("trailing bytes in custom section `{CUSTOM_SECTION_NAME}`");
+    }
+    Ok(encodings)
+}
+
+fn decode_custom_section(wasm: &[u8]) -> Result<(Resolve, WorldId, StringEncodings)> {
     let (resolve, world) = wit_parser::decoding::decode_world(wasm)?;
     let mut custom_section = None;
 
@@ -215,15 +341,16 @@ fn decode_custom_section(wasm: &[u8]) -> Result<(Resolve, WorldId, StringEncodin
             _ => {}
         }
     }
-    let string_encoding = match custom_section {
+    let string_encodings = match custom_section {
         None => bail!("missing custom section of name `{CUSTOM_SECTION_NAME}`"),
-        Some([CURRENT_VERSION, byte]) => decode_string_encoding(*byte)?,
+        Some([CURRENT_VERSION, byte]) => StringEncodings::from(decode_string_encoding(*byte)?),
+        Some(data @ [OVERRIDES_VERSION, ..]) => decode_string_encodings(data)?,
         Some([]) => bail!("custom section `{CUSTOM_SECTION_NAME}` in unknown format"),
         Some([version, ..]) => bail!(
-            "custom section `{CUSTOM_SECTION_NAME}` uses format {version} but only {CURRENT_VERSION} is supported"
+            "custom section `{CUSTOM_SECTION_NAME}` uses format {version} but only {CURRENT_VERSION} and {OVERRIDES_VERSION} are supported"
         ),
     };
-    Ok((resolve, world, string_encoding))
+    Ok((resolve, world, string_encodings))
 }
 
 fn encode_string_encoding(e: StringEncoding) -> u8 {
@@ -255,7 +382,7 @@ impl Bindgen {
             // Historical 0x03 format where the support here will be deleted in
             // the future
             0x03 => {
-                encoding = decode_string_encoding(reader.read_u8()?)?;
+                encoding = decode_string_encoding(reader.read_u8()?)?.into();
                 let world_name = reader.read_string()?;
                 wasm = &data[reader.original_position()..];
 
@@ -289,8 +416,9 @@ impl Bindgen {
     /// merge operation only succeeds if the two metadata descriptions are
     /// entirely disjoint.
     ///
-    /// Note that at this time there's no support for changing string encodings
-    /// between metadata.
+    /// Each item keeps the string encoding it was bound with, so the two
+    /// metadata may use different encodings, but it's an error for an item
+    /// present in both to use different encodings.
     pub fn merge(&mut self, other: Bindgen) -> Result<WorldId> {
         let Bindgen {
             resolve,
@@ -348,8 +476,13 @@ impl Bindgen {
 
 impl ModuleMetadata {
     /// Creates a new `ModuleMetadata` instance holding the given set of
-    /// interfaces which are expected to all use the `encoding` specified.
-    pub fn new(resolve: &Resolve, world: WorldId, encoding: StringEncoding) -> ModuleMetadata {
+    /// interfaces which are expected to use the `encodings` specified.
+    pub fn new(
+        resolve: &Resolve,
+        world: WorldId,
+        encodings: impl Into<StringEncodings>,
+    ) -> ModuleMetadata {
+        let encodings = encodings.into();
         let mut ret = ModuleMetadata::default();
 
         let world = &resolve.worlds[world];
@@ -357,6 +490,7 @@ impl ModuleMetadata {
             let name = resolve.name_world_key(name);
             match item {
                 WorldItem::Function(_) => {
+                    let encoding = encodings.get(&encodings.imports, &name, None);
                     let prev = ret
                         .import_encodings
                         .insert((BARE_FUNC_MODULE_NAME.to_string(), name.clone()), encoding);
@@ -364,6 +498,7 @@ impl ModuleMetadata {
                 }
                 WorldItem::Interface { id, .. } => {
                     for (func, _) in resolve.interfaces[*id].functions.iter() {
+                        let encoding = encodings.get(&encodings.imports, &name, Some(func));
                         let prev = ret
                             .import_encodings
                             .insert((name.clone(), func.clone()), encoding);
@@ -378,12 +513,14 @@ impl ModuleMetadata {
             let name = resolve.name_world_key(name);
             match item {
                 WorldItem::Function(func) => {
+                    let encoding = encodings.get(&encodings.exports, &name, None);
                     let name = func.core_export_name(None).into_owned();
                     let prev = ret.export_encodings.insert(name.clone(), encoding);
                     assert!(prev.is_none());
                 }
                 WorldItem::Interface { id, .. } => {
                     for (_, func) in resolve.interfaces[*id].functions.iter() {
+                        let encoding = encodings.get(&encodings.exports, &name, Some(&func.name));
                         let name = func.core_export_name(Some(&name)).into_owned();
                         let prev = ret.export_encodings.insert(name, encoding);
                         assert!(prev.is_none());
@@ -487,6 +487,7 @@ impl WitPrinter {
             }
             Type::Char => self.output.push_str("char"),
             Type::String => self.output.push_str("string"),
+            Type::ErrorContext => self.output.push_str("error-context"),
 
             Type::Id(id) => {
                 let ty = &resolve.types[*id];
@@ -529,11 +530,11 @@ impl WitPrinter {
                         self.output.push_str(">");
                     }
                     TypeDefKind::Type(ty) => self.print_type_name(resolve, ty)?,
-                    TypeDefKind::Future(_) => {
-                        todo!("document has an unnamed future type")
+                    TypeDefKind::Future(t) => {
+                        self.print_future_type(resolve, t)?;
                     }
-                    TypeDefKind::Stream(_) => {
-                        todo!("document has an unnamed stream type")
+                    TypeDefKind::Stream(s) => {
+                        self.print_stream_type(resolve, s)?;
                     }
                     TypeDefKind::Unknown => unreachable!(),
                 }
@@ -652,7 +653,8 @@ impl WitPrinter {
             | Type::F32
             | Type::F64
             | Type::Char
-            | Type::String => return Ok(()),
+            | Type::String
+            | Type::ErrorContext => return Ok(()),
 
             Type::Id(id) => {
                 let ty = &resolve.types[*id];
@@ -690,8 +692,12 @@ impl WitPrinter {
                         }
                         None => bail!("unnamed type in document"),
                     },
-                    TypeDefKind::Future(_) => todo!("declare future"),
-                    TypeDefKind::Stream(_) => todo!("declare stream"),
+                    TypeDefKind::Future(inner) => {
+                        self.declare_future(resolve, ty.name.as_deref(), inner)?
+                    }
+                    TypeDefKind::Stream(inner) => {
+                        self.declare_stream(resolve, ty.name.as_deref(), inner)?
+                    }
                     TypeDefKind::Unknown => unreachable!(),
                 }
             }
@@ -878,6 +884,65 @@ impl WitPrinter {
         Ok(())
     }
 
+    fn declare_future(
+        &mut self,
+        resolve: &Resolve,
+        name: Option<&str>,
+        ty: &Option<Type>,
+    ) -> Result<()> {
+        if let Some(name) = name {
+            self.output.push_str("type ");
+            self.print_name(name);
+            self.output.push_str(" = ");
+            self.print_future_type(resolve, ty)?;
+            self.print_semicolon();
+            self.output.push_str("\n");
+        }
+
+        Ok(())
+    }
+
+    fn declare_stream(&mut self, resolve: &Resolve, name: Option<&str>, ty: &Stream) -> Result<()> {
+        if let Some(name) = name {
+            self.output.push_str("type ");
+            self.print_name(name);
+            self.output.push_str(" = ");
+            self.print_stream_type(resolve, ty)?;
+            self.print_semicolon();
+            self.output.push_str("\n");
+        }
+
+        Ok(())
+    }
+
+    fn print_future_type(&mut self, resolve: &Resolve, ty: &Option<Type>) -> Result<()> {
+        self.output.push_str("future");
+        if let Some(ty) = ty {
+            self.output.push_str("<");
+            self.print_type_name(resolve, ty)?;
+            self.output.push_str(">");
+        }
+        Ok(())
+    }
+
+    fn print_stream_type(&mut self, resolve: &Resolve, stream: &Stream) -> Result<()> {
+        self.output.push_str("stream");
+        if stream.element.is_none() && stream.end.is_none() {
+            return Ok(());
+        }
+        self.output.push_str("<");
+        match &stream.element {
+            Some(ty) => self.print_type_name(resolve, ty)?,
+            None => self.output.push_str("_"),
+        }
+        if let Some(end) = &stream.end {
+            self.output.push_str(", ");
+            self.print_type_name(resolve, end)?;
+        }
+        self.output.push_str(">");
+        Ok(())
+    }
+
     fn print_name(&mut self, name: &str) {
         if is_keyword(name) {
             self.output.push_str("%");
@@ -900,7 +965,7 @@ impl WitPrinter {
     fn print_stability(&mut self, stability: &Stability) {
         match stability {
             Stability::Unknown => {}
-            Stability::Stable { since, feature } => {
+            Stability::Stable { since, feature, .. } => {
                 self.output.push_str("@since(version = ");
                 self.output.push_str(&since.to_string());
                 if let Some(feature) = feature {
@@ -909,12 +974,17 @@ impl WitPrinter {
                 }
                 self.output.push_str(")\n");
             }
-            Stability::Unstable { feature } => {
+            Stability::Unstable { feature, .. } => {
                 self.output.push_str("@unstable(feature = ");
                 self.output.push_str(feature);
                 self.output.push_str(")\n");
             }
         }
+        if let Some(version) = stability.deprecated() {
+            self.output.push_str("@deprecated(version = ");
+            self.output.push_str(&version.to_string());
+            self.output.push_str(")\n");
+        }
     }
 }
 
@@ -953,6 +1023,7 @@ fn is_keyword(name: &str) -> bool {
             | "enum"
             | "bool"
             | "string"
+            | "error-context"
             | "option"
             | "result"
             | "future"
@@ -0,0 +1,406 @@
+//! Support for returning fixed results, written in WAVE, from the functions
+//! of generated modules, as used by [`DummyComponent`](crate::DummyComponent)
+//! and [`ImportStubber`](crate::ImportStubber).
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use wasm_wave::value::{resolve_wit_func_type, Value};
+use wasm_wave::wasm::{WasmFunc, WasmValue};
+use wit_parser::abi::AbiVariant;
+use wit_parser::{
+    AddressSize, Function, Handle, Int, Resolve, SizeAlign, Type, TypeDefKind, TypeId,
+};
+
+/// Builds the bodies of exported functions which return fixed results, along
+/// with the initial contents of linear memory holding those results in their
+/// canonical ABI representation.
+pub(crate) struct ResultData<'a> {
+    resolve: &'a Resolve,
+    bytes: Vec<u8>,
+    sizes: SizeAlign,
+    /// The `$id` of the `[resource-new]` import of each resource exported by
+    /// the module, used to create handles to return.
+    resources: HashMap<TypeId, String>,
+}
+
+impl<'a> ResultData<'a> {
+    pub fn new(resolve: &'a Resolve, resources: HashMap<TypeId, String>) -> ResultData<'a> {
+        let mut sizes = SizeAlign::new(AddressSize::Wasm32);
+        sizes.fill(resolve);
+        ResultData {
+            resolve,
+            // Leave the first few bytes unused so no value is placed at
+            // address 0.
+            bytes: vec![0; 8],
+            sizes,
+            resources,
+        }
+    }
+
+    /// Returns the initial contents of linear memory.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns a body for `func` which returns the WAVE-encoded `value`.
+    ///
+    /// A function with a single result takes a value of that type, a
+    /// function with several results takes a tuple of them, and a function
+    /// without results takes `()`.
+    pub fn value_body(&mut self, func: &Function, value: &str) -> Result<String> {
+        if func.results.len() == 0 {
+            if value.trim() != "()" {
+                bail!("function has no results so its value must be `()`");
+            }
+            return Ok(String::new());
+        }
+
+        let func_ty = resolve_wit_func_type(self.resolve, func)?;
+        let mut tys = func_ty.results().collect::<Vec<_>>();
+        let values = if tys.len() == 1 {
+            vec![wasm_wave::from_str::<Value>(&tys.pop().unwrap(), value)?]
+        } else {
+            let ty = wasm_wave::value::Type::tuple(tys).unwrap();
+            let value = wasm_wave::from_str::<Value>(&ty, value)?;
+            value.unwrap_tuple().map(|v| v.into_owned()).collect()
+        };
+        self.body(func, Some(&values))
+    }
+
+    /// Returns a body for `func` which returns zero values: numbers are zero,
+    /// strings and lists are empty, options are `none`, and the first case is
+    /// used for variants, enums and results.
+    ///
+    /// Handles are created for resources exported by the module, and an
+    /// error is returned if a result contains a handle to an imported
+    /// resource.
+    #[cfg(feature = "dummy-component")]
+    pub fn zero_body(&mut self, func: &Function) -> Result<String> {
+        self.body(func, None)
+    }
+
+    fn body(&mut self, func: &Function, values: Option<&[Value]>) -> Result<String> {
+        let results = func.results.iter_types().copied().collect::<Vec<_>>();
+        if results.is_empty() {
+            return Ok(String::new());
+        }
+
+        let sig = self.resolve.wasm_signature(AbiVariant::GuestExport, func);
+        if sig.retptr {
+            let (size, align) = self.sizes.record(results.iter());
+            let ptr = self.alloc(size, align);
+            let offsets = self.sizes.field_offsets(results.iter());
+            let mut body = String::new();
+            for (i, (offset, ty)) in offsets.into_iter().enumerate() {
+                let value = values.map(|v| &v[i]);
+                self.store(ty, value, ptr + offset, &mut body)?;
+            }
+            body.push_str(&format!("i32.const {ptr}"));
+            return Ok(body);
+        }
+
+        assert_eq!(sig.results.len(), 1);
+        self.flat(&results[0], values.map(|v| &v[0]))
+    }
+
+    fn alloc(&mut self, size: usize, align: usize) -> usize {
+        let ptr = self.bytes.len().next_multiple_of(align.max(1));
+        self.bytes.resize(ptr + size, 0);
+        ptr
+    }
+
+    fn write(&mut self, offset: usize, bytes: &[u8]) {
+        self.bytes[offset..][..bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn write_int(&mut self, offset: usize, int: Int, value: u64) {
+        let bytes = value.to_le_bytes();
+        self.write(offset, &bytes[..int_size(int)]);
+    }
+
+    fn write_ptr_len(&mut self, offset: usize, ptr: usize, len: usize) {
+        self.write(offset, &(ptr as u32).to_le_bytes());
+        self.write(offset + 4, &(len as u32).to_le_bytes());
+    }
+
+    /// Stores `value` of type `ty` at `offset` in the canonical ABI's memory
+    /// representation, allocating space for any strings and lists.
+    ///
+    /// A `value` of `None` stores the zero value of `ty`, which is all zero
+    /// bytes except for handles. Handles are created at runtime, so the
+    /// instructions to create and store them are appended to `body`.
+    fn store(
+        &mut self,
+        ty: &Type,
+        value: Option<&Value>,
+        offset: usize,
+        body: &mut String,
+    ) -> Result<()> {
+        let Some(value) = value else {
+            return self.store_zero(ty, offset, body);
+        };
+        match ty {
+            Type::Bool => self.write(offset, &[value.unwrap_bool() as u8]),
+            Type::U8 => self.write(offset, &value.unwrap_u8().to_le_bytes()),
+            Type::S8 => self.write(offset, &value.unwrap_s8().to_le_bytes()),
+            Type::U16 => self.write(offset, &value.unwrap_u16().to_le_bytes()),
+            Type::S16 => self.write(offset, &value.unwrap_s16().to_le_bytes()),
+            Type::U32 => self.write(offset, &value.unwrap_u32().to_le_bytes()),
+            Type::S32 => self.write(offset, &value.unwrap_s32().to_le_bytes()),
+            Type::U64 => self.write(offset, &value.unwrap_u64().to_le_bytes()),
+            Type::S64 => self.write(offset, &value.unwrap_s64().to_le_bytes()),
+            Type::F32 => self.write(offset, &value.unwrap_float32().to_le_bytes()),
+            Type::F64 => self.write(offset, &value.unwrap_float64().to_le_bytes()),
+            Type::Char => self.write(offset, &(value.unwrap_char() as u32).to_le_bytes()),
+            Type::String => {
+                let s = value.unwrap_string();
+                let ptr = self.alloc(s.len(), 1);
+                self.write(ptr, s.as_bytes());
+                self.write_ptr_len(offset, ptr, s.len());
+            }
+            Type::ErrorContext => bail!("cannot return a constant `error-context`"),
+            Type::Id(id) => match &self.resolve.types[*id].kind {
+                TypeDefKind::Type(ty) => self.store(ty, Some(value), offset, body)?,
+                TypeDefKind::Record(r) => {
+                    let offsets = self.sizes.field_offsets(r.fields.iter().map(|f| &f.ty));
+                    for ((field, ty), (_, value)) in offsets.into_iter().zip(value.unwrap_record())
+                    {
+                        self.store(ty, Some(&value), offset + field, body)?;
+                    }
+                }
+                TypeDefKind::Tuple(t) => {
+                    let offsets = self.sizes.field_offsets(t.types.iter());
+                    for ((field, ty), value) in offsets.into_iter().zip(value.unwrap_tuple()) {
+                        self.store(ty, Some(&value), offset + field, body)?;
+                    }
+                }
+                TypeDefKind::Flags(f) => {
+                    for name in value.unwrap_flags() {
+                        let bit = f.flags.iter().position(|f| f.name == name).unwrap();
+                        self.bytes[offset + bit / 8] |= 1 << (bit % 8);
+                    }
+                }
+                TypeDefKind::Enum(e) => {
+                    let name = value.unwrap_enum();
+                    let case = e.cases.iter().position(|c| c.name == name).unwrap();
+                    self.write_int(offset, e.tag(), case as u64);
+                }
+                TypeDefKind::Variant(v) => {
+                    let (name, payload) = value.unwrap_variant();
+                    let case = v.cases.iter().position(|c| c.name == name).unwrap();
+                    self.write_int(offset, v.tag(), case as u64);
+                    if let (Some(ty), Some(payload)) = (&v.cases[case].ty, payload) {
+                        let cases = v.cases.iter().map(|c| c.ty.as_ref());
+                        let payload_offset = self.sizes.payload_offset(v.tag(), cases);
+                        self.store(ty, Some(&payload), offset + payload_offset, body)?;
+                    }
+                }
+                TypeDefKind::Option(ty) => match value.unwrap_option() {
+                    Some(payload) => {
+                        self.write_int(offset, Int::U8, 1);
+                        let payload_offset = self.sizes.payload_offset(Int::U8, [None, Some(ty)]);
+                        self.store(ty, Some(&payload), offset + payload_offset, body)?;
+                    }
+                    None => self.write_int(offset, Int::U8, 0),
+                },
+                TypeDefKind::Result(r) => {
+                    let payload_offset = self
+                        .sizes
+                        .payload_offset(Int::U8, [r.ok.as_ref(), r.err.as_ref()]);
+                    let (case, ty, payload) = match value.unwrap_result() {
+                        Ok(payload) => (0, r.ok, payload),
+                        Err(payload) => (1, r.err, payload),
+                    };
+                    self.write_int(offset, Int::U8, case);
+                    if let (Some(ty), Some(payload)) = (ty, payload) {
+                        self.store(&ty, Some(&payload), offset + payload_offset, body)?;
+                    }
+                }
+                TypeDefKind::List(ty) => {
+                    let values = value.unwrap_list().collect::<Vec<_>>();
+                    let size = self.sizes.size(ty);
+                    let ptr = self.alloc(size * values.len(), self.sizes.align(ty));
+                    for (i, value) in values.iter().enumerate() {
+                        self.store(ty, Some(value), ptr + i * size, body)?;
+                    }
+                    self.write_ptr_len(offset, ptr, values.len());
+                }
+                kind @ (TypeDefKind::Resource
+                | TypeDefKind::Handle(_)
+                | TypeDefKind::Future(_)
+                | TypeDefKind::Stream(_)
+                | TypeDefKind::Unknown) => {
+                    bail!("cannot return a constant `{}`", kind.as_str())
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Stores the zero value of `ty` at `offset`, which only requires
+    /// creating any handles it contains as memory starts zeroed.
+    fn store_zero(&mut self, ty: &Type, offset: usize, body: &mut String) -> Result<()> {
+        let Type::Id(id) = ty else {
+            return Ok(());
+        };
+        match &self.resolve.types[*id].kind {
+            TypeDefKind::Type(ty) => self.store_zero(ty, offset, body)?,
+            TypeDefKind::Record(r) => {
+                let offsets = self.sizes.field_offsets(r.fields.iter().map(|f| &f.ty));
+                for (field, ty) in offsets {
+                    self.store_zero(ty, offset + field, body)?;
+                }
+            }
+            TypeDefKind::Tuple(t) => {
+                let offsets = self.sizes.field_offsets(t.types.iter());
+                for (field, ty) in offsets {
+                    self.store_zero(ty, offset + field, body)?;
+                }
+            }
+            TypeDefKind::Variant(v) => {
+                if let Some(ty) = &v.cases[0].ty {
+                    let cases = v.cases.iter().map(|c| c.ty.as_ref());
+                    let payload_offset = self.sizes.payload_offset(v.tag(), cases);
+                    self.store_zero(ty, offset + payload_offset, body)?;
+                }
+            }
+            TypeDefKind::Result(r) => {
+                if let Some(ty) = &r.ok {
+                    let payload_offset = self
+                        .sizes
+                        .payload_offset(Int::U8, [r.ok.as_ref(), r.err.as_ref()]);
+                    self.store_zero(ty, offset + payload_offset, body)?;
+                }
+            }
+            TypeDefKind::Handle(Handle::Own(resource) | Handle::Borrow(resource)) => {
+                let handle = self.new_handle(*resource)?;
+                body.push_str(&format!("(i32.store (i32.const {offset}) {handle})\n"));
+            }
+            TypeDefKind::Future(_) | TypeDefKind::Stream(_) => {
+                bail!(
+                    "cannot return a `{}`",
+                    self.resolve.types[*id].kind.as_str()
+                )
+            }
+            TypeDefKind::Flags(_)
+            | TypeDefKind::Enum(_)
+            | TypeDefKind::Option(_)
+            | TypeDefKind::List(_)
+            | TypeDefKind::Resource
+            | TypeDefKind::Unknown => {}
+        }
+        Ok(())
+    }
+
+    /// Returns the instructions producing the single core wasm value which
+    /// `value` of type `ty`, or its zero value, flattens to.
+    ///
+    /// This is only called for results which the canonical ABI returns
+    /// directly rather than through memory, which are necessarily scalars or
+    /// aggregates containing a single scalar.
+    fn flat(&self, ty: &Type, value: Option<&Value>) -> Result<String> {
+        let i32 = |f: fn(&Value) -> i64| {
+            let bits = value.map(f).unwrap_or(0);
+            format!("i32.const {}", bits as i32)
+        };
+        let i64 = |bits: Option<i64>| format!("i64.const {}", bits.unwrap_or(0));
+        Ok(match ty {
+            Type::Bool => i32(|v| v.unwrap_bool().into()),
+            Type::U8 => i32(|v| v.unwrap_u8().into()),
+            Type::S8 => i32(|v| v.unwrap_s8().into()),
+            Type::U16 => i32(|v| v.unwrap_u16().into()),
+            Type::S16 => i32(|v| v.unwrap_s16().into()),
+            Type::U32 => i32(|v| v.unwrap_u32().into()),
+            Type::S32 => i32(|v| v.unwrap_s32().into()),
+            Type::Char => i32(|v| u32::from(v.unwrap_char()).into()),
+            Type::U64 => i64(value.map(|v| v.unwrap_u64() as i64)),
+            Type::S64 => i64(value.map(|v| v.unwrap_s64())),
+            Type::F32 => {
+                let bits = value.map(|v| v.unwrap_float32().to_bits()).unwrap_or(0);
+                format!("i32.const {} f32.reinterpret_i32", bits as i32)
+            }
+            Type::F64 => {
+                let bits = value.map(|v| v.unwrap_float64().to_bits()).unwrap_or(0);
+                format!("i64.const {} f64.reinterpret_i64", bits as i64)
+            }
+            Type::String | Type::ErrorContext => unreachable!(),
+            Type::Id(id) => match &self.resolve.types[*id].kind {
+                TypeDefKind::Type(ty) => self.flat(ty, value)?,
+                TypeDefKind::Record(r) => {
+                    let field = value.map(|v| v.unwrap_record().next().unwrap().1);
+                    self.flat(&r.fields[0].ty, field.as_deref())?
+                }
+                TypeDefKind::Tuple(t) => {
+                    let field = value.map(|v| v.unwrap_tuple().next().unwrap());
+                    self.flat(&t.types[0], field.as_deref())?
+                }
+                TypeDefKind::Flags(f) => {
+                    let bits = value.map(|v| {
+                        v.unwrap_flags()
+                            .map(|name| f.flags.iter().position(|f| f.name == name).unwrap())
+                            .fold(0u32, |bits, bit| bits | (1 << bit))
+                    });
+                    format!("i32.const {}", bits.unwrap_or(0) as i32)
+                }
+                TypeDefKind::Enum(e) => {
+                    let case = value.map(|v| {
+                        let name = v.unwrap_enum();
+                        e.cases.iter().position(|c| c.name == name).unwrap()
+                    });
+                    format!("i32.const {}", case.unwrap_or(0))
+                }
+                TypeDefKind::Variant(v) => {
+                    let case = value.map(|value| {
+                        let (name, _) = value.unwrap_variant();
+                        v.cases.iter().position(|c| c.name == name).unwrap()
+                    });
+                    format!("i32.const {}", case.unwrap_or(0))
+                }
+                TypeDefKind::Result(_) => match value.map(|v| v.unwrap_result().is_ok()) {
+                    Some(false) => "i32.const 1".to_string(),
+                    _ => "i32.const 0".to_string(),
+                },
+                TypeDefKind::Handle(Handle::Own(resource) | Handle::Borrow(resource)) => {
+                    if value.is_some() {
+                        bail!("cannot return a constant `handle`");
+                    }
+                    self.new_handle(*resource)?
+                }
+                kind @ (TypeDefKind::Future(_) | TypeDefKind::Stream(_)) => {
+                    bail!("cannot return a `{}`", kind.as_str())
+                }
+                TypeDefKind::Option(_)
+                | TypeDefKind::List(_)
+                | TypeDefKind::Resource
+                | TypeDefKind::Unknown => unreachable!(),
+            },
+        })
+    }
+
+    /// Returns the instructions creating a new handle to `resource`, which
+    /// must be exported by the module.
+    fn new_handle(&self, mut resource: TypeId) -> Result<String> {
+        while let TypeDefKind::Type(Type::Id(id)) = self.resolve.types[resource].kind {
+            resource = id;
+        }
+        match self.resources.get(&resource) {
+            Some(id) => Ok(format!("(call {id} (i32.const 0))")),
+            None => bail!(
+                "cannot create a handle to the imported resource `{}`",
+                self.resolve.types[resource]
+                    .name
+                    .as_deref()
+                    .unwrap_or("<unnamed>")
+            ),
+        }
+    }
+}
+
+fn int_size(int: Int) -> usize {
+    match int {
+        Int::U8 => 1,
+        Int::U16 => 2,
+        Int::U32 => 4,
+        Int::U64 => 8,
+    }
+}
@@ -0,0 +1,193 @@
+use crate::dummy::{dummy_module_with, DummyOptions};
+use crate::result_data::ResultData;
+use crate::{decode, embed_component_metadata, ComponentEncoder, DecodedWasm, StringEncoding};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use wasm_compose::graph::{Component, CompositionGraph, EncodeOptions};
+use wit_parser::{Resolve, World, WorldId, WorldItem};
+
+/// Removes imported interfaces from a component by composing it with a
+/// generated component which implements them.
+///
+/// By default every function of a stubbed interface traps when called.
+/// Functions can instead be configured with [`ImportStubber::result`] to
+/// return a constant value, written in the WebAssembly Value Encoding (WAVE).
+///
+/// This is useful for running a component on a host which lacks some of the
+/// component's imports, such as `wasi:sockets`, when those imports are known
+/// to be unused or can be given a fixed behavior.
+#[derive(Default)]
+pub struct ImportStubber {
+    interfaces: Vec<String>,
+    results: IndexMap<String, String>,
+    validate: bool,
+}
+
+impl ImportStubber {
+    /// Stubs out the imported interface `name`, for example
+    /// `wasi:sockets/tcp@0.2.0`.
+    pub fn interface(mut self, name: impl Into<String>) -> Self {
+        self.interfaces.push(name.into());
+        self
+    }
+
+    /// Configures the stubbed function `func` to return `value` rather than
+    /// trap.
+    ///
+    /// The function is named as it is exported from a core module, for
+    /// example `wasi:cli/environment@0.2.0#get-arguments`. The `value` is
+    /// parsed as WAVE according to the function's results: a function with a
+    /// single result takes a value of that type, a function with several
+    /// results takes a tuple of them, and a function without results takes
+    /// `()` to make it return without trapping.
+    ///
+    /// Results containing resources, futures or streams aren't supported.
+    pub fn result(mut self, func: impl Into<String>, value: impl Into<String>) -> Self {
+        self.results.insert(func.into(), value.into());
+        self
+    }
+
+    /// Sets whether the stub and final components are validated.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Stubs the configured interfaces of `component`, returning a new
+    /// component which no longer imports them.
+    pub fn encode(&self, component: &[u8]) -> Result<Vec<u8>> {
+        let (mut resolve, world) = match decode(component)? {
+            DecodedWasm::Component(resolve, world) => (resolve, world),
+            DecodedWasm::WitPackages(..) => bail!("input is a WIT package, not a component"),
+        };
+        if self.interfaces.is_empty() {
+            bail!("no interfaces were specified to stub");
+        }
+
+        let stub_world = self.stub_world(&mut resolve, world)?;
+        let stub = self.stub_component(&resolve, stub_world)?;
+
+        let mut graph = CompositionGraph::new();
+        let stub = graph.add_component(Component::from_bytes("stub", stub)?)?;
+        let main = graph.add_component(Component::from_bytes("component", component)?)?;
+        let stub_instance = graph.instantiate(stub)?;
+        let main_instance = graph.instantiate(main)?;
+        for name in self.interfaces.iter() {
+            let (export, _, _) = graph
+                .get_component(stub)
+                .unwrap()
+                .export_by_name(name)
+                .unwrap();
+            let (import, _) = graph
+                .get_component(main)
+                .unwrap()
+                .import_by_name(name)
+                .unwrap();
+            graph
+                .connect(stub_instance, Some(export), main_instance, import)
+                .with_context(|| format!("failed to connect stub of `{name}`"))?;
+        }
+        graph.encode(EncodeOptions {
+            define_components: true,
+            export: Some(main_instance),
+            validate: self.validate,
+        })
+    }
+
+    /// Creates a world which exports the interfaces to stub, importing
+    /// whatever those interfaces depend on.
+    fn stub_world(&self, resolve: &mut Resolve, world: WorldId) -> Result<WorldId> {
+        let w = &resolve.worlds[world];
+        let mut keys = HashSet::new();
+        for name in self.interfaces.iter() {
+            let key = w
+                .imports
+                .iter()
+                .find(|(key, item)| {
+                    matches!(item, WorldItem::Interface { .. })
+                        && resolve.name_world_key(key) == *name
+                })
+                .map(|(key, _)| key.clone());
+            match key {
+                Some(key) => keys.insert(key),
+                None => bail!("component does not import an interface named `{name}`"),
+            };
+        }
+
+        let stub = World {
+            name: format!("{}-stub", w.name),
+            imports: w.imports.clone(),
+            exports: Default::default(),
+            package: w.package,
+            docs: Default::default(),
+            stability: Default::default(),
+            includes: Default::default(),
+            include_names: Default::default(),
+        };
+        let keep = World {
+            name: format!("{}-stubbed", w.name),
+            imports: w
+                .imports
+                .iter()
+                .filter(|(key, _)| keys.contains(*key))
+                .map(|(key, item)| (key.clone(), item.clone()))
+                .collect(),
+            ..stub.clone()
+        };
+        let stub = resolve.worlds.alloc(stub);
+        let keep = resolve.worlds.alloc(keep);
+        if let Some(pkg) = resolve.worlds[world].package {
+            let name = resolve.worlds[stub].name.clone();
+            resolve.packages[pkg].worlds.insert(name, stub);
+        }
+
+        // Narrow the imports down to the stubbed interfaces and their
+        // dependencies, then turn the stubbed interfaces into exports.
+        resolve.intersect_worlds(keep, stub);
+        let w = &mut resolve.worlds[stub];
+        for key in keys {
+            let item = w.imports.shift_remove(&key).unwrap();
+            w.exports.insert(key, item);
+        }
+        Ok(stub)
+    }
+
+    /// Creates a component targeting `world` which implements all of its
+    /// exported functions.
+    fn stub_component(&self, resolve: &Resolve, world: WorldId) -> Result<Vec<u8>> {
+        let mut results = self.results.clone();
+        let mut options = DummyOptions {
+            skip_imports: true,
+            ..Default::default()
+        };
+        let mut data = ResultData::new(resolve, HashMap::new());
+        for (key, item) in resolve.worlds[world].exports.iter() {
+            let WorldItem::Interface { id, .. } = item else {
+                continue;
+            };
+            let name = resolve.name_world_key(key);
+            for (_, func) in resolve.interfaces[*id].functions.iter() {
+                let export = func.core_export_name(Some(&name)).into_owned();
+                if let Some(value) = results.shift_remove(&export) {
+                    let body = data
+                        .value_body(func, &value)
+                        .with_context(|| format!("invalid result for function `{export}`"))?;
+                    options.bodies.insert(export, body);
+                }
+            }
+        }
+        if let Some((func, _)) = results.first() {
+            bail!("no function named `{func}` was found in the stubbed interfaces");
+        }
+        options.data = Some(data.into_bytes());
+
+        let mut module = dummy_module_with(resolve, world, &options);
+        embed_component_metadata(&mut module, resolve, world, StringEncoding::UTF8)?;
+        ComponentEncoder::default()
+            .module(&module)?
+            .validate(self.validate)
+            .encode()
+            .context("failed to encode stub component")
+    }
+}
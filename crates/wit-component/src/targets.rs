@@ -1,13 +1,159 @@
-use crate::encoding::encode_world;
-use anyhow::{Context, Result};
+use crate::encoding::{component_features, encode_world};
+use crate::{decode, DecodedWasm};
+use anyhow::{bail, Context, Result};
+use serde_derive::Serialize;
+use std::fmt;
 use wasm_encoder::{ComponentBuilder, ComponentExportKind, ComponentTypeRef};
-use wasmparser::{Validator, WasmFeatures};
-use wit_parser::{Resolve, WorldId};
+use wasmparser::Validator;
+use wit_parser::{diff_worlds, ApiChangeKind, PathSegment, Resolve, WorldId};
 
 /// This function checks whether `component_to_test` correctly conforms to the world specified.
 /// It does so by instantiating a generated component that imports a component instance with
 /// the component type as described by the "target" world.
+///
+/// If the component does not conform then the returned error lists each of
+/// the [`TargetViolation`]s found, as returned by [`target_violations`].
 pub fn targets(resolve: &Resolve, world: WorldId, component_to_test: &[u8]) -> Result<()> {
+    let violations = target_violations(resolve, world, component_to_test)?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let w = &resolve.worlds[world];
+    let mut msg = format!("component does not conform to world `{}`:", w.name);
+    for violation in violations.iter() {
+        msg.push_str(&format!("\n    {violation}"));
+    }
+
+    // Point at the WIT definitions of the world items that were violated.
+    let mut noted = Vec::new();
+    for violation in violations.iter() {
+        let segment = &violation.path[0];
+        if noted.contains(&segment) {
+            continue;
+        }
+        noted.push(segment);
+        let location = match segment.kind {
+            "import" => w
+                .imports
+                .keys()
+                .find(|key| resolve.name_world_key(key) == segment.name)
+                .and_then(|key| resolve.source_locations.world_import(world, key)),
+            _ => w
+                .exports
+                .keys()
+                .find(|key| resolve.name_world_key(key) == segment.name)
+                .and_then(|key| resolve.source_locations.world_export(world, key)),
+        };
+        if let Some(location) = location {
+            msg.push_str(&format!(
+                "\nnote: `{}` is {}ed by world `{}` at {location}",
+                segment.name, segment.kind, w.name
+            ));
+        }
+    }
+    bail!(msg)
+}
+
+/// A way in which a component fails to conform to a world, as reported by
+/// [`target_violations`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TargetViolation {
+    /// The path to the offending item, starting at the world import or export
+    /// it's found within.
+    pub path: Vec<PathSegment>,
+    /// What is wrong with the item.
+    #[serde(flatten)]
+    pub kind: TargetViolationKind,
+}
+
+/// The kind of a [`TargetViolation`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum TargetViolationKind {
+    /// The world exports an item, such as an interface or a function within
+    /// an interface, which the component does not export.
+    MissingExport,
+    /// The component imports an item which the world does not import.
+    ExtraImport,
+    /// An item within a type or function signature, such as a record field
+    /// or parameter, is present in the world but not in the component.
+    Missing,
+    /// An item within a type or function signature is present in the
+    /// component but not in the world.
+    Unexpected,
+    /// The item has a different type or name in the component than in the
+    /// world.
+    Mismatch {
+        /// A description of the item in the world.
+        expected: String,
+        /// A description of the item in the component.
+        found: String,
+    },
+}
+
+impl fmt::Display for TargetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = |skip_kind: bool| {
+            self.path
+                .iter()
+                .enumerate()
+                .map(|(i, s)| match s.name.as_str() {
+                    "" => s.kind.to_string(),
+                    name if i == 0 && skip_kind => name.to_string(),
+                    name => format!("{} {name}", s.kind),
+                })
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+        match &self.kind {
+            TargetViolationKind::MissingExport => write!(f, "missing export `{}`", path(true)),
+            TargetViolationKind::ExtraImport => {
+                write!(f, "import `{}` is not allowed by the world", path(true))
+            }
+            TargetViolationKind::Missing => write!(f, "missing `{}`", path(false)),
+            TargetViolationKind::Unexpected => write!(f, "unexpected `{}`", path(false)),
+            TargetViolationKind::Mismatch { expected, found } => write!(
+                f,
+                "type mismatch at `{}`: expected `{expected}`, found `{found}`",
+                path(false)
+            ),
+        }
+    }
+}
+
+/// Returns the ways in which `component_to_test` fails to conform to the
+/// world specified, or an empty list if it conforms.
+///
+/// Conformance is checked in the same manner as [`targets`]. When the check
+/// fails the component's own world is compared with the target world to
+/// describe each mismatch in WIT terms. If no mismatch can be described this
+/// way then the underlying validation error is returned instead.
+pub fn target_violations(
+    resolve: &Resolve,
+    world: WorldId,
+    component_to_test: &[u8],
+) -> Result<Vec<TargetViolation>> {
+    let err = match validate(resolve, world, component_to_test) {
+        Ok(()) => return Ok(Vec::new()),
+        Err(err) => err,
+    };
+
+    let violations = match decode(component_to_test) {
+        Ok(DecodedWasm::Component(component_resolve, component_world)) => {
+            violations(resolve, world, &component_resolve, component_world)
+        }
+        _ => Vec::new(),
+    };
+    if violations.is_empty() {
+        return Err(err);
+    }
+    Ok(violations)
+}
+
+/// Tests `component_to_test` against `world` by instantiating it in a
+/// synthesized component, returning the validation error if that fails.
+fn validate(resolve: &Resolve, world: WorldId, component_to_test: &[u8]) -> Result<()> {
     let mut root_component = ComponentBuilder::default();
 
     // (1) Embed the component to test.
@@ -36,9 +182,45 @@ pub fn targets(resolve: &Resolve, world: WorldId, component_to_test: &[u8]) -> R
 
     let bytes = root_component.finish();
 
-    Validator::new_with_features(WasmFeatures::default() | WasmFeatures::COMPONENT_MODEL)
+    Validator::new_with_features(component_features())
         .validate_all(&bytes)
         .context("failed to validate encoded bytes")?;
 
     Ok(())
 }
+
+/// Compares the world of a component, `component`, with the target `world`.
+///
+/// This classifies the structural differences found by [`diff_worlds`]. The
+/// component may import less than the world and export more, so those
+/// differences aren't violations.
+fn violations(
+    resolve: &Resolve,
+    world: WorldId,
+    component_resolve: &Resolve,
+    component: WorldId,
+) -> Vec<TargetViolation> {
+    let mut violations = Vec::new();
+    for change in diff_worlds(component_resolve, component, resolve, world) {
+        let last = change.path.last().unwrap().kind;
+        let within_type = matches!(last, "param" | "result" | "field" | "case" | "flag");
+        let import = change.path[0].kind == "import";
+        let kind = match change.kind {
+            ApiChangeKind::DocsChanged => continue,
+            ApiChangeKind::Changed { prev, new } => TargetViolationKind::Mismatch {
+                expected: new,
+                found: prev,
+            },
+            ApiChangeKind::Added if within_type => TargetViolationKind::Missing,
+            ApiChangeKind::Removed if within_type => TargetViolationKind::Unexpected,
+            ApiChangeKind::Added if !import => TargetViolationKind::MissingExport,
+            ApiChangeKind::Removed if import => TargetViolationKind::ExtraImport,
+            ApiChangeKind::Added | ApiChangeKind::Removed => continue,
+        };
+        violations.push(TargetViolation {
+            path: change.path,
+            kind,
+        });
+    }
+    violations
+}
@@ -5,33 +5,43 @@ use std::mem;
 use wasmparser::names::{ComponentName, ComponentNameKind};
 use wasmparser::{
     types::Types, Encoding, ExternalKind, FuncType, Parser, Payload, TypeRef, ValType,
-    ValidPayload, Validator,
+    ValidPayload, Validator, WasmFeatures,
 };
 use wit_parser::{
     abi::{AbiVariant, WasmSignature, WasmType},
-    Function, InterfaceId, PackageName, Resolve, TypeDefKind, TypeId, WorldId, WorldItem, WorldKey,
+    Function, InterfaceId, PackageName, Resolve, SourceLocation, TypeDefKind, TypeId, WorldId,
+    WorldItem, WorldKey,
 };
 
-fn is_canonical_function(name: &str) -> bool {
+pub fn is_canonical_function(name: &str) -> bool {
     name.starts_with("cabi_") || name.starts_with("canonical_abi_")
 }
 
-fn wasm_sig_to_func_type(signature: WasmSignature) -> FuncType {
-    fn from_wasm_type(ty: &WasmType) -> ValType {
-        match ty {
-            WasmType::I32 => ValType::I32,
-            WasmType::I64 => ValType::I64,
-            WasmType::F32 => ValType::F32,
-            WasmType::F64 => ValType::F64,
-            WasmType::Pointer => ValType::I32,
-            WasmType::PointerOrI64 => ValType::I64,
-            WasmType::Length => ValType::I32,
-        }
+/// Returns the core wasm type of `ty`, where pointers and lengths are 64-bit
+/// if `memory64` is set.
+pub fn wasm_type_to_val_type(ty: &WasmType, memory64: bool) -> ValType {
+    match ty {
+        WasmType::I32 => ValType::I32,
+        WasmType::I64 => ValType::I64,
+        WasmType::F32 => ValType::F32,
+        WasmType::F64 => ValType::F64,
+        WasmType::Pointer | WasmType::Length if memory64 => ValType::I64,
+        WasmType::Pointer => ValType::I32,
+        WasmType::PointerOrI64 => ValType::I64,
+        WasmType::Length => ValType::I32,
     }
+}
 
+pub fn wasm_sig_to_func_type(signature: WasmSignature, memory64: bool) -> FuncType {
     FuncType::new(
-        signature.params.iter().map(from_wasm_type),
-        signature.results.iter().map(from_wasm_type),
+        signature
+            .params
+            .iter()
+            .map(|ty| wasm_type_to_val_type(ty, memory64)),
+        signature
+            .results
+            .iter()
+            .map(|ty| wasm_type_to_val_type(ty, memory64)),
     )
 }
 
@@ -88,6 +98,10 @@ pub struct ValidatedModule<'a> {
     /// Whether or not this module exported a linear memory.
     pub has_memory: bool,
 
+    /// Whether or not the exported linear memory is a 64-bit memory, in which
+    /// case pointers and lengths in the canonical ABI are `i64` values.
+    pub memory64: bool,
+
     /// Whether or not this module exported a `cabi_realloc` function.
     pub realloc: Option<&'a str>,
 
@@ -133,14 +147,17 @@ pub fn validate_module<'a>(
     exports: &IndexSet<WorldKey>,
     adapters: &IndexSet<&str>,
 ) -> Result<ValidatedModule<'a>> {
-    let mut validator = Validator::new();
+    let mut validator =
+        Validator::new_with_features(WasmFeatures::default() | WasmFeatures::MEMORY64);
     let mut types = None;
     let mut import_funcs = IndexMap::new();
     let mut export_funcs = IndexMap::new();
+    let mut memory = None;
     let mut ret = ValidatedModule {
         required_imports: Default::default(),
         adapters_required: Default::default(),
         has_memory: false,
+        memory64: false,
         realloc: None,
         adapter_realloc: None,
         metadata: &metadata.metadata,
@@ -199,6 +216,7 @@ pub fn validate_module<'a>(
                         ExternalKind::Memory => {
                             if export.name == "memory" {
                                 ret.has_memory = true;
+                                memory = Some(export.index);
                             }
                         }
                         _ => continue,
@@ -210,6 +228,9 @@ pub fn validate_module<'a>(
     }
 
     let types = types.unwrap();
+    if let Some(memory) = memory {
+        ret.memory64 = types.memory_at(memory).memory64;
+    }
     let world = &metadata.resolve.worlds[metadata.world];
     let mut exported_resource_funcs = Vec::new();
 
@@ -217,8 +238,13 @@ pub fn validate_module<'a>(
         // An empty module name is indicative of the top-level import namespace,
         // so look for top-level functions here.
         if *name == BARE_FUNC_MODULE_NAME {
-            let required =
-                validate_imports_top_level(&metadata.resolve, metadata.world, funcs, &types)?;
+            let required = validate_imports_top_level(
+                &metadata.resolve,
+                metadata.world,
+                funcs,
+                &types,
+                ret.memory64,
+            )?;
             let prev = ret.required_imports.insert(BARE_FUNC_MODULE_NAME, required);
             assert!(prev.is_none());
             continue;
@@ -244,6 +270,7 @@ pub fn validate_module<'a>(
                         name,
                         funcs,
                         &types,
+                        ret.memory64,
                     )
                     .with_context(|| format!("failed to validate import interface `{name}`"))?;
                     let prev = ret.required_imports.insert(name, required);
@@ -264,6 +291,7 @@ pub fn validate_module<'a>(
             &metadata.resolve.name_world_key(name),
             &export_funcs,
             &types,
+            ret.memory64,
             &mut ret.post_returns,
             &mut ret.required_resource_funcs,
         )?;
@@ -350,6 +378,9 @@ pub struct ValidatedAdapter<'a> {
     /// import from the `required_import` above.
     pub needs_memory: Option<(String, String)>,
 
+    /// Whether or not the imported memory is a 64-bit memory.
+    pub memory64: bool,
+
     /// Set of names required to be exported from the main module which are
     /// imported by this adapter through the `__main_module__` synthetic export.
     /// This is how the WASI adapter imports `_start`, for example.
@@ -397,7 +428,8 @@ pub fn validate_adapter_module<'a>(
     is_library: bool,
     adapters: &IndexSet<&str>,
 ) -> Result<ValidatedAdapter<'a>> {
-    let mut validator = Validator::new();
+    let mut validator =
+        Validator::new_with_features(WasmFeatures::default() | WasmFeatures::MEMORY64);
     let mut import_funcs = IndexMap::new();
     let mut export_funcs = IndexMap::new();
     let mut types = None;
@@ -406,6 +438,7 @@ pub fn validate_adapter_module<'a>(
         required_imports: Default::default(),
         required_resource_funcs: Default::default(),
         needs_memory: None,
+        memory64: false,
         needs_core_exports: Default::default(),
         import_realloc: None,
         export_realloc: None,
@@ -447,9 +480,10 @@ pub fn validate_adapter_module<'a>(
 
                         // A memory is allowed to be imported into the adapter
                         // module so that's skipped here
-                        TypeRef::Memory(_) => {
+                        TypeRef::Memory(ty) => {
                             ret.needs_memory =
                                 Some((import.module.to_string(), import.name.to_string()));
+                            ret.memory64 = ty.memory64;
                         }
 
                         TypeRef::Global(_) | TypeRef::Table(_) if is_library => (),
@@ -513,7 +547,7 @@ pub fn validate_adapter_module<'a>(
         // An empty module name is indicative of the top-level import namespace,
         // so look for top-level functions here.
         if *name == BARE_FUNC_MODULE_NAME {
-            let required = validate_imports_top_level(resolve, world, funcs, &types)?;
+            let required = validate_imports_top_level(resolve, world, funcs, &types, ret.memory64)?;
             ret.required_imports
                 .insert(BARE_FUNC_MODULE_NAME.to_string(), required);
             continue;
@@ -527,11 +561,15 @@ pub fn validate_adapter_module<'a>(
         if !(is_library && adapters.contains(name)) {
             match resolve.worlds[world].imports.get(&world_key(resolve, name)) {
                 Some(WorldItem::Interface { id: interface, .. }) => {
-                    let required =
-                        validate_imported_interface(resolve, *interface, name, funcs, &types)
-                            .with_context(|| {
-                                format!("failed to validate import interface `{name}`")
-                            })?;
+                    let required = validate_imported_interface(
+                        resolve,
+                        *interface,
+                        name,
+                        funcs,
+                        &types,
+                        ret.memory64,
+                    )
+                    .with_context(|| format!("failed to validate import interface `{name}`"))?;
                     let prev = ret.required_imports.insert(name.to_string(), required);
                     assert!(prev.is_none());
                 }
@@ -555,19 +593,18 @@ pub fn validate_adapter_module<'a>(
             };
             let id = types.core_function_at(idx);
             let actual = types[id].unwrap_func();
-            validate_func_sig(name, ty, actual)?;
+            validate_func_sig(name, None, ty, actual)?;
         }
     }
 
-    let world = &resolve.worlds[world];
-
     for name in exports {
         validate_exported_item(
             resolve,
-            &world.exports[name],
+            &resolve.worlds[world].exports[name],
             &resolve.name_world_key(name),
             &export_funcs,
             &types,
+            ret.memory64,
             &mut ret.post_returns,
             &mut ret.required_resource_funcs,
         )?;
@@ -575,7 +612,7 @@ pub fn validate_adapter_module<'a>(
 
     for (name, interface_name, funcs) in exported_resource_funcs {
         let world_key = world_key(resolve, interface_name);
-        match world.exports.get(&world_key) {
+        match resolve.worlds[world].exports.get(&world_key) {
             Some(WorldItem::Interface { id, .. }) => {
                 validate_exported_interface_resource_imports(
                     resolve,
@@ -621,6 +658,7 @@ fn validate_imports_top_level(
     world: WorldId,
     funcs: &IndexMap<&str, u32>,
     types: &Types,
+    memory64: bool,
 ) -> Result<RequiredImports> {
     let is_resource = |name: &str| match resolve.worlds[world]
         .imports
@@ -633,10 +671,11 @@ fn validate_imports_top_level(
     };
     let mut required = RequiredImports::default();
     for (name, ty) in funcs {
-        match resolve.worlds[world].imports.get(&world_key(resolve, name)) {
+        let key = world_key(resolve, name);
+        match resolve.worlds[world].imports.get(&key) {
             Some(WorldItem::Function(func)) => {
                 let ty = types[types.core_type_at(*ty).unwrap_sub()].unwrap_func();
-                validate_func(resolve, ty, func, AbiVariant::GuestImport)?;
+                validate_func(resolve, ty, func, AbiVariant::GuestImport, memory64, None)?;
             }
             Some(_) => bail!("expected world top-level import `{name}` to be a function"),
             None => match valid_imported_resource_func(name, *ty, types, is_resource)? {
@@ -661,7 +700,7 @@ fn valid_imported_resource_func<'a>(
         if is_resource(resource_name) {
             let ty = types[types.core_type_at(ty).unwrap_sub()].unwrap_func();
             let expected = FuncType::new([ValType::I32], []);
-            validate_func_sig(func_name, &expected, ty)?;
+            validate_func_sig(func_name, None, &expected, ty)?;
             return Ok(Some(resource_name));
         }
     }
@@ -684,7 +723,7 @@ fn valid_exported_resource_func<'a>(
         if is_resource(resource_name) {
             let ty = types[types.core_type_at(ty).unwrap_sub()].unwrap_func();
             let expected = FuncType::new([ValType::I32], [ValType::I32]);
-            validate_func_sig(func_name, &expected, ty)?;
+            validate_func_sig(func_name, None, &expected, ty)?;
             return Ok(Some(resource_name));
         }
     }
@@ -697,6 +736,7 @@ fn validate_imported_interface(
     name: &str,
     imports: &IndexMap<&str, u32>,
     types: &Types,
+    memory64: bool,
 ) -> Result<RequiredImports> {
     let mut required = RequiredImports::default();
     let is_resource = |name: &str| {
@@ -710,7 +750,10 @@ fn validate_imported_interface(
         match resolve.interfaces[interface].functions.get(*func_name) {
             Some(f) => {
                 let ty = types[types.core_type_at(*ty).unwrap_sub()].unwrap_func();
-                validate_func(resolve, ty, f, AbiVariant::GuestImport)?;
+                let location = resolve
+                    .source_locations
+                    .interface_function(interface, &f.name);
+                validate_func(resolve, ty, f, AbiVariant::GuestImport, memory64, location)?;
             }
             None => match valid_imported_resource_func(func_name, *ty, types, is_resource)? {
                 Some(name) => {
@@ -733,10 +776,13 @@ fn validate_func(
     ty: &wasmparser::FuncType,
     func: &Function,
     abi: AbiVariant,
+    memory64: bool,
+    location: Option<&SourceLocation>,
 ) -> Result<()> {
     validate_func_sig(
         &func.name,
-        &wasm_sig_to_func_type(resolve.wasm_signature(abi, func)),
+        location,
+        &wasm_sig_to_func_type(resolve.wasm_signature(abi, func), memory64),
         ty,
     )
 }
@@ -745,6 +791,7 @@ fn validate_post_return(
     resolve: &Resolve,
     ty: &wasmparser::FuncType,
     func: &Function,
+    memory64: bool,
 ) -> Result<()> {
     // The expected signature of a post-return function is to take all the
     // parameters that are returned by the guest function and then return no
@@ -755,16 +802,23 @@ fn validate_post_return(
     sig.params = mem::take(&mut sig.results);
     validate_func_sig(
         &format!("{} post-return", func.name),
-        &wasm_sig_to_func_type(sig),
+        None,
+        &wasm_sig_to_func_type(sig, memory64),
         ty,
     )
 }
 
-fn validate_func_sig(name: &str, expected: &FuncType, ty: &wasmparser::FuncType) -> Result<()> {
+pub fn validate_func_sig(
+    name: &str,
+    location: Option<&SourceLocation>,
+    expected: &FuncType,
+    ty: &wasmparser::FuncType,
+) -> Result<()> {
     if ty != expected {
         bail!(
-            "type mismatch for function `{}`: expected `{:?} -> {:?}` but found `{:?} -> {:?}`",
+            "type mismatch for function `{}`{}: expected `{:?} -> {:?}` but found `{:?} -> {:?}`",
             name,
+            defined_at(location),
             expected.params(),
             expected.results(),
             ty.params(),
@@ -775,27 +829,45 @@ fn validate_func_sig(name: &str, expected: &FuncType, ty: &wasmparser::FuncType)
     Ok(())
 }
 
+/// Renders where an item was defined in WIT source, if known, for use in error
+/// messages.
+fn defined_at(location: Option<&SourceLocation>) -> String {
+    match location {
+        Some(location) => format!(" (defined at {location})"),
+        None => String::new(),
+    }
+}
+
 fn validate_exported_item<'a>(
     resolve: &'a Resolve,
     item: &'a WorldItem,
     export_name: &str,
     exports: &IndexMap<&str, u32>,
     types: &Types,
+    memory64: bool,
     post_returns: &mut IndexSet<String>,
     required_resource_funcs: &mut IndexMap<String, IndexMap<String, ResourceInfo>>,
 ) -> Result<()> {
-    let mut validate = |func: &Function, name: Option<&str>| {
+    let mut validate = |func: &Function, name: Option<&str>, location| {
         let expected_export_name = func.core_export_name(name);
         let func_index = match exports.get(expected_export_name.as_ref()) {
             Some(func_index) => func_index,
             None => bail!(
-                "module does not export required function `{}`",
-                expected_export_name
+                "module does not export required function `{}`{}",
+                expected_export_name,
+                defined_at(location),
             ),
         };
         let id = types.core_function_at(*func_index);
         let ty = types[id].unwrap_func();
-        validate_func(resolve, ty, func, AbiVariant::GuestExport)?;
+        validate_func(
+            resolve,
+            ty,
+            func,
+            AbiVariant::GuestExport,
+            memory64,
+            location,
+        )?;
 
         let post_return = format!("{POST_RETURN_PREFIX}{expected_export_name}");
         if let Some(index) = exports.get(&post_return[..]) {
@@ -803,16 +875,19 @@ fn validate_exported_item<'a>(
             assert!(ok);
             let id = types.core_function_at(*index);
             let ty = types[id].unwrap_func();
-            validate_post_return(resolve, ty, func)?;
+            validate_post_return(resolve, ty, func, memory64)?;
         }
         Ok(())
     };
     match item {
-        WorldItem::Function(func) => validate(func, None)?,
-        WorldItem::Interface { id: interface, .. } => {
-            let interface = &resolve.interfaces[*interface];
+        // Functions of the world itself have no known location since the
+        // world of the module's metadata is synthesized when it's decoded.
+        WorldItem::Function(func) => validate(func, None, None)?,
+        WorldItem::Interface { id, .. } => {
+            let interface = &resolve.interfaces[*id];
             for (_, f) in interface.functions.iter() {
-                validate(f, Some(export_name)).with_context(|| {
+                let location = resolve.source_locations.interface_function(*id, &f.name);
+                validate(f, Some(export_name), location).with_context(|| {
                     format!("failed to validate exported interface `{export_name}`")
                 })?;
             }
@@ -833,7 +908,7 @@ fn validate_exported_item<'a>(
                     let id = types.core_function_at(*func_idx);
                     let ty = types[id].unwrap_func();
                     let expected = FuncType::new([ValType::I32], []);
-                    validate_func_sig(name, &expected, ty)?;
+                    validate_func_sig(name, None, &expected, ty)?;
                     info.dtor_export = Some(name.to_string());
                 }
                 let prev = map.insert(name.to_string(), info);
@@ -178,6 +178,18 @@ fn run_test(path: &Path) -> Result<()> {
         UnresolvedPackageGroup::parse(&component_wit_path, &wit)
             .context("failed to parse printed WIT")?;
 
+        // Check that the component can be split apart and rebuilt
+        let unbundled = wit_component::unbundle(&bytes).context("failed to unbundle")?;
+        let rebundled = wit_component::bundle(&unbundled.manifest, |file| {
+            let item = unbundled.items.iter().find(|i| i.file == file).unwrap();
+            Ok(item.bytes.clone())
+        })
+        .context("failed to bundle")?;
+        assert!(
+            rebundled == bytes,
+            "unbundled component failed to round-trip"
+        );
+
         // Check that the producer data got piped through properly
         let metadata = wasm_metadata::Metadata::from_binary(&bytes)?;
         match metadata {
@@ -0,0 +1,29 @@
+(module
+  (import "new" "get-two" (func $get_two (param i64)))
+  (import "env" "memory" (memory i64 0))
+
+  (global $__stack_pointer (mut i64) i64.const 0)
+
+  ;; Like `adapt-inject-stack` except that the adapter's memory, and therefore
+  ;; its stack pointer and the return pointer passed to `get-two`, are 64-bit.
+  (func (export "get_sum") (result i32)
+    (local i64 i64)
+
+    global.get $__stack_pointer
+    local.tee 0
+    i64.const 8
+    i64.sub
+    local.tee 1
+    global.set $__stack_pointer
+
+    local.get 1
+    call $get_two
+
+    (i32.add
+      (i32.load (local.get 1))
+      (i32.load offset=4 (local.get 1)))
+
+    local.get 0
+    global.set $__stack_pointer
+  )
+)
@@ -0,0 +1,4 @@
+(module
+  (import "old" "get_sum" (func (result i32)))
+  (memory (export "memory") i64 1)
+)
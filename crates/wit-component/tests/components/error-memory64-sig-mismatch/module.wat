@@ -0,0 +1,7 @@
+(module
+  (import "foo:foo/host" "log" (func (param i32 i32)))
+  (import "foo:foo/host" "get" (func (param i64)))
+  (memory (export "memory") i64 1)
+  (func (export "cabi_realloc") (param i64 i64 i32 i64) (result i64) unreachable)
+  (func (export "run") (param i64 i64) (result i64) unreachable)
+)
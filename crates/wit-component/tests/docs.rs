@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use libtest_mimic::{Arguments, Trial};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use wit_component::{DocsFormat, DocsGenerator};
+use wit_parser::Resolve;
+
+/// Tests the generation of reference documentation for WIT packages.
+///
+/// This test looks in the `docs/` directory for test cases. Each test case is
+/// a `*.wit` file, `foo.wit`, which is accompanied by a `foo` directory
+/// containing the expected Markdown pages in `foo/markdown` and the expected
+/// HTML pages in `foo/html`. All features are enabled when parsing the WIT.
+///
+/// Run the test with the environment variable `BLESS` set to update
+/// the baseline files.
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let mut trials = Vec::new();
+    for entry in fs::read_dir("tests/docs")? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("wit") {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        trials.push(Trial::test(name, move || {
+            run_test(&path)
+                .context(format!("failed test `{}`", path.display()))
+                .map_err(|e| format!("{e:?}").into())
+        }));
+    }
+
+    let mut args = Arguments::from_args();
+    if cfg!(target_family = "wasm") && !cfg!(target_feature = "atomics") {
+        args.test_threads = Some(1);
+    }
+    libtest_mimic::run(&args, trials).exit();
+}
+
+fn run_test(path: &Path) -> Result<()> {
+    let mut resolve = Resolve::new();
+    resolve.all_features = true;
+    resolve.push_file(path)?;
+
+    let expected = path.with_extension("");
+    for (dir, format) in [
+        ("markdown", DocsFormat::Markdown),
+        ("html", DocsFormat::Html),
+    ] {
+        let dir = expected.join(dir);
+        let pages = DocsGenerator::new(format).generate(&resolve);
+        if std::env::var_os("BLESS").is_some() {
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            for page in pages {
+                let path = dir.join(&page.path);
+                fs::create_dir_all(path.parent().unwrap())?;
+                fs::write(&path, &page.contents)
+                    .with_context(|| format!("failed to write {path:?}"))?;
+            }
+        } else {
+            for page in pages {
+                let path = dir.join(&page.path);
+                assert_eq!(
+                    fs::read_to_string(&path)
+                        .with_context(|| format!("failed to read {path:?}"))?
+                        .replace("\r\n", "\n"),
+                    page.contents,
+                    "expectation `{}` did not match actual",
+                    path.display(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,210 @@
+#![cfg(all(feature = "dummy-component", not(target_family = "wasm")))]
+
+use anyhow::{Context, Result};
+use wasmtime::component::{Component, Instance, Linker, Val};
+use wasmtime::{Config, Engine, Store};
+use wit_component::{DummyBehavior, DummyComponent};
+use wit_parser::{Resolve, WorldId};
+
+const WIT: &str = r#"
+package test:test;
+
+interface api {
+  resource counter {
+    constructor(start: u32);
+    get: func() -> u32;
+    fork: func() -> counter;
+  }
+
+  record info {
+    name: string,
+    tags: list<string>,
+    id: u64,
+  }
+
+  get-info: func() -> info;
+  pick: func() -> option<u32>;
+  status: func() -> result<u32, string>;
+}
+
+world test {
+  import log: func(msg: string);
+
+  export api;
+  export run: func() -> u32;
+}
+"#;
+
+fn world() -> Result<(Resolve, WorldId)> {
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", WIT)?;
+    let world = resolve.select_world(&pkg, None)?;
+    Ok((resolve, world))
+}
+
+fn instantiate(component: &[u8]) -> Result<(Store<()>, Instance)> {
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    let engine = Engine::new(&config)?;
+    let mut linker = Linker::new(&engine);
+    linker
+        .root()
+        .func_wrap("log", |_store, (_msg,): (String,)| Ok(()))?;
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &Component::new(&engine, component)?)?;
+    Ok((store, instance))
+}
+
+fn call(
+    store: &mut Store<()>,
+    instance: &Instance,
+    interface: Option<&str>,
+    name: &str,
+    params: &[Val],
+) -> Result<Val> {
+    let func = {
+        let mut exports = instance.exports(&mut *store);
+        match interface {
+            Some(interface) => exports.instance(interface).unwrap().func(name),
+            None => exports.root().func(name),
+        }
+    }
+    .with_context(|| format!("function `{name}` not found"))?;
+    let mut results = [Val::Bool(false)];
+    func.call(&mut *store, params, &mut results)?;
+    func.post_return(&mut *store)?;
+    let [result] = results;
+    Ok(result)
+}
+
+#[test]
+fn dummy_zero() -> Result<()> {
+    let (resolve, world) = world()?;
+    let component = DummyComponent::default()
+        .validate(true)
+        .behavior(DummyBehavior::Zero)
+        .encode(&resolve, world)?;
+    let (mut store, instance) = instantiate(&component)?;
+    let api = Some("test:test/api");
+
+    let run = call(&mut store, &instance, None, "run", &[])?;
+    assert!(matches!(run, Val::U32(0)), "{run:?}");
+
+    let info = call(&mut store, &instance, api, "get-info", &[])?;
+    let Val::Record(fields) = info else {
+        panic!("{info:?}")
+    };
+    assert!(matches!(&fields[0], (_, Val::String(s)) if s.is_empty()));
+    assert!(matches!(&fields[1], (_, Val::List(l)) if l.is_empty()));
+    assert!(matches!(&fields[2], (_, Val::U64(0))));
+
+    let pick = call(&mut store, &instance, api, "pick", &[])?;
+    assert!(matches!(pick, Val::Option(None)), "{pick:?}");
+    let status = call(&mut store, &instance, api, "status", &[])?;
+    assert!(
+        matches!(&status, Val::Result(Ok(Some(v))) if matches!(**v, Val::U32(0))),
+        "{status:?}"
+    );
+
+    // Resources exported by the world can be created, used and dropped.
+    let counter = call(
+        &mut store,
+        &instance,
+        api,
+        "[constructor]counter",
+        &[Val::U32(5)],
+    )?;
+    let get = call(
+        &mut store,
+        &instance,
+        api,
+        "[method]counter.get",
+        &[counter.clone()],
+    )?;
+    assert!(matches!(get, Val::U32(0)), "{get:?}");
+    let fork = call(
+        &mut store,
+        &instance,
+        api,
+        "[method]counter.fork",
+        &[counter.clone()],
+    )?;
+    for resource in [counter, fork] {
+        let Val::Resource(resource) = resource else {
+            panic!("{resource:?}")
+        };
+        resource.resource_drop(&mut store)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn dummy_values() -> Result<()> {
+    let (resolve, world) = world()?;
+    let component = DummyComponent::default()
+        .validate(true)
+        .export("run", DummyBehavior::Value("42".to_string()))
+        .export(
+            "test:test/api#get-info",
+            DummyBehavior::Value(r#"{name: "x", tags: ["a", "b"], id: 7}"#.to_string()),
+        )
+        .export(
+            "test:test/api#status",
+            DummyBehavior::Value(r#"err("no")"#.to_string()),
+        )
+        .encode(&resolve, world)?;
+    let (mut store, instance) = instantiate(&component)?;
+    let api = Some("test:test/api");
+
+    let run = call(&mut store, &instance, None, "run", &[])?;
+    assert!(matches!(run, Val::U32(42)), "{run:?}");
+
+    let info = call(&mut store, &instance, api, "get-info", &[])?;
+    let Val::Record(fields) = info else {
+        panic!("{info:?}")
+    };
+    assert!(matches!(&fields[0], (_, Val::String(s)) if s == "x"));
+    assert!(
+        matches!(&fields[1], (_, Val::List(l)) if matches!(&l[..], [Val::String(a), Val::String(b)] if a == "a" && b == "b"))
+    );
+    assert!(matches!(&fields[2], (_, Val::U64(7))));
+
+    let status = call(&mut store, &instance, api, "status", &[])?;
+    assert!(
+        matches!(&status, Val::Result(Err(Some(v))) if matches!(&**v, Val::String(s) if s == "no")),
+        "{status:?}"
+    );
+
+    // Functions without a configured behavior trap.
+    assert!(call(&mut store, &instance, api, "pick", &[]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn dummy_errors() -> Result<()> {
+    let (resolve, world) = world()?;
+
+    let err = DummyComponent::default()
+        .export("missing", DummyBehavior::Zero)
+        .encode(&resolve, world)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("does not export a function named `missing`"),
+        "{err}"
+    );
+
+    let err = DummyComponent::default()
+        .export("run", DummyBehavior::Value("\"str\"".to_string()))
+        .encode(&resolve, world)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid result for function `run`"),
+        "{err}"
+    );
+
+    Ok(())
+}
@@ -57,7 +57,9 @@ fn run_test(path: &Path, is_dir: bool) -> Result<()> {
     for package in packages {
         assert_print(&resolve, &[package], path, is_dir)?;
 
-        let features = WasmFeatures::default() | WasmFeatures::COMPONENT_MODEL;
+        let features = WasmFeatures::default()
+            | WasmFeatures::COMPONENT_MODEL
+            | WasmFeatures::COMPONENT_MODEL_ASYNC;
 
         // First convert the WIT package to a binary WebAssembly output, then
         // convert that binary wasm to textual wasm, then assert it matches the
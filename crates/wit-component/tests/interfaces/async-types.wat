@@ -0,0 +1,114 @@
+(component
+  (type (;0;)
+    (component
+      (type (;0;)
+        (instance
+          (type (;0;) (future))
+          (export (;1;) "f" (type (eq 0)))
+          (type (;2;) (future u32))
+          (export (;3;) "f2" (type (eq 2)))
+          (type (;4;) (stream))
+          (export (;5;) "s" (type (eq 4)))
+          (type (;6;) (stream string))
+          (export (;7;) "s2" (type (eq 6)))
+          (type (;8;) error-context)
+          (export (;9;) "e" (type (eq 8)))
+          (type (;10;) (list u8))
+          (type (;11;) (future 10))
+          (type (;12;) (future 3))
+          (type (;13;) (stream 12))
+          (type (;14;) error-context)
+          (type (;15;) (record (field "a" 11) (field "b" 13) (field "c" 14)))
+          (export (;16;) "r" (type (eq 15)))
+          (type (;17;) (stream u8))
+          (type (;18;) error-context)
+          (type (;19;) (result 17 (error 18)))
+          (type (;20;) (func (param "x" 3) (param "y" 7) (result 19)))
+          (export (;0;) "start" (func (type 20)))
+          (type (;21;) (future))
+          (type (;22;) (option 21))
+          (type (;23;) (func (param "x" 22) (param "e" 9)))
+          (export (;1;) "cancel" (func (type 23)))
+        )
+      )
+      (export (;0;) "foo:async-types/types" (instance (type 0)))
+    )
+  )
+  (export (;1;) "types" (type 0))
+  (type (;2;)
+    (component
+      (type (;0;)
+        (component
+          (type (;0;)
+            (instance
+              (type (;0;) (future))
+              (export (;1;) "f" (type (eq 0)))
+              (type (;2;) (future u32))
+              (export (;3;) "f2" (type (eq 2)))
+              (type (;4;) (stream))
+              (export (;5;) "s" (type (eq 4)))
+              (type (;6;) (stream string))
+              (export (;7;) "s2" (type (eq 6)))
+              (type (;8;) error-context)
+              (export (;9;) "e" (type (eq 8)))
+              (type (;10;) (list u8))
+              (type (;11;) (future 10))
+              (type (;12;) (future 3))
+              (type (;13;) (stream 12))
+              (type (;14;) error-context)
+              (type (;15;) (record (field "a" 11) (field "b" 13) (field "c" 14)))
+              (export (;16;) "r" (type (eq 15)))
+              (type (;17;) (stream u8))
+              (type (;18;) error-context)
+              (type (;19;) (result 17 (error 18)))
+              (type (;20;) (func (param "x" 3) (param "y" 7) (result 19)))
+              (export (;0;) "start" (func (type 20)))
+              (type (;21;) (future))
+              (type (;22;) (option 21))
+              (type (;23;) (func (param "x" 22) (param "e" 9)))
+              (export (;1;) "cancel" (func (type 23)))
+            )
+          )
+          (import "foo:async-types/types" (instance (;0;) (type 0)))
+          (type (;1;)
+            (instance
+              (type (;0;) (future))
+              (export (;1;) "f" (type (eq 0)))
+              (type (;2;) (future u32))
+              (export (;3;) "f2" (type (eq 2)))
+              (type (;4;) (stream))
+              (export (;5;) "s" (type (eq 4)))
+              (type (;6;) (stream string))
+              (export (;7;) "s2" (type (eq 6)))
+              (type (;8;) error-context)
+              (export (;9;) "e" (type (eq 8)))
+              (type (;10;) (list u8))
+              (type (;11;) (future 10))
+              (type (;12;) (future 3))
+              (type (;13;) (stream 12))
+              (type (;14;) error-context)
+              (type (;15;) (record (field "a" 11) (field "b" 13) (field "c" 14)))
+              (export (;16;) "r" (type (eq 15)))
+              (type (;17;) (stream u8))
+              (type (;18;) error-context)
+              (type (;19;) (result 17 (error 18)))
+              (type (;20;) (func (param "x" 3) (param "y" 7) (result 19)))
+              (export (;0;) "start" (func (type 20)))
+              (type (;21;) (future))
+              (type (;22;) (option 21))
+              (type (;23;) (func (param "x" 22) (param "e" 9)))
+              (export (;1;) "cancel" (func (type 23)))
+            )
+          )
+          (export (;1;) "foo:async-types/types" (instance (type 1)))
+        )
+      )
+      (export (;0;) "foo:async-types/w" (component (type 0)))
+    )
+  )
+  (export (;3;) "w" (type 2))
+  (@custom "package-docs" "\00{}")
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
+)
@@ -0,0 +1,39 @@
+(component
+  (type (;0;)
+    (component
+      (type (;0;)
+        (instance
+          (type (;0;) u32)
+          (export (;1;) "t" (type (eq 0)))
+          (type (;2;) (func (param "x" 1)))
+          (export (;0;) "f" (func (type 2)))
+        )
+      )
+      (export (;0;) "a:b/old@0.2.0" (instance (type 0)))
+    )
+  )
+  (export (;1;) "old" (type 0))
+  (type (;2;)
+    (component
+      (type (;0;)
+        (component
+          (type (;0;)
+            (instance
+              (type (;0;) u32)
+              (export (;1;) "t" (type (eq 0)))
+              (type (;2;) (func (param "x" 1)))
+              (export (;0;) "f" (func (type 2)))
+            )
+          )
+          (import "a:b/old@0.2.0" (instance (;0;) (type 0)))
+        )
+      )
+      (export (;0;) "a:b/w@0.2.0" (component (type 0)))
+    )
+  )
+  (export (;3;) "w" (type 2))
+  (@custom "package-docs" "\01{\22worlds\22:{\22w\22:{\22stability\22:{\22stable\22:{\22since\22:\220.1.0\22}},\22interface_import_stability\22:{\22a:b/old@0.2.0\22:{\22stable\22:{\22since\22:\220.1.0\22,\22deprecated\22:\220.2.0\22}}}}},\22interfaces\22:{\22old\22:{\22stability\22:{\22stable\22:{\22since\22:\220.1.0\22,\22deprecated\22:\220.2.0\22}},\22funcs\22:{\22f\22:{\22stability\22:{\22stable\22:{\22since\22:\220.1.0\22}}}},\22types\22:{\22t\22:{\22stability\22:{\22stable\22:{\22since\22:\220.1.0\22,\22deprecated\22:\220.2.0\22}}}}}}}")
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
+)
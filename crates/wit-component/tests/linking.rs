@@ -176,6 +176,10 @@ fn linking() -> Result<()> {
     )?
     .encode()?;
 
+    run(&component)
+}
+
+fn run(component: &[u8]) -> Result<()> {
     #[cfg(target_family = "wasm")]
     {
         _ = component;
@@ -200,7 +204,7 @@ fn linking() -> Result<()> {
             .instance("test:test/test")?
             .func_wrap("bar", |_store, (v,): (i32,)| Ok((v + 7,)))?;
         let mut store = Store::new(&engine, ());
-        let instance = linker.instantiate(&mut store, &Component::new(&engine, &component)?)?;
+        let instance = linker.instantiate(&mut store, &Component::new(&engine, component)?)?;
         let func = instance
             .exports(&mut store)
             .instance("test:test/test")
@@ -212,3 +216,111 @@ fn linking() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(not(target_family = "wasm"))]
+#[test]
+fn linking_library_path() -> Result<()> {
+    let first = tempfile::tempdir()?;
+    let second = tempfile::tempdir()?;
+    let (first, second) = (first.path(), second.path());
+    std::fs::write(first.join("libfoo.so"), encode(FOO, None)?)?;
+    std::fs::write(second.join("libc.so"), encode(LIBC, None)?)?;
+    // Shadowed by the copy in `first`, which is searched first.
+    std::fs::write(second.join("libfoo.so"), b"not a library")?;
+
+    let linker = wit_component::Linker::default()
+        .validate(true)
+        .library("libbar.so", &encode(BAR, Some(WIT))?, false)?
+        .library_path(first)
+        .library_path(second);
+
+    let order = linker
+        .load_order()?
+        .into_iter()
+        .map(|library| (library.name, library.path, library.needed_by))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        order,
+        [
+            ("libbar.so".to_string(), None, vec![]),
+            (
+                "libfoo.so".to_string(),
+                Some(first.join("libfoo.so")),
+                vec!["libbar.so".to_string()]
+            ),
+            (
+                "libc.so".to_string(),
+                Some(second.join("libc.so")),
+                vec!["libfoo.so".to_string()]
+            ),
+        ]
+    );
+
+    run(&linker.encode()?)?;
+    Ok(())
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[test]
+fn linking_library_path_missing() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("libfoo.so"), encode(FOO, None)?)?;
+
+    let err = wit_component::Linker::default()
+        .library("libbar.so", &encode(BAR, Some(WIT))?, false)?
+        .library_path(dir.path())
+        .encode()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing libraries:\n\tlibfoo.so needs libc.so"
+    );
+    Ok(())
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[test]
+fn linking_conflicting_versions() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let foo = FOO.replace(r#"(needed "libc.so")"#, r#"(needed "libc.so.7")"#);
+    std::fs::write(dir.path().join("libfoo.so"), encode(&foo, None)?)?;
+    std::fs::write(dir.path().join("libc.so.7"), encode(LIBC, None)?)?;
+
+    let err = wit_component::Linker::default()
+        .library("libbar.so", &encode(BAR, Some(WIT))?, false)?
+        .library("libc.so.6", &encode(LIBC, None)?, false)?
+        .library_path(dir.path())
+        .encode()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "conflicting versions of library libc.so: libc.so.6, libc.so.7"
+    );
+    Ok(())
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[test]
+fn linking_needed_path() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let lib = dir.path().join("lib");
+    std::fs::create_dir(&lib)?;
+    std::fs::write(dir.path().join("libfoo.so"), encode(FOO, None)?)?;
+
+    for needed in ["../libfoo.so", "lib/../libfoo.so", "..", ""] {
+        let bar = BAR.replace(
+            r#"(needed "libfoo.so")"#,
+            &format!(r#"(needed "{needed}")"#),
+        );
+        let err = wit_component::Linker::default()
+            .library("libbar.so", &encode(&bar, Some(WIT))?, false)?
+            .library_path(&lib)
+            .encode()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("invalid needed library name `{needed}`: expected a plain file name")
+        );
+    }
+    Ok(())
+}
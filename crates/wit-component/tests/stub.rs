@@ -0,0 +1,156 @@
+#![cfg(feature = "stub")]
+
+use anyhow::Result;
+use wit_component::{ComponentEncoder, ImportStubber, StringEncoding};
+use wit_parser::Resolve;
+
+const WIT: &str = r#"
+package test:test;
+
+interface env {
+  record point { x: u32, y: u32 }
+
+  get-name: func() -> string;
+  get-count: func() -> u32;
+  origin: func() -> point;
+  log: func(msg: string);
+}
+
+world test {
+  import env;
+
+  export run: func() -> u32;
+}
+"#;
+
+const MODULE: &str = r#"
+(module
+  (import "test:test/env" "get-name" (func $get-name (param i32)))
+  (import "test:test/env" "get-count" (func $get-count (result i32)))
+  (import "test:test/env" "origin" (func $origin (param i32)))
+  (import "test:test/env" "log" (func $log (param i32 i32)))
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (func (export "cabi_realloc") (param i32 i32 i32 i32) (result i32)
+    (local $ret i32)
+    (local.set $ret (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (i32.const 64)))
+    (local.get $ret))
+  (func (export "run") (result i32)
+    (call $log (i32.const 16) (i32.const 1))
+    (call $get-name (i32.const 100))
+    (call $origin (i32.const 200))
+    (i32.add
+      (i32.add (i32.load (i32.const 104)) (call $get-count))
+      (i32.add (i32.load (i32.const 200)) (i32.load (i32.const 204)))))
+  (data (i32.const 16) "x")
+)
+"#;
+
+fn component() -> Result<Vec<u8>> {
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", WIT)?;
+    let world = resolve.select_world(&pkg, None)?;
+    let mut module = wat::parse_str(MODULE)?;
+    wit_component::embed_component_metadata(&mut module, &resolve, world, StringEncoding::UTF8)?;
+    ComponentEncoder::default()
+        .module(&module)?
+        .validate(true)
+        .encode()
+}
+
+fn stub() -> ImportStubber {
+    ImportStubber::default()
+        .validate(true)
+        .interface("test:test/env")
+        .result("test:test/env#get-name", r#""hi""#)
+        .result("test:test/env#get-count", "7")
+        .result("test:test/env#origin", "{x: 1, y: 2}")
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn run(component: &[u8]) -> Result<u32> {
+    use wasmtime::{
+        component::{Component, Linker},
+        Config, Engine, Store,
+    };
+
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+
+    // No imports are provided by the host, so instantiation only succeeds
+    // if stubbing removed all of them.
+    let engine = Engine::new(&config)?;
+    let linker = Linker::new(&engine);
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &Component::new(&engine, component)?)?;
+    let func = instance.get_typed_func::<(), (u32,)>(&mut store, "run")?;
+    Ok(func.call(&mut store, ())?.0)
+}
+
+#[test]
+fn stub_results() -> Result<()> {
+    let component = stub()
+        .result("test:test/env#log", "()")
+        .encode(&component()?)?;
+
+    #[cfg(not(target_family = "wasm"))]
+    {
+        assert_eq!(run(&component)?, 12);
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        _ = component;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stub_traps() -> Result<()> {
+    let component = stub().encode(&component()?)?;
+
+    #[cfg(not(target_family = "wasm"))]
+    {
+        assert!(run(&component).is_err());
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        _ = component;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stub_errors() -> Result<()> {
+    let component = component()?;
+
+    let err = ImportStubber::default()
+        .interface("test:test/missing")
+        .encode(&component)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("does not import an interface named `test:test/missing`"),
+        "{err}"
+    );
+
+    let err = stub()
+        .result("test:test/env#missing", "1")
+        .encode(&component)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("no function named `test:test/env#missing`"),
+        "{err}"
+    );
+
+    let err = stub()
+        .result("test:test/env#log", "1")
+        .encode(&component)
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("value must be `()`"), "{err:?}");
+
+    Ok(())
+}
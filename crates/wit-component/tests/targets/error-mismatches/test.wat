@@ -0,0 +1,161 @@
+(component
+  (type (;0;)
+    (instance
+      (type (;0;) (func (param "msg" string)))
+      (export (;0;) "log" (func (type 0)))
+      (type (;1;) (func (result u64)))
+      (export (;1;) "now" (func (type 1)))
+    )
+  )
+  (import "host" (instance (;0;) (type 0)))
+  (type (;1;)
+    (instance
+      (type (;0;) (record (field "a" u32)))
+      (export (;1;) "r" (type (eq 0)))
+    )
+  )
+  (import "test:foo/types" (instance (;1;) (type 1)))
+  (core module (;0;)
+    (type (;0;) (func (param i32 i32)))
+    (type (;1;) (func (result i64)))
+    (type (;2;) (func (param i64) (result i32)))
+    (type (;3;) (func (param i32 i32 i32 i32) (result i32)))
+    (import "host" "log" (func (;0;) (type 0)))
+    (import "host" "now" (func (;1;) (type 1)))
+    (func (;2;) (type 2) (param i64) (result i32)
+      unreachable
+    )
+    (func (;3;) (type 3) (param $old_ptr i32) (param $old_size i32) (param $align i32) (param $new_size i32) (result i32)
+      (local $ret i32) (local $end i32)
+      global.get $heap
+      local.get $align
+      i32.const 1
+      i32.sub
+      i32.add
+      i32.const 0
+      local.get $align
+      i32.sub
+      i32.and
+      local.set $ret
+      local.get $ret
+      local.get $new_size
+      i32.add
+      local.set $end
+      local.get $end
+      memory.size
+      i32.const 16
+      i32.shl
+      i32.gt_u
+      if ;; label = @1
+        local.get $end
+        memory.size
+        i32.const 16
+        i32.shl
+        i32.sub
+        i32.const 65535
+        i32.add
+        i32.const 16
+        i32.shr_u
+        memory.grow
+        i32.const -1
+        i32.eq
+        if ;; label = @2
+          unreachable
+        end
+      end
+      local.get $end
+      global.set $heap
+      local.get $old_ptr
+      if ;; label = @1
+        local.get $ret
+        local.get $old_ptr
+        local.get $old_size
+        memory.copy
+      end
+      local.get $ret
+    )
+    (memory (;0;) 1)
+    (global $heap (;0;) (mut i32) i32.const 8)
+    (export "test:foo/api#f" (func 2))
+    (export "memory" (memory 0))
+    (export "cabi_realloc" (func 3))
+    (data (;0;) (i32.const 0) "\00\00\00\00\00\00\00\00")
+    (@producers
+      (processed-by "wit-component" "0.211.1")
+    )
+  )
+  (core module (;1;)
+    (type (;0;) (func (param i32 i32)))
+    (func $indirect-host-log (;0;) (type 0) (param i32 i32)
+      local.get 0
+      local.get 1
+      i32.const 0
+      call_indirect (type 0)
+    )
+    (table (;0;) 1 1 funcref)
+    (export "0" (func $indirect-host-log))
+    (export "$imports" (table 0))
+    (@producers
+      (processed-by "wit-component" "0.211.1")
+    )
+  )
+  (core module (;2;)
+    (type (;0;) (func (param i32 i32)))
+    (import "" "0" (func (;0;) (type 0)))
+    (import "" "$imports" (table (;0;) 1 1 funcref))
+    (elem (;0;) (i32.const 0) func 0)
+    (@producers
+      (processed-by "wit-component" "0.211.1")
+    )
+  )
+  (core instance (;0;) (instantiate 1))
+  (alias core export 0 "0" (core func (;0;)))
+  (alias export 0 "now" (func (;0;)))
+  (core func (;1;) (canon lower (func 0)))
+  (core instance (;1;)
+    (export "log" (func 0))
+    (export "now" (func 1))
+  )
+  (core instance (;2;) (instantiate 0
+      (with "host" (instance 1))
+    )
+  )
+  (alias core export 2 "memory" (core memory (;0;)))
+  (alias core export 2 "cabi_realloc" (core func (;2;)))
+  (alias core export 0 "$imports" (core table (;0;)))
+  (alias export 0 "log" (func (;1;)))
+  (core func (;3;) (canon lower (func 1) (memory 0) string-encoding=utf8))
+  (core instance (;3;)
+    (export "$imports" (table 0))
+    (export "0" (func 3))
+  )
+  (core instance (;4;) (instantiate 2
+      (with "" (instance 3))
+    )
+  )
+  (alias export 1 "r" (type (;2;)))
+  (type (;3;) (func (param "x" u64) (result 2)))
+  (alias core export 2 "test:foo/api#f" (core func (;4;)))
+  (func (;2;) (type 3) (canon lift (core func 4)))
+  (alias export 1 "r" (type (;4;)))
+  (component (;0;)
+    (type (;0;) (record (field "a" u32)))
+    (import "import-type-r" (type (;1;) (eq 0)))
+    (import "import-type-r0" (type (;2;) (eq 1)))
+    (type (;3;) (func (param "x" u64) (result 2)))
+    (import "import-func-f" (func (;0;) (type 3)))
+    (export (;4;) "r" (type 1))
+    (type (;5;) (func (param "x" u64) (result 4)))
+    (export (;1;) "f" (func 0) (func (type 5)))
+  )
+  (instance (;2;) (instantiate 0
+      (with "import-func-f" (func 2))
+      (with "import-type-r" (type 4))
+      (with "import-type-r0" (type 2))
+    )
+  )
+  (export (;3;) "test:foo/api" (instance 2))
+  (@producers
+    (processed-by "wit-component" "0.211.1")
+  )
+)
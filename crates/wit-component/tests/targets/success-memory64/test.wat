@@ -0,0 +1,107 @@
+(component
+  (type (;0;)
+    (instance
+      (type (;0;) (func (param "msg" string)))
+      (export (;0;) "log" (func (type 0)))
+      (type (;1;) (list u8))
+      (type (;2;) (func (result 1)))
+      (export (;1;) "get" (func (type 2)))
+    )
+  )
+  (import "foo:foo/host" (instance (;0;) (type 0)))
+  (core module (;0;)
+    (type (;0;) (func (param i64 i64)))
+    (type (;1;) (func (param i64)))
+    (type (;2;) (func (param i64 i64 i32 i64) (result i64)))
+    (type (;3;) (func (param i64 i64) (result i64)))
+    (import "foo:foo/host" "log" (func (;0;) (type 0)))
+    (import "foo:foo/host" "get" (func (;1;) (type 1)))
+    (func (;2;) (type 2) (param i64 i64 i32 i64) (result i64)
+      unreachable
+    )
+    (func (;3;) (type 3) (param i64 i64) (result i64)
+      unreachable
+    )
+    (func (;4;) (type 1) (param i64)
+      unreachable
+    )
+    (memory (;0;) i64 1)
+    (export "memory" (memory 0))
+    (export "cabi_realloc" (func 2))
+    (export "run" (func 3))
+    (export "cabi_post_run" (func 4))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+      (processed-by "my-fake-bindgen" "123.45")
+    )
+  )
+  (core module (;1;)
+    (type (;0;) (func (param i64 i64)))
+    (type (;1;) (func (param i64)))
+    (func $indirect-foo:foo/host-log (;0;) (type 0) (param i64 i64)
+      local.get 0
+      local.get 1
+      i32.const 0
+      call_indirect (type 0)
+    )
+    (func $indirect-foo:foo/host-get (;1;) (type 1) (param i64)
+      local.get 0
+      i32.const 1
+      call_indirect (type 1)
+    )
+    (table (;0;) 2 2 funcref)
+    (export "0" (func $indirect-foo:foo/host-log))
+    (export "1" (func $indirect-foo:foo/host-get))
+    (export "$imports" (table 0))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
+  )
+  (core module (;2;)
+    (type (;0;) (func (param i64 i64)))
+    (type (;1;) (func (param i64)))
+    (import "" "0" (func (;0;) (type 0)))
+    (import "" "1" (func (;1;) (type 1)))
+    (import "" "$imports" (table (;0;) 2 2 funcref))
+    (elem (;0;) (i32.const 0) func 0 1)
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
+  )
+  (core instance (;0;) (instantiate 1))
+  (alias core export 0 "0" (core func (;0;)))
+  (alias core export 0 "1" (core func (;1;)))
+  (core instance (;1;)
+    (export "log" (func 0))
+    (export "get" (func 1))
+  )
+  (core instance (;2;) (instantiate 0
+      (with "foo:foo/host" (instance 1))
+    )
+  )
+  (alias core export 2 "memory" (core memory (;0;)))
+  (alias core export 2 "cabi_realloc" (core func (;2;)))
+  (alias core export 0 "$imports" (core table (;0;)))
+  (alias export 0 "log" (func (;0;)))
+  (core func (;3;) (canon lower (func 0) (memory 0) string-encoding=utf8))
+  (alias export 0 "get" (func (;1;)))
+  (core func (;4;) (canon lower (func 1) (memory 0) (realloc 2)))
+  (core instance (;3;)
+    (export "$imports" (table 0))
+    (export "0" (func 3))
+    (export "1" (func 4))
+  )
+  (core instance (;4;) (instantiate 2
+      (with "" (instance 3))
+    )
+  )
+  (type (;1;) (list string))
+  (type (;2;) (func (param "args" 1) (result string)))
+  (alias core export 2 "run" (core func (;5;)))
+  (alias core export 2 "cabi_post_run" (core func (;6;)))
+  (func (;2;) (type 2) (canon lift (core func 5) (memory 0) (realloc 2) string-encoding=utf8 (post-return 6)))
+  (export (;3;) "run" (func 2))
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
+)
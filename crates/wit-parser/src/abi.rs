@@ -1,4 +1,7 @@
-use crate::{Function, Handle, Int, Resolve, Type, TypeDefKind};
+use crate::{
+    AddressSize, FlagsRepr, Function, Handle, Int, Resolve, SizeAlign, Type, TypeDefKind, TypeId,
+};
+use std::fmt;
 
 /// A core WebAssembly signature with params and results.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
@@ -207,7 +210,8 @@ impl Resolve {
             | Type::U16
             | Type::S32
             | Type::U32
-            | Type::Char => result.push(WasmType::I32),
+            | Type::Char
+            | Type::ErrorContext => result.push(WasmType::I32),
 
             Type::U64 | Type::S64 => result.push(WasmType::I64),
             Type::F32 => result.push(WasmType::F32),
@@ -307,3 +311,1353 @@ impl Resolve {
         }
     }
 }
+
+/// The encoding of strings in linear memory, corresponding to the
+/// `string-encoding` canonical option.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    /// Strings are encoded with UTF-8 and lengths count bytes.
+    #[default]
+    UTF8,
+    /// Strings are encoded with UTF-16 and lengths count code units.
+    UTF16,
+    /// Strings are encoded as either latin1 or UTF-16, where the high bit of
+    /// the length indicates UTF-16.
+    CompactUTF16,
+}
+
+impl fmt::Display for StringEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StringEncoding::UTF8 => f.write_str("utf8"),
+            StringEncoding::UTF16 => f.write_str("utf16"),
+            StringEncoding::CompactUTF16 => f.write_str("latin1+utf16"),
+        }
+    }
+}
+
+/// A value flowing through the instructions produced by a [`Generator`].
+///
+/// Every value is defined exactly once, either as a result of an
+/// [`Instruction`] or as a parameter of a [`Block`], and may be used by any
+/// later instruction in the same block or in a block nested within it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Value(pub u32);
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// A single step of lifting or lowering produced by a [`Generator`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    /// The operation performed by this instruction.
+    pub op: Op,
+    /// The values consumed by this instruction, whose meaning is documented
+    /// on each [`Op`].
+    pub operands: Vec<Value>,
+    /// The values defined by this instruction.
+    pub results: Vec<Value>,
+}
+
+/// A nested sequence of instructions, used for the cases of variants and the
+/// per-element bodies of lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /// Values defined on entry to this block.
+    pub params: Vec<Value>,
+    /// The instructions executed within this block.
+    pub instructions: Vec<Instruction>,
+    /// Values yielded by this block to the instruction that contains it.
+    pub results: Vec<Value>,
+}
+
+/// The width and interpretation of a load from or store to linear memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MemoryType {
+    /// An 8-bit zero-extended integer (`i32.load8_u`, `i32.store8`).
+    U8,
+    /// An 8-bit sign-extended integer (`i32.load8_s`).
+    S8,
+    /// A 16-bit zero-extended integer (`i32.load16_u`, `i32.store16`).
+    U16,
+    /// A 16-bit sign-extended integer (`i32.load16_s`).
+    S16,
+    I32,
+    I64,
+    F32,
+    F64,
+    /// A pointer, whose width depends on the [`AddressSize`] in use.
+    Pointer,
+    /// A length, whose width depends on the [`AddressSize`] in use.
+    Length,
+}
+
+impl MemoryType {
+    fn name(&self) -> &'static str {
+        match self {
+            MemoryType::U8 => "u8",
+            MemoryType::S8 => "s8",
+            MemoryType::U16 => "u16",
+            MemoryType::S16 => "s16",
+            MemoryType::I32 => "i32",
+            MemoryType::I64 => "i64",
+            MemoryType::F32 => "f32",
+            MemoryType::F64 => "f64",
+            MemoryType::Pointer => "pointer",
+            MemoryType::Length => "length",
+        }
+    }
+}
+
+/// The operations of the abstract instruction stream produced by a
+/// [`Generator`].
+///
+/// "Interface values" are source-level values of a WIT type while "core
+/// values" are WebAssembly values described by [`WasmType`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    /// Defines the `nth` parameter of the function being generated. This is
+    /// an interface value for [`AbiVariant::GuestImport`] and a core value
+    /// for [`AbiVariant::GuestExport`] and post-return functions.
+    Param { nth: usize },
+    /// Defines a core constant of the given type.
+    Const { ty: WasmType, value: i64 },
+    /// Reinterprets a core value between two types of a variant's flattened
+    /// payload, for example an `f32` carried in an `i32` slot.
+    Bitcast { from: WasmType, to: WasmType },
+
+    /// Converts an interface value of a primitive type to its single core
+    /// value.
+    PrimitiveLower { ty: Type },
+    /// Converts a core value to an interface value of a primitive type.
+    PrimitiveLift { ty: Type },
+
+    /// Consumes an address and defines the value loaded at `address +
+    /// offset`.
+    Load { ty: MemoryType, offset: usize },
+    /// Consumes a value and an address and stores the value at `address +
+    /// offset`.
+    Store { ty: MemoryType, offset: usize },
+
+    /// Consumes a string and defines its pointer and length in the given
+    /// encoding. Memory is allocated with `realloc` and owned by the callee
+    /// when present, otherwise it need only live for the duration of the
+    /// call.
+    StringLower {
+        encoding: StringEncoding,
+        realloc: Option<String>,
+    },
+    /// Consumes a pointer and length and defines a string, taking ownership
+    /// of the memory.
+    StringLift { encoding: StringEncoding },
+
+    /// Consumes a list whose elements have the same representation in
+    /// memory as in the source language and defines its pointer and length.
+    /// `realloc` is as for [`Op::StringLower`].
+    ListCanonLower {
+        element: Type,
+        realloc: Option<String>,
+    },
+    /// Consumes a pointer and length and defines a list of elements with a
+    /// canonical representation, taking ownership of the memory.
+    ListCanonLift { element: Type },
+    /// Consumes a list and defines its pointer and length. Memory for
+    /// `size * len` bytes aligned to `align` is allocated and then `body` is
+    /// run for each element with the element and its address as parameters.
+    ListLower {
+        element: Type,
+        size: usize,
+        align: usize,
+        realloc: Option<String>,
+        body: Block,
+    },
+    /// Consumes a pointer and length and defines a list. `body` is run for
+    /// each element with its address as parameter and yields the element.
+    ListLift {
+        element: Type,
+        size: usize,
+        align: usize,
+        body: Block,
+    },
+
+    /// Consumes a record and defines each of its fields.
+    RecordLower { ty: TypeId },
+    /// Consumes each field of a record and defines the record.
+    RecordLift { ty: TypeId },
+    /// Consumes a tuple and defines each of its elements.
+    TupleLower { ty: TypeId },
+    /// Consumes each element of a tuple and defines the tuple.
+    TupleLift { ty: TypeId },
+    /// Consumes a flags value and defines one `i32` per 32 flags.
+    FlagsLower { ty: TypeId },
+    /// Consumes one `i32` per 32 flags and defines a flags value.
+    FlagsLift { ty: TypeId },
+    /// Consumes an enum value and defines its discriminant.
+    EnumLower { ty: TypeId },
+    /// Consumes a discriminant and defines an enum value.
+    EnumLift { ty: TypeId },
+
+    /// Consumes a `variant`, `option` or `result` value and selects the
+    /// block for its case, whose parameter is the case's payload if it has
+    /// one. Defines the discriminant followed by the values yielded by the
+    /// selected block, which all yield values of the same types.
+    VariantLower { ty: TypeId, cases: Vec<Block> },
+    /// Consumes a discriminant and selects the block for that case, which
+    /// yields the case's payload if it has one. Defines the `variant`,
+    /// `option` or `result` value.
+    VariantLift { ty: TypeId, cases: Vec<Block> },
+
+    /// Consumes a resource handle and defines its `i32` representation,
+    /// transferring ownership for `own` handles.
+    HandleLower { handle: Handle, ty: TypeId },
+    /// Consumes an `i32` and defines a resource handle.
+    HandleLift { handle: Handle, ty: TypeId },
+    /// Consumes a future and defines its `i32` handle.
+    FutureLower { ty: TypeId },
+    /// Consumes an `i32` handle and defines a future.
+    FutureLift { ty: TypeId },
+    /// Consumes a stream and defines its `i32` handle.
+    StreamLower { ty: TypeId },
+    /// Consumes an `i32` handle and defines a stream.
+    StreamLift { ty: TypeId },
+
+    /// Defines a pointer to `size` bytes of temporary memory aligned to
+    /// `align` which holds parameters passed indirectly to an import.
+    ParamArea { size: usize, align: usize },
+    /// Defines a pointer to `size` bytes of memory aligned to `align` which
+    /// holds results returned indirectly. For exports this memory must
+    /// outlive the call, for example as a static area.
+    ReturnArea { size: usize, align: usize },
+    /// Consumes the core arguments of an imported function, calls it, and
+    /// defines its core results.
+    CallWasm { name: String, sig: WasmSignature },
+    /// Consumes the interface arguments of an exported function, calls the
+    /// guest's implementation, and defines its interface results.
+    CallInterface { name: String },
+    /// Consumes the values returned from the function being generated.
+    Return,
+
+    /// Consumes a pointer to `size` bytes aligned to `align` allocated with
+    /// `realloc` and frees it.
+    GuestDeallocate { size: usize, align: usize },
+    /// Consumes a string's pointer and length and frees its memory.
+    GuestDeallocateString,
+    /// Consumes a list's pointer and length, runs `body` for each element
+    /// with its address as parameter, and then frees the list's memory.
+    GuestDeallocateList {
+        element: Type,
+        size: usize,
+        align: usize,
+        body: Block,
+    },
+    /// Consumes a discriminant and runs the block for that case.
+    GuestDeallocateVariant { cases: Vec<Block> },
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.print(f, 0)
+    }
+}
+
+impl Instruction {
+    fn print(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        write!(f, "{:indent$}", "")?;
+        if !self.results.is_empty() {
+            write_values(f, &self.results, ", ")?;
+            f.write_str(" = ")?;
+        }
+        let mut blocks: Vec<(String, &Block)> = Vec::new();
+        match &self.op {
+            Op::Param { nth } => write!(f, "param {nth}")?,
+            Op::Const { ty, value } => write!(f, "const {} {value}", wasm_type_name(*ty))?,
+            Op::Bitcast { from, to } => write!(
+                f,
+                "bitcast {} -> {}",
+                wasm_type_name(*from),
+                wasm_type_name(*to)
+            )?,
+            Op::PrimitiveLower { ty } => write!(f, "lower {}", type_name(ty))?,
+            Op::PrimitiveLift { ty } => write!(f, "lift {}", type_name(ty))?,
+            Op::Load { ty, offset } => write!(f, "load {} offset={offset}", ty.name())?,
+            Op::Store { ty, offset } => write!(f, "store {} offset={offset}", ty.name())?,
+            Op::StringLower { encoding, realloc } => {
+                write!(f, "string.lower {encoding}")?;
+                write_realloc(f, realloc)?;
+            }
+            Op::StringLift { encoding } => write!(f, "string.lift {encoding}")?,
+            Op::ListCanonLower { element, realloc } => {
+                write!(f, "list.canon.lower {}", type_name(element))?;
+                write_realloc(f, realloc)?;
+            }
+            Op::ListCanonLift { element } => write!(f, "list.canon.lift {}", type_name(element))?,
+            Op::ListLower {
+                element,
+                size,
+                align,
+                realloc,
+                body,
+            } => {
+                write!(
+                    f,
+                    "list.lower {} size={size} align={align}",
+                    type_name(element)
+                )?;
+                write_realloc(f, realloc)?;
+                blocks.push(("element".to_string(), body));
+            }
+            Op::ListLift {
+                element,
+                size,
+                align,
+                body,
+            } => {
+                write!(
+                    f,
+                    "list.lift {} size={size} align={align}",
+                    type_name(element)
+                )?;
+                blocks.push(("element".to_string(), body));
+            }
+            Op::RecordLower { ty } => write!(f, "record.lower type{}", ty.index())?,
+            Op::RecordLift { ty } => write!(f, "record.lift type{}", ty.index())?,
+            Op::TupleLower { ty } => write!(f, "tuple.lower type{}", ty.index())?,
+            Op::TupleLift { ty } => write!(f, "tuple.lift type{}", ty.index())?,
+            Op::FlagsLower { ty } => write!(f, "flags.lower type{}", ty.index())?,
+            Op::FlagsLift { ty } => write!(f, "flags.lift type{}", ty.index())?,
+            Op::EnumLower { ty } => write!(f, "enum.lower type{}", ty.index())?,
+            Op::EnumLift { ty } => write!(f, "enum.lift type{}", ty.index())?,
+            Op::VariantLower { ty, cases } => {
+                write!(f, "variant.lower type{}", ty.index())?;
+                for (i, case) in cases.iter().enumerate() {
+                    blocks.push((format!("case {i}"), case));
+                }
+            }
+            Op::VariantLift { ty, cases } => {
+                write!(f, "variant.lift type{}", ty.index())?;
+                for (i, case) in cases.iter().enumerate() {
+                    blocks.push((format!("case {i}"), case));
+                }
+            }
+            Op::HandleLower { handle, ty } => {
+                write!(f, "handle.lower {} type{}", handle_name(handle), ty.index())?
+            }
+            Op::HandleLift { handle, ty } => {
+                write!(f, "handle.lift {} type{}", handle_name(handle), ty.index())?
+            }
+            Op::FutureLower { ty } => write!(f, "future.lower type{}", ty.index())?,
+            Op::FutureLift { ty } => write!(f, "future.lift type{}", ty.index())?,
+            Op::StreamLower { ty } => write!(f, "stream.lower type{}", ty.index())?,
+            Op::StreamLift { ty } => write!(f, "stream.lift type{}", ty.index())?,
+            Op::ParamArea { size, align } => write!(f, "param-area size={size} align={align}")?,
+            Op::ReturnArea { size, align } => write!(f, "return-area size={size} align={align}")?,
+            Op::CallWasm { name, sig } => {
+                write!(f, "call.wasm {name:?} (")?;
+                for (i, ty) in sig.params.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    f.write_str(wasm_type_name(*ty))?;
+                }
+                f.write_str(") -> (")?;
+                for (i, ty) in sig.results.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    f.write_str(wasm_type_name(*ty))?;
+                }
+                f.write_str(")")?;
+            }
+            Op::CallInterface { name } => write!(f, "call.interface {name:?}")?,
+            Op::Return => f.write_str("return")?,
+            Op::GuestDeallocate { size, align } => write!(f, "dealloc size={size} align={align}")?,
+            Op::GuestDeallocateString => f.write_str("dealloc.string")?,
+            Op::GuestDeallocateList {
+                element,
+                size,
+                align,
+                body,
+            } => {
+                write!(
+                    f,
+                    "dealloc.list {} size={size} align={align}",
+                    type_name(element)
+                )?;
+                blocks.push(("element".to_string(), body));
+            }
+            Op::GuestDeallocateVariant { cases } => {
+                f.write_str("dealloc.variant")?;
+                for (i, case) in cases.iter().enumerate() {
+                    blocks.push((format!("case {i}"), case));
+                }
+            }
+        }
+        if !self.operands.is_empty() {
+            f.write_str(" ")?;
+            write_values(f, &self.operands, " ")?;
+        }
+        for (label, block) in blocks {
+            write!(f, "\n{:indent$}  {label}", "")?;
+            if !block.params.is_empty() {
+                f.write_str(" (")?;
+                write_values(f, &block.params, ", ")?;
+                f.write_str(")")?;
+            }
+            f.write_str(":")?;
+            for instr in block.instructions.iter() {
+                f.write_str("\n")?;
+                instr.print(f, indent + 4)?;
+            }
+            if !block.results.is_empty() {
+                write!(f, "\n{:indent$}    yield ", "")?;
+                write_values(f, &block.results, " ")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_values(f: &mut fmt::Formatter<'_>, values: &[Value], sep: &str) -> fmt::Result {
+    for (i, v) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        write!(f, "{v}")?;
+    }
+    Ok(())
+}
+
+fn write_realloc(f: &mut fmt::Formatter<'_>, realloc: &Option<String>) -> fmt::Result {
+    match realloc {
+        Some(name) => write!(f, " realloc={name}"),
+        None => Ok(()),
+    }
+}
+
+fn wasm_type_name(ty: WasmType) -> &'static str {
+    match ty {
+        WasmType::I32 => "i32",
+        WasmType::I64 => "i64",
+        WasmType::F32 => "f32",
+        WasmType::F64 => "f64",
+        WasmType::Pointer => "pointer",
+        WasmType::PointerOrI64 => "pointer-or-i64",
+        WasmType::Length => "length",
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::S8 => "s8".to_string(),
+        Type::S16 => "s16".to_string(),
+        Type::S32 => "s32".to_string(),
+        Type::S64 => "s64".to_string(),
+        Type::F32 => "f32".to_string(),
+        Type::F64 => "f64".to_string(),
+        Type::Char => "char".to_string(),
+        Type::String => "string".to_string(),
+        Type::ErrorContext => "error-context".to_string(),
+        Type::Id(id) => format!("type{}", id.index()),
+    }
+}
+
+fn handle_name(handle: &Handle) -> &'static str {
+    match handle {
+        Handle::Own(_) => "own",
+        Handle::Borrow(_) => "borrow",
+    }
+}
+
+/// Generates the instructions to lift and lower the parameters and results
+/// of a [`Function`] according to the canonical ABI.
+///
+/// The instructions produced describe one side of a call for the configured
+/// [`AbiVariant`]:
+///
+/// * [`AbiVariant::GuestImport`] - interface arguments are lowered, the
+///   imported core function is called with [`Op::CallWasm`], and its results
+///   are lifted back to interface values.
+/// * [`AbiVariant::GuestExport`] - core arguments are lifted, the guest's
+///   implementation is called with [`Op::CallInterface`], and its results are
+///   lowered to core values. [`Generator::post_return`] additionally
+///   produces the instructions to free those results after they were read.
+pub struct Generator<'a> {
+    resolve: &'a Resolve,
+    variant: AbiVariant,
+    sizes: SizeAlign,
+    string_encoding: StringEncoding,
+    realloc: String,
+    next_value: u32,
+    blocks: Vec<Vec<Instruction>>,
+}
+
+impl<'a> Generator<'a> {
+    /// Creates a new generator for functions in `resolve` using a 32-bit
+    /// memory, UTF-8 strings, and `cabi_realloc` as the allocation function.
+    pub fn new(resolve: &'a Resolve, variant: AbiVariant) -> Generator<'a> {
+        let mut sizes = SizeAlign::default();
+        sizes.fill(resolve);
+        Generator {
+            resolve,
+            variant,
+            sizes,
+            string_encoding: StringEncoding::UTF8,
+            realloc: "cabi_realloc".to_string(),
+            next_value: 0,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Configures the address size of the linear memory values live in.
+    pub fn address_size(mut self, size: AddressSize) -> Self {
+        self.sizes = SizeAlign::new(size);
+        self.sizes.fill(self.resolve);
+        self
+    }
+
+    /// Configures the encoding of strings in linear memory.
+    pub fn string_encoding(mut self, encoding: StringEncoding) -> Self {
+        self.string_encoding = encoding;
+        self
+    }
+
+    /// Configures the name of the allocation function used when ownership
+    /// of memory is transferred out of the guest.
+    pub fn realloc(mut self, name: &str) -> Self {
+        self.realloc = name.to_string();
+        self
+    }
+
+    /// Generates the instructions for one side of a call to `func`.
+    pub fn call(&mut self, func: &Function) -> Vec<Instruction> {
+        self.reset();
+        let sig = self.resolve.wasm_signature(self.variant, func);
+        let params = func.params.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+        let results = func.results.iter_types().collect::<Vec<_>>();
+
+        match self.variant {
+            AbiVariant::GuestImport => {
+                let args = (0..params.len())
+                    .map(|nth| self.emit1(Op::Param { nth }, Vec::new()))
+                    .collect::<Vec<_>>();
+
+                let mut flat = Vec::new();
+                if sig.indirect_params {
+                    let (size, align) = self.sizes.params(params.iter().copied());
+                    let ptr = self.emit1(Op::ParamArea { size, align }, Vec::new());
+                    let offsets = self.sizes.field_offsets(params.iter().copied());
+                    for ((offset, ty), arg) in offsets.into_iter().zip(args) {
+                        self.store(ty, arg, ptr, offset);
+                    }
+                    flat.push(ptr);
+                } else {
+                    for (ty, arg) in params.iter().zip(args) {
+                        flat.extend(self.lower(ty, arg));
+                    }
+                }
+
+                let retptr = if sig.retptr {
+                    let (size, align) = self.sizes.params(results.iter().copied());
+                    let ptr = self.emit1(Op::ReturnArea { size, align }, Vec::new());
+                    flat.push(ptr);
+                    Some(ptr)
+                } else {
+                    None
+                };
+
+                let nresults = sig.results.len();
+                let name = func.name.clone();
+                let core_results = self.emit(Op::CallWasm { name, sig }, flat, nresults);
+
+                let values = match retptr {
+                    Some(ptr) => {
+                        let offsets = self.sizes.field_offsets(results.iter().copied());
+                        offsets
+                            .into_iter()
+                            .map(|(offset, ty)| self.load(ty, ptr, offset))
+                            .collect()
+                    }
+                    None => {
+                        let mut core_results = core_results.into_iter();
+                        results
+                            .iter()
+                            .map(|ty| self.lift(ty, &mut core_results))
+                            .collect()
+                    }
+                };
+                self.emit(Op::Return, values, 0);
+            }
+
+            AbiVariant::GuestExport => {
+                let args = if sig.indirect_params {
+                    let ptr = self.emit1(Op::Param { nth: 0 }, Vec::new());
+                    let offsets = self.sizes.field_offsets(params.iter().copied());
+                    let args = offsets
+                        .into_iter()
+                        .map(|(offset, ty)| self.load(ty, ptr, offset))
+                        .collect::<Vec<_>>();
+                    let (size, align) = self.sizes.params(params.iter().copied());
+                    self.emit(Op::GuestDeallocate { size, align }, vec![ptr], 0);
+                    args
+                } else {
+                    let mut core_params = (0..sig.params.len())
+                        .map(|nth| self.emit1(Op::Param { nth }, Vec::new()))
+                        .collect::<Vec<_>>()
+                        .into_iter();
+                    params
+                        .iter()
+                        .map(|ty| self.lift(ty, &mut core_params))
+                        .collect()
+                };
+
+                let name = func.name.clone();
+                let values = self.emit(Op::CallInterface { name }, args, results.len());
+
+                let ret = if sig.retptr {
+                    let (size, align) = self.sizes.params(results.iter().copied());
+                    let ptr = self.emit1(Op::ReturnArea { size, align }, Vec::new());
+                    let offsets = self.sizes.field_offsets(results.iter().copied());
+                    for ((offset, ty), value) in offsets.into_iter().zip(values) {
+                        self.store(ty, value, ptr, offset);
+                    }
+                    vec![ptr]
+                } else {
+                    let mut flat = Vec::new();
+                    for (ty, value) in results.iter().zip(values) {
+                        flat.extend(self.lower(ty, value));
+                    }
+                    flat
+                };
+                self.emit(Op::Return, ret, 0);
+            }
+        }
+
+        self.blocks.pop().unwrap()
+    }
+
+    /// Generates the instructions of the post-return function for an
+    /// exported `func`, which frees any memory owned by its results.
+    ///
+    /// The parameters of the post-return function are the core results of
+    /// `func`. Only meaningful for [`AbiVariant::GuestExport`].
+    pub fn post_return(&mut self, func: &Function) -> Vec<Instruction> {
+        assert_eq!(self.variant, AbiVariant::GuestExport);
+        self.reset();
+        let sig = self.resolve.wasm_signature(self.variant, func);
+        let results = func.results.iter_types().collect::<Vec<_>>();
+        let params = (0..sig.results.len())
+            .map(|nth| self.emit1(Op::Param { nth }, Vec::new()))
+            .collect::<Vec<_>>();
+
+        if sig.retptr {
+            let offsets = self.sizes.field_offsets(results.iter().copied());
+            for (offset, ty) in offsets {
+                self.deallocate_memory(ty, params[0], offset);
+            }
+        } else {
+            let mut params = params.into_iter();
+            for ty in results {
+                self.deallocate_flat(ty, &mut params);
+            }
+        }
+        self.emit(Op::Return, Vec::new(), 0);
+        self.blocks.pop().unwrap()
+    }
+
+    fn reset(&mut self) {
+        self.next_value = 0;
+        self.blocks.clear();
+        self.blocks.push(Vec::new());
+    }
+
+    fn value(&mut self) -> Value {
+        let ret = Value(self.next_value);
+        self.next_value += 1;
+        ret
+    }
+
+    fn emit(&mut self, op: Op, operands: Vec<Value>, nresults: usize) -> Vec<Value> {
+        let results = (0..nresults).map(|_| self.value()).collect::<Vec<_>>();
+        self.blocks.last_mut().unwrap().push(Instruction {
+            op,
+            operands,
+            results: results.clone(),
+        });
+        results
+    }
+
+    fn emit1(&mut self, op: Op, operands: Vec<Value>) -> Value {
+        self.emit(op, operands, 1)[0]
+    }
+
+    fn push_block(&mut self) {
+        self.blocks.push(Vec::new());
+    }
+
+    fn finish_block(&mut self, params: Vec<Value>, results: Vec<Value>) -> Block {
+        Block {
+            params,
+            instructions: self.blocks.pop().unwrap(),
+            results,
+        }
+    }
+
+    /// Memory lowered when lowering results of an export is handed over to
+    /// the caller and must be allocated, otherwise it's only borrowed for
+    /// the duration of an import call.
+    fn lower_realloc(&self) -> Option<String> {
+        match self.variant {
+            AbiVariant::GuestImport => None,
+            AbiVariant::GuestExport => Some(self.realloc.clone()),
+        }
+    }
+
+    fn flat_types(&self, ty: &Type) -> Vec<WasmType> {
+        let mut flat = Vec::new();
+        self.resolve.push_flat(ty, &mut flat);
+        flat
+    }
+
+    fn bitcast(&mut self, value: Value, from: WasmType, to: WasmType) -> Value {
+        if from == to {
+            value
+        } else {
+            self.emit1(Op::Bitcast { from, to }, vec![value])
+        }
+    }
+
+    /// Returns the discriminant type and case payloads of `variant`,
+    /// `option` and `result` types.
+    fn variant_cases(&self, id: TypeId) -> Option<(Int, Vec<Option<Type>>)> {
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Variant(v) => Some((v.tag(), v.cases.iter().map(|c| c.ty).collect())),
+            TypeDefKind::Option(t) => Some((Int::U8, vec![None, Some(*t)])),
+            TypeDefKind::Result(r) => Some((Int::U8, vec![r.ok, r.err])),
+            _ => None,
+        }
+    }
+
+    fn lower(&mut self, ty: &Type, value: Value) -> Vec<Value> {
+        let id = match ty {
+            Type::String => {
+                let op = Op::StringLower {
+                    encoding: self.string_encoding,
+                    realloc: self.lower_realloc(),
+                };
+                return self.emit(op, vec![value], 2);
+            }
+            Type::Id(id) => *id,
+            _ => return vec![self.emit1(Op::PrimitiveLower { ty: *ty }, vec![value])],
+        };
+        if let Some((_, cases)) = self.variant_cases(id) {
+            return self.lower_variant_flat(id, &cases, value);
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.lower(t, value),
+            TypeDefKind::Record(r) => {
+                let fields = self.emit(Op::RecordLower { ty: id }, vec![value], r.fields.len());
+                let mut flat = Vec::new();
+                for (field, value) in r.fields.iter().zip(fields) {
+                    flat.extend(self.lower(&field.ty, value));
+                }
+                flat
+            }
+            TypeDefKind::Tuple(t) => {
+                let elems = self.emit(Op::TupleLower { ty: id }, vec![value], t.types.len());
+                let mut flat = Vec::new();
+                for (ty, value) in t.types.iter().zip(elems) {
+                    flat.extend(self.lower(ty, value));
+                }
+                flat
+            }
+            TypeDefKind::Flags(f) => {
+                let count = f.repr().count();
+                self.emit(Op::FlagsLower { ty: id }, vec![value], count)
+            }
+            TypeDefKind::Enum(_) => vec![self.emit1(Op::EnumLower { ty: id }, vec![value])],
+            TypeDefKind::List(element) => self.lower_list(element, value),
+            TypeDefKind::Handle(handle) => {
+                let op = Op::HandleLower {
+                    handle: *handle,
+                    ty: id,
+                };
+                vec![self.emit1(op, vec![value])]
+            }
+            TypeDefKind::Future(_) => vec![self.emit1(Op::FutureLower { ty: id }, vec![value])],
+            TypeDefKind::Stream(_) => vec![self.emit1(Op::StreamLower { ty: id }, vec![value])],
+            TypeDefKind::Variant(_) | TypeDefKind::Option(_) | TypeDefKind::Result(_) => {
+                unreachable!()
+            }
+            TypeDefKind::Resource | TypeDefKind::Unknown => unreachable!(),
+        }
+    }
+
+    fn lower_variant_flat(
+        &mut self,
+        id: TypeId,
+        cases: &[Option<Type>],
+        value: Value,
+    ) -> Vec<Value> {
+        let flat = self.flat_types(&Type::Id(id));
+        let joined = &flat[1..];
+        let mut blocks = Vec::new();
+        for case in cases {
+            self.push_block();
+            let mut params = Vec::new();
+            let mut results = Vec::new();
+            if let Some(ty) = case {
+                let payload = self.value();
+                params.push(payload);
+                let values = self.lower(ty, payload);
+                for ((value, from), to) in values.into_iter().zip(self.flat_types(ty)).zip(joined) {
+                    results.push(self.bitcast(value, from, *to));
+                }
+            }
+            for ty in joined[results.len()..].iter() {
+                results.push(self.emit1(Op::Const { ty: *ty, value: 0 }, Vec::new()));
+            }
+            blocks.push(self.finish_block(params, results));
+        }
+        let op = Op::VariantLower {
+            ty: id,
+            cases: blocks,
+        };
+        self.emit(op, vec![value], flat.len())
+    }
+
+    fn lower_list(&mut self, element: &Type, value: Value) -> Vec<Value> {
+        let realloc = self.lower_realloc();
+        if self.is_canonical_element(element) {
+            let op = Op::ListCanonLower {
+                element: *element,
+                realloc,
+            };
+            return self.emit(op, vec![value], 2);
+        }
+        self.push_block();
+        let elem = self.value();
+        let addr = self.value();
+        self.store(element, elem, addr, 0);
+        let body = self.finish_block(vec![elem, addr], Vec::new());
+        let op = Op::ListLower {
+            element: *element,
+            size: self.sizes.size(element),
+            align: self.sizes.align(element),
+            realloc,
+            body,
+        };
+        self.emit(op, vec![value], 2)
+    }
+
+    fn lift(&mut self, ty: &Type, flat: &mut impl Iterator<Item = Value>) -> Value {
+        let id = match ty {
+            Type::String => {
+                let operands = flat.take(2).collect();
+                let op = Op::StringLift {
+                    encoding: self.string_encoding,
+                };
+                return self.emit1(op, operands);
+            }
+            Type::Id(id) => *id,
+            _ => {
+                let operand = flat.next().unwrap();
+                return self.emit1(Op::PrimitiveLift { ty: *ty }, vec![operand]);
+            }
+        };
+        if let Some((_, cases)) = self.variant_cases(id) {
+            return self.lift_variant_flat(id, &cases, flat);
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.lift(t, flat),
+            TypeDefKind::Record(r) => {
+                let fields = r.fields.iter().map(|f| self.lift(&f.ty, flat)).collect();
+                self.emit1(Op::RecordLift { ty: id }, fields)
+            }
+            TypeDefKind::Tuple(t) => {
+                let elems = t.types.iter().map(|ty| self.lift(ty, flat)).collect();
+                self.emit1(Op::TupleLift { ty: id }, elems)
+            }
+            TypeDefKind::Flags(f) => {
+                let operands = flat.take(f.repr().count()).collect();
+                self.emit1(Op::FlagsLift { ty: id }, operands)
+            }
+            TypeDefKind::Enum(_) => {
+                let operand = flat.next().unwrap();
+                self.emit1(Op::EnumLift { ty: id }, vec![operand])
+            }
+            TypeDefKind::List(element) => {
+                let operands = flat.take(2).collect();
+                self.lift_list(element, operands)
+            }
+            TypeDefKind::Handle(handle) => {
+                let operand = flat.next().unwrap();
+                let op = Op::HandleLift {
+                    handle: *handle,
+                    ty: id,
+                };
+                self.emit1(op, vec![operand])
+            }
+            TypeDefKind::Future(_) => {
+                let operand = flat.next().unwrap();
+                self.emit1(Op::FutureLift { ty: id }, vec![operand])
+            }
+            TypeDefKind::Stream(_) => {
+                let operand = flat.next().unwrap();
+                self.emit1(Op::StreamLift { ty: id }, vec![operand])
+            }
+            TypeDefKind::Variant(_) | TypeDefKind::Option(_) | TypeDefKind::Result(_) => {
+                unreachable!()
+            }
+            TypeDefKind::Resource | TypeDefKind::Unknown => unreachable!(),
+        }
+    }
+
+    fn lift_variant_flat(
+        &mut self,
+        id: TypeId,
+        cases: &[Option<Type>],
+        flat: &mut impl Iterator<Item = Value>,
+    ) -> Value {
+        let types = self.flat_types(&Type::Id(id));
+        let values = flat.take(types.len()).collect::<Vec<_>>();
+        let mut blocks = Vec::new();
+        for case in cases {
+            self.push_block();
+            let mut results = Vec::new();
+            if let Some(ty) = case {
+                let mut payload = Vec::new();
+                for ((value, from), to) in
+                    values[1..].iter().zip(&types[1..]).zip(self.flat_types(ty))
+                {
+                    payload.push(self.bitcast(*value, *from, to));
+                }
+                results.push(self.lift(ty, &mut payload.into_iter()));
+            }
+            blocks.push(self.finish_block(Vec::new(), results));
+        }
+        let op = Op::VariantLift {
+            ty: id,
+            cases: blocks,
+        };
+        self.emit1(op, vec![values[0]])
+    }
+
+    fn lift_list(&mut self, element: &Type, operands: Vec<Value>) -> Value {
+        if self.is_canonical_element(element) {
+            return self.emit1(Op::ListCanonLift { element: *element }, operands);
+        }
+        self.push_block();
+        let addr = self.value();
+        let elem = self.load(element, addr, 0);
+        let body = self.finish_block(vec![addr], vec![elem]);
+        let op = Op::ListLift {
+            element: *element,
+            size: self.sizes.size(element),
+            align: self.sizes.align(element),
+            body,
+        };
+        self.emit1(op, operands)
+    }
+
+    fn store(&mut self, ty: &Type, value: Value, addr: Value, offset: usize) {
+        let id = match ty {
+            Type::String => {
+                let flat = self.lower(ty, value);
+                self.store_pointer_and_length(&flat, addr, offset);
+                return;
+            }
+            Type::Id(id) => *id,
+            _ => {
+                let core = self.emit1(Op::PrimitiveLower { ty: *ty }, vec![value]);
+                let ty = primitive_memory_type(ty);
+                self.emit(Op::Store { ty, offset }, vec![core, addr], 0);
+                return;
+            }
+        };
+        if let Some((tag, cases)) = self.variant_cases(id) {
+            let payload_offset = offset
+                + self
+                    .sizes
+                    .payload_offset(tag, cases.iter().map(|c| c.as_ref()));
+            let mut blocks = Vec::new();
+            for case in cases.iter() {
+                self.push_block();
+                let mut params = Vec::new();
+                if let Some(ty) = case {
+                    let payload = self.value();
+                    params.push(payload);
+                    self.store(ty, payload, addr, payload_offset);
+                }
+                blocks.push(self.finish_block(params, Vec::new()));
+            }
+            let op = Op::VariantLower {
+                ty: id,
+                cases: blocks,
+            };
+            let discriminant = self.emit1(op, vec![value]);
+            let ty = tag_memory_type(tag);
+            self.emit(Op::Store { ty, offset }, vec![discriminant, addr], 0);
+            return;
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.store(t, value, addr, offset),
+            TypeDefKind::Record(r) => {
+                let fields = self.emit(Op::RecordLower { ty: id }, vec![value], r.fields.len());
+                let offsets = self.sizes.field_offsets(r.fields.iter().map(|f| &f.ty));
+                for ((field_offset, ty), value) in offsets.into_iter().zip(fields) {
+                    self.store(ty, value, addr, offset + field_offset);
+                }
+            }
+            TypeDefKind::Tuple(t) => {
+                let elems = self.emit(Op::TupleLower { ty: id }, vec![value], t.types.len());
+                let offsets = self.sizes.field_offsets(t.types.iter());
+                for ((elem_offset, ty), value) in offsets.into_iter().zip(elems) {
+                    self.store(ty, value, addr, offset + elem_offset);
+                }
+            }
+            TypeDefKind::Flags(f) => {
+                let repr = f.repr();
+                let values = self.emit(Op::FlagsLower { ty: id }, vec![value], repr.count());
+                let ty = flags_memory_type(&repr);
+                for (i, value) in values.into_iter().enumerate() {
+                    let offset = offset + i * 4;
+                    self.emit(Op::Store { ty, offset }, vec![value, addr], 0);
+                }
+            }
+            TypeDefKind::Enum(e) => {
+                let discriminant = self.emit1(Op::EnumLower { ty: id }, vec![value]);
+                let ty = tag_memory_type(e.tag());
+                self.emit(Op::Store { ty, offset }, vec![discriminant, addr], 0);
+            }
+            TypeDefKind::List(_) => {
+                let flat = self.lower(ty, value);
+                self.store_pointer_and_length(&flat, addr, offset);
+            }
+            TypeDefKind::Handle(_) | TypeDefKind::Future(_) | TypeDefKind::Stream(_) => {
+                let handle = self.lower(ty, value)[0];
+                let ty = MemoryType::I32;
+                self.emit(Op::Store { ty, offset }, vec![handle, addr], 0);
+            }
+            TypeDefKind::Variant(_) | TypeDefKind::Option(_) | TypeDefKind::Result(_) => {
+                unreachable!()
+            }
+            TypeDefKind::Resource | TypeDefKind::Unknown => unreachable!(),
+        }
+    }
+
+    fn store_pointer_and_length(&mut self, flat: &[Value], addr: Value, offset: usize) {
+        let ptr_size = self.pointer_size();
+        let ty = MemoryType::Pointer;
+        self.emit(Op::Store { ty, offset }, vec![flat[0], addr], 0);
+        let ty = MemoryType::Length;
+        let offset = offset + ptr_size;
+        self.emit(Op::Store { ty, offset }, vec![flat[1], addr], 0);
+    }
+
+    fn load(&mut self, ty: &Type, addr: Value, offset: usize) -> Value {
+        let id = match ty {
+            Type::String => {
+                let flat = self.load_pointer_and_length(addr, offset);
+                return self.lift(ty, &mut flat.into_iter());
+            }
+            Type::Id(id) => *id,
+            _ => {
+                let mem = primitive_memory_type(ty);
+                let core = self.emit1(Op::Load { ty: mem, offset }, vec![addr]);
+                return self.emit1(Op::PrimitiveLift { ty: *ty }, vec![core]);
+            }
+        };
+        if let Some((tag, cases)) = self.variant_cases(id) {
+            let ty = tag_memory_type(tag);
+            let discriminant = self.emit1(Op::Load { ty, offset }, vec![addr]);
+            let payload_offset = offset
+                + self
+                    .sizes
+                    .payload_offset(tag, cases.iter().map(|c| c.as_ref()));
+            let mut blocks = Vec::new();
+            for case in cases.iter() {
+                self.push_block();
+                let results = match case {
+                    Some(ty) => vec![self.load(ty, addr, payload_offset)],
+                    None => Vec::new(),
+                };
+                blocks.push(self.finish_block(Vec::new(), results));
+            }
+            let op = Op::VariantLift {
+                ty: id,
+                cases: blocks,
+            };
+            return self.emit1(op, vec![discriminant]);
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.load(t, addr, offset),
+            TypeDefKind::Record(r) => {
+                let offsets = self.sizes.field_offsets(r.fields.iter().map(|f| &f.ty));
+                let fields = offsets
+                    .into_iter()
+                    .map(|(field_offset, ty)| self.load(ty, addr, offset + field_offset))
+                    .collect();
+                self.emit1(Op::RecordLift { ty: id }, fields)
+            }
+            TypeDefKind::Tuple(t) => {
+                let offsets = self.sizes.field_offsets(t.types.iter());
+                let elems = offsets
+                    .into_iter()
+                    .map(|(elem_offset, ty)| self.load(ty, addr, offset + elem_offset))
+                    .collect();
+                self.emit1(Op::TupleLift { ty: id }, elems)
+            }
+            TypeDefKind::Flags(f) => {
+                let repr = f.repr();
+                let ty = flags_memory_type(&repr);
+                let values = (0..repr.count())
+                    .map(|i| {
+                        let offset = offset + i * 4;
+                        self.emit1(Op::Load { ty, offset }, vec![addr])
+                    })
+                    .collect();
+                self.emit1(Op::FlagsLift { ty: id }, values)
+            }
+            TypeDefKind::Enum(e) => {
+                let ty = tag_memory_type(e.tag());
+                let discriminant = self.emit1(Op::Load { ty, offset }, vec![addr]);
+                self.emit1(Op::EnumLift { ty: id }, vec![discriminant])
+            }
+            TypeDefKind::List(_) => {
+                let flat = self.load_pointer_and_length(addr, offset);
+                self.lift(ty, &mut flat.into_iter())
+            }
+            TypeDefKind::Handle(_) | TypeDefKind::Future(_) | TypeDefKind::Stream(_) => {
+                let mem = MemoryType::I32;
+                let handle = self.emit1(Op::Load { ty: mem, offset }, vec![addr]);
+                self.lift(ty, &mut Some(handle).into_iter())
+            }
+            TypeDefKind::Variant(_) | TypeDefKind::Option(_) | TypeDefKind::Result(_) => {
+                unreachable!()
+            }
+            TypeDefKind::Resource | TypeDefKind::Unknown => unreachable!(),
+        }
+    }
+
+    fn load_pointer_and_length(&mut self, addr: Value, offset: usize) -> Vec<Value> {
+        let ptr_size = self.pointer_size();
+        let ty = MemoryType::Pointer;
+        let ptr = self.emit1(Op::Load { ty, offset }, vec![addr]);
+        let ty = MemoryType::Length;
+        let offset = offset + ptr_size;
+        let len = self.emit1(Op::Load { ty, offset }, vec![addr]);
+        vec![ptr, len]
+    }
+
+    fn pointer_size(&self) -> usize {
+        // A string is a pointer followed by a length of the same width.
+        self.sizes.size(&Type::String) / 2
+    }
+
+    /// Returns whether lists of `ty` can be passed without converting each
+    /// element, meaning their source representation matches memory.
+    fn is_canonical_element(&self, ty: &Type) -> bool {
+        match ty {
+            Type::U8
+            | Type::S8
+            | Type::U16
+            | Type::S16
+            | Type::U32
+            | Type::S32
+            | Type::U64
+            | Type::S64
+            | Type::F32
+            | Type::F64 => true,
+            Type::Id(id) => match &self.resolve.types[*id].kind {
+                TypeDefKind::Type(t) => self.is_canonical_element(t),
+                _ => false,
+            },
+            Type::Bool | Type::Char | Type::String | Type::ErrorContext => false,
+        }
+    }
+
+    /// Returns whether values of `ty` own memory which must be freed.
+    fn needs_deallocate(&self, ty: &Type) -> bool {
+        let id = match ty {
+            Type::String => return true,
+            Type::Id(id) => *id,
+            _ => return false,
+        };
+        if let Some((_, cases)) = self.variant_cases(id) {
+            return cases.iter().flatten().any(|t| self.needs_deallocate(t));
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.needs_deallocate(t),
+            TypeDefKind::Record(r) => r.fields.iter().any(|f| self.needs_deallocate(&f.ty)),
+            TypeDefKind::Tuple(t) => t.types.iter().any(|t| self.needs_deallocate(t)),
+            TypeDefKind::List(_) => true,
+            _ => false,
+        }
+    }
+
+    fn deallocate_flat(&mut self, ty: &Type, flat: &mut impl Iterator<Item = Value>) {
+        let count = self.flat_types(ty).len();
+        if !self.needs_deallocate(ty) {
+            flat.take(count).for_each(drop);
+            return;
+        }
+        let id = match ty {
+            Type::String => {
+                let operands = flat.take(2).collect();
+                self.emit(Op::GuestDeallocateString, operands, 0);
+                return;
+            }
+            Type::Id(id) => *id,
+            _ => unreachable!(),
+        };
+        if let Some((_, cases)) = self.variant_cases(id) {
+            let types = self.flat_types(ty);
+            let values = flat.take(count).collect::<Vec<_>>();
+            let mut blocks = Vec::new();
+            for case in cases.iter() {
+                self.push_block();
+                if let Some(ty) = case {
+                    let mut payload = Vec::new();
+                    for ((value, from), to) in
+                        values[1..].iter().zip(&types[1..]).zip(self.flat_types(ty))
+                    {
+                        payload.push(self.bitcast(*value, *from, to));
+                    }
+                    self.deallocate_flat(ty, &mut payload.into_iter());
+                }
+                blocks.push(self.finish_block(Vec::new(), Vec::new()));
+            }
+            self.emit(
+                Op::GuestDeallocateVariant { cases: blocks },
+                vec![values[0]],
+                0,
+            );
+            return;
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.deallocate_flat(t, flat),
+            TypeDefKind::Record(r) => {
+                for field in r.fields.iter() {
+                    self.deallocate_flat(&field.ty, flat);
+                }
+            }
+            TypeDefKind::Tuple(t) => {
+                for ty in t.types.iter() {
+                    self.deallocate_flat(ty, flat);
+                }
+            }
+            TypeDefKind::List(element) => {
+                let operands = flat.take(2).collect();
+                self.deallocate_list(element, operands);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn deallocate_list(&mut self, element: &Type, operands: Vec<Value>) {
+        self.push_block();
+        let addr = self.value();
+        self.deallocate_memory(element, addr, 0);
+        let body = self.finish_block(vec![addr], Vec::new());
+        let op = Op::GuestDeallocateList {
+            element: *element,
+            size: self.sizes.size(element),
+            align: self.sizes.align(element),
+            body,
+        };
+        self.emit(op, operands, 0);
+    }
+
+    fn deallocate_memory(&mut self, ty: &Type, addr: Value, offset: usize) {
+        if !self.needs_deallocate(ty) {
+            return;
+        }
+        let id = match ty {
+            Type::String => {
+                let operands = self.load_pointer_and_length(addr, offset);
+                self.emit(Op::GuestDeallocateString, operands, 0);
+                return;
+            }
+            Type::Id(id) => *id,
+            _ => unreachable!(),
+        };
+        if let Some((tag, cases)) = self.variant_cases(id) {
+            let ty = tag_memory_type(tag);
+            let discriminant = self.emit1(Op::Load { ty, offset }, vec![addr]);
+            let payload_offset = offset
+                + self
+                    .sizes
+                    .payload_offset(tag, cases.iter().map(|c| c.as_ref()));
+            let mut blocks = Vec::new();
+            for case in cases.iter() {
+                self.push_block();
+                if let Some(ty) = case {
+                    self.deallocate_memory(ty, addr, payload_offset);
+                }
+                blocks.push(self.finish_block(Vec::new(), Vec::new()));
+            }
+            let op = Op::GuestDeallocateVariant { cases: blocks };
+            self.emit(op, vec![discriminant], 0);
+            return;
+        }
+        match &self.resolve.types[id].kind {
+            TypeDefKind::Type(t) => self.deallocate_memory(t, addr, offset),
+            TypeDefKind::Record(r) => {
+                let offsets = self.sizes.field_offsets(r.fields.iter().map(|f| &f.ty));
+                for (field_offset, ty) in offsets {
+                    self.deallocate_memory(ty, addr, offset + field_offset);
+                }
+            }
+            TypeDefKind::Tuple(t) => {
+                let offsets = self.sizes.field_offsets(t.types.iter());
+                for (elem_offset, ty) in offsets {
+                    self.deallocate_memory(ty, addr, offset + elem_offset);
+                }
+            }
+            TypeDefKind::List(element) => {
+                let operands = self.load_pointer_and_length(addr, offset);
+                self.deallocate_list(element, operands);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn primitive_memory_type(ty: &Type) -> MemoryType {
+    match ty {
+        Type::Bool | Type::U8 => MemoryType::U8,
+        Type::S8 => MemoryType::S8,
+        Type::U16 => MemoryType::U16,
+        Type::S16 => MemoryType::S16,
+        Type::U32 | Type::S32 | Type::Char | Type::ErrorContext => MemoryType::I32,
+        Type::U64 | Type::S64 => MemoryType::I64,
+        Type::F32 => MemoryType::F32,
+        Type::F64 => MemoryType::F64,
+        Type::String | Type::Id(_) => unreachable!(),
+    }
+}
+
+fn tag_memory_type(tag: Int) -> MemoryType {
+    match tag {
+        Int::U8 => MemoryType::U8,
+        Int::U16 => MemoryType::U16,
+        Int::U32 => MemoryType::I32,
+        Int::U64 => MemoryType::I64,
+    }
+}
+
+fn flags_memory_type(repr: &FlagsRepr) -> MemoryType {
+    match repr {
+        FlagsRepr::U8 => MemoryType::U8,
+        FlagsRepr::U16 => MemoryType::U16,
+        FlagsRepr::U32(_) => MemoryType::I32,
+    }
+}
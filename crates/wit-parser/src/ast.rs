@@ -1,4 +1,4 @@
-use crate::{Error, UnresolvedPackage, UnresolvedPackageGroup};
+use crate::{Error, SourceLocation, UnresolvedPackage, UnresolvedPackageGroup};
 use anyhow::{bail, Context, Result};
 use lex::{Span, Token, Tokenizer};
 use semver::Version;
@@ -724,6 +724,7 @@ enum Type<'a> {
     F64(Span),
     Char(Span),
     String(Span),
+    ErrorContext(Span),
     Name(Id<'a>),
     List(List<'a>),
     Handle(Handle<'a>),
@@ -1295,6 +1296,7 @@ impl<'a> Type<'a> {
 
             Some((span, Token::Bool)) => Ok(Type::Bool(span)),
             Some((span, Token::String_)) => Ok(Type::String(span)),
+            Some((span, Token::ErrorContext)) => Ok(Type::ErrorContext(span)),
 
             // list<T>
             Some((span, Token::List)) => {
@@ -1421,7 +1423,8 @@ impl<'a> Type<'a> {
             | Type::F32(span)
             | Type::F64(span)
             | Type::Char(span)
-            | Type::String(span) => *span,
+            | Type::String(span)
+            | Type::ErrorContext(span) => *span,
             Type::Name(id) => id.span,
             Type::List(l) => l.span,
             Type::Handle(h) => h.span(),
@@ -1504,6 +1507,10 @@ enum Attribute<'a> {
         span: Span,
         feature: Id<'a>,
     },
+    Deprecated {
+        span: Span,
+        version: Version,
+    },
 }
 
 impl<'a> Attribute<'a> {
@@ -1542,6 +1549,17 @@ impl<'a> Attribute<'a> {
                         feature,
                     }
                 }
+                "deprecated" => {
+                    tokens.eat(Token::LeftParen)?;
+                    eat_id(tokens, "version")?;
+                    tokens.eat(Token::Equals)?;
+                    let (_span, version) = parse_version(tokens)?;
+                    tokens.eat(Token::RightParen)?;
+                    Attribute::Deprecated {
+                        span: id.span,
+                        version,
+                    }
+                }
                 other => {
                     bail!(Error::new(id.span, format!("unknown attribute `{other}`"),))
                 }
@@ -1553,7 +1571,9 @@ impl<'a> Attribute<'a> {
 
     fn span(&self) -> Span {
         match self {
-            Attribute::Since { span, .. } | Attribute::Unstable { span, .. } => *span,
+            Attribute::Since { span, .. }
+            | Attribute::Unstable { span, .. }
+            | Attribute::Deprecated { span, .. } => *span,
         }
     }
 }
@@ -1778,12 +1798,28 @@ impl SourceMap {
         Err(err)
     }
 
-    fn highlight_err(&self, start: u32, end: Option<u32>, err: impl fmt::Display) -> String {
-        let i = match self.sources.binary_search_by_key(&start, |src| src.offset) {
+    /// Returns the file, line and column that `span` starts at.
+    pub(crate) fn location(&self, span: Span) -> SourceLocation {
+        let src = self.source_at(span.start);
+        let pos = usize::try_from(span.start - src.offset).unwrap();
+        let (line, col) = linecol_in(pos, &src.contents);
+        SourceLocation {
+            path: src.path.clone(),
+            line: u32::try_from(line + 1).unwrap(),
+            column: u32::try_from(col + 1).unwrap(),
+        }
+    }
+
+    fn source_at(&self, pos: u32) -> &Source {
+        let i = match self.sources.binary_search_by_key(&pos, |src| src.offset) {
             Ok(i) => i,
             Err(i) => i - 1,
         };
-        let src = &self.sources[i];
+        &self.sources[i]
+    }
+
+    fn highlight_err(&self, start: u32, end: Option<u32>, err: impl fmt::Display) -> String {
+        let src = self.source_at(start);
         let start = usize::try_from(start - src.offset).unwrap();
         let end = end.map(|end| usize::try_from(end - src.offset).unwrap());
         let (line, col) = linecol_in(start, &src.contents);
@@ -1808,21 +1844,7 @@ impl SourceMap {
                 }
             }
         }
-        return msg;
-
-        fn linecol_in(pos: usize, text: &str) -> (usize, usize) {
-            let mut cur = 0;
-            // Use split_terminator instead of lines so that if there is a `\r`,
-            // it is included in the offset calculation. The `+1` values below
-            // account for the `\n`.
-            for (i, line) in text.split_terminator('\n').enumerate() {
-                if cur + line.len() + 1 > pos {
-                    return (i, pos - cur);
-                }
-                cur += line.len() + 1;
-            }
-            (text.lines().count(), 0)
-        }
+        msg
     }
 
     /// Returns an iterator over all filenames added to this source map.
@@ -1831,6 +1853,20 @@ impl SourceMap {
     }
 }
 
+fn linecol_in(pos: usize, text: &str) -> (usize, usize) {
+    let mut cur = 0;
+    // Use split_terminator instead of lines so that if there is a `\r`,
+    // it is included in the offset calculation. The `+1` values below
+    // account for the `\n`.
+    for (i, line) in text.split_terminator('\n').enumerate() {
+        if cur + line.len() + 1 > pos {
+            return (i, pos - cur);
+        }
+        cur += line.len() + 1;
+    }
+    (text.lines().count(), 0)
+}
+
 pub enum ParsedUsePath {
     Name(String),
     Package(crate::PackageName, String),
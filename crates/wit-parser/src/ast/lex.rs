@@ -75,6 +75,7 @@ pub enum Token {
     Enum,
     Bool,
     String_,
+    ErrorContext,
     Option_,
     Result_,
     Future,
@@ -306,6 +307,7 @@ impl<'a> Tokenizer<'a> {
                     "enum" => Enum,
                     "bool" => Bool,
                     "string" => String_,
+                    "error-context" => ErrorContext,
                     "option" => Option_,
                     "result" => Result_,
                     "future" => Future,
@@ -559,6 +561,7 @@ impl Token {
             Enum => "keyword `enum`",
             Bool => "keyword `bool`",
             String_ => "keyword `string`",
+            ErrorContext => "keyword `error-context`",
             Option_ => "keyword `option`",
             Result_ => "keyword `result`",
             Future => "keyword `future`",
@@ -1111,6 +1111,7 @@ impl<'a> Resolver<'a> {
             ast::Type::F64(_) => TypeDefKind::Type(Type::F64),
             ast::Type::Char(_) => TypeDefKind::Type(Type::Char),
             ast::Type::String(_) => TypeDefKind::Type(Type::String),
+            ast::Type::ErrorContext(_) => TypeDefKind::Type(Type::ErrorContext),
             ast::Type::Name(name) => {
                 let id = self.resolve_type_name(name)?;
                 TypeDefKind::Type(Type::Id(id))
@@ -1374,6 +1375,19 @@ impl<'a> Resolver<'a> {
     }
 
     fn stability(&mut self, attrs: &[ast::Attribute<'_>]) -> Result<Stability> {
+        // `@deprecated` may only follow one of the other attributes.
+        let (attrs, deprecated) = match attrs {
+            [rest @ .., ast::Attribute::Deprecated { version, span }] => {
+                if rest.is_empty() {
+                    bail!(Error::new(
+                        *span,
+                        "`@deprecated` must be paired with `@since` or `@unstable`",
+                    ))
+                }
+                (rest, Some(version.clone()))
+            }
+            _ => (attrs, None),
+        };
         match attrs {
             [] => Ok(Stability::Unknown),
             [ast::Attribute::Since {
@@ -1381,10 +1395,16 @@ impl<'a> Resolver<'a> {
             }] => Ok(Stability::Stable {
                 since: version.clone(),
                 feature: feature.as_ref().map(|s| s.name.to_string()),
+                deprecated,
             }),
             [ast::Attribute::Unstable { feature, .. }] => Ok(Stability::Unstable {
                 feature: feature.name.to_string(),
+                deprecated,
             }),
+            [ast::Attribute::Deprecated { span, .. }, ..] => bail!(Error::new(
+                *span,
+                "`@deprecated` must follow `@since` or `@unstable`",
+            )),
             [_, b, ..] => {
                 bail!(Error::new(
                     b.span(),
@@ -1487,6 +1507,7 @@ fn collect_deps<'a>(ty: &ast::Type<'a>, deps: &mut Vec<ast::Id<'a>>) {
         | ast::Type::F64(_)
         | ast::Type::Char(_)
         | ast::Type::String(_)
+        | ast::Type::ErrorContext(_)
         | ast::Type::Flags(_)
         | ast::Type::Enum(_) => {}
         ast::Type::Name(name) => deps.push(name.clone()),
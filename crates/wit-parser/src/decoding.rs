@@ -410,6 +410,44 @@ pub fn decode(bytes: &[u8]) -> Result<DecodedWasm> {
     decode_reader(bytes)
 }
 
+/// Decodes an in-memory WebAssembly binary like [`decode`], keeping only the
+/// `@unstable` items whose features are selected.
+///
+/// Binary WIT packages retain the `@unstable` gates of their items, and may
+/// contain items gated by any number of features. Here `features` and
+/// `all_features` select which of those items are kept, with the same meaning
+/// as [`Resolve::features`] and [`Resolve::all_features`], and all other gated
+/// items are removed with [`Resolve::filter_features`].
+pub fn decode_with_features<'a>(
+    bytes: &[u8],
+    features: impl IntoIterator<Item = &'a str>,
+    all_features: bool,
+) -> Result<DecodedWasm> {
+    let mut decoded = decode(bytes)?;
+    let resolve = match &mut decoded {
+        DecodedWasm::WitPackages(resolve, _) => resolve,
+        DecodedWasm::Component(resolve, _) => resolve,
+    };
+    resolve.features = features.into_iter().map(|f| f.to_string()).collect();
+    resolve.all_features = all_features;
+    let remap = resolve
+        .filter_features()
+        .context("failed to select features of the decoded WIT")?;
+    match &mut decoded {
+        DecodedWasm::WitPackages(_, pkgs) => {
+            for pkg in pkgs.iter_mut() {
+                *pkg = remap.packages[pkg.index()];
+            }
+        }
+        DecodedWasm::Component(_, world) => {
+            *world = remap
+                .map_world(*world, None)
+                .context("the component's world is gated by a feature that isn't selected")?;
+        }
+    }
+    Ok(decoded)
+}
+
 /// Decodes the single component type `world` specified as a WIT world.
 ///
 /// The `world` should be an exported component type. The `world` must have been
@@ -1257,6 +1295,9 @@ impl WitPackageDecoder<'_> {
         // more-or-less unreachable due to expected validation to be added to
         // the component model binary format itself.
         let def = &self.types[id];
+        if let types::ComponentDefinedType::ErrorContext = def {
+            return Ok(Type::ErrorContext);
+        }
         let kind = self.convert_defined(def)?;
         match &kind {
             TypeDefKind::Type(_)
@@ -1264,15 +1305,15 @@ impl WitPackageDecoder<'_> {
             | TypeDefKind::Tuple(_)
             | TypeDefKind::Option(_)
             | TypeDefKind::Result(_)
-            | TypeDefKind::Handle(_) => {}
+            | TypeDefKind::Handle(_)
+            | TypeDefKind::Future(_)
+            | TypeDefKind::Stream(_) => {}
 
             TypeDefKind::Resource
             | TypeDefKind::Record(_)
             | TypeDefKind::Enum(_)
             | TypeDefKind::Variant(_)
-            | TypeDefKind::Flags(_)
-            | TypeDefKind::Future(_)
-            | TypeDefKind::Stream(_) => {
+            | TypeDefKind::Flags(_) => {
                 bail!("unexpected unnamed type of kind '{}'", kind.as_str());
             }
             TypeDefKind::Unknown => unreachable!(),
@@ -1399,6 +1440,24 @@ impl WitPackageDecoder<'_> {
                 let id = self.type_map[&(*id).into()];
                 Ok(TypeDefKind::Handle(Handle::Borrow(id)))
             }
+
+            types::ComponentDefinedType::Future(t) => {
+                let t = match t {
+                    Some(t) => Some(self.convert_valtype(t)?),
+                    None => None,
+                };
+                Ok(TypeDefKind::Future(t))
+            }
+
+            types::ComponentDefinedType::Stream(t) => {
+                let element = match t {
+                    Some(t) => Some(self.convert_valtype(t)?),
+                    None => None,
+                };
+                Ok(TypeDefKind::Stream(Stream { element, end: None }))
+            }
+
+            types::ComponentDefinedType::ErrorContext => Ok(TypeDefKind::Type(Type::ErrorContext)),
         }
     }
 
@@ -1669,11 +1728,38 @@ impl Registrar<'_> {
                 Ok(())
             }
 
+            types::ComponentDefinedType::Future(t) => {
+                let ty = match &self.resolve.types[id].kind {
+                    TypeDefKind::Future(r) => r,
+                    TypeDefKind::Type(Type::Id(_)) => return Ok(()),
+                    _ => bail!("expected a future"),
+                };
+                match (t, ty) {
+                    (Some(a), Some(b)) => self.valtype(a, b),
+                    (None, None) => Ok(()),
+                    _ => bail!("disagreement on future payload"),
+                }
+            }
+
+            types::ComponentDefinedType::Stream(t) => {
+                let ty = match &self.resolve.types[id].kind {
+                    TypeDefKind::Stream(r) => r,
+                    TypeDefKind::Type(Type::Id(_)) => return Ok(()),
+                    _ => bail!("expected a stream"),
+                };
+                match (t, &ty.element) {
+                    (Some(a), Some(b)) => self.valtype(a, b),
+                    (None, None) => Ok(()),
+                    _ => bail!("disagreement on stream element"),
+                }
+            }
+
             // These have no recursive structure so they can bail out.
             types::ComponentDefinedType::Flags(_)
             | types::ComponentDefinedType::Enum(_)
             | types::ComponentDefinedType::Own(_)
-            | types::ComponentDefinedType::Borrow(_) => Ok(()),
+            | types::ComponentDefinedType::Borrow(_)
+            | types::ComponentDefinedType::ErrorContext => Ok(()),
         }
     }
 
@@ -1687,6 +1773,7 @@ impl Registrar<'_> {
         };
         let wit = match wit {
             Type::Id(id) => *id,
+            Type::ErrorContext => return Ok(()),
             _ => bail!("expected id-based type"),
         };
         let prev = match self.type_map.insert(wasm.into(), wit) {
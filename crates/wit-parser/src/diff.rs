@@ -0,0 +1,633 @@
+//! Structural comparison of two versions of a WIT world.
+//!
+//! The [`diff_worlds`] function compares the imports and exports of two worlds
+//! item-by-item and produces a list of [`ApiChange`]s, each classified by the
+//! [`Severity`] of the semver bump it requires.
+
+use crate::{
+    Docs, Function, FunctionKind, Interface, InterfaceId, Resolve, Results, Type, TypeDefKind,
+    TypeId, WorldId, WorldItem, WorldKey,
+};
+use indexmap::IndexMap;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde_derive::Serialize;
+
+/// The severity of an [`ApiChange`] in terms of semantic versioning.
+///
+/// Severities are ordered such that `Patch < Minor < Major`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    /// The change has no effect on the structure of the API, such as a change
+    /// to documentation.
+    Patch,
+    /// The change is backwards-compatible, such as a new import.
+    Minor,
+    /// The change is not backwards-compatible, such as a removed import or a
+    /// changed function signature.
+    Major,
+}
+
+/// One segment of the path to the item affected by an [`ApiChange`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PathSegment {
+    /// The kind of item, for example `"import"`, `"export"`, `"func"`,
+    /// `"param"`, `"result"`, `"method"`, `"static"`, `"constructor"`,
+    /// `"field"`, `"case"`, `"flag"`, or the kind of a type definition such as
+    /// `"record"` or `"resource"`.
+    pub kind: &'static str,
+    /// The name of the item, or an empty string for an anonymous function
+    /// result.
+    pub name: String,
+}
+
+/// The kind of change made to an item.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case", tag = "kind"))]
+pub enum ApiChangeKind {
+    /// The item was added.
+    Added,
+    /// The item was removed.
+    Removed,
+    /// The type or kind of the item changed.
+    Changed {
+        /// A description of the item in the previous version.
+        prev: String,
+        /// A description of the item in the new version.
+        new: String,
+    },
+    /// The documentation of the item changed.
+    DocsChanged,
+}
+
+/// A single difference between two versions of a world.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ApiChange {
+    /// The path to the item which changed, starting at the world import or
+    /// export it's found within.
+    pub path: Vec<PathSegment>,
+    /// What happened to the item.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: ApiChangeKind,
+    /// How this change affects compatibility.
+    pub severity: Severity,
+}
+
+impl fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self
+            .path
+            .iter()
+            .map(|s| match s.name.as_str() {
+                "" => s.kind.to_string(),
+                name => format!("{} {name}", s.kind),
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        write!(f, "{}: ", self.severity)?;
+        match &self.kind {
+            ApiChangeKind::Added => write!(f, "added `{path}`"),
+            ApiChangeKind::Removed => write!(f, "removed `{path}`"),
+            ApiChangeKind::Changed { prev, new } => {
+                write!(f, "changed `{path}` from `{prev}` to `{new}`")
+            }
+            ApiChangeKind::DocsChanged => write!(f, "changed documentation of `{path}`"),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Patch => "patch",
+            Severity::Minor => "minor",
+            Severity::Major => "major",
+        })
+    }
+}
+
+/// Compares the world `prev` in `prev_resolve` with the world `new` in
+/// `new_resolve` and returns the list of changes between the two.
+///
+/// Package versions are ignored when matching up interfaces between the two
+/// worlds. Changes are classified from the perspective of a component
+/// targeting `prev` which is to be used where `new` is expected, the same
+/// predicate tested by `wit_component::semver_check`:
+///
+/// * Adding an import is minor while removing an import is major.
+/// * Adding an export is major while removing an export is minor.
+/// * Within imported interfaces functions may be added but not removed, and
+///   within exported interfaces functions may be removed but not added.
+/// * Any change to the structure of a type or function signature is major.
+/// * Changes to documentation are patch changes.
+///
+/// The maximum severity of the returned changes, if any, is the semver bump
+/// required for `new`.
+pub fn diff_worlds(
+    prev_resolve: &Resolve,
+    prev: WorldId,
+    new_resolve: &Resolve,
+    new: WorldId,
+) -> Vec<ApiChange> {
+    let mut diff = Diff {
+        prev: prev_resolve,
+        new: new_resolve,
+        path: Vec::new(),
+        changes: Vec::new(),
+    };
+    let prev = &prev_resolve.worlds[prev];
+    let new = &new_resolve.worlds[new];
+    diff.docs(&prev.docs, &new.docs);
+    diff.items(Direction::Import, &prev.imports, &new.imports);
+    diff.items(Direction::Export, &prev.exports, &new.exports);
+    diff.changes
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Import,
+    Export,
+}
+
+impl Direction {
+    fn desc(&self) -> &'static str {
+        match self {
+            Direction::Import => "import",
+            Direction::Export => "export",
+        }
+    }
+
+    /// Returns the severity of adding and removing an item in this direction
+    /// respectively.
+    fn added_removed(&self) -> (Severity, Severity) {
+        match self {
+            Direction::Import => (Severity::Minor, Severity::Major),
+            Direction::Export => (Severity::Major, Severity::Minor),
+        }
+    }
+}
+
+struct Diff<'a> {
+    prev: &'a Resolve,
+    new: &'a Resolve,
+    path: Vec<PathSegment>,
+    changes: Vec<ApiChange>,
+}
+
+impl<'a> Diff<'a> {
+    fn push(&mut self, kind: &'static str, name: &str) {
+        self.path.push(PathSegment {
+            kind,
+            name: name.to_string(),
+        });
+    }
+
+    fn pop(&mut self) {
+        self.path.pop();
+    }
+
+    fn change(&mut self, kind: ApiChangeKind, severity: Severity) {
+        self.changes.push(ApiChange {
+            path: self.path.clone(),
+            kind,
+            severity,
+        });
+    }
+
+    /// Records a change of the item at the current path from `prev` to `new`.
+    fn changed(&mut self, prev: impl Into<String>, new: impl Into<String>) {
+        self.change(
+            ApiChangeKind::Changed {
+                prev: prev.into(),
+                new: new.into(),
+            },
+            Severity::Major,
+        );
+    }
+
+    fn docs(&mut self, prev: &Docs, new: &Docs) {
+        if prev.contents != new.contents {
+            self.change(ApiChangeKind::DocsChanged, Severity::Patch);
+        }
+    }
+
+    fn items(
+        &mut self,
+        dir: Direction,
+        prev: &IndexMap<WorldKey, WorldItem>,
+        new: &IndexMap<WorldKey, WorldItem>,
+    ) {
+        let prev = prev
+            .iter()
+            .map(|(k, v)| (world_key(self.prev, k), v))
+            .collect::<IndexMap<_, _>>();
+        let new = new
+            .iter()
+            .map(|(k, v)| (world_key(self.new, k), v))
+            .collect::<IndexMap<_, _>>();
+        let (added, removed) = dir.added_removed();
+
+        for (name, prev_item) in prev.iter() {
+            self.push(dir.desc(), name);
+            match new.get(name) {
+                Some(new_item) => self.item(dir, prev_item, new_item),
+                None => self.change(ApiChangeKind::Removed, removed),
+            }
+            self.pop();
+        }
+        for name in new.keys() {
+            if !prev.contains_key(name) {
+                self.push(dir.desc(), name);
+                self.change(ApiChangeKind::Added, added);
+                self.pop();
+            }
+        }
+    }
+
+    fn item(&mut self, dir: Direction, prev: &WorldItem, new: &WorldItem) {
+        match (prev, new) {
+            (WorldItem::Interface { id: a, .. }, WorldItem::Interface { id: b, .. }) => {
+                self.interface(dir, &self.prev.interfaces[*a], &self.new.interfaces[*b])
+            }
+            (WorldItem::Function(a), WorldItem::Function(b)) => self.function(a, b),
+            (WorldItem::Type(a), WorldItem::Type(b)) => self.type_def(*a, *b),
+            (a, b) => self.changed(world_item_desc(a), world_item_desc(b)),
+        }
+    }
+
+    fn interface(&mut self, dir: Direction, prev: &Interface, new: &Interface) {
+        self.docs(&prev.docs, &new.docs);
+
+        for (name, a) in prev.types.iter() {
+            let kind = self.prev.types[*a].kind.as_str();
+            self.push(kind, name);
+            match new.types.get(name) {
+                Some(b) => self.type_def(*a, *b),
+                None => self.change(ApiChangeKind::Removed, Severity::Major),
+            }
+            self.pop();
+        }
+        for (name, b) in new.types.iter() {
+            if !prev.types.contains_key(name) {
+                self.push(self.new.types[*b].kind.as_str(), name);
+                self.change(ApiChangeKind::Added, Severity::Minor);
+                self.pop();
+            }
+        }
+
+        let (added, removed) = dir.added_removed();
+        for (name, a) in prev.functions.iter() {
+            let pushed = self.push_function(self.prev, a);
+            match new.functions.get(name) {
+                Some(b) => self.function(a, b),
+                None => self.change(ApiChangeKind::Removed, removed),
+            }
+            self.path.truncate(self.path.len() - pushed);
+        }
+        for (name, b) in new.functions.iter() {
+            if !prev.functions.contains_key(name) {
+                let pushed = self.push_function(self.new, b);
+                self.change(ApiChangeKind::Added, added);
+                self.path.truncate(self.path.len() - pushed);
+            }
+        }
+    }
+
+    /// Pushes the path segments for `func`, returning how many were pushed.
+    fn push_function(&mut self, resolve: &Resolve, func: &Function) -> usize {
+        let (kind, resource) = match func.kind {
+            FunctionKind::Freestanding => ("func", None),
+            FunctionKind::Method(id) => ("method", Some(id)),
+            FunctionKind::Static(id) => ("static", Some(id)),
+            FunctionKind::Constructor(id) => ("constructor", Some(id)),
+        };
+        match resource.and_then(|id| resolve.types[id].name.as_deref()) {
+            Some(resource) => {
+                self.push("resource", resource);
+                self.push(kind, func.item_name());
+                2
+            }
+            None => {
+                self.push(kind, func.item_name());
+                1
+            }
+        }
+    }
+
+    fn function(&mut self, prev: &Function, new: &Function) {
+        self.docs(&prev.docs, &new.docs);
+        self.named_types("param", &prev.params, &new.params);
+        match (&prev.results, &new.results) {
+            (Results::Anon(a), Results::Anon(b)) => {
+                if !self.types_equal(*a, *b) {
+                    self.push("result", "");
+                    self.changed(self.prev_type(*a), self.new_type(*b));
+                    self.pop();
+                }
+            }
+            (Results::Named(a), Results::Named(b)) => self.named_types("result", a, b),
+            (a, b) => {
+                let a = self.results_desc(self.prev, a);
+                let b = self.results_desc(self.new, b);
+                self.push("result", "");
+                self.changed(a, b);
+                self.pop();
+            }
+        }
+    }
+
+    /// Compares lists of named types, such as parameters and named results,
+    /// position-by-position.
+    fn named_types(&mut self, kind: &'static str, prev: &[(String, Type)], new: &[(String, Type)]) {
+        for (i, (name, a)) in prev.iter().enumerate() {
+            self.push(kind, name);
+            match new.get(i) {
+                Some((new_name, _)) if new_name != name => {
+                    self.changed(name.as_str(), new_name.as_str())
+                }
+                Some((_, b)) => {
+                    if !self.types_equal(*a, *b) {
+                        self.changed(self.prev_type(*a), self.new_type(*b));
+                    }
+                }
+                None => self.change(ApiChangeKind::Removed, Severity::Major),
+            }
+            self.pop();
+        }
+        for (name, _) in new.iter().skip(prev.len()) {
+            self.push(kind, name);
+            self.change(ApiChangeKind::Added, Severity::Major);
+            self.pop();
+        }
+    }
+
+    fn type_def(&mut self, prev: TypeId, new: TypeId) {
+        let a = &self.prev.types[prev];
+        let b = &self.new.types[new];
+        self.docs(&a.docs, &b.docs);
+        match (&a.kind, &b.kind) {
+            (TypeDefKind::Record(a), TypeDefKind::Record(b)) => {
+                for (i, field) in a.fields.iter().enumerate() {
+                    self.push("field", &field.name);
+                    match b.fields.get(i) {
+                        Some(other) if other.name != field.name => {
+                            self.changed(field.name.as_str(), other.name.as_str())
+                        }
+                        Some(other) => {
+                            self.docs(&field.docs, &other.docs);
+                            if !self.types_equal(field.ty, other.ty) {
+                                self.changed(self.prev_type(field.ty), self.new_type(other.ty));
+                            }
+                        }
+                        None => self.change(ApiChangeKind::Removed, Severity::Major),
+                    }
+                    self.pop();
+                }
+                for field in b.fields.iter().skip(a.fields.len()) {
+                    self.push("field", &field.name);
+                    self.change(ApiChangeKind::Added, Severity::Major);
+                    self.pop();
+                }
+            }
+            (TypeDefKind::Variant(a), TypeDefKind::Variant(b)) => {
+                for (i, case) in a.cases.iter().enumerate() {
+                    self.push("case", &case.name);
+                    match b.cases.get(i) {
+                        Some(other) if other.name != case.name => {
+                            self.changed(case.name.as_str(), other.name.as_str())
+                        }
+                        Some(other) => {
+                            self.docs(&case.docs, &other.docs);
+                            let equal = match (case.ty, other.ty) {
+                                (Some(a), Some(b)) => self.types_equal(a, b),
+                                (None, None) => true,
+                                _ => false,
+                            };
+                            if !equal {
+                                let a = case.ty.map(|t| self.prev_type(t));
+                                let b = other.ty.map(|t| self.new_type(t));
+                                self.changed(
+                                    a.unwrap_or_else(|| "no payload".to_string()),
+                                    b.unwrap_or_else(|| "no payload".to_string()),
+                                );
+                            }
+                        }
+                        None => self.change(ApiChangeKind::Removed, Severity::Major),
+                    }
+                    self.pop();
+                }
+                for case in b.cases.iter().skip(a.cases.len()) {
+                    self.push("case", &case.name);
+                    self.change(ApiChangeKind::Added, Severity::Major);
+                    self.pop();
+                }
+            }
+            (TypeDefKind::Enum(a), TypeDefKind::Enum(b)) => {
+                let a = a.cases.iter().map(|c| (c.name.as_str(), &c.docs));
+                let b = b.cases.iter().map(|c| (c.name.as_str(), &c.docs));
+                self.names("case", a.collect(), b.collect());
+            }
+            (TypeDefKind::Flags(a), TypeDefKind::Flags(b)) => {
+                let a = a.flags.iter().map(|c| (c.name.as_str(), &c.docs));
+                let b = b.flags.iter().map(|c| (c.name.as_str(), &c.docs));
+                self.names("flag", a.collect(), b.collect());
+            }
+            (TypeDefKind::Resource, TypeDefKind::Resource) => {}
+            (ka, kb) if ka.as_str() != kb.as_str() => self.changed(ka.as_str(), kb.as_str()),
+            _ => {
+                if !self.type_defs_equal(prev, new) {
+                    self.changed(type_def_desc(self.prev, prev), type_def_desc(self.new, new));
+                }
+            }
+        }
+    }
+
+    /// Compares the positional list of names of enum cases or flags.
+    fn names(&mut self, kind: &'static str, prev: Vec<(&str, &Docs)>, new: Vec<(&str, &Docs)>) {
+        for (i, (name, docs)) in prev.iter().enumerate() {
+            self.push(kind, name);
+            match new.get(i) {
+                Some((other, _)) if other != name => self.changed(*name, *other),
+                Some((_, other_docs)) => self.docs(docs, other_docs),
+                None => self.change(ApiChangeKind::Removed, Severity::Major),
+            }
+            self.pop();
+        }
+        for (name, _) in new.iter().skip(prev.len()) {
+            self.push(kind, name);
+            self.change(ApiChangeKind::Added, Severity::Major);
+            self.pop();
+        }
+    }
+
+    fn prev_type(&self, ty: Type) -> String {
+        type_desc(self.prev, ty)
+    }
+
+    fn new_type(&self, ty: Type) -> String {
+        type_desc(self.new, ty)
+    }
+
+    fn results_desc(&self, resolve: &Resolve, results: &Results) -> String {
+        match results {
+            Results::Anon(ty) => type_desc(resolve, *ty),
+            Results::Named(params) => {
+                let params = params
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {}", type_desc(resolve, *ty)))
+                    .collect::<Vec<_>>();
+                format!("({})", params.join(", "))
+            }
+        }
+    }
+
+    /// Tests whether `a` in the previous world is the same type as `b` in the
+    /// new world.
+    ///
+    /// Named types are considered equal if their names and the interfaces they
+    /// are defined in match, as their definitions are compared separately.
+    fn types_equal(&self, a: Type, b: Type) -> bool {
+        match (a, b) {
+            (Type::Id(a), Type::Id(b)) => self.type_defs_equal(a, b),
+            (a, b) => a == b,
+        }
+    }
+
+    fn type_defs_equal(&self, a: TypeId, b: TypeId) -> bool {
+        let da = &self.prev.types[a];
+        let db = &self.new.types[b];
+        if da.name.is_some() || db.name.is_some() {
+            return da.name == db.name
+                && type_owner_name(self.prev, a) == type_owner_name(self.new, b);
+        }
+        let opt = |a: Option<Type>, b: Option<Type>| match (a, b) {
+            (Some(a), Some(b)) => self.types_equal(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        match (&da.kind, &db.kind) {
+            (TypeDefKind::Type(a), TypeDefKind::Type(b))
+            | (TypeDefKind::List(a), TypeDefKind::List(b))
+            | (TypeDefKind::Option(a), TypeDefKind::Option(b)) => self.types_equal(*a, *b),
+            (TypeDefKind::Tuple(a), TypeDefKind::Tuple(b)) => {
+                a.types.len() == b.types.len()
+                    && a.types
+                        .iter()
+                        .zip(&b.types)
+                        .all(|(a, b)| self.types_equal(*a, *b))
+            }
+            (TypeDefKind::Result(a), TypeDefKind::Result(b)) => {
+                opt(a.ok, b.ok) && opt(a.err, b.err)
+            }
+            (TypeDefKind::Handle(a), TypeDefKind::Handle(b)) => match (a, b) {
+                (crate::Handle::Own(a), crate::Handle::Own(b))
+                | (crate::Handle::Borrow(a), crate::Handle::Borrow(b)) => {
+                    self.type_defs_equal(*a, *b)
+                }
+                _ => false,
+            },
+            (TypeDefKind::Future(a), TypeDefKind::Future(b)) => opt(*a, *b),
+            (TypeDefKind::Stream(a), TypeDefKind::Stream(b)) => {
+                opt(a.element, b.element) && opt(a.end, b.end)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the name of `key` ignoring any package version.
+fn world_key(resolve: &Resolve, key: &WorldKey) -> String {
+    match key {
+        WorldKey::Name(name) => name.clone(),
+        WorldKey::Interface(id) => interface_name(resolve, *id),
+    }
+}
+
+fn interface_name(resolve: &Resolve, id: InterfaceId) -> String {
+    let interface = &resolve.interfaces[id];
+    match (&interface.name, interface.package) {
+        (Some(name), Some(pkg)) => {
+            let pkg = &resolve.packages[pkg].name;
+            format!("{}:{}/{name}", pkg.namespace, pkg.name)
+        }
+        (Some(name), None) => name.clone(),
+        (None, _) => String::new(),
+    }
+}
+
+fn type_owner_name(resolve: &Resolve, id: TypeId) -> Option<String> {
+    match resolve.types[id].owner {
+        crate::TypeOwner::Interface(i) => Some(interface_name(resolve, i)),
+        crate::TypeOwner::World(w) => Some(resolve.worlds[w].name.clone()),
+        crate::TypeOwner::None => None,
+    }
+}
+
+fn world_item_desc(item: &WorldItem) -> &'static str {
+    match item {
+        WorldItem::Interface { .. } => "interface",
+        WorldItem::Function(_) => "function",
+        WorldItem::Type(_) => "type",
+    }
+}
+
+/// Renders `ty` in WIT syntax.
+fn type_desc(resolve: &Resolve, ty: Type) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::S8 => "s8".to_string(),
+        Type::S16 => "s16".to_string(),
+        Type::S32 => "s32".to_string(),
+        Type::S64 => "s64".to_string(),
+        Type::F32 => "f32".to_string(),
+        Type::F64 => "f64".to_string(),
+        Type::Char => "char".to_string(),
+        Type::String => "string".to_string(),
+        Type::ErrorContext => "error-context".to_string(),
+        Type::Id(id) => match &resolve.types[id].name {
+            Some(name) => name.clone(),
+            None => type_def_desc(resolve, id),
+        },
+    }
+}
+
+/// Renders the definition of the anonymous type `id` in WIT syntax.
+fn type_def_desc(resolve: &Resolve, id: TypeId) -> String {
+    let opt = |ty: Option<Type>| match ty {
+        Some(ty) => type_desc(resolve, ty),
+        None => "_".to_string(),
+    };
+    match &resolve.types[id].kind {
+        TypeDefKind::Type(ty) => type_desc(resolve, *ty),
+        TypeDefKind::List(ty) => format!("list<{}>", type_desc(resolve, *ty)),
+        TypeDefKind::Option(ty) => format!("option<{}>", type_desc(resolve, *ty)),
+        TypeDefKind::Tuple(t) => {
+            let types = t
+                .types
+                .iter()
+                .map(|ty| type_desc(resolve, *ty))
+                .collect::<Vec<_>>();
+            format!("tuple<{}>", types.join(", "))
+        }
+        TypeDefKind::Result(r) => format!("result<{}, {}>", opt(r.ok), opt(r.err)),
+        TypeDefKind::Handle(crate::Handle::Own(id)) => {
+            format!("own<{}>", type_desc(resolve, Type::Id(*id)))
+        }
+        TypeDefKind::Handle(crate::Handle::Borrow(id)) => {
+            format!("borrow<{}>", type_desc(resolve, Type::Id(*id)))
+        }
+        TypeDefKind::Future(ty) => format!("future<{}>", opt(*ty)),
+        TypeDefKind::Stream(s) => format!("stream<{}, {}>", opt(s.element), opt(s.end)),
+        kind => kind.as_str().to_string(),
+    }
+}
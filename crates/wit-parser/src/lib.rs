@@ -24,9 +24,20 @@ mod resolve;
 pub use resolve::{Package, PackageId, Remap, Resolve};
 mod live;
 pub use live::LiveTypes;
+mod locations;
+pub use locations::{SourceItem, SourceLocation, SourceLocations};
+mod diff;
+pub use diff::{diff_worlds, ApiChange, ApiChangeKind, PathSegment, Severity};
+#[cfg(feature = "manifest")]
+mod manifest;
+#[cfg(feature = "manifest")]
+pub use manifest::{
+    CachedPackage, DepsManifest, LockFile, LockedPackage, PackageCache, LOCK_FILE_NAME,
+    PACKAGE_CACHE_ENV,
+};
 
 #[cfg(feature = "serde")]
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 #[cfg(feature = "serde")]
 mod serde_;
 #[cfg(feature = "serde")]
@@ -154,8 +165,8 @@ pub enum AstItem {
 /// This is directly encoded as an "ID" in the binary component representation
 /// with an interfaced tacked on as well.
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-#[cfg_attr(feature = "serde", serde(into = "String"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", try_from = "String"))]
 pub struct PackageName {
     /// A namespace such as `wasi` in `wasi:foo/bar`
     pub namespace: String,
@@ -297,7 +308,7 @@ impl UnresolvedPackageGroup {
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct World {
     /// The WIT identifier name of this world.
     pub name: String,
@@ -309,17 +320,26 @@ pub struct World {
     pub exports: IndexMap<WorldKey, WorldItem>,
 
     /// The package that owns this world.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_optional_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_optional_id",
+            deserialize_with = "deserialize_optional_id"
+        )
+    )]
     pub package: Option<PackageId>,
 
     /// Documentation associated with this world declaration.
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 
     /// Stability annotation for this world itself.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Stability::is_unknown")
+        serde(default, skip_serializing_if = "Stability::is_unknown")
     )]
     pub stability: Stability,
 
@@ -374,17 +394,20 @@ impl WorldKey {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum WorldItem {
     /// An interface is being imported or exported from a world, indicating that
     /// it's a namespace of functions.
     Interface {
-        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+        )]
         id: InterfaceId,
         #[cfg_attr(
             feature = "serde",
-            serde(skip_serializing_if = "Stability::is_unknown")
+            serde(default, skip_serializing_if = "Stability::is_unknown")
         )]
         stability: Stability,
     },
@@ -395,7 +418,10 @@ pub enum WorldItem {
     /// A type is being exported from this world.
     ///
     /// Note that types are never imported into worlds at this time.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Type(TypeId),
 }
 
@@ -410,7 +436,7 @@ impl WorldItem {
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Interface {
     /// Optionally listed name of this interface.
     ///
@@ -421,46 +447,64 @@ pub struct Interface {
     ///
     /// Export names are listed within the types themselves. Note that the
     /// export name here matches the name listed in the `TypeDef`.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id_map"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_id_map",
+            deserialize_with = "deserialize_id_map"
+        )
+    )]
     pub types: IndexMap<String, TypeId>,
 
     /// Exported functions from this interface.
     pub functions: IndexMap<String, Function>,
 
     /// Documentation associated with this interface.
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 
     /// Stability attribute for this interface.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Stability::is_unknown")
+        serde(default, skip_serializing_if = "Stability::is_unknown")
     )]
     pub stability: Stability,
 
     /// The package that owns this interface.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_optional_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_optional_id",
+            deserialize_with = "deserialize_optional_id"
+        )
+    )]
     pub package: Option<PackageId>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TypeDef {
     pub name: Option<String>,
     pub kind: TypeDefKind,
     pub owner: TypeOwner,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
     /// Stability attribute for this type.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Stability::is_unknown")
+        serde(default, skip_serializing_if = "Stability::is_unknown")
     )]
     pub stability: Stability,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum TypeDefKind {
     Record(Record),
@@ -510,14 +554,20 @@ impl TypeDefKind {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum TypeOwner {
     /// This type was defined within a `world` block.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     World(WorldId),
     /// This type was defined within an `interface` block.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Interface(InterfaceId),
     /// This type wasn't inherently defined anywhere, such as a `list<T>`, which
     /// doesn't need an owner.
@@ -526,12 +576,18 @@ pub enum TypeOwner {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Handle {
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Own(TypeId),
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Borrow(TypeId),
 }
 
@@ -550,6 +606,7 @@ pub enum Type {
     F64,
     Char,
     String,
+    ErrorContext,
     Id(TypeId),
 }
 
@@ -562,32 +619,38 @@ pub enum Int {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Record {
     pub fields: Vec<Field>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Field {
     pub name: String,
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ty: Type,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Flags {
     pub flags: Vec<Flag>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Flag {
     pub name: String,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 }
 
@@ -620,24 +683,27 @@ impl FlagsRepr {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Tuple {
     pub types: Vec<Type>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Variant {
     pub cases: Vec<Case>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Case {
     pub name: String,
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ty: Option<Type>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 }
 
@@ -648,16 +714,19 @@ impl Variant {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Enum {
     pub cases: Vec<EnumCase>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EnumCase {
     pub name: String,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 }
 
@@ -679,21 +748,21 @@ fn discriminant_type(num_cases: usize) -> Int {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Result_ {
     pub ok: Option<Type>,
     pub err: Option<Type>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Stream {
     pub element: Option<Type>,
     pub end: Option<Type>,
 }
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Docs {
     pub contents: Option<String>,
 }
@@ -776,33 +845,51 @@ impl Results {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Function {
     pub name: String,
     pub kind: FunctionKind,
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_params"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_params",
+            deserialize_with = "deserialize_params"
+        )
+    )]
     pub params: Params,
     pub results: Results,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
     /// Stability attribute for this function.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Stability::is_unknown")
+        serde(default, skip_serializing_if = "Stability::is_unknown")
     )]
     pub stability: Stability,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum FunctionKind {
     Freestanding,
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Method(TypeId),
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Static(TypeId),
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")
+    )]
     Constructor(TypeId),
 }
 
@@ -830,9 +917,10 @@ impl Function {
 /// interface, function, or type.
 ///
 /// This is added for WebAssembly/component-model#332 where @since and @unstable
-/// annotations were added to WIT.
+/// annotations were added to WIT. Either may additionally be paired with
+/// `@deprecated`.
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde_derive::Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Stability {
     /// `@since(version = 1.2.3)`
@@ -845,13 +933,39 @@ pub enum Stability {
         since: Version,
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         feature: Option<String>,
+        /// The version this item was deprecated in, from an accompanying
+        /// `@deprecated(version = 1.2.3)`.
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default,
+                skip_serializing_if = "Option::is_none",
+                serialize_with = "serialize_optional_version",
+                deserialize_with = "deserialize_optional_version"
+            )
+        )]
+        deprecated: Option<Version>,
     },
 
     /// `@unstable(feature = foo)`
     ///
     /// This item is explicitly tagged `@unstable`. A feature name is listed and
     /// this item is excluded by default in `Resolve` unless explicitly enabled.
-    Unstable { feature: String },
+    Unstable {
+        feature: String,
+        /// The version this item was deprecated in, from an accompanying
+        /// `@deprecated(version = 1.2.3)`.
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default,
+                skip_serializing_if = "Option::is_none",
+                serialize_with = "serialize_optional_version",
+                deserialize_with = "deserialize_optional_version"
+            )
+        )]
+        deprecated: Option<Version>,
+    },
 
     /// This item does not have either `@since` or `@unstable`.
     Unknown,
@@ -862,6 +976,17 @@ impl Stability {
     pub fn is_unknown(&self) -> bool {
         matches!(self, Stability::Unknown)
     }
+
+    /// Returns the version this item was deprecated in, if it's tagged with
+    /// `@deprecated`.
+    pub fn deprecated(&self) -> Option<&Version> {
+        match self {
+            Stability::Stable { deprecated, .. } | Stability::Unstable { deprecated, .. } => {
+                deprecated.as_ref()
+            }
+            Stability::Unknown => None,
+        }
+    }
 }
 
 impl Default for Stability {
@@ -0,0 +1,147 @@
+use crate::{InterfaceId, Remap, TypeId, WorldId, WorldKey};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// A position within a WIT source file where an item was defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The file that the item was defined in.
+    pub path: PathBuf,
+    /// The 1-based line of the item's definition.
+    pub line: u32,
+    /// The 1-based column of the item's definition.
+    pub column: u32,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
+
+/// An item within a [`Resolve`](crate::Resolve) which may have a
+/// [`SourceLocation`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SourceItem {
+    /// A type definition.
+    Type(TypeId),
+    /// An interface definition.
+    Interface(InterfaceId),
+    /// A world definition.
+    World(WorldId),
+    /// A function defined within an interface, identified by its name.
+    InterfaceFunction(InterfaceId, String),
+    /// An explicit import of a world.
+    WorldImport(WorldId, WorldKey),
+    /// An explicit export of a world.
+    WorldExport(WorldId, WorldKey),
+}
+
+/// A side table mapping items within a [`Resolve`](crate::Resolve) back to
+/// where they were defined in WIT source files.
+///
+/// This is filled in as packages are parsed from source, for example with
+/// [`Resolve::push_path`](crate::Resolve::push_path). Items which were
+/// decoded from a binary, or which were synthesized during world elaboration,
+/// have no location.
+#[derive(Debug, Clone, Default)]
+pub struct SourceLocations {
+    map: HashMap<SourceItem, SourceLocation>,
+}
+
+impl SourceLocations {
+    /// Returns the location that `item` was defined at, if known.
+    pub fn get(&self, item: &SourceItem) -> Option<&SourceLocation> {
+        self.map.get(item)
+    }
+
+    /// Returns the location of the type `id`, if known.
+    pub fn ty(&self, id: TypeId) -> Option<&SourceLocation> {
+        self.get(&SourceItem::Type(id))
+    }
+
+    /// Returns the location of the interface `id`, if known.
+    pub fn interface(&self, id: InterfaceId) -> Option<&SourceLocation> {
+        self.get(&SourceItem::Interface(id))
+    }
+
+    /// Returns the location of the world `id`, if known.
+    pub fn world(&self, id: WorldId) -> Option<&SourceLocation> {
+        self.get(&SourceItem::World(id))
+    }
+
+    /// Returns the location of the function `name` within `interface`, if
+    /// known.
+    pub fn interface_function(
+        &self,
+        interface: InterfaceId,
+        name: &str,
+    ) -> Option<&SourceLocation> {
+        self.get(&SourceItem::InterfaceFunction(interface, name.to_string()))
+    }
+
+    /// Returns the location of the import `key` of `world`, if known.
+    pub fn world_import(&self, world: WorldId, key: &WorldKey) -> Option<&SourceLocation> {
+        self.get(&SourceItem::WorldImport(world, key.clone()))
+    }
+
+    /// Returns the location of the export `key` of `world`, if known.
+    pub fn world_export(&self, world: WorldId, key: &WorldKey) -> Option<&SourceLocation> {
+        self.get(&SourceItem::WorldExport(world, key.clone()))
+    }
+
+    /// Records that `item` was defined at `location`.
+    ///
+    /// The first location recorded for an item is retained.
+    pub fn insert(&mut self, item: SourceItem, location: SourceLocation) {
+        self.map.entry(item).or_insert(location);
+    }
+
+    /// Returns an iterator over all items with known locations.
+    pub fn iter(&self) -> impl Iterator<Item = (&SourceItem, &SourceLocation)> {
+        self.map.iter()
+    }
+
+    /// Returns the number of items with known locations.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether no locations are known.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Moves all locations from `other` into `self`, translating ids from
+    /// `other`'s `Resolve` into this one with `remap`.
+    pub(crate) fn merge(&mut self, other: SourceLocations, remap: &Remap) {
+        for (item, location) in other.map {
+            if let Some(item) = item.remap(remap) {
+                self.insert(item, location);
+            }
+        }
+    }
+}
+
+impl SourceItem {
+    fn remap(self, remap: &Remap) -> Option<SourceItem> {
+        let ty = |id: TypeId| remap.types.get(id.index()).copied().flatten();
+        let iface = |id: InterfaceId| remap.interfaces.get(id.index()).copied().flatten();
+        let world = |id: WorldId| remap.worlds.get(id.index()).copied().flatten();
+        let key = |key: WorldKey| match key {
+            WorldKey::Name(name) => Some(WorldKey::Name(name)),
+            WorldKey::Interface(id) => Some(WorldKey::Interface(iface(id)?)),
+        };
+        Some(match self {
+            SourceItem::Type(id) => SourceItem::Type(ty(id)?),
+            SourceItem::Interface(id) => SourceItem::Interface(iface(id)?),
+            SourceItem::World(id) => SourceItem::World(world(id)?),
+            SourceItem::InterfaceFunction(id, name) => {
+                SourceItem::InterfaceFunction(iface(id)?, name)
+            }
+            SourceItem::WorldImport(id, k) => SourceItem::WorldImport(world(id)?, key(k)?),
+            SourceItem::WorldExport(id, k) => SourceItem::WorldExport(world(id)?, key(k)?),
+        })
+    }
+}
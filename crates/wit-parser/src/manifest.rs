@@ -0,0 +1,608 @@
+//! Support for resolving WIT dependencies from a local package cache.
+//!
+//! A package directory may contain a `deps.toml` manifest next to its `*.wit`
+//! files which lists the packages it depends on along with semver
+//! requirements:
+//!
+//! ```toml
+//! # Optional, defaults to the `WIT_PACKAGE_CACHE` environment variable.
+//! cache = "../wit-cache"
+//!
+//! [dependencies]
+//! "wasi:io" = "0.2"
+//! "wasi:clocks" = "=0.2.1"
+//! ```
+//!
+//! Dependencies are looked up in the cache directory, which is laid out as
+//! `$cache/$namespace/$name/$version` where each entry is either a directory
+//! of `*.wit` files, a single `*.wit` file, or a wasm-encoded package in the
+//! text or binary format (`$version.wit`, `$version.wasm`, `$version.wat`).
+//! No network access is ever performed.
+//!
+//! A `wit.lock` lockfile next to the manifest records the exact versions
+//! selected, and those versions are preferred over newer ones in the cache as
+//! long as they still satisfy the manifest. The lockfile is written by
+//! `wasm-tools component wit --vendor`:
+//!
+//! ```toml
+//! version = 1
+//!
+//! [[package]]
+//! name = "wasi:io"
+//! version = "0.2.0"
+//! ```
+
+use crate::{PackageName, Resolve, UnresolvedPackageGroup};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use semver::{Version, VersionReq};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// The name of the environment variable consulted for the package cache
+/// directory when a manifest doesn't specify one.
+pub const PACKAGE_CACHE_ENV: &str = "WIT_PACKAGE_CACHE";
+
+/// The name of the lockfile stored next to a `deps.toml` manifest.
+pub const LOCK_FILE_NAME: &str = "wit.lock";
+
+/// A parsed `deps.toml` manifest.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DepsManifest {
+    /// The directory containing the manifest, used to resolve relative paths.
+    #[serde(skip)]
+    pub base: PathBuf,
+    /// The package cache directory configured with the `cache` key, if any.
+    #[serde(default)]
+    pub cache: Option<PathBuf>,
+    /// Dependencies keyed by `namespace:name` along with the versions of them
+    /// which are acceptable.
+    #[serde(default)]
+    pub dependencies: IndexMap<String, VersionReq>,
+}
+
+/// A parsed `wit.lock` lockfile listing the exact versions of the packages
+/// selected for a [`DepsManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockFile {
+    /// The version of the lockfile format, currently always 1.
+    pub version: u32,
+    /// The locked packages, sorted by name.
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A package pinned to a version in a [`LockFile`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockedPackage {
+    /// The package name in the form `namespace:name`.
+    pub name: String,
+    /// The exact version of the package which was selected.
+    pub version: Version,
+}
+
+/// A package selected from a [`PackageCache`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedPackage {
+    /// The fully-versioned name of this package.
+    pub name: PackageName,
+    /// The location of this package within the cache.
+    pub path: PathBuf,
+}
+
+/// An on-disk cache of WIT packages shared between projects.
+#[derive(Debug, Clone)]
+pub struct PackageCache {
+    root: PathBuf,
+}
+
+impl DepsManifest {
+    /// Reads and parses the manifest at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<DepsManifest> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read manifest {path:?}"))?;
+        DepsManifest::parse(path, &contents)
+    }
+
+    /// Parses `contents` as the manifest located at `path`.
+    pub fn parse(path: impl AsRef<Path>, contents: &str) -> Result<DepsManifest> {
+        let path = path.as_ref();
+        let mut manifest: DepsManifest = toml::from_str(contents)
+            .with_context(|| format!("failed to parse manifest {path:?}"))?;
+        manifest.base = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        for name in manifest.dependencies.keys() {
+            validate_package_name(name)
+                .with_context(|| format!("failed to parse manifest {path:?}"))?;
+        }
+        Ok(manifest)
+    }
+
+    /// Reads the `wit.lock` lockfile next to this manifest, if there is one.
+    pub fn lock_file(&self) -> Result<Option<LockFile>> {
+        let path = self.base.join(LOCK_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        LockFile::from_file(path).map(Some)
+    }
+
+    /// Returns the names of the packages defined in the `deps` directory
+    /// `path`, laid out as described in [`Resolve::push_dir`], without
+    /// resolving them.
+    ///
+    /// This is suitable for passing as the `existing` packages to
+    /// [`DepsManifest::resolve`]. An empty list is returned if `path` doesn't
+    /// exist.
+    pub fn vendored_packages(path: impl AsRef<Path>) -> Result<Vec<PackageName>> {
+        let path = path.as_ref();
+        let mut resolve = Resolve::default();
+        let groups = resolve
+            .parse_deps_dir(path)
+            .with_context(|| format!("failed to parse dependency directory: {}", path.display()))?;
+        Ok(resolve
+            .package_names
+            .into_keys()
+            .chain(
+                groups
+                    .into_iter()
+                    .flat_map(|g| g.packages.into_iter().map(|p| p.name)),
+            )
+            .collect())
+    }
+
+    /// Returns the package cache used for this manifest.
+    ///
+    /// This is the `cache` key resolved relative to the manifest, or otherwise
+    /// the directory in the `WIT_PACKAGE_CACHE` environment variable.
+    pub fn package_cache(&self) -> Result<PackageCache> {
+        if let Some(cache) = &self.cache {
+            return Ok(PackageCache::new(self.base.join(cache)));
+        }
+        match std::env::var_os(PACKAGE_CACHE_ENV) {
+            Some(dir) => Ok(PackageCache::new(dir)),
+            None => bail!(
+                "no package cache configured: set `cache` in the manifest or the \
+                 `{PACKAGE_CACHE_ENV}` environment variable"
+            ),
+        }
+    }
+
+    /// Selects the packages from the cache satisfying this manifest,
+    /// including the packages they themselves depend on.
+    ///
+    /// The version recorded in `lock` is chosen for a package if it still
+    /// matches the requirement and is present in the cache, and otherwise the
+    /// highest version in the cache matching the requirement is chosen.
+    /// Packages from the cache accept any semver-compatible version of their
+    /// own dependencies, so a package using `foo:bar@0.1.0` is satisfied by
+    /// `0.1.2` being selected elsewhere.
+    ///
+    /// Packages in `existing` which satisfy a requirement, for example those
+    /// already vendored in a `deps` directory, are used in preference to the
+    /// cache and are not returned.
+    ///
+    /// An error is returned if a requirement can't be satisfied or if two
+    /// different semver-compatible versions of the same package are required,
+    /// such as `0.2.0` and `0.2.1`, including when one of them is in
+    /// `existing`.
+    pub fn resolve(
+        &self,
+        existing: &[PackageName],
+        lock: Option<&LockFile>,
+    ) -> Result<Vec<CachedPackage>> {
+        // The cache is only opened once it's needed so that packages which
+        // have vendored all of their dependencies don't require one.
+        let mut cache = None;
+        let mut queue = self
+            .dependencies
+            .iter()
+            .map(|(name, req)| (name.clone(), req.clone(), "the manifest".to_string()))
+            .collect::<VecDeque<_>>();
+        let mut selected = BTreeMap::new();
+        for pkg in existing {
+            if let Some(version) = &pkg.version {
+                let key = format!("{}:{}", pkg.namespace, pkg.name);
+                selected
+                    .entry((key, compat_track(version)))
+                    .or_insert_with(|| (version.clone(), "an existing package".to_string()));
+            }
+        }
+        let mut ret = Vec::new();
+
+        while let Some((key, req, origin)) = queue.pop_front() {
+            let satisfied_by_existing = existing.iter().any(|pkg| {
+                format!("{}:{}", pkg.namespace, pkg.name) == key
+                    && pkg.version.as_ref().is_some_and(|v| req.matches(v))
+            });
+            if satisfied_by_existing {
+                continue;
+            }
+            let satisfied_by_selected = selected
+                .iter()
+                .any(|((k, _), (v, _))| *k == key && req.matches(v));
+            if satisfied_by_selected {
+                continue;
+            }
+
+            if cache.is_none() {
+                cache = Some(self.package_cache()?);
+            }
+            let versions = cache.as_ref().unwrap().versions(&key)?;
+            let locked = lock
+                .into_iter()
+                .flat_map(|lock| lock.packages.iter())
+                .filter(|p| p.name == key && req.matches(&p.version))
+                .find_map(|p| versions.iter().find(|(v, _)| *v == p.version));
+            let (version, path) =
+                match locked.or_else(|| versions.iter().rev().find(|(v, _)| req.matches(v))) {
+                    Some(pair) => pair.clone(),
+                    None => {
+                        let available = versions
+                            .iter()
+                            .map(|(v, _)| v.to_string())
+                            .collect::<Vec<_>>();
+                        bail!(
+                            "no version of `{key}` in the package cache matches `{req}` \
+                         required by {origin} (available: [{}])",
+                            available.join(", ")
+                        )
+                    }
+                };
+
+            let track = (key.clone(), compat_track(&version));
+            match selected.get(&track) {
+                Some((prev, _)) if *prev == version => continue,
+                Some((prev, prev_origin)) => bail!(
+                    "conflicting versions of `{key}` required: {prev} by {prev_origin} \
+                     and {version} by {origin}"
+                ),
+                None => {}
+            }
+            selected.insert(track, (version.clone(), origin));
+
+            let (namespace, name) = key.split_once(':').unwrap();
+            let pkg = CachedPackage {
+                name: PackageName {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                    version: Some(version),
+                },
+                path,
+            };
+
+            // Wasm-encoded packages carry all of their dependencies with them,
+            // but WIT text needs its dependencies located as well.
+            if !pkg.is_wasm() {
+                for dep in pkg.foreign_deps()? {
+                    let dep_key = format!("{}:{}", dep.namespace, dep.name);
+                    let version = match &dep.version {
+                        Some(version) => version,
+                        None => bail!(
+                            "`{}` depends on unversioned package `{dep_key}` which cannot be \
+                             resolved from the package cache",
+                            pkg.name
+                        ),
+                    };
+                    // A `use` of `foo:bar@0.1.0` is satisfied by any
+                    // semver-compatible version, such as `0.1.2` selected by
+                    // the manifest, see `unify_dep_versions`.
+                    let req = VersionReq::parse(&format!("^{version}")).unwrap();
+                    queue.push_back((dep_key, req, format!("`{}`", pkg.name)));
+                }
+            }
+            ret.push(pkg);
+        }
+        Ok(ret)
+    }
+}
+
+impl LockFile {
+    /// Reads and parses the lockfile at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<LockFile> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read lockfile {path:?}"))?;
+        LockFile::parse(path, &contents)
+    }
+
+    /// Parses `contents` as the lockfile located at `path`.
+    pub fn parse(path: impl AsRef<Path>, contents: &str) -> Result<LockFile> {
+        let path = path.as_ref();
+        let lock: LockFile = toml::from_str(contents)
+            .with_context(|| format!("failed to parse lockfile {path:?}"))?;
+        if lock.version != 1 {
+            bail!(
+                "unsupported version {} of lockfile {path:?}, expected 1",
+                lock.version
+            );
+        }
+        for pkg in lock.packages.iter() {
+            validate_package_name(&pkg.name)
+                .with_context(|| format!("failed to parse lockfile {path:?}"))?;
+        }
+        Ok(lock)
+    }
+
+    /// Creates a lockfile pinning each of the versioned `packages`.
+    pub fn new<'a>(packages: impl IntoIterator<Item = &'a PackageName>) -> LockFile {
+        let mut packages = packages
+            .into_iter()
+            .filter_map(|pkg| {
+                Some(LockedPackage {
+                    name: format!("{}:{}", pkg.namespace, pkg.name),
+                    version: pkg.version.clone()?,
+                })
+            })
+            .collect::<Vec<_>>();
+        packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        packages.dedup();
+        LockFile {
+            version: 1,
+            packages,
+        }
+    }
+
+    /// Renders this lockfile in its TOML format.
+    pub fn to_toml(&self) -> String {
+        format!(
+            "# This file is generated by `wasm-tools component wit --vendor`.\n\n{}",
+            toml::to_string(self).unwrap()
+        )
+    }
+}
+
+impl PackageCache {
+    /// Creates a new cache rooted at the directory `root`.
+    pub fn new(root: impl Into<PathBuf>) -> PackageCache {
+        PackageCache { root: root.into() }
+    }
+
+    /// Returns the root directory of this cache.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns all versions of the package `namespace:name` found in this
+    /// cache, sorted from lowest to highest, along with their paths.
+    pub fn versions(&self, package: &str) -> Result<Vec<(Version, PathBuf)>> {
+        let (namespace, name) = match package.split_once(':') {
+            Some(pair) => pair,
+            None => bail!("invalid package name `{package}`"),
+        };
+        let dir = self.root.join(namespace).join(name);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut ret = Vec::new();
+        let cx = || format!("failed to read package cache directory {dir:?}");
+        for entry in dir.read_dir().with_context(cx)? {
+            let path = entry.with_context(cx)?.path();
+            let version = if path.is_dir() {
+                path.file_name().and_then(|s| s.to_str())
+            } else {
+                match path.extension().and_then(|s| s.to_str()) {
+                    Some("wit") | Some("wasm") | Some("wat") => {
+                        path.file_stem().and_then(|s| s.to_str())
+                    }
+                    _ => None,
+                }
+            };
+            // Entries which aren't named after a version are ignored to avoid
+            // tripping over things like `.DS_Store` files.
+            if let Some(Ok(version)) = version.map(Version::parse) {
+                ret.push((version, path));
+            }
+        }
+        ret.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(ret)
+    }
+}
+
+impl CachedPackage {
+    /// Returns whether this package is encoded as wasm rather than WIT text.
+    pub fn is_wasm(&self) -> bool {
+        matches!(
+            self.path.extension().and_then(|s| s.to_str()),
+            Some("wasm") | Some("wat")
+        )
+    }
+
+    /// Returns the name this package is given when vendored into a `deps`
+    /// directory, for example `wasi-io-0.2.0.wit`.
+    pub fn vendored_name(&self) -> String {
+        let mut ret = format!(
+            "{}-{}-{}",
+            self.name.namespace,
+            self.name.name,
+            self.name.version.as_ref().unwrap()
+        );
+        if let Some(ext) = self.path.extension().and_then(|s| s.to_str()) {
+            if !self.path.is_dir() {
+                ret.push('.');
+                ret.push_str(ext);
+            }
+        }
+        ret
+    }
+
+    /// Parses this WIT text package and returns the names of the packages it
+    /// depends on, verifying that it defines the package it's named after.
+    fn foreign_deps(&self) -> Result<Vec<PackageName>> {
+        let group = UnresolvedPackageGroup::parse_path(&self.path)
+            .with_context(|| format!("failed to parse package: {}", self.path.display()))?;
+        if !group.packages.iter().any(|p| p.name == self.name) {
+            bail!(
+                "package cache entry {} does not define package `{}`",
+                self.path.display(),
+                self.name
+            );
+        }
+        let defined = group
+            .packages
+            .iter()
+            .map(|p| p.name.clone())
+            .collect::<Vec<_>>();
+        let mut ret = Vec::new();
+        for pkg in group.packages.iter() {
+            for dep in pkg.foreign_deps.keys() {
+                if !defined.contains(dep) && !ret.contains(dep) {
+                    ret.push(dep.clone());
+                }
+            }
+        }
+        Ok(ret)
+    }
+}
+
+/// Returns the portion of `version` within which all versions are expected
+/// to be semver-compatible with one another.
+fn compat_track(version: &Version) -> (u64, u64, u64) {
+    match (version.major, version.minor) {
+        (0, 0) => (0, 0, version.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    }
+}
+
+/// Points the dependencies of the packages in `groups` at the
+/// semver-compatible versions of them in `available`.
+///
+/// A package from the cache may `use foo:bar@0.1.0` when `0.1.2` was selected
+/// instead, and this renames its dependency accordingly as WIT resolution
+/// otherwise requires an exact match. Dependencies which are available as-is
+/// are left alone, as are those that would then be listed twice.
+pub(crate) fn unify_dep_versions(groups: &mut [UnresolvedPackageGroup], available: &[PackageName]) {
+    for pkg in groups.iter_mut().flat_map(|g| g.packages.iter_mut()) {
+        let deps = std::mem::take(&mut pkg.foreign_deps);
+        let spans = std::mem::take(&mut pkg.foreign_dep_spans);
+        for ((name, items), span) in deps.into_iter().zip(spans) {
+            let name = match compatible_version(&name, available) {
+                Some(compat) if !pkg.foreign_deps.contains_key(compat) => compat.clone(),
+                _ => name,
+            };
+            pkg.foreign_deps.insert(name, items);
+            pkg.foreign_dep_spans.push(span);
+        }
+    }
+}
+
+/// Returns the highest version of `name` in `available` which is
+/// semver-compatible with it, if `name` itself isn't available.
+fn compatible_version<'a>(
+    name: &PackageName,
+    available: &'a [PackageName],
+) -> Option<&'a PackageName> {
+    let version = name.version.as_ref()?;
+    if available.contains(name) {
+        return None;
+    }
+    available
+        .iter()
+        .filter(|p| p.namespace == name.namespace && p.name == name.name)
+        .filter(|p| match &p.version {
+            Some(v) => v >= version && compat_track(v) == compat_track(version),
+            None => false,
+        })
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+fn validate_package_name(name: &str) -> Result<()> {
+    match name.split_once(':') {
+        Some((namespace, name))
+            if !namespace.is_empty() && !name.is_empty() && !name.contains(['@', '/']) =>
+        {
+            Ok(())
+        }
+        _ => bail!("invalid package name `{name}`, expected `namespace:name`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DepsManifest, LockFile};
+    use crate::PackageName;
+
+    #[test]
+    fn parse() {
+        let manifest = DepsManifest::parse(
+            "dir/deps.toml",
+            r#"
+                cache = 'a # b' # comment
+                dependencies = { "foo:bar" = "0.1", 'foo:baz' = """=1.0.0""" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(manifest.base.to_str(), Some("dir"));
+        assert_eq!(manifest.cache.unwrap().to_str(), Some("a # b"));
+        let deps = manifest
+            .dependencies
+            .iter()
+            .map(|(k, v)| format!("{k} {v}"))
+            .collect::<Vec<_>>();
+        assert_eq!(deps, ["foo:bar ^0.1", "foo:baz =1.0.0"]);
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        let cases = [
+            ("cache = 1", "invalid type"),
+            ("registry = \"x\"", "unknown field `registry`"),
+            (
+                "[dependencies]\n\"foo\" = \"1\"",
+                "invalid package name `foo`",
+            ),
+            (
+                "[dependencies]\n\"a:b\" = \"1.x.y\"",
+                "unexpected character",
+            ),
+            ("[dependencies]\n[dependencies]", "duplicate key"),
+        ];
+        for (contents, expected) in cases {
+            let err = DepsManifest::parse("deps.toml", contents).unwrap_err();
+            let err = format!("{err:#}");
+            assert!(err.contains(expected), "{contents:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn lock_file() {
+        let names = ["foo:baz@1.0.0", "foo:bar@0.1.2", "foo:bar@0.1.2", "foo:qux"].map(|s| {
+            let (namespace, rest) = s.split_once(':').unwrap();
+            let (name, version) = match rest.split_once('@') {
+                Some((name, version)) => (name, Some(version.parse().unwrap())),
+                None => (rest, None),
+            };
+            PackageName {
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+                version,
+            }
+        });
+        let lock = LockFile::new(&names);
+        let contents = lock.to_toml();
+        assert_eq!(
+            contents,
+            "# This file is generated by `wasm-tools component wit --vendor`.
+
+version = 1
+
+[[package]]
+name = \"foo:bar\"
+version = \"0.1.2\"
+
+[[package]]
+name = \"foo:baz\"
+version = \"1.0.0\"
+"
+        );
+        assert_eq!(LockFile::parse("wit.lock", &contents).unwrap(), lock);
+
+        let err = LockFile::parse("wit.lock", "version = 2").unwrap_err();
+        assert!(err.to_string().contains("unsupported version 2"), "{err}");
+    }
+}
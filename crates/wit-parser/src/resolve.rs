@@ -1,18 +1,18 @@
 use crate::ast::lex::Span;
 use crate::ast::{parse_use_path, ParsedUsePath};
 #[cfg(feature = "serde")]
-use crate::serde_::{serialize_arena, serialize_id_map};
+use crate::serde_::{deserialize_id_map, serialize_arena, serialize_id_map};
 use crate::{
     AstItem, Docs, Error, Function, FunctionKind, Handle, IncludeName, Interface, InterfaceId,
-    InterfaceSpan, PackageName, Results, SourceMap, Stability, Type, TypeDef, TypeDefKind, TypeId,
-    TypeOwner, UnresolvedPackage, UnresolvedPackageGroup, World, WorldId, WorldItem, WorldKey,
-    WorldSpan,
+    InterfaceSpan, LiveTypes, PackageName, Results, SourceItem, SourceLocations, SourceMap,
+    Stability, Type, TypeDef, TypeDefKind, TypeId, TypeOwner, UnresolvedPackage,
+    UnresolvedPackageGroup, World, WorldId, WorldItem, WorldKey, WorldSpan,
 };
-use anyhow::{anyhow, bail, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use id_arena::{Arena, Id};
 use indexmap::{IndexMap, IndexSet};
 #[cfg(feature = "serde")]
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::mem;
 use std::path::{Path, PathBuf};
@@ -78,6 +78,14 @@ pub struct Resolve {
     /// Activate all features for this [`Resolve`].
     #[cfg_attr(feature = "serde", serde(skip))]
     pub all_features: bool,
+
+    /// Locations in WIT source files of the items within this [`Resolve`].
+    ///
+    /// This is populated for packages parsed from source, such as with
+    /// [`Resolve::push_path`], and is empty for packages decoded from a
+    /// binary.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub source_locations: SourceLocations,
 }
 
 /// A WIT package within a `Resolve`.
@@ -86,30 +94,45 @@ pub struct Resolve {
 /// have a unique identifier that affects generated components and uniquely
 /// identifiers this particular package.
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Package {
     /// A unique name corresponding to this package.
     pub name: PackageName,
 
     /// Documentation associated with this package.
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Docs::is_empty"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Docs::is_empty")
+    )]
     pub docs: Docs,
 
     /// All interfaces contained in this packaged, keyed by the interface's
     /// name.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id_map"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_id_map",
+            deserialize_with = "deserialize_id_map"
+        )
+    )]
     pub interfaces: IndexMap<String, InterfaceId>,
 
     /// All worlds contained in this package, keyed by the world's name.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_id_map"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_id_map",
+            deserialize_with = "deserialize_id_map"
+        )
+    )]
     pub worlds: IndexMap<String, WorldId>,
 }
 
 pub type PackageId = Id<Package>;
 
 enum ParsedFile {
-    #[cfg(feature = "decoding")]
-    Package(PackageId),
+    #[cfg(any(feature = "decoding", feature = "serde"))]
+    Packages(Vec<PackageId>),
     Unresolved(UnresolvedPackageGroup),
 }
 
@@ -163,6 +186,9 @@ impl Resolve {
     /// * A single standalone WIT file.
     /// * A wasm-encoded WIT package as a single file in the wasm binary format.
     /// * A wasm-encoded WIT package as a single file in the wasm text format.
+    /// * A `*.json` file containing a serialized [`Resolve`], such as the
+    ///   output of `wasm-tools component wit --json`. This requires the `serde`
+    ///   feature of this crate.
     ///
     /// In all of these cases packages are allowed to depend on previously
     /// inserted packages into this `Resolve`. Resolution for packages is based
@@ -278,6 +304,14 @@ impl Resolve {
     /// * `$path/deps/my-package.{wasm,wat}` - a wasm-encoded WIT package either
     ///   in the text for binary format.
     ///
+    /// Additionally a `$path/deps.toml` manifest may list dependencies to load
+    /// from a shared package cache, see `DepsManifest` for more information.
+    /// Packages in the `deps` folder take precedence over those in the cache,
+    /// and versions pinned by a `$path/wit.lock` lockfile are preferred over
+    /// others in the cache. Dependencies of all packages, including those in
+    /// `path` itself, are then resolved to the semver-compatible versions
+    /// selected. This requires the `manifest` feature.
+    ///
     /// In all cases entries in the `deps` folder are added to `self` first
     /// before adding files found in `path` itself. All WIT packages found are
     /// candidates for name-based resolution that other packages may used.
@@ -296,16 +330,25 @@ impl Resolve {
     pub fn push_dir(&mut self, path: impl AsRef<Path>) -> Result<(Vec<PackageId>, Vec<PathBuf>)> {
         let path = path.as_ref();
         let deps_path = path.join("deps");
-        let unresolved_deps = self.parse_deps_dir(&deps_path).with_context(|| {
+        let mut unresolved_deps = self.parse_deps_dir(&deps_path).with_context(|| {
             format!(
                 "failed to parse dependency directory: {}",
                 deps_path.display()
             )
         })?;
+        let mut unresolved_top_level = UnresolvedPackageGroup::parse_dir(path)
+            .with_context(|| format!("failed to parse package: {}", path.display()))?;
+        let manifest_path = path.join("deps.toml");
+        if manifest_path.is_file() {
+            let groups = self.parse_deps_manifest(
+                &manifest_path,
+                &mut unresolved_deps,
+                &mut unresolved_top_level,
+            )?;
+            unresolved_deps.extend(groups);
+        }
         let (_, mut path_bufs) = self.sort_unresolved_packages(unresolved_deps)?;
 
-        let unresolved_top_level = UnresolvedPackageGroup::parse_dir(path)
-            .with_context(|| format!("failed to parse package: {}", path.display()))?;
         let (pkgs_ids, mut top_level_path_bufs) =
             self.sort_unresolved_packages(vec![unresolved_top_level])?;
 
@@ -313,7 +356,28 @@ impl Resolve {
         Ok((pkgs_ids, path_bufs))
     }
 
-    fn parse_deps_dir(&mut self, path: &Path) -> Result<Vec<UnresolvedPackageGroup>> {
+    /// Parses every WIT package within the directory `path` and pushes them
+    /// all into this `Resolve`.
+    ///
+    /// The entries of `path` are handled in the same manner as those of the
+    /// `deps` directory in [`Resolve::push_dir`], so `path` is typically such a
+    /// `deps` directory. Packages within it may depend on one another and are
+    /// added in topological order.
+    ///
+    /// Returns the [`PackageId`]s of all packages found within `path`.
+    pub fn push_deps_dir(&mut self, path: impl AsRef<Path>) -> Result<Vec<PackageId>> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            bail!("`{}` is not a directory", path.display());
+        }
+        let unresolved = self
+            .parse_deps_dir(path)
+            .with_context(|| format!("failed to parse dependency directory: {}", path.display()))?;
+        let (pkg_ids, _) = self.sort_unresolved_packages(unresolved)?;
+        Ok(pkg_ids)
+    }
+
+    pub(crate) fn parse_deps_dir(&mut self, path: &Path) -> Result<Vec<UnresolvedPackageGroup>> {
         let mut unresolved_deps = Vec::new();
         // If there's no `deps` dir, then there's no deps, so return the
         // empty set.
@@ -344,8 +408,13 @@ impl Resolve {
                 unresolved_deps.push(
                     match Path::new(&filename).extension().and_then(|s| s.to_str()) {
                         Some("wit") | Some("wat") | Some("wasm") => match self._push_file(&path)? {
-                            #[cfg(feature = "decoding")]
-                            ParsedFile::Package(_) => continue,
+                            #[cfg(any(feature = "decoding", feature = "serde"))]
+                            ParsedFile::Packages(_) => continue,
+                            ParsedFile::Unresolved(pkgs) => pkgs,
+                        },
+                        #[cfg(feature = "serde")]
+                        Some("json") => match self._push_file(&path)? {
+                            ParsedFile::Packages(_) => continue,
                             ParsedFile::Unresolved(pkgs) => pkgs,
                         },
 
@@ -360,6 +429,75 @@ impl Resolve {
         Ok(unresolved_deps)
     }
 
+    /// Selects the packages required by the `deps.toml` manifest at `path`
+    /// from the package cache, skipping those already provided by `deps`
+    /// or previously inserted into `self`.
+    ///
+    /// Dependencies of the packages in `deps`, the cache, and `top_level` are
+    /// updated to refer to the semver-compatible versions selected.
+    #[cfg(feature = "manifest")]
+    fn parse_deps_manifest(
+        &mut self,
+        path: &Path,
+        deps: &mut [UnresolvedPackageGroup],
+        top_level: &mut UnresolvedPackageGroup,
+    ) -> Result<Vec<UnresolvedPackageGroup>> {
+        let existing = self
+            .package_names
+            .keys()
+            .chain(deps.iter().flat_map(|g| g.packages.iter().map(|p| &p.name)))
+            .cloned()
+            .collect::<Vec<_>>();
+        let manifest = crate::DepsManifest::from_file(path)?;
+        let lock = manifest.lock_file()?;
+        let cached = manifest
+            .resolve(&existing, lock.as_ref())
+            .with_context(|| format!("failed to resolve dependencies of {}", path.display()))?;
+        let mut unresolved_deps = Vec::new();
+        for pkg in cached {
+            if pkg.path.is_dir() {
+                unresolved_deps.push(
+                    UnresolvedPackageGroup::parse_dir(&pkg.path).with_context(|| {
+                        format!("failed to parse package: {}", pkg.path.display())
+                    })?,
+                );
+                continue;
+            }
+            match self._push_file(&pkg.path)? {
+                #[cfg(any(feature = "decoding", feature = "serde"))]
+                ParsedFile::Packages(_) => {}
+                ParsedFile::Unresolved(pkgs) => unresolved_deps.push(pkgs),
+            }
+        }
+
+        let available = existing
+            .into_iter()
+            .chain(self.package_names.keys().cloned())
+            .chain(
+                unresolved_deps
+                    .iter()
+                    .flat_map(|g| g.packages.iter().map(|p| p.name.clone())),
+            )
+            .collect::<Vec<_>>();
+        crate::manifest::unify_dep_versions(deps, &available);
+        crate::manifest::unify_dep_versions(&mut unresolved_deps, &available);
+        crate::manifest::unify_dep_versions(std::slice::from_mut(top_level), &available);
+        Ok(unresolved_deps)
+    }
+
+    #[cfg(not(feature = "manifest"))]
+    fn parse_deps_manifest(
+        &mut self,
+        path: &Path,
+        _deps: &mut [UnresolvedPackageGroup],
+        _top_level: &mut UnresolvedPackageGroup,
+    ) -> Result<Vec<UnresolvedPackageGroup>> {
+        bail!(
+            "cannot load {}: support for dependency manifests requires the `manifest` feature",
+            path.display()
+        )
+    }
+
     /// Parses the contents of `path` from the filesystem and pushes the result
     /// into this `Resolve`.
     ///
@@ -370,14 +508,18 @@ impl Resolve {
     /// * A WIT package encoded as WebAssembly, either in text or binary form.
     ///   In this the package and all of its dependencies are automatically
     ///   inserted into `self`.
+    /// * A `*.json` file containing a serialized [`Resolve`] when the `serde`
+    ///   feature is enabled. In this case all packages in the file are
+    ///   inserted into `self` and the packages which no other package depends
+    ///   on are returned.
     ///
-    /// In both situations the `PackageId`s of the resulting resolved packages
+    /// In all situations the `PackageId`s of the resulting resolved packages
     /// are returned from this method. The return value is mostly useful in
     /// conjunction with [`Resolve::select_world`].
     pub fn push_file(&mut self, path: impl AsRef<Path>) -> Result<Vec<PackageId>> {
         match self._push_file(path.as_ref())? {
-            #[cfg(feature = "decoding")]
-            ParsedFile::Package(id) => Ok(vec![id]),
+            #[cfg(any(feature = "decoding", feature = "serde"))]
+            ParsedFile::Packages(ids) => Ok(ids),
             ParsedFile::Unresolved(pkgs) => self.push_group(pkgs),
         }
     }
@@ -386,11 +528,36 @@ impl Resolve {
         let contents = std::fs::read(path)
             .with_context(|| format!("failed to read path for WIT [{}]", path.display()))?;
 
+        // If serde is enabled at compile time then `*.json` files are a
+        // serialized `Resolve`, as emitted by `wasm-tools component wit --json`.
+        #[cfg(feature = "serde")]
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            let resolve: Resolve = serde_json::from_slice(&contents)
+                .with_context(|| format!("failed to parse JSON WIT [{}]", path.display()))?;
+
+            // The "main" packages of the JSON are those which no other package
+            // depends on.
+            let mut deps = HashSet::new();
+            for (id, _) in resolve.packages.iter() {
+                deps.extend(resolve.package_direct_deps(id));
+            }
+            let main = resolve
+                .packages
+                .iter()
+                .map(|(id, _)| id)
+                .filter(|id| !deps.contains(id))
+                .collect::<Vec<_>>();
+            let remap = self.merge(resolve)?;
+            return Ok(ParsedFile::Packages(
+                main.iter().map(|id| remap.packages[id.index()]).collect(),
+            ));
+        }
+
         // If decoding is enabled at compile time then try to see if this is a
         // wasm file.
         #[cfg(feature = "decoding")]
         {
-            use crate::decoding::{decode, DecodedWasm};
+            use crate::decoding::{decode_with_features, DecodedWasm};
 
             #[cfg(feature = "wat")]
             let is_wasm = wat::Detect::from_bytes(&contents).is_wasm();
@@ -404,13 +571,14 @@ impl Resolve {
                     e
                 })?;
 
-                match decode(&contents)? {
+                let features = self.features.iter().map(|f| f.as_str());
+                match decode_with_features(&contents, features, self.all_features)? {
                     DecodedWasm::Component(..) => {
                         bail!("found an actual component instead of an encoded WIT package in wasm")
                     }
                     DecodedWasm::WitPackages(resolve, pkgs) => {
                         let remap = self.merge(resolve)?;
-                        return Ok(ParsedFile::Package(remap.packages[pkgs[0].index()]));
+                        return Ok(ParsedFile::Packages(vec![remap.packages[pkgs[0].index()]]));
                     }
                 }
             }
@@ -440,7 +608,13 @@ impl Resolve {
         unresolved: UnresolvedPackage,
         source_map: &SourceMap,
     ) -> Result<PackageId> {
-        source_map.rewrite_error(|| Remap::default().append(self, unresolved))
+        let mut remap = Remap::default();
+        let id = source_map.rewrite_error(|| remap.append(self, unresolved))?;
+        for (item, span) in remap.item_spans {
+            self.source_locations
+                .insert(item, source_map.location(span));
+        }
+        Ok(id)
     }
 
     /// Appends new [`UnresolvedPackageGroup`] to this [`Resolve`], creating a
@@ -482,7 +656,7 @@ impl Resolve {
             | Type::F32
             | Type::F64 => true,
 
-            Type::Bool | Type::Char | Type::String => false,
+            Type::Bool | Type::Char | Type::String | Type::ErrorContext => false,
 
             Type::Id(id) => match &self.types[*id].kind {
                 TypeDefKind::List(_)
@@ -569,6 +743,7 @@ impl Resolve {
             packages,
             package_names,
             features: _,
+            source_locations,
             ..
         } = resolve;
 
@@ -699,10 +874,161 @@ impl Resolve {
             assert!(prev.is_none());
         }
 
+        self.source_locations.merge(source_locations, &remap);
+
         log::trace!("now have {} packages", self.packages.len());
         Ok(remap)
     }
 
+    /// Removes all items gated by `@unstable` whose feature isn't activated by
+    /// [`Resolve::features`] or [`Resolve::all_features`].
+    ///
+    /// Items are filtered by their features as WIT source is parsed, but a
+    /// [`Resolve`] decoded from a binary WIT package may contain every
+    /// feature-gated item along with its gate, for example when the package
+    /// was encoded with all features active. This applies the features
+    /// selected afterwards, such as by whoever consumes the package.
+    ///
+    /// The returned [`Remap`] maps ids from before this operation to ids
+    /// afterwards, with `None` for items that were removed. Packages are never
+    /// removed.
+    ///
+    /// This fails if an item that is kept refers to one that is removed, such
+    /// as an ungated function using a type gated by a feature that isn't
+    /// active.
+    pub fn filter_features(&mut self) -> Result<Remap> {
+        // Build into a new `Resolve` so that `self` is left untouched if this
+        // fails.
+        let old = &*self;
+        let mut new = Resolve {
+            features: old.features.clone(),
+            all_features: old.all_features,
+            ..Resolve::default()
+        };
+
+        let excluded_interfaces = old
+            .interfaces
+            .iter()
+            .filter(|(_, iface)| !new.include_stability(&iface.stability))
+            .map(|(id, _)| id)
+            .collect::<HashSet<_>>();
+        let excluded_worlds = old
+            .worlds
+            .iter()
+            .filter(|(_, world)| !new.include_stability(&world.stability))
+            .map(|(id, _)| id)
+            .collect::<HashSet<_>>();
+
+        // Items are visited in the order of their arenas, which means that
+        // anything an item refers to has already been visited, as with
+        // `Resolve::merge`.
+        let mut remap = Remap::default();
+        for (_, ty) in old.types.iter() {
+            let owner_excluded = match ty.owner {
+                TypeOwner::Interface(id) => excluded_interfaces.contains(&id),
+                TypeOwner::World(id) => excluded_worlds.contains(&id),
+                TypeOwner::None => false,
+            };
+            if owner_excluded || !new.include_stability(&ty.stability) {
+                remap.types.push(None);
+                continue;
+            }
+            let mut ty = ty.clone();
+            remap
+                .update_typedef(&mut new, &mut ty, None)
+                .with_context(|| match &ty.name {
+                    Some(name) => format!("failed to update type `{name}`"),
+                    None => "failed to update anonymous type".to_string(),
+                })?;
+            remap.types.push(Some(new.types.alloc(ty)));
+        }
+
+        for (id, iface) in old.interfaces.iter() {
+            if excluded_interfaces.contains(&id) {
+                remap.interfaces.push(None);
+                continue;
+            }
+            let mut iface = iface.clone();
+            remap
+                .update_interface(&mut new, &mut iface, None)
+                .with_context(|| match &iface.name {
+                    Some(name) => format!("failed to update interface `{name}`"),
+                    None => "failed to update anonymous interface".to_string(),
+                })?;
+            remap.interfaces.push(Some(new.interfaces.alloc(iface)));
+        }
+
+        for (id, world) in old.worlds.iter() {
+            if excluded_worlds.contains(&id) {
+                remap.worlds.push(None);
+                continue;
+            }
+            let mut world = world.clone();
+            let mut update = |map: &mut IndexMap<WorldKey, WorldItem>| -> Result<_> {
+                for (mut name, mut item) in mem::take(map) {
+                    if !new.include_stability(item.stability(old)) {
+                        continue;
+                    }
+                    remap.update_world_key(&mut name, None)?;
+                    match &mut item {
+                        WorldItem::Function(f) => remap.update_function(&mut new, f, None)?,
+                        WorldItem::Interface { id, .. } => *id = remap.map_interface(*id, None)?,
+                        WorldItem::Type(i) => *i = remap.map_type(*i, None)?,
+                    }
+                    map.insert(name, item);
+                }
+                Ok(())
+            };
+            update(&mut world.imports)
+                .and_then(|()| update(&mut world.exports))
+                .with_context(|| format!("failed to update world `{}`", world.name))?;
+            remap.worlds.push(Some(new.worlds.alloc(world)));
+        }
+
+        for (_, pkg) in old.packages.iter() {
+            let mut pkg = pkg.clone();
+            pkg.interfaces
+                .retain(|_, id| remap.interfaces[id.index()].is_some());
+            for (_, id) in pkg.interfaces.iter_mut() {
+                *id = remap.map_interface(*id, None)?;
+            }
+            pkg.worlds
+                .retain(|_, id| remap.worlds[id.index()].is_some());
+            for (_, id) in pkg.worlds.iter_mut() {
+                *id = remap.map_world(*id, None)?;
+            }
+            remap.packages.push(new.packages.alloc(pkg));
+        }
+        for (name, id) in old.package_names.iter() {
+            new.package_names
+                .insert(name.clone(), remap.packages[id.index()]);
+        }
+
+        // Fixup "parent" links now that all items have been moved.
+        for (_, iface) in new.interfaces.iter_mut() {
+            if let Some(pkg) = &mut iface.package {
+                *pkg = remap.packages[pkg.index()];
+            }
+        }
+        for (_, world) in new.worlds.iter_mut() {
+            if let Some(pkg) = &mut world.package {
+                *pkg = remap.packages[pkg.index()];
+            }
+        }
+        for (_, id) in new.types.iter_mut() {
+            match &mut id.owner {
+                TypeOwner::Interface(id) => *id = remap.map_interface(*id, None)?,
+                TypeOwner::World(id) => *id = remap.map_world(*id, None)?,
+                TypeOwner::None => {}
+            }
+        }
+
+        new.source_locations
+            .merge(old.source_locations.clone(), &remap);
+        *self = new;
+        Ok(remap)
+    }
+
     /// Merges the world `from` into the world `into`.
     ///
     /// This will attempt to merge one world into another, unioning all of its
@@ -815,6 +1141,163 @@ impl Resolve {
         Ok(())
     }
 
+    /// Restricts the world `into` to the imports and exports it has in common
+    /// with the world `other`.
+    ///
+    /// Items are matched by name and by what they refer to, so for example an
+    /// import of `wasi:io/streams@0.2.0` is kept only if `other` also imports
+    /// that same version of the interface, and a function import `foo` is
+    /// kept only if `other` also imports a function named `foo` with the same
+    /// signature. Types match when they're the same type once aliases are
+    /// followed, so types defined separately in each world never match even
+    /// if they're structurally identical. This can be used, for example, to
+    /// compute the imports supported by several different hosts.
+    ///
+    /// Interfaces which the remaining items depend on, such as those brought
+    /// in with `use`, are kept as imports even if `other` lacks them so that
+    /// `into` remains well-formed. Types defined in `into` itself, including
+    /// resources, are always kept.
+    pub fn intersect_worlds(&mut self, other: WorldId, into: WorldId) {
+        let other = &self.worlds[other];
+        let world = &self.worlds[into];
+        let imports = matching_world_items(self, &world.imports, &other.imports, true);
+        let exports = matching_world_items(self, &world.exports, &other.exports, true);
+        self.retain_world_items(into, imports, exports);
+    }
+
+    /// Removes from the world `from` the imports and exports which are also
+    /// present in the world `other`.
+    ///
+    /// Items are matched in the same way as [`Resolve::intersect_worlds`].
+    /// For example subtracting the world of a platform from the world of a
+    /// component yields the imports which the component still needs beyond
+    /// what the platform provides.
+    ///
+    /// Interfaces which the remaining items depend on are kept as imports
+    /// even if `other` has them so that `from` remains well-formed. Types
+    /// defined in `from` itself, including resources, are always kept.
+    pub fn subtract_worlds(&mut self, other: WorldId, from: WorldId) {
+        let other = &self.worlds[other];
+        let world = &self.worlds[from];
+        let imports = matching_world_items(self, &world.imports, &other.imports, false);
+        let exports = matching_world_items(self, &world.exports, &other.exports, false);
+        self.retain_world_items(from, imports, exports);
+    }
+
+    /// Converts all of the exports of `world` into imports.
+    ///
+    /// This is useful when testing the composition of components, for example
+    /// to generate a world which imports everything a component exports. Items
+    /// already imported under the same name are merged, and an error is
+    /// returned if an export conflicts with a different import of the same
+    /// name.
+    pub fn importize(&mut self, world: WorldId) -> Result<()> {
+        let exports = mem::take(&mut self.worlds[world].exports);
+        for (name, export) in exports {
+            let imports = &mut self.worlds[world].imports;
+            match imports.get(&name) {
+                Some(import) => match (import, &export) {
+                    (WorldItem::Interface { id: a, .. }, WorldItem::Interface { id: b, .. })
+                        if a == b => {}
+                    _ => {
+                        let name = self.name_world_key(&name);
+                        bail!("export `{name}` conflicts with an import of the same name");
+                    }
+                },
+                None => {
+                    imports.insert(name, export);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the imports and exports of `world` to be those named in
+    /// `imports` and `exports` plus all imports those items depend on.
+    fn retain_world_items(
+        &mut self,
+        world: WorldId,
+        mut imports: HashSet<WorldKey>,
+        exports: HashSet<WorldKey>,
+    ) {
+        let w = &self.worlds[world];
+
+        // Types defined within the world itself are always kept since they
+        // can't be removed from the `Resolve` entirely.
+        for (key, item) in w.imports.iter() {
+            if let WorldItem::Type(id) = item {
+                if self.types[*id].owner == TypeOwner::World(world) {
+                    imports.insert(key.clone());
+                }
+            }
+        }
+
+        let type_keys = w
+            .imports
+            .iter()
+            .filter_map(|(key, item)| match item {
+                WorldItem::Type(id) => Some((*id, key)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut worklist = w
+            .imports
+            .iter()
+            .filter(|(key, _)| imports.contains(key))
+            .map(|(key, item)| (key, item, false))
+            .chain(
+                w.exports
+                    .iter()
+                    .filter(|(key, _)| exports.contains(key))
+                    .map(|(key, item)| (key, item, true)),
+            )
+            .collect::<Vec<_>>();
+        while let Some((key, item, is_export)) = worklist.pop() {
+            let mut live = LiveTypes::default();
+            live.add_world_item(self, item);
+            for ty in live.iter() {
+                let required = match self.types[ty].owner {
+                    TypeOwner::World(_) => match type_keys.get(&ty) {
+                        Some(required) if *required != key => (*required).clone(),
+                        _ => continue,
+                    },
+                    TypeOwner::Interface(id) => {
+                        let required = WorldKey::Interface(id);
+                        if required == *key || (is_export && exports.contains(&required)) {
+                            continue;
+                        }
+                        required
+                    }
+                    TypeOwner::None => continue,
+                };
+                if !imports.insert(required.clone()) {
+                    continue;
+                }
+                if let Some((key, item)) = w.imports.get_key_value(&required) {
+                    worklist.push((key, item, false));
+                } else if let Some((key, item)) = w.exports.get_key_value(&required) {
+                    worklist.push((key, item, false));
+                }
+            }
+        }
+
+        // Exported interfaces which are used by the remaining items but are
+        // themselves removed become imports instead, in the same order as
+        // they were exported so that dependencies still come first.
+        let promoted = w
+            .exports
+            .iter()
+            .filter(|(key, _)| imports.contains(key) && !w.imports.contains_key(*key))
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect::<Vec<_>>();
+
+        let w = &mut self.worlds[world];
+        w.imports.retain(|key, _| imports.contains(key));
+        w.imports.extend(promoted);
+        w.exports.retain(|key, _| exports.contains(key));
+    }
+
     /// Returns the ID of the specified `interface`.
     ///
     /// Returns `None` for unnamed interfaces.
@@ -1157,69 +1640,141 @@ impl Resolve {
 
     #[doc(hidden)]
     pub fn assert_valid(&self) {
+        if let Err(e) = self.validate() {
+            panic!("invalid `Resolve`: {e:#}");
+        }
+    }
+
+    /// Checks the internal invariants of this `Resolve`, such as that items
+    /// point back at the package or interface which contains them, and that
+    /// packages and interfaces are topologically sorted.
+    ///
+    /// Every `Resolve` created by this crate upholds these, but one
+    /// deserialized from JSON may not.
+    pub(crate) fn validate(&self) -> Result<()> {
         let mut package_interfaces = Vec::new();
         let mut package_worlds = Vec::new();
         for (id, pkg) in self.packages.iter() {
             let mut interfaces = HashSet::new();
-            for (name, iface) in pkg.interfaces.iter() {
-                assert!(interfaces.insert(*iface));
-                let iface = &self.interfaces[*iface];
-                assert_eq!(name, iface.name.as_ref().unwrap());
-                assert_eq!(iface.package.unwrap(), id);
-            }
-            package_interfaces.push(pkg.interfaces.values().copied().collect::<HashSet<_>>());
+            for (name, iface_id) in pkg.interfaces.iter() {
+                ensure!(
+                    interfaces.insert(*iface_id),
+                    "interface `{name}` is listed twice in package `{}`",
+                    pkg.name
+                );
+                let iface = &self.interfaces[*iface_id];
+                ensure!(
+                    iface.name.as_ref() == Some(name),
+                    "interface `{name}` of package `{}` is named {:?}",
+                    pkg.name,
+                    iface.name
+                );
+                ensure!(
+                    iface.package == Some(id),
+                    "interface `{name}` does not belong to package `{}`",
+                    pkg.name
+                );
+            }
+            package_interfaces.push(interfaces);
             let mut worlds = HashSet::new();
-            for (name, world) in pkg.worlds.iter() {
-                assert!(worlds.insert(*world));
-                let world = &self.worlds[*world];
-                assert_eq!(*name, world.name);
-                assert_eq!(world.package.unwrap(), id);
-            }
-            package_worlds.push(pkg.worlds.values().copied().collect::<HashSet<_>>());
+            for (name, world_id) in pkg.worlds.iter() {
+                ensure!(
+                    worlds.insert(*world_id),
+                    "world `{name}` is listed twice in package `{}`",
+                    pkg.name
+                );
+                let world = &self.worlds[*world_id];
+                ensure!(
+                    *name == world.name,
+                    "world `{name}` of package `{}` is named `{}`",
+                    pkg.name,
+                    world.name
+                );
+                ensure!(
+                    world.package == Some(id),
+                    "world `{name}` does not belong to package `{}`",
+                    pkg.name
+                );
+            }
+            package_worlds.push(worlds);
         }
 
         let mut interface_types = Vec::new();
         for (id, iface) in self.interfaces.iter() {
-            assert!(self.packages.get(iface.package.unwrap()).is_some());
+            let name = iface.name.as_deref().unwrap_or("<anonymous>");
+            let Some(pkg) = iface.package else {
+                bail!("interface `{name}` does not belong to a package");
+            };
             if iface.name.is_some() {
-                assert!(package_interfaces[iface.package.unwrap().index()].contains(&id));
+                ensure!(
+                    package_interfaces[pkg.index()].contains(&id),
+                    "interface `{name}` is missing from its package"
+                );
             }
 
-            for (name, ty) in iface.types.iter() {
+            for (ty_name, ty) in iface.types.iter() {
                 let ty = &self.types[*ty];
-                assert_eq!(ty.name.as_ref(), Some(name));
-                assert_eq!(ty.owner, TypeOwner::Interface(id));
+                ensure!(
+                    ty.name.as_ref() == Some(ty_name),
+                    "type `{ty_name}` of interface `{name}` is named {:?}",
+                    ty.name
+                );
+                ensure!(
+                    ty.owner == TypeOwner::Interface(id),
+                    "type `{ty_name}` is not owned by interface `{name}`"
+                );
             }
             interface_types.push(iface.types.values().copied().collect::<HashSet<_>>());
-            for (name, f) in iface.functions.iter() {
-                assert_eq!(*name, f.name);
+            for (func_name, f) in iface.functions.iter() {
+                ensure!(
+                    *func_name == f.name,
+                    "function `{func_name}` of interface `{name}` is named `{}`",
+                    f.name
+                );
             }
         }
 
         let mut world_types = Vec::new();
         for (id, world) in self.worlds.iter() {
             log::debug!("validating world {}", &world.name);
-            assert!(self.packages.get(world.package.unwrap()).is_some());
-            assert!(package_worlds[world.package.unwrap().index()].contains(&id));
+            let Some(pkg) = world.package else {
+                bail!("world `{}` does not belong to a package", world.name);
+            };
+            ensure!(
+                package_worlds[pkg.index()].contains(&id),
+                "world `{}` is missing from its package",
+                world.name
+            );
 
             let mut types = HashSet::new();
-            for (name, item) in world.imports.iter().chain(world.exports.iter()) {
-                log::debug!("validating world item: {}", self.name_world_key(name));
+            for (key, item) in world.imports.iter().chain(world.exports.iter()) {
+                log::debug!("validating world item: {}", self.name_world_key(key));
                 match item {
                     WorldItem::Interface { .. } => {}
-                    WorldItem::Function(f) => {
-                        assert_eq!(f.name, name.clone().unwrap_name());
-                    }
+                    // Note that the function's name isn't compared with
+                    // `name` since `include ... with { a as b }` renames the
+                    // key in the world but not the function itself.
+                    WorldItem::Function(_) => {}
                     WorldItem::Type(ty) => {
-                        assert!(types.insert(*ty));
+                        let WorldKey::Name(name) = key else {
+                            bail!("type in world `{}` has an interface name", world.name);
+                        };
+                        ensure!(
+                            types.insert(*ty),
+                            "type `{name}` is listed twice in world `{}`",
+                            world.name
+                        );
                         let ty = &self.types[*ty];
-                        assert_eq!(ty.name, Some(name.clone().unwrap_name()));
+                        ensure!(
+                            ty.name.as_ref() == Some(name),
+                            "type `{name}` of world `{}` is named {:?}",
+                            world.name,
+                            ty.name
+                        );
 
-                        // TODO: `Resolve::merge_worlds` doesn't uphold this
-                        // invariant, and that should be fixed.
-                        if false {
-                            assert_eq!(ty.owner, TypeOwner::World(id));
-                        }
+                        // TODO: `Resolve::merge_worlds` doesn't uphold the
+                        // invariant that `ty.owner` is `TypeOwner::World(id)`,
+                        // and that should be fixed.
                     }
                 }
             }
@@ -1227,23 +1782,60 @@ impl Resolve {
         }
 
         for (ty_id, ty) in self.types.iter() {
+            let name = ty.name.as_deref().unwrap_or("<anonymous>");
             match ty.owner {
-                TypeOwner::Interface(id) => {
-                    assert!(self.interfaces.get(id).is_some());
-                    assert!(interface_types[id.index()].contains(&ty_id));
-                }
-                TypeOwner::World(id) => {
-                    assert!(self.worlds.get(id).is_some());
-                    assert!(world_types[id.index()].contains(&ty_id));
-                }
+                TypeOwner::Interface(id) => ensure!(
+                    interface_types[id.index()].contains(&ty_id),
+                    "type `{name}` is missing from the interface which owns it"
+                ),
+                TypeOwner::World(id) => ensure!(
+                    world_types[id.index()].contains(&ty_id),
+                    "type `{name}` is missing from the world which owns it"
+                ),
                 TypeOwner::None => {}
             }
         }
 
-        self.assert_topologically_sorted();
+        self.validate_package_deps_acyclic()?;
+        self.validate_topologically_sorted()
+    }
+
+    /// Checks that packages don't depend on each other in a cycle, which
+    /// [`Resolve::topological_packages`] relies on.
+    fn validate_package_deps_acyclic(&self) -> Result<()> {
+        // 0 = unvisited, 1 = being visited, 2 = done
+        let mut state = vec![0u8; self.packages.len()];
+        for (id, _) in self.packages.iter() {
+            if state[id.index()] != 0 {
+                continue;
+            }
+            state[id.index()] = 1;
+            let mut stack = vec![(id, self.package_direct_deps(id).collect::<Vec<_>>())];
+            while let Some((id, deps)) = stack.last_mut() {
+                match deps.pop() {
+                    Some(dep) => match state[dep.index()] {
+                        0 => {
+                            state[dep.index()] = 1;
+                            stack.push((dep, self.package_direct_deps(dep).collect()));
+                        }
+                        1 => bail!(
+                            "package `{}` depends on itself through `{}`",
+                            self.packages[dep].name,
+                            self.packages[*id].name
+                        ),
+                        _ => {}
+                    },
+                    None => {
+                        state[id.index()] = 2;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 
-    fn assert_topologically_sorted(&self) {
+    fn validate_topologically_sorted(&self) -> Result<()> {
         let mut positions = IndexMap::new();
         for id in self.topological_packages() {
             let pkg = &self.packages[id];
@@ -1273,7 +1865,11 @@ impl Resolve {
                 let other_package = self.interfaces[id].package;
                 let other_package_pos = positions.get_index_of(&other_package).unwrap();
 
-                assert!(other_package_pos <= my_package_pos);
+                ensure!(
+                    other_package_pos <= my_package_pos,
+                    "world `{}` uses an interface from a package sorted after its own",
+                    world.name
+                );
             }
         }
 
@@ -1301,21 +1897,31 @@ impl Resolve {
             let my_package_pos = positions.get_index_of(&my_package).unwrap();
             let other_package_pos = positions.get_index_of(&other_package).unwrap();
 
+            let name = ty.name.as_deref().unwrap_or("<anonymous>");
             if my_package_pos == other_package_pos {
                 let interfaces = &positions[&my_package];
                 let my_interface_pos = interfaces.get_index_of(&my_interface).unwrap();
                 let other_interface_pos = interfaces.get_index_of(&other_interface).unwrap();
-                assert!(other_interface_pos <= my_interface_pos);
+                ensure!(
+                    other_interface_pos <= my_interface_pos,
+                    "type `{name}` refers to a type in an interface sorted after its own"
+                );
             } else {
-                assert!(other_package_pos < my_package_pos);
+                ensure!(
+                    other_package_pos < my_package_pos,
+                    "type `{name}` refers to a type in a package sorted after its own"
+                );
             }
         }
+        Ok(())
     }
 
     fn include_stability(&self, stability: &Stability) -> bool {
         match stability {
             Stability::Stable { .. } | Stability::Unknown => true,
-            Stability::Unstable { feature } => self.features.contains(feature) || self.all_features,
+            Stability::Unstable { feature, .. } => {
+                self.features.contains(feature) || self.all_features
+            }
         }
     }
 }
@@ -1341,6 +1947,14 @@ pub struct Remap {
     own_handles: HashMap<TypeId, TypeId>,
 
     type_has_borrow: Vec<Option<bool>>,
+
+    /// Spans of the items appended into a `Resolve`, used to fill in
+    /// [`Resolve::source_locations`].
+    item_spans: Vec<(SourceItem, Span)>,
+
+    /// Spans of the explicit imports and exports of the world currently being
+    /// appended, keyed by whether they're an import.
+    world_item_spans: Vec<(bool, WorldKey, Span)>,
 }
 
 fn apply_map<T>(map: &[Option<Id<T>>], id: Id<T>, desc: &str, span: Option<Span>) -> Result<Id<T>> {
@@ -1431,6 +2045,7 @@ impl Remap {
                 // modified.
                 _ => new_id,
             };
+            self.item_spans.push((SourceItem::Type(new_id), *span));
             self.types.push(Some(new_id));
         }
 
@@ -1450,10 +2065,23 @@ impl Remap {
                 self.interfaces.push(None);
                 continue;
             }
+            let func_spans = iface
+                .functions
+                .iter()
+                .zip(&span.funcs)
+                .filter(|((_, f), _)| resolve.include_stability(&f.stability))
+                .map(|((name, _), span)| (name.clone(), *span))
+                .collect::<Vec<_>>();
             self.update_interface(resolve, &mut iface, Some(span))?;
             assert!(iface.package.is_none());
             iface.package = Some(pkgid);
             let new_id = resolve.interfaces.alloc(iface);
+            self.item_spans
+                .push((SourceItem::Interface(new_id), span.span));
+            for (name, span) in func_spans {
+                self.item_spans
+                    .push((SourceItem::InterfaceFunction(new_id, name), span));
+            }
             assert_eq!(self.interfaces.len(), id.index());
             self.interfaces.push(Some(new_id));
         }
@@ -1498,6 +2126,15 @@ impl Remap {
             self.update_world(&mut world, resolve, &span)?;
 
             let new_id = resolve.worlds.alloc(world);
+            self.item_spans.push((SourceItem::World(new_id), span.span));
+            for (import, key, span) in mem::take(&mut self.world_item_spans) {
+                let item = if import {
+                    SourceItem::WorldImport(new_id, key)
+                } else {
+                    SourceItem::WorldExport(new_id, key)
+                };
+                self.item_spans.push((item, span));
+            }
             assert_eq!(self.worlds.len(), id.index());
             self.worlds.push(Some(new_id));
         }
@@ -1953,6 +2590,7 @@ impl Remap {
                 continue;
             }
             self.update_world_key(&mut name, Some(*span))?;
+            self.world_item_spans.push((true, name.clone(), *span));
             match item {
                 WorldItem::Interface { id, stability } => {
                     let id = self.map_interface(id, Some(*span))?;
@@ -1994,6 +2632,7 @@ impl Remap {
                 continue;
             }
             self.update_world_key(&mut name, Some(*span))?;
+            self.world_item_spans.push((false, name.clone(), *span));
             match item {
                 WorldItem::Interface { id, stability } => {
                     let id = self.map_interface(id, Some(*span))?;
@@ -2679,9 +3318,117 @@ impl<'a> MergeMap<'a> {
     }
 }
 
+/// Returns the keys of `items` which, depending on `present`, either are or
+/// aren't also in `other` as a matching item, see [`world_items_match`].
+fn matching_world_items(
+    resolve: &Resolve,
+    items: &IndexMap<WorldKey, WorldItem>,
+    other: &IndexMap<WorldKey, WorldItem>,
+    present: bool,
+) -> HashSet<WorldKey> {
+    items
+        .iter()
+        .filter(|(key, item)| {
+            let matches = other
+                .get(*key)
+                .is_some_and(|o| world_items_match(resolve, o, item));
+            matches == present
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Returns whether the world items `a` and `b` refer to the same thing.
+///
+/// Interfaces must be the same interface, and therefore from the same version
+/// of the same package. Functions must have the same signature, and types
+/// must be the same type once aliases are followed.
+fn world_items_match(resolve: &Resolve, a: &WorldItem, b: &WorldItem) -> bool {
+    match (a, b) {
+        (WorldItem::Interface { id: a, .. }, WorldItem::Interface { id: b, .. }) => a == b,
+        (WorldItem::Function(a), WorldItem::Function(b)) => {
+            a.kind == b.kind
+                && a.params.len() == b.params.len()
+                && a.params
+                    .iter()
+                    .zip(&b.params)
+                    .all(|((a_name, a), (b_name, b))| {
+                        a_name == b_name && types_match(resolve, a, b)
+                    })
+                && match (&a.results, &b.results) {
+                    (Results::Anon(a), Results::Anon(b)) => types_match(resolve, a, b),
+                    (Results::Named(a), Results::Named(b)) => {
+                        a.len() == b.len()
+                            && a.iter().zip(b).all(|((a_name, a), (b_name, b))| {
+                                a_name == b_name && types_match(resolve, a, b)
+                            })
+                    }
+                    _ => false,
+                }
+        }
+        (WorldItem::Type(a), WorldItem::Type(b)) => dealias(resolve, *a) == dealias(resolve, *b),
+        _ => false,
+    }
+}
+
+/// Returns whether `a` and `b` are the same type.
+///
+/// Named types, such as records and resources, are only the same as
+/// themselves, while anonymous types such as `list<T>` are compared by their
+/// structure since each world has its own copy of them.
+fn types_match(resolve: &Resolve, a: &Type, b: &Type) -> bool {
+    let (a, b) = match (a, b) {
+        (Type::Id(a), Type::Id(b)) => (dealias(resolve, *a), dealias(resolve, *b)),
+        _ => return a == b,
+    };
+    if a == b {
+        return true;
+    }
+    let (a, b) = (&resolve.types[a], &resolve.types[b]);
+    if a.name.is_some() || b.name.is_some() {
+        return false;
+    }
+    let optional_types_match = |a: &Option<Type>, b: &Option<Type>| match (a, b) {
+        (Some(a), Some(b)) => types_match(resolve, a, b),
+        (None, None) => true,
+        _ => false,
+    };
+    match (&a.kind, &b.kind) {
+        (TypeDefKind::List(a), TypeDefKind::List(b))
+        | (TypeDefKind::Option(a), TypeDefKind::Option(b)) => types_match(resolve, a, b),
+        (TypeDefKind::Tuple(a), TypeDefKind::Tuple(b)) => {
+            a.types.len() == b.types.len()
+                && a.types
+                    .iter()
+                    .zip(&b.types)
+                    .all(|(a, b)| types_match(resolve, a, b))
+        }
+        (TypeDefKind::Result(a), TypeDefKind::Result(b)) => {
+            optional_types_match(&a.ok, &b.ok) && optional_types_match(&a.err, &b.err)
+        }
+        (TypeDefKind::Handle(Handle::Own(a)), TypeDefKind::Handle(Handle::Own(b)))
+        | (TypeDefKind::Handle(Handle::Borrow(a)), TypeDefKind::Handle(Handle::Borrow(b))) => {
+            dealias(resolve, *a) == dealias(resolve, *b)
+        }
+        (TypeDefKind::Future(a), TypeDefKind::Future(b)) => optional_types_match(a, b),
+        (TypeDefKind::Stream(a), TypeDefKind::Stream(b)) => {
+            optional_types_match(&a.element, &b.element) && optional_types_match(&a.end, &b.end)
+        }
+        _ => false,
+    }
+}
+
+/// Follows type aliases, such as those created by `use`, starting at `id`.
+fn dealias(resolve: &Resolve, mut id: TypeId) -> TypeId {
+    while let TypeDefKind::Type(Type::Id(next)) = resolve.types[id].kind {
+        id = next;
+    }
+    id
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Resolve;
+    use crate::{Resolve, SourceLocation, WorldItem, WorldKey};
     use anyhow::Result;
 
     #[test]
@@ -2737,4 +3484,306 @@ mod tests {
             .is_ok());
         Ok(())
     }
+
+    #[test]
+    fn world_algebra() -> Result<()> {
+        let mut resolve = Resolve::default();
+        let pkgs = resolve.push_str(
+            "test.wit",
+            r#"
+                package foo:bar;
+
+                interface types {
+                    type t = u32;
+                }
+
+                interface other {}
+
+                world app {
+                    use types.{t};
+                    resource r;
+                    import other;
+                    import get: func() -> t;
+                    import take: func(x: r);
+                    import log: func();
+                    export run: func();
+                }
+
+                world host {
+                    use types.{t};
+                    import get: func() -> t;
+                    import log: func();
+                    export run: func();
+                }
+
+                world platform {
+                    import other;
+                    import log: func();
+                    import take: interface {}
+                }
+            "#,
+        )?;
+        let names = |resolve: &Resolve, world, imports: bool| {
+            let world = &resolve.worlds[world];
+            let items = if imports {
+                &world.imports
+            } else {
+                &world.exports
+            };
+            items
+                .keys()
+                .map(|k| resolve.name_world_key(k))
+                .collect::<Vec<_>>()
+        };
+
+        // Interfaces used by the remaining types are kept, as are all types
+        // defined within the world.
+        let app = resolve.select_world(&pkgs, Some("app"))?;
+        let host = resolve.select_world(&pkgs, Some("host"))?;
+        resolve.intersect_worlds(host, app);
+        resolve.assert_valid();
+        assert_eq!(
+            names(&resolve, app, true),
+            ["foo:bar/types", "t", "r", "get", "log"]
+        );
+        assert_eq!(names(&resolve, app, false), ["run"]);
+
+        // Items of a different kind with the same name don't match.
+        resolve.push_str(
+            "test.wit",
+            r#"
+                package foo:baz;
+
+                world app {
+                    resource r;
+                    import take: func(x: r);
+                    import log: func();
+                    export run: func();
+                }
+            "#,
+        )?;
+        let app = resolve.select_world(&pkgs, Some("foo:baz/app"))?;
+        let platform = resolve.select_world(&pkgs, Some("platform"))?;
+        resolve.subtract_worlds(platform, app);
+        resolve.assert_valid();
+        assert_eq!(names(&resolve, app, true), ["r", "take"]);
+
+        resolve.importize(app)?;
+        resolve.assert_valid();
+        assert_eq!(names(&resolve, app, true), ["r", "take", "run"]);
+        assert!(resolve.worlds[app].exports.is_empty());
+
+        // Functions only match if their signatures do, where anonymous types
+        // are compared by structure.
+        resolve.push_str(
+            "test.wit",
+            r#"
+                package foo:sig;
+
+                world a {
+                    import log: func(x: u32);
+                    import get: func() -> list<u32>;
+                }
+
+                world b {
+                    import log: func();
+                    import get: func() -> list<u32>;
+                }
+            "#,
+        )?;
+        let a = resolve.select_world(&pkgs, Some("foo:sig/a"))?;
+        let b = resolve.select_world(&pkgs, Some("foo:sig/b"))?;
+        resolve.intersect_worlds(b, a);
+        resolve.assert_valid();
+        assert_eq!(names(&resolve, a, true), ["get"]);
+
+        // Exported interfaces which a remaining export `use`s are imported
+        // instead when they're removed.
+        resolve.push_str(
+            "test.wit",
+            r#"
+                package foo:uses;
+
+                interface types {
+                    record info { a: u32 }
+                }
+
+                interface api {
+                    use types.{info};
+                    get: func() -> info;
+                }
+
+                world app {
+                    export types;
+                    export api;
+                }
+
+                world other {
+                    export types;
+                }
+            "#,
+        )?;
+        let app = resolve.select_world(&pkgs, Some("foo:uses/app"))?;
+        let other = resolve.select_world(&pkgs, Some("foo:uses/other"))?;
+        resolve.subtract_worlds(other, app);
+        resolve.assert_valid();
+        assert_eq!(names(&resolve, app, true), ["foo:uses/types"]);
+        assert_eq!(names(&resolve, app, false), ["foo:uses/api"]);
+        assert!(matches!(
+            resolve.worlds[app].imports[0],
+            WorldItem::Interface { .. }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn source_locations() -> Result<()> {
+        let mut resolve = Resolve::default();
+        let pkgs = resolve.push_str(
+            "test.wit",
+            "package foo:bar;
+
+interface types {
+  type t = u32;
+
+  f: func(x: t);
+}
+
+world w {
+  import types;
+  export run: func();
+}
+",
+        )?;
+        let pkg = &resolve.packages[pkgs[0]];
+        let types = pkg.interfaces["types"];
+        let w = pkg.worlds["w"];
+        let t = resolve.interfaces[types].types["t"];
+
+        let loc = |l: Option<&SourceLocation>| l.map(|l| l.to_string());
+        let locs = &resolve.source_locations;
+        assert_eq!(loc(locs.interface(types)).as_deref(), Some("test.wit:3:11"));
+        assert_eq!(loc(locs.ty(t)).as_deref(), Some("test.wit:4:8"));
+        assert_eq!(
+            loc(locs.interface_function(types, "f")).as_deref(),
+            Some("test.wit:6:3")
+        );
+        assert_eq!(loc(locs.world(w)).as_deref(), Some("test.wit:9:7"));
+        assert_eq!(
+            loc(locs.world_import(w, &WorldKey::Interface(types))).as_deref(),
+            Some("test.wit:10:10")
+        );
+        assert_eq!(
+            loc(locs.world_export(w, &WorldKey::Name("run".to_string()))).as_deref(),
+            Some("test.wit:11:10")
+        );
+
+        // Locations are carried along when merging into another `Resolve`.
+        let mut merged = Resolve::default();
+        let remap = merged.merge(resolve)?;
+        let w = remap.worlds[w.index()].unwrap();
+        assert_eq!(
+            loc(merged.source_locations.world(w)).as_deref(),
+            Some("test.wit:9:7")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn filter_features() -> Result<()> {
+        let wit = r#"
+            package foo:bar;
+
+            interface i {
+                @unstable(feature = a)
+                record r { x: u32 }
+                @unstable(feature = a)
+                f: func(x: r);
+                @unstable(feature = b)
+                g: func();
+                h: func();
+            }
+
+            @unstable(feature = b)
+            interface j {
+                k: func();
+            }
+
+            world w {
+                import i;
+                @unstable(feature = b)
+                import j;
+                @unstable(feature = a)
+                export run: func();
+            }
+        "#;
+        let mut resolve = Resolve::default();
+        resolve.all_features = true;
+        let pkgs = resolve.push_str("test.wit", wit)?;
+        let w = resolve.packages[pkgs[0]].worlds["w"];
+
+        let mut filtered = resolve.clone();
+        filtered.all_features = false;
+        filtered.features.insert("a".to_string());
+        let remap = filtered.filter_features()?;
+        filtered.assert_valid();
+
+        let pkg = &filtered.packages[remap.packages[pkgs[0].index()]];
+        assert_eq!(pkg.interfaces.keys().collect::<Vec<_>>(), ["i"]);
+        let i = &filtered.interfaces[pkg.interfaces["i"]];
+        assert_eq!(i.types.keys().collect::<Vec<_>>(), ["r"]);
+        assert_eq!(i.functions.keys().collect::<Vec<_>>(), ["f", "h"]);
+        let w = &filtered.worlds[remap.worlds[w.index()].unwrap()];
+        assert_eq!(w.imports.len(), 1);
+        assert_eq!(w.exports.len(), 1);
+
+        // Filtering without any features matches parsing without any.
+        let mut expected = Resolve::default();
+        expected.push_str("test.wit", wit)?;
+        let mut filtered = resolve.clone();
+        filtered.all_features = false;
+        filtered.filter_features()?;
+        filtered.assert_valid();
+        let names = |r: &Resolve| {
+            r.interfaces
+                .iter()
+                .map(|(_, i)| (i.name.clone(), i.functions.keys().cloned().collect()))
+                .collect::<Vec<(_, Vec<_>)>>()
+        };
+        assert_eq!(names(&filtered), names(&expected));
+        assert_eq!(filtered.types.len(), expected.types.len());
+
+        // An ungated item may not refer to one that's filtered out.
+        let mut resolve = Resolve::default();
+        resolve.all_features = true;
+        resolve.push_str(
+            "test.wit",
+            r#"
+                package foo:bar;
+
+                interface i {
+                    @unstable(feature = a)
+                    record r { x: u32 }
+                    f: func(x: r);
+                }
+            "#,
+        )?;
+        resolve.all_features = false;
+        let Err(err) = resolve.filter_features() else {
+            panic!("filtering should fail");
+        };
+        assert_eq!(
+            format!("{err:#}"),
+            "failed to update interface `i`: failed to update function `f`: \
+             found a reference to a type which is excluded due to its feature \
+             not being activated"
+        );
+
+        // A failed filter leaves the `Resolve` as it was.
+        resolve.assert_valid();
+        let i = &resolve.interfaces[resolve.packages.iter().next().unwrap().1.interfaces["i"]];
+        assert_eq!(i.types.keys().collect::<Vec<_>>(), ["r"]);
+        assert_eq!(i.functions.keys().collect::<Vec<_>>(), ["f"]);
+        Ok(())
+    }
 }
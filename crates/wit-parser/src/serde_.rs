@@ -1,9 +1,14 @@
-use crate::{Params, Type};
-use id_arena::{Arena, Id};
+use crate::{
+    Interface, Package, PackageName, Params, Resolve, Results, Type, TypeDef, World, WorldKey,
+};
+use id_arena::{Arena, ArenaBehavior, DefaultArenaBehavior, Id};
 use indexmap::IndexMap;
 use semver::Version;
+use serde::de::{Deserializer, Visitor};
 use serde::ser::{SerializeMap, SerializeSeq, Serializer};
 use serde::{de::Error, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
 
 pub fn serialize_none<S>(serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -72,6 +77,7 @@ impl Serialize for Type {
             Type::F64 => serializer.serialize_str("f64"),
             Type::Char => serializer.serialize_str("char"),
             Type::String => serializer.serialize_str("string"),
+            Type::ErrorContext => serializer.serialize_str("error-context"),
             Type::Id(type_id) => serializer.serialize_u64(type_id.index() as u64),
         }
     }
@@ -100,9 +106,9 @@ where
     seq.end()
 }
 
-#[derive(Debug, Clone, PartialEq, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
 struct Param {
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub name: String,
     #[serde(rename = "type")]
     pub typ: Type,
@@ -122,3 +128,301 @@ where
     let version: String = String::deserialize(deserializer)?;
     version.parse().map_err(|e| D::Error::custom(e))
 }
+
+pub fn serialize_optional_version<S>(
+    version: &Option<Version>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    version
+        .as_ref()
+        .map(|v| v.to_string())
+        .serialize(serializer)
+}
+
+pub fn deserialize_optional_version<'de, D>(deserializer: D) -> Result<Option<Version>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    match <Option<String>>::deserialize(deserializer)? {
+        Some(version) => Ok(Some(version.parse().map_err(|e| D::Error::custom(e))?)),
+        None => Ok(None),
+    }
+}
+
+/// Types which are allocated within the arenas of a [`Resolve`].
+pub trait ArenaItem: Sized {
+    const ARENA: usize;
+    const NAME: &'static str;
+}
+
+impl ArenaItem for World {
+    const ARENA: usize = 0;
+    const NAME: &'static str = "world";
+}
+
+impl ArenaItem for Interface {
+    const ARENA: usize = 1;
+    const NAME: &'static str = "interface";
+}
+
+impl ArenaItem for TypeDef {
+    const ARENA: usize = 2;
+    const NAME: &'static str = "type";
+}
+
+impl ArenaItem for Package {
+    const ARENA: usize = 3;
+    const NAME: &'static str = "package";
+}
+
+/// The arenas of the `Resolve` currently being deserialized.
+///
+/// Ids are serialized as plain indices, but an `Id` is additionally tagged
+/// with the arena it belongs to. This records the tag of each arena in the
+/// `Resolve` under construction so ids can be recreated, along with the number
+/// of items each arena must contain for all deserialized ids to be in bounds.
+struct ArenaIds {
+    ids: [u32; 4],
+    required_len: [usize; 4],
+}
+
+thread_local! {
+    static ARENA_IDS: RefCell<Option<ArenaIds>> = const { RefCell::new(None) };
+}
+
+fn arena_id<T>(arena: &Arena<T>) -> u32 {
+    DefaultArenaBehavior::<T>::arena_id(arena.next_id())
+}
+
+fn new_id<T: ArenaItem, E: Error>(index: u64) -> Result<Id<T>, E> {
+    ARENA_IDS.with(|ids| {
+        let mut ids = ids.borrow_mut();
+        let ids = ids.as_mut().ok_or_else(|| {
+            E::custom(format!(
+                "{} ids can only be deserialized as part of a `Resolve`",
+                T::NAME
+            ))
+        })?;
+        let index = usize::try_from(index).map_err(E::custom)?;
+        let required = index
+            .checked_add(1)
+            .ok_or_else(|| E::custom(format!("{} id {index} is too large", T::NAME)))?;
+        let len = &mut ids.required_len[T::ARENA];
+        *len = (*len).max(required);
+        Ok(DefaultArenaBehavior::<T>::new_id(ids.ids[T::ARENA], index))
+    })
+}
+
+pub fn deserialize_id<'de, T, D>(deserializer: D) -> Result<Id<T>, D::Error>
+where
+    T: ArenaItem,
+    D: Deserializer<'de>,
+{
+    new_id(u64::deserialize(deserializer)?)
+}
+
+pub fn deserialize_optional_id<'de, T, D>(deserializer: D) -> Result<Option<Id<T>>, D::Error>
+where
+    T: ArenaItem,
+    D: Deserializer<'de>,
+{
+    match Option::<u64>::deserialize(deserializer)? {
+        Some(index) => Ok(Some(new_id(index)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn deserialize_id_map<'de, T, D>(deserializer: D) -> Result<IndexMap<String, Id<T>>, D::Error>
+where
+    T: ArenaItem,
+    D: Deserializer<'de>,
+{
+    IndexMap::<String, u64>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, index)| Ok((key, new_id(index)?)))
+        .collect()
+}
+
+impl<'de> Deserialize<'de> for Type {
+    fn deserialize<D>(deserializer: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TypeVisitor;
+
+        impl<'de> Visitor<'de> for TypeVisitor {
+            type Value = Type;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a primitive type name or a type index")
+            }
+
+            fn visit_str<E: Error>(self, name: &str) -> Result<Type, E> {
+                Ok(match name {
+                    "bool" => Type::Bool,
+                    "u8" => Type::U8,
+                    "u16" => Type::U16,
+                    "u32" => Type::U32,
+                    "u64" => Type::U64,
+                    "s8" => Type::S8,
+                    "s16" => Type::S16,
+                    "s32" => Type::S32,
+                    "s64" => Type::S64,
+                    "f32" => Type::F32,
+                    "f64" => Type::F64,
+                    "char" => Type::Char,
+                    "string" => Type::String,
+                    "error-context" => Type::ErrorContext,
+                    _ => return Err(E::custom(format!("unknown primitive type `{name}`"))),
+                })
+            }
+
+            fn visit_u64<E: Error>(self, index: u64) -> Result<Type, E> {
+                Ok(Type::Id(new_id(index)?))
+            }
+        }
+
+        deserializer.deserialize_any(TypeVisitor)
+    }
+}
+
+pub fn deserialize_params<'de, D>(deserializer: D) -> Result<Params, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<Param>::deserialize(deserializer)?
+        .into_iter()
+        .map(|p| (p.name, p.typ))
+        .collect())
+}
+
+impl<'de> Deserialize<'de> for Results {
+    fn deserialize<D>(deserializer: D) -> Result<Results, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // A single result without a name is how anonymous results are
+        // serialized, see `serialize_anon_result`.
+        let mut params = deserialize_params(deserializer)?;
+        if params.len() == 1 && params[0].0.is_empty() {
+            return Ok(Results::Anon(params.pop().unwrap().1));
+        }
+        Ok(Results::Named(params))
+    }
+}
+
+impl<'de> Deserialize<'de> for WorldKey {
+    fn deserialize<D>(deserializer: D) -> Result<WorldKey, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Mirrors the `From<WorldKey> for String` implementation used for
+        // serialization.
+        let key = String::deserialize(deserializer)?;
+        if let Some(index) = key.strip_prefix("interface-") {
+            if let Ok(index) = index.parse::<u64>() {
+                return Ok(WorldKey::Interface(new_id::<Interface, _>(index)?));
+            }
+        }
+        Ok(WorldKey::Name(key))
+    }
+}
+
+/// The serialized form of a [`Resolve`].
+#[derive(serde_derive::Deserialize)]
+struct ResolveRepr {
+    worlds: Vec<World>,
+    interfaces: Vec<Interface>,
+    types: Vec<TypeDef>,
+    packages: Vec<Package>,
+}
+
+impl<'de> Deserialize<'de> for Resolve {
+    fn deserialize<D>(deserializer: D) -> Result<Resolve, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut resolve = Resolve::default();
+        let ids = ArenaIds {
+            ids: [
+                arena_id(&resolve.worlds),
+                arena_id(&resolve.interfaces),
+                arena_id(&resolve.types),
+                arena_id(&resolve.packages),
+            ],
+            required_len: [0; 4],
+        };
+
+        // Deserialize with the ids of `resolve`'s arenas in scope, taking care
+        // to restore any outer state for nested deserializations.
+        let prev = ARENA_IDS.with(|s| s.borrow_mut().replace(ids));
+        let repr = ResolveRepr::deserialize(deserializer);
+        let ids = ARENA_IDS.with(|s| std::mem::replace(&mut *s.borrow_mut(), prev));
+        let repr = repr?;
+        let required_len = ids.unwrap().required_len;
+
+        let lens = [
+            repr.worlds.len(),
+            repr.interfaces.len(),
+            repr.types.len(),
+            repr.packages.len(),
+        ];
+        let names = [World::NAME, Interface::NAME, TypeDef::NAME, Package::NAME];
+        for ((len, required), name) in lens.iter().zip(required_len).zip(names) {
+            if required > *len {
+                return Err(D::Error::custom(format!(
+                    "{name} index {} is out of bounds, only {len} {name}s are defined",
+                    required - 1
+                )));
+            }
+        }
+
+        for world in repr.worlds {
+            resolve.worlds.alloc(world);
+        }
+        for interface in repr.interfaces {
+            resolve.interfaces.alloc(interface);
+        }
+        for ty in repr.types {
+            resolve.types.alloc(ty);
+        }
+        for package in repr.packages {
+            let name = package.name.clone();
+            let id = resolve.packages.alloc(package);
+            if resolve.package_names.insert(name.clone(), id).is_some() {
+                return Err(D::Error::custom(format!("duplicate package `{name}`")));
+            }
+        }
+        resolve
+            .validate()
+            .map_err(|e| D::Error::custom(format!("invalid WIT: {e:#}")))?;
+        Ok(resolve)
+    }
+}
+
+impl TryFrom<String> for PackageName {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> anyhow::Result<PackageName> {
+        let (namespace, rest) = name
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("package name `{name}` is missing a namespace"))?;
+        let (name, version) = match rest.split_once('@') {
+            Some((name, version)) => {
+                let version = version
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("invalid version in `{name}`: {e}"))?;
+                (name, Some(version))
+            }
+            None => (rest, None),
+        };
+        Ok(PackageName {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            version,
+        })
+    }
+}
@@ -0,0 +1,77 @@
+//! Tests for the canonical ABI instruction generator in `wit_parser::abi`.
+//!
+//! Each `tests/abi/*.wit` file is parsed and the instructions for every
+//! function are rendered into a `*.wit.abi` file next to it, which can be
+//! updated with:
+//!
+//!     BLESS=1 cargo test --test abi
+
+use anyhow::{bail, Context, Result};
+use pretty_assertions::StrComparison;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use wit_parser::abi::{AbiVariant, Generator, StringEncoding};
+use wit_parser::Resolve;
+
+#[test]
+fn abi() -> Result<()> {
+    for entry in fs::read_dir("tests/abi")? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("wit") {
+            continue;
+        }
+        run(&path).with_context(|| format!("test {path:?} failed"))?;
+    }
+    Ok(())
+}
+
+fn run(test: &Path) -> Result<()> {
+    let mut resolve = Resolve::new();
+    resolve.push_file(test)?;
+
+    let mut output = String::new();
+    for (_, iface) in resolve.interfaces.iter() {
+        for (name, func) in iface.functions.iter() {
+            let variants = [
+                ("import", AbiVariant::GuestImport),
+                ("export", AbiVariant::GuestExport),
+            ];
+            for (label, variant) in variants {
+                let mut generator = Generator::new(&resolve, variant)
+                    .string_encoding(StringEncoding::UTF8)
+                    .realloc("cabi_realloc");
+                writeln!(output, ";; {label} {name}")?;
+                for instr in generator.call(func) {
+                    writeln!(output, "{instr}")?;
+                }
+                if variant == AbiVariant::GuestExport {
+                    writeln!(output, ";; post-return {name}")?;
+                    for instr in generator.post_return(func) {
+                        writeln!(output, "{instr}")?;
+                    }
+                }
+                writeln!(output)?;
+            }
+        }
+    }
+
+    let expected_file = test.with_extension("wit.abi");
+    if env::var_os("BLESS").is_some() {
+        fs::write(&expected_file, &output)?;
+        return Ok(());
+    }
+    let expected = fs::read_to_string(&expected_file).context(format!(
+        "failed to read test expectation file {:?}\nthis can be fixed with BLESS=1",
+        expected_file
+    ))?;
+    let expected = expected.replace("\r\n", "\n");
+    if expected != output {
+        bail!(
+            "failed test: result is not as expected:{}",
+            StrComparison::new(&expected, &output),
+        );
+    }
+    Ok(())
+}
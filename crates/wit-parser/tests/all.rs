@@ -96,6 +96,10 @@ impl Runner {
             let json_result = serde_json::to_string_pretty(&resolve)?;
             // "foo.wit" => "foo.wit.json"
             self.read_or_write_to_file(test, &json_result, "json")?;
+
+            // Deserializing the JSON should produce the same `Resolve` again.
+            let roundtrip: Resolve = serde_json::from_str(&json_result)?;
+            assert_eq!(json_result, serde_json::to_string_pretty(&roundtrip)?);
             return Ok(());
         };
 
@@ -0,0 +1,74 @@
+//! Shared support for translating WebAssembly code offsets to source
+//! locations using DWARF debugging information.
+
+use ::addr2line::Context;
+use anyhow::{Context as _, Result};
+use gimli::{EndianSlice, LittleEndian};
+use std::collections::HashMap;
+use std::ops::Range;
+use wasmparser::{Encoding, Parser, Payload};
+
+/// A core wasm module found within a binary along with the custom sections
+/// needed to load its DWARF debugging information.
+pub struct Module<'a> {
+    /// The range of the binary this module occupies.
+    pub range: Range<u64>,
+    /// The offset of the contents of this module's code section, if any.
+    ///
+    /// DWARF addresses are relative to this offset.
+    pub code_start: Option<u64>,
+    custom_sections: HashMap<&'a str, &'a [u8]>,
+}
+
+impl<'a> Module<'a> {
+    /// Parses `wasm`, which is either a core module or a component, and
+    /// returns all core modules found within it.
+    pub fn parse_all(wasm: &'a [u8]) -> Result<Vec<Module<'a>>> {
+        let mut ret = Vec::new();
+        let mut cur_module = None;
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload? {
+                Payload::Version {
+                    encoding: Encoding::Module,
+                    range,
+                    ..
+                } => {
+                    assert!(cur_module.is_none());
+                    cur_module = Some(Module {
+                        range: range.start as u64..0,
+                        code_start: None,
+                        custom_sections: HashMap::new(),
+                    });
+                }
+
+                Payload::CustomSection(s) => {
+                    if let Some(cur) = &mut cur_module {
+                        cur.custom_sections.insert(s.name(), s.data());
+                    }
+                }
+                Payload::CodeSectionStart { range, .. } => {
+                    assert!(cur_module.is_some());
+                    cur_module.as_mut().unwrap().code_start = Some(range.start as u64);
+                }
+
+                Payload::End(offset) => {
+                    if let Some(mut module) = cur_module.take() {
+                        module.range.end = offset as u64;
+                        ret.push(module);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Creates an `addr2line` context from the DWARF sections of this module.
+    pub fn context(&self) -> Result<Context<EndianSlice<'a, LittleEndian>>> {
+        let dwarf = gimli::Dwarf::load(|id| -> Result<_> {
+            let data = self.custom_sections.get(id.name()).copied().unwrap_or(&[]);
+            Ok(EndianSlice::new(data, LittleEndian))
+        })?;
+        Context::from_dwarf(dwarf).context("failed to create addr2line dwarf mapping context")
+    }
+}
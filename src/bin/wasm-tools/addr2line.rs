@@ -1,11 +1,8 @@
-use addr2line::{Context, LookupResult};
+use addr2line::LookupResult;
 use anyhow::{anyhow, bail, Context as _, Result};
-use gimli::EndianSlice;
-use std::collections::HashMap;
 use std::io::Write;
-use std::ops::Range;
 use std::u64;
-use wasmparser::{Encoding, Parser, Payload};
+use wasm_tools::addr2line::Module;
 
 /// Translate a WebAssembly address to a filename and line number using DWARF
 /// debugging information.
@@ -41,12 +38,6 @@ pub struct Opts {
     code_section_relative: bool,
 }
 
-struct Module<'a> {
-    range: Range<u64>,
-    code_start: Option<u64>,
-    custom_sections: HashMap<&'a str, &'a [u8]>,
-}
-
 impl Opts {
     pub fn general_opts(&self) -> &wasm_tools::GeneralOpts {
         self.io.general_opts()
@@ -55,9 +46,8 @@ impl Opts {
     pub fn run(&self) -> Result<()> {
         let wasm = self.io.parse_input_wasm()?;
 
-        let modules = self
-            .parse_custom_sections(&wasm)
-            .context("failed to parse input and read custom sections")?;
+        let modules =
+            Module::parse_all(&wasm).context("failed to parse input and read custom sections")?;
         let mut output = self.io.output_writer()?;
 
         for addr in self.addresses.iter() {
@@ -68,46 +58,6 @@ impl Opts {
         Ok(())
     }
 
-    fn parse_custom_sections<'a>(&self, wasm: &'a [u8]) -> Result<Vec<Module<'a>>> {
-        let mut ret = Vec::new();
-        let mut cur_module = None;
-        for payload in Parser::new(0).parse_all(wasm) {
-            match payload? {
-                Payload::Version {
-                    encoding: Encoding::Module,
-                    range,
-                    ..
-                } => {
-                    assert!(cur_module.is_none());
-                    cur_module = Some(Module {
-                        range: range.start as u64..0,
-                        code_start: None,
-                        custom_sections: HashMap::new(),
-                    });
-                }
-
-                Payload::CustomSection(s) => {
-                    if let Some(cur) = &mut cur_module {
-                        cur.custom_sections.insert(s.name(), s.data());
-                    }
-                }
-                Payload::CodeSectionStart { range, .. } => {
-                    assert!(cur_module.is_some());
-                    cur_module.as_mut().unwrap().code_start = Some(range.start as u64);
-                }
-
-                Payload::End(offset) => {
-                    if let Some(mut module) = cur_module.take() {
-                        module.range.end = offset as u64;
-                        ret.push(module);
-                    }
-                }
-                _ => {}
-            }
-        }
-        Ok(ret)
-    }
-
     fn addr2line(&self, addr: &str, modules: &[Module<'_>], out: &mut dyn Write) -> Result<()> {
         // Support either `0x` or `@` prefixes for hex addresses since 0x is
         // standard and @ is used by wasmprinter (and web browsers I think?)
@@ -122,16 +72,7 @@ impl Opts {
             .find(|module| module.range.start <= addr && addr <= module.range.end)
             .ok_or_else(|| anyhow!("no module found which contains this address"))?;
 
-        let dwarf = gimli::Dwarf::load(|id| -> Result<_> {
-            let data = module
-                .custom_sections
-                .get(id.name())
-                .copied()
-                .unwrap_or(&[]);
-            Ok(EndianSlice::new(data, gimli::LittleEndian))
-        })?;
-        let cx = Context::from_dwarf(dwarf)
-            .context("failed to create addr2line dwarf mapping context")?;
+        let cx = module.context()?;
 
         // Addresses in DWARF are relative to the start of the text section, so
         // factor that in here.
@@ -11,9 +11,12 @@ use wasm_tools::Output;
 use wasmparser::WasmFeatures;
 use wat::Detect;
 use wit_component::{
-    embed_component_metadata, ComponentEncoder, DecodedWasm, Linker, StringEncoding, WitPrinter,
+    embed_component_metadata, ComponentEncoder, DecodedWasm, DocsFormat, DocsGenerator, Linker,
+    RequiredBy, StringEncoding, WitPrinter,
+};
+use wit_parser::{
+    DepsManifest, LockFile, PackageId, Resolve, Stability, WorldItem, LOCK_FILE_NAME,
 };
-use wit_parser::{PackageId, Resolve};
 
 /// WebAssembly wit-based component tooling.
 #[derive(Parser)]
@@ -24,6 +27,10 @@ pub enum Opts {
     Targets(TargetsOpts),
     Link(LinkOpts),
     SemverCheck(SemverCheckOpts),
+    Unbundle(UnbundleOpts),
+    Bundle(BundleOpts),
+    Stub(StubOpts),
+    Dummy(DummyOpts),
 }
 
 impl Opts {
@@ -35,6 +42,10 @@ impl Opts {
             Opts::Targets(targets) => targets.run(),
             Opts::Link(link) => link.run(),
             Opts::SemverCheck(s) => s.run(),
+            Opts::Unbundle(u) => u.run(),
+            Opts::Bundle(b) => b.run(),
+            Opts::Stub(s) => s.run(),
+            Opts::Dummy(d) => d.run(),
         }
     }
 
@@ -46,6 +57,10 @@ impl Opts {
             Opts::Targets(targets) => targets.general_opts(),
             Opts::Link(link) => link.general_opts(),
             Opts::SemverCheck(s) => s.general_opts(),
+            Opts::Unbundle(u) => u.general_opts(),
+            Opts::Bundle(b) => b.general_opts(),
+            Opts::Stub(s) => s.general_opts(),
+            Opts::Dummy(d) => d.general_opts(),
         }
     }
 }
@@ -136,6 +151,53 @@ pub struct NewOpts {
     /// Use memory.grow to realloc memory and stack allocation.
     #[clap(long)]
     realloc_via_memory_grow: bool,
+
+    /// The WIT source that the module's `component-type` metadata was
+    /// generated from, such as the path passed to `component embed`.
+    ///
+    /// This is only used to point errors about the module at where the items
+    /// involved are defined in WIT.
+    #[clap(long, value_name = "PATH", conflicts_with = "infer_world")]
+    wit: Option<PathBuf>,
+
+    /// Infer the WIT world of an input module which has no `component-type`
+    /// metadata embedded within it.
+    ///
+    /// The module's imports and exports must follow the naming conventions of
+    /// the canonical ABI. Interfaces are looked up in the packages loaded with
+    /// `--deps`, and functions imported from `$root` or exported with plain
+    /// names are inferred from their core signatures, which may only use
+    /// scalar types.
+    #[clap(long)]
+    infer_world: bool,
+
+    /// A directory of WIT packages, such as a `wit/deps` directory, to match
+    /// the interfaces imported and exported by the module against when using
+    /// `--infer-world`.
+    ///
+    /// This option may be specified multiple times.
+    #[clap(long, value_name = "DIR", requires = "infer_world")]
+    deps: Vec<PathBuf>,
+
+    /// Write the WIT inferred with `--infer-world` to this file for review.
+    #[clap(long, value_name = "FILE", requires = "infer_world")]
+    emit_wit: Option<PathBuf>,
+
+    /// Explain to stderr why each adapter retains the imports it does.
+    ///
+    /// Adapters are reduced to the exports the component requires of them
+    /// and the imports those exports call. This lists the exports kept for
+    /// each adapter and, for each import retained, the chain of calls that
+    /// reaches it from each kept export. The report is printed as text unless
+    /// `--explain-imports=json` is given.
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text"
+    )]
+    explain_imports: Option<ReportFormat>,
 }
 
 impl NewOpts {
@@ -145,10 +207,34 @@ impl NewOpts {
 
     /// Executes the application.
     fn run(self) -> Result<()> {
-        let wasm = self.io.parse_input_wasm()?;
-        let mut encoder = ComponentEncoder::default()
-            .validate(!self.skip_validation)
-            .module(&wasm)?;
+        let mut wasm = self.io.parse_input_wasm()?;
+        let mut wit_source = None;
+        if let Some(path) = &self.wit {
+            let mut resolve = Resolve::default();
+            resolve.push_path(path)?;
+            wit_source = Some(resolve);
+        }
+        if self.infer_world {
+            let mut resolve = Resolve::default();
+            for dir in self.deps.iter() {
+                resolve.push_deps_dir(dir)?;
+            }
+            let world = wit_component::infer_world(&mut resolve, &wasm)
+                .context("failed to infer a world for the module")?;
+            if let Some(path) = &self.emit_wit {
+                let pkg = resolve.worlds[world].package.unwrap();
+                let wit = WitPrinter::default().print(&resolve, &[pkg])?;
+                std::fs::write(path, wit)
+                    .with_context(|| format!("failed to write `{}`", path.display()))?;
+            }
+            embed_component_metadata(&mut wasm, &resolve, world, StringEncoding::UTF8)?;
+            wit_source = Some(resolve);
+        }
+        let mut encoder = ComponentEncoder::default().validate(!self.skip_validation);
+        if let Some(resolve) = &wit_source {
+            encoder = encoder.wit_source(resolve)?;
+        }
+        let mut encoder = encoder.module(&wasm)?;
 
         for (name, wasm) in self.adapters.iter() {
             encoder = encoder.adapter(name, wasm)?;
@@ -156,6 +242,19 @@ impl NewOpts {
 
         encoder = encoder.realloc_via_memory_grow(self.realloc_via_memory_grow);
 
+        match self.explain_imports {
+            Some(ReportFormat::Text) => {
+                for report in encoder.explain_adapter_imports()? {
+                    eprint!("{}", explain_imports_text(&report));
+                }
+            }
+            Some(ReportFormat::Json) => {
+                let reports = encoder.explain_adapter_imports()?;
+                eprintln!("{}", serde_json::to_string_pretty(&reports)?);
+            }
+            None => {}
+        }
+
         let bytes = encoder
             .import_name_map(self.import_names.into_iter().collect())
             .encode()
@@ -172,13 +271,15 @@ struct WitResolve {
     /// Path to WIT files to load.
     ///
     /// This can be a directory containing `*.wit` files, a `*.wit` file itself,
-    /// or a `*.wasm` file which is a WIT package encoded as WebAssembly.
+    /// a `*.wasm` file which is a WIT package encoded as WebAssembly, or a
+    /// `*.json` file as produced by `component wit --json`.
     wit: PathBuf,
 
     /// Features to enable when parsing the `wit` option.
     ///
     /// This flag enables the `@unstable` feature in WIT documents where the
-    /// items are otherwise hidden by default.
+    /// items are otherwise hidden by default. This applies to binary WIT
+    /// packages as well, which may contain items gated by any feature.
     #[clap(long)]
     features: Vec<String>,
 
@@ -194,14 +295,21 @@ impl WitResolve {
     fn resolve_with_features(features: &[String], all_features: bool) -> Resolve {
         let mut resolve = Resolve::default();
         resolve.all_features = all_features;
+        resolve.features = Self::parse_features(features).into_iter().collect();
+        return resolve;
+    }
+
+    /// Splits each of `features` on whitespace and commas.
+    fn parse_features(features: &[String]) -> Vec<String> {
+        let mut ret = Vec::new();
         for feature in features {
             for f in feature.split_whitespace() {
                 for f in f.split(',').filter(|s| !s.is_empty()) {
-                    resolve.features.insert(f.to_string());
+                    ret.push(f.to_string());
                 }
             }
         }
-        return resolve;
+        ret
     }
 
     fn load(&self) -> Result<(Resolve, Vec<PackageId>)> {
@@ -242,6 +350,31 @@ pub struct EmbedOpts {
     #[clap(long, value_name = "ENCODING")]
     encoding: Option<StringEncoding>,
 
+    /// Use a different string encoding for one import of the world.
+    ///
+    /// The `NAME` is an imported interface or function, such as
+    /// `wasi:cli/environment@0.2.0`, or a function within an imported
+    /// interface, such as `wasi:cli/environment@0.2.0#get-arguments`. This
+    /// overrides `--encoding` for that item and may be specified multiple
+    /// times.
+    #[clap(
+        long = "import-encoding",
+        value_name = "NAME=ENCODING",
+        value_parser = parse_item_encoding
+    )]
+    import_encodings: Vec<(String, StringEncoding)>,
+
+    /// Use a different string encoding for one export of the world.
+    ///
+    /// This is the same as `--import-encoding` except that `NAME` refers to an
+    /// export of the world.
+    #[clap(
+        long = "export-encoding",
+        value_name = "NAME=ENCODING",
+        value_parser = parse_item_encoding
+    )]
+    export_encodings: Vec<(String, StringEncoding)>,
+
     /// The world that the component uses.
     ///
     /// This is the path, within the `WIT` source provided as a positional
@@ -271,6 +404,13 @@ pub struct EmbedOpts {
     wat: bool,
 }
 
+fn parse_item_encoding(s: &str) -> Result<(String, StringEncoding)> {
+    match s.split_once('=') {
+        Some((name, encoding)) => Ok((name.to_string(), encoding.parse()?)),
+        None => bail!("expected `NAME=ENCODING` but found `{s}`"),
+    }
+}
+
 impl EmbedOpts {
     fn general_opts(&self) -> &wasm_tools::GeneralOpts {
         self.io.general_opts()
@@ -287,12 +427,12 @@ impl EmbedOpts {
         let world = resolve.select_world(&pkg_ids, self.world.as_deref())?;
         let mut wasm = wasm.unwrap_or_else(|| wit_component::dummy_module(&resolve, world));
 
-        embed_component_metadata(
-            &mut wasm,
-            &resolve,
-            world,
-            self.encoding.unwrap_or(StringEncoding::UTF8),
-        )?;
+        let encodings = wit_component::metadata::StringEncodings {
+            default: self.encoding.unwrap_or(StringEncoding::UTF8),
+            imports: self.import_encodings.into_iter().collect(),
+            exports: self.export_encodings.into_iter().collect(),
+        };
+        embed_component_metadata(&mut wasm, &resolve, world, encodings)?;
 
         self.io.output_wasm(&wasm, self.wat)?;
 
@@ -387,6 +527,24 @@ pub struct LinkOpts {
     /// Use built-in implementations of `dlopen`/`dlsym`
     #[clap(long)]
     use_built_in_libdl: bool,
+
+    /// Directory to search for libraries needed by the input libraries but
+    /// not passed explicitly (may be repeated)
+    ///
+    /// Each name listed in the `needed` entries of a library's `dylink.0`
+    /// section is looked up as a file of that name in each directory, in the
+    /// order given, and the first match is linked.
+    #[clap(
+        long = "library-path",
+        short = 'L',
+        visible_alias = "rpath",
+        value_name = "DIR"
+    )]
+    library_paths: Vec<PathBuf>,
+
+    /// Print the libraries to be linked, in load order, to stderr
+    #[clap(long)]
+    print_load_order: bool,
 }
 
 impl LinkOpts {
@@ -417,6 +575,23 @@ impl LinkOpts {
             linker = linker.adapter(name, wasm)?;
         }
 
+        for path in &self.library_paths {
+            linker = linker.library_path(path);
+        }
+
+        if self.print_load_order {
+            for library in linker.load_order()? {
+                let mut line = library.name;
+                if let Some(path) = &library.path {
+                    line.push_str(&format!(" ({})", path.display()));
+                }
+                if !library.needed_by.is_empty() {
+                    line.push_str(&format!(" needed by {}", library.needed_by.join(", ")));
+                }
+                eprintln!("{line}");
+            }
+        }
+
         let bytes = linker
             .encode()
             .context("failed to encode a component from modules")?;
@@ -444,8 +619,9 @@ pub struct WitOpts {
     /// The file specified can be a `*.wit` file parsed as a single-document
     /// package. It can be a directory to be parsed as a WIT package. It can be
     /// a `*.wat` or `*.wasm` file for either the binary representation of a WIT
-    /// package or a component itself to extract the interface from. The type of
-    /// input is inferred from the contents of the path specified.
+    /// package or a component itself to extract the interface from. It can be a
+    /// `*.json` file as produced by the `--json` flag. The type of input is
+    /// inferred from the contents of the path specified.
     ///
     /// If not provided or if this is `-` then stdin is read entirely and
     /// processed.
@@ -495,19 +671,223 @@ pub struct WitOpts {
     )]
     json: bool,
 
-    /// Features to enable when parsing the `wit` option.
+    /// Generate reference documentation into the directory specified instead
+    /// of emitting WIT.
+    ///
+    /// One page is written per package, interface, and world known from the
+    /// input, including dependencies, along with an `index` page listing all
+    /// packages.
+    #[clap(
+        long,
+        value_name = "DIR",
+        conflicts_with = "wasm",
+        conflicts_with = "wat",
+        conflicts_with = "json",
+        conflicts_with = "out_dir",
+        conflicts_with = "output"
+    )]
+    docs: Option<PathBuf>,
+
+    /// The format of the documentation generated with `--docs`.
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        default_value = "markdown",
+        requires = "docs"
+    )]
+    docs_format: DocsFormatArg,
+
+    /// Copy the dependencies selected by the `deps.toml` manifest of the input
+    /// directory from the package cache into its `deps` directory.
+    ///
+    /// Packages already in the `deps` directory are kept and versions pinned
+    /// by the `wit.lock` lockfile are preferred. The lockfile is then updated
+    /// with the versions in `deps`. After vendoring the package no longer
+    /// requires the package cache to be parsed.
+    #[clap(
+        long,
+        conflicts_with = "wasm",
+        conflicts_with = "wat",
+        conflicts_with = "json",
+        conflicts_with = "out_dir",
+        conflicts_with = "output",
+        conflicts_with = "docs"
+    )]
+    vendor: bool,
+
+    /// Features to enable when parsing the input.
     ///
     /// This flag enables the `@unstable` feature in WIT documents where the
     /// items are otherwise hidden by default.
+    ///
+    /// When emitting a binary WIT package with `--wasm` or `--wat` all
+    /// `@unstable` items are kept, along with their gates, unless features are
+    /// selected with this flag. Consumers of the package can then select the
+    /// features they need. Likewise, an input binary WIT package or component
+    /// is emitted with all of its items unless this flag selects features.
     #[clap(long)]
     features: Vec<String>,
 
-    /// Enable all features when parsing the `wit` option.
+    /// Enable all features when parsing the input.
     ///
     /// This flag enables all `@unstable` features in WIT documents where the
     /// items are otherwise hidden by default.
     #[clap(long)]
     all_features: bool,
+
+    /// The world to modify with `--union`, `--intersect`, `--subtract`, and
+    /// `--importize` before emitting the input.
+    ///
+    /// Worlds are specified as either a kebab-name within the input package or
+    /// an ID such as `wasi:http/proxy`. Operations are applied in the order
+    /// union, intersect, subtract, and then importize.
+    #[clap(long, value_name = "WORLD")]
+    world: Option<String>,
+
+    /// Add all imports and exports of this world to `--world`.
+    #[clap(long, value_name = "WORLD", requires = "world")]
+    union: Vec<String>,
+
+    /// Keep only the imports and exports of `--world` which this world also
+    /// has, such as the imports supported by several hosts.
+    #[clap(long, value_name = "WORLD", requires = "world")]
+    intersect: Vec<String>,
+
+    /// Remove the imports and exports of `--world` which this world also has,
+    /// such as those a platform provides to a component.
+    #[clap(long, value_name = "WORLD", requires = "world")]
+    subtract: Vec<String>,
+
+    /// Turn all exports of `--world` into imports.
+    #[clap(long, requires = "world")]
+    importize: bool,
+
+    /// Print the canonical ABI memory layout of the types and functions used
+    /// by a world instead of emitting WIT.
+    ///
+    /// The size, alignment, field offsets, padding and discriminants of each
+    /// record, variant, tuple, flags and enum type are printed for both 32-bit
+    /// and 64-bit memories, along with the flattened core wasm signature of
+    /// each function. The world reported on is `--world` if specified, and
+    /// otherwise every world in the input.
+    #[clap(
+        long,
+        conflicts_with = "wasm",
+        conflicts_with = "wat",
+        conflicts_with = "json",
+        conflicts_with = "out_dir",
+        conflicts_with = "docs"
+    )]
+    layout: bool,
+}
+
+/// Prints a warning for each item tagged with `@deprecated` that is used by a
+/// world in `decoded`.
+fn warn_deprecated(decoded: &DecodedWasm) {
+    let resolve = decoded.resolve();
+    let worlds = match decoded {
+        DecodedWasm::WitPackages(_, pkgs) => pkgs
+            .iter()
+            .flat_map(|pkg| resolve.packages[*pkg].worlds.values().copied())
+            .collect::<Vec<_>>(),
+        DecodedWasm::Component(_, world) => vec![*world],
+    };
+    for world in worlds {
+        let w = &resolve.worlds[world];
+        let world_name = resolve.id_of_name(w.package.unwrap(), &w.name);
+        let warn = |kind: &str, name: &str, stability: &Stability| {
+            if let Some(version) = stability.deprecated() {
+                eprintln!(
+                    "warning: world `{world_name}` uses {kind} `{name}` which was deprecated \
+                     in version {version}"
+                );
+            }
+        };
+        for (key, item) in w.imports.iter().chain(w.exports.iter()) {
+            let name = resolve.name_world_key(key);
+            match item {
+                WorldItem::Interface { id, stability } => {
+                    let iface = &resolve.interfaces[*id];
+                    warn("interface", &name, stability);
+                    if !iface.stability.is_unknown() && iface.stability != *stability {
+                        warn("interface", &name, &iface.stability);
+                    }
+                    for (ty, id) in iface.types.iter() {
+                        warn(
+                            "type",
+                            &format!("{name}#{ty}"),
+                            &resolve.types[*id].stability,
+                        );
+                    }
+                    for (func, f) in iface.functions.iter() {
+                        warn("function", &format!("{name}#{func}"), &f.stability);
+                    }
+                }
+                WorldItem::Function(f) => warn("function", &name, &f.stability),
+                WorldItem::Type(id) => warn("type", &name, &resolve.types[*id].stability),
+            }
+        }
+    }
+}
+
+/// Copies the file or directory at `src` to `dst`, replacing anything
+/// already at `dst`.
+fn copy_path(src: &Path, dst: &Path) -> Result<()> {
+    if dst.is_dir() {
+        std::fs::remove_dir_all(dst).with_context(|| format!("failed to remove {dst:?}"))?;
+    }
+    if !src.is_dir() {
+        std::fs::copy(src, dst).with_context(|| format!("failed to copy {src:?} to {dst:?}"))?;
+        return Ok(());
+    }
+    std::fs::create_dir_all(dst).with_context(|| format!("failed to create directory: {dst:?}"))?;
+    for entry in src
+        .read_dir()
+        .with_context(|| format!("failed to read directory {src:?}"))?
+    {
+        let entry = entry?;
+        copy_path(&entry.path(), &dst.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Renders the report of `component new --explain-imports` as text.
+fn explain_imports_text(report: &wit_component::AdapterImports) -> String {
+    let mut out = format!("adapter `{}`:\n  kept exports:\n", report.adapter);
+    for export in report.exports.iter() {
+        let reason = match export.required_by {
+            RequiredBy::MainModule => "imported by the main module",
+            RequiredBy::WorldExport => "implements a world export",
+            RequiredBy::Adapter => "needed by the adapter",
+        };
+        out.push_str(&format!("    {} ({reason})\n", export.name));
+    }
+    if report.imports.is_empty() {
+        out.push_str("  no imports retained\n");
+    }
+    for import in report.imports.iter() {
+        out.push_str(&format!(
+            "  import `{}::{}` retained by:\n",
+            import.module, import.name
+        ));
+        if import.chains.is_empty() {
+            out.push_str("    (no calls from kept exports)\n");
+        }
+        for chain in import.chains.iter() {
+            out.push_str(&format!(
+                "    export `{}`: {}\n",
+                chain.export,
+                chain.calls.join(" -> ")
+            ));
+        }
+    }
+    out
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+enum DocsFormatArg {
+    Markdown,
+    Html,
 }
 
 impl WitOpts {
@@ -517,7 +897,23 @@ impl WitOpts {
 
     /// Executes the application.
     fn run(self) -> Result<()> {
-        let decoded = self.decode_input()?;
+        if self.vendor {
+            return self.vendor_deps();
+        }
+
+        let mut decoded = self.decode_input()?;
+        self.transform_world(&mut decoded)?;
+        warn_deprecated(&decoded);
+
+        if let Some(dir) = &self.docs {
+            self.emit_docs(&decoded, dir)?;
+            return Ok(());
+        }
+
+        if self.layout {
+            self.emit_layout(&decoded)?;
+            return Ok(());
+        }
 
         // Now that the WIT document has been decoded, it's time to emit it.
         // This interprets all of the output options and performs such a task.
@@ -533,16 +929,34 @@ impl WitOpts {
         Ok(())
     }
 
+    /// Returns the `Resolve` to parse WIT source into, with the features
+    /// selected.
+    ///
+    /// Binary WIT packages keep all `@unstable` items unless features are
+    /// explicitly selected.
+    fn source_resolve(&self) -> Resolve {
+        let all_features =
+            self.all_features || ((self.wasm || self.wat) && self.features.is_empty());
+        WitResolve::resolve_with_features(&self.features, all_features)
+    }
+
     fn decode_input(&self) -> Result<DecodedWasm> {
         // If the input is a directory then it's probably raw WIT files, so use
         // `parse_wit_from_path`.
         if let Some(input) = &self.input {
             if input.is_dir() {
-                let mut resolve =
-                    WitResolve::resolve_with_features(&self.features, self.all_features);
+                let mut resolve = self.source_resolve();
                 let (pkg_ids, _) = resolve.push_dir(&input)?;
                 return Ok(DecodedWasm::WitPackages(resolve, pkg_ids));
             }
+
+            // JSON files are the output of `--json`, so load them back in as a
+            // `Resolve`.
+            if input.extension().and_then(|s| s.to_str()) == Some("json") {
+                let mut resolve = self.source_resolve();
+                let pkg_ids = resolve.push_file(&input)?;
+                return Ok(DecodedWasm::WitPackages(resolve, pkg_ids));
+            }
         }
 
         // ... otherwise if the input is not a directory then it's read into
@@ -574,7 +988,15 @@ impl WitOpts {
                     e
                 })?;
                 if wasmparser::Parser::is_component(&input) {
-                    wit_component::decode(&input)
+                    if self.features.is_empty() {
+                        return wit_component::decode(&input);
+                    }
+                    let features = WitResolve::parse_features(&self.features);
+                    wit_component::decode_with_features(
+                        &input,
+                        features.iter().map(|f| f.as_str()),
+                        self.all_features,
+                    )
                 } else {
                     let (_wasm, bindgen) = wit_component::metadata::decode(&input)?;
                     Ok(DecodedWasm::Component(bindgen.resolve, bindgen.world))
@@ -587,8 +1009,7 @@ impl WitOpts {
                     Ok(s) => s,
                     Err(_) => bail!("input was not valid utf-8"),
                 };
-                let mut resolve =
-                    WitResolve::resolve_with_features(&self.features, self.all_features);
+                let mut resolve = self.source_resolve();
                 let ids = resolve.push_str(path, input)?;
                 Ok(DecodedWasm::WitPackages(resolve, ids))
             }
@@ -606,7 +1027,9 @@ impl WitOpts {
         let bytes = wit_component::encode(None, decoded.resolve(), decoded_package)?;
         if !self.skip_validation {
             wasmparser::Validator::new_with_features(
-                WasmFeatures::default() | WasmFeatures::COMPONENT_MODEL,
+                WasmFeatures::default()
+                    | WasmFeatures::COMPONENT_MODEL
+                    | WasmFeatures::COMPONENT_MODEL_ASYNC,
             )
             .validate_all(&bytes)?;
         }
@@ -683,12 +1106,116 @@ impl WitOpts {
         Ok(())
     }
 
+    fn transform_world(&self, decoded: &mut DecodedWasm) -> Result<()> {
+        let world = match &self.world {
+            Some(world) => world,
+            None => return Ok(()),
+        };
+        let (resolve, pkgs) = match decoded {
+            DecodedWasm::WitPackages(resolve, pkgs) => (resolve, pkgs.clone()),
+            DecodedWasm::Component(resolve, world) => {
+                let pkg = resolve.worlds[*world].package.unwrap();
+                (resolve, vec![pkg])
+            }
+        };
+        let target = resolve.select_world(&pkgs, Some(world))?;
+        for other in self.union.iter() {
+            let other = resolve.select_world(&pkgs, Some(other))?;
+            resolve
+                .merge_worlds(other, target)
+                .with_context(|| format!("failed to union world `{world}`"))?;
+        }
+        for other in self.intersect.iter() {
+            let other = resolve.select_world(&pkgs, Some(other))?;
+            resolve.intersect_worlds(other, target);
+        }
+        for other in self.subtract.iter() {
+            let other = resolve.select_world(&pkgs, Some(other))?;
+            resolve.subtract_worlds(other, target);
+        }
+        if self.importize {
+            resolve
+                .importize(target)
+                .with_context(|| format!("failed to importize world `{world}`"))?;
+        }
+        Ok(())
+    }
+
+    fn vendor_deps(&self) -> Result<()> {
+        let dir = match &self.input {
+            Some(input) if input.is_dir() => input,
+            _ => bail!("`--vendor` requires the input to be a WIT package directory"),
+        };
+        let manifest = DepsManifest::from_file(dir.join("deps.toml"))?;
+        let lock = manifest.lock_file()?;
+        let deps = dir.join("deps");
+        let mut packages = DepsManifest::vendored_packages(&deps)?;
+        let selected = manifest.resolve(&packages, lock.as_ref())?;
+        if !selected.is_empty() {
+            std::fs::create_dir_all(&deps)
+                .with_context(|| format!("failed to create directory: {deps:?}"))?;
+        }
+        for pkg in selected {
+            let dst = deps.join(pkg.vendored_name());
+            copy_path(&pkg.path, &dst)?;
+            println!("Vendoring: {} -> {}", pkg.name, dst.display());
+            packages.push(pkg.name);
+        }
+
+        let path = dir.join(LOCK_FILE_NAME);
+        let new_lock = LockFile::new(&packages);
+        if lock.as_ref() != Some(&new_lock) {
+            std::fs::write(&path, new_lock.to_toml())
+                .with_context(|| format!("failed to write file: {path:?}"))?;
+            println!("Writing: {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn emit_docs(&self, decoded: &DecodedWasm, dir: &Path) -> Result<()> {
+        let format = match self.docs_format {
+            DocsFormatArg::Markdown => DocsFormat::Markdown,
+            DocsFormatArg::Html => DocsFormat::Html,
+        };
+        for page in DocsGenerator::new(format).generate(decoded.resolve()) {
+            let path = dir.join(&page.path);
+            let parent = path.parent().unwrap();
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory: {parent:?}"))?;
+            std::fs::write(&path, &page.contents)
+                .with_context(|| format!("failed to write file: {path:?}"))?;
+            println!("Writing: {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn emit_layout(&self, decoded: &DecodedWasm) -> Result<()> {
+        let resolve = decoded.resolve();
+        let worlds = match (decoded, &self.world) {
+            (DecodedWasm::WitPackages(_, pkgs), Some(world)) => {
+                vec![resolve.select_world(pkgs, Some(world))?]
+            }
+            (DecodedWasm::WitPackages(_, pkgs), None) => pkgs
+                .iter()
+                .flat_map(|pkg| resolve.packages[*pkg].worlds.values().copied())
+                .collect(),
+            (DecodedWasm::Component(_, world), _) => vec![*world],
+        };
+        let output = worlds
+            .into_iter()
+            .map(|world| wit_component::print_layout(resolve, world))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.output.output(&self.general, Output::Str(&output))?;
+        Ok(())
+    }
+
     fn emit_json(&self, decoded: &DecodedWasm) -> Result<()> {
         assert!(!self.wasm && !self.wat);
 
         let resolve = decoded.resolve();
         let output = serde_json::to_string_pretty(&resolve)?;
-        self.output.output(&self.general, Output::Json(&output))?;
+        self.output.output(&self.general, Output::Str(&output))?;
 
         Ok(())
     }
@@ -711,6 +1238,15 @@ pub struct TargetsOpts {
 
     #[clap(flatten)]
     input: wasm_tools::InputArg,
+
+    /// Print the ways in which the component fails to conform to the world as
+    /// a JSON array on stdout.
+    ///
+    /// Each entry has the `path` to the offending item within the world and
+    /// the `kind` of the violation, such as `missing-export`, `extra-import`,
+    /// or `mismatch`. An empty array is printed if the component conforms.
+    #[clap(long)]
+    json: bool,
 }
 
 impl TargetsOpts {
@@ -724,6 +1260,17 @@ impl TargetsOpts {
         let world = resolve.select_world(&pkg_ids, self.world.as_deref())?;
         let component_to_test = self.input.parse_wasm()?;
 
+        if self.json {
+            let violations = wit_component::target_violations(&resolve, world, &component_to_test)?;
+            println!("{}", serde_json::to_string_pretty(&violations)?);
+            if !violations.is_empty() {
+                bail!(
+                    "component does not conform to world `{}`",
+                    resolve.worlds[world].name
+                );
+            }
+            return Ok(());
+        }
         wit_component::targets(&resolve, world, &component_to_test)?;
 
         Ok(())
@@ -752,6 +1299,20 @@ pub struct SemverCheckOpts {
     /// evolution of the "prev" world specified.
     #[clap(long)]
     new: String,
+
+    /// Print a report of every change between the two worlds.
+    ///
+    /// Each change is classified as a `major`, `minor`, or `patch` change and
+    /// the report is printed to stdout either as one line per change or as a
+    /// JSON array.
+    #[clap(long, value_name = "FORMAT")]
+    report: Option<ReportFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+enum ReportFormat {
+    Text,
+    Json,
 }
 
 impl SemverCheckOpts {
@@ -763,7 +1324,277 @@ impl SemverCheckOpts {
         let (resolve, pkg_ids) = self.resolve.load()?;
         let prev = resolve.select_world(&pkg_ids, Some(self.prev.as_str()))?;
         let new = resolve.select_world(&pkg_ids, Some(self.new.as_str()))?;
+        if let Some(format) = self.report {
+            let changes = wit_parser::diff_worlds(&resolve, prev, &resolve, new);
+            match format {
+                ReportFormat::Text => {
+                    for change in changes.iter() {
+                        println!("{change}");
+                    }
+                }
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+            }
+        }
         wit_component::semver_check(resolve, prev, new)?;
         Ok(())
     }
 }
+
+/// Split a component into its core modules and nested components.
+///
+/// Each core module and nested component is written to its own file in the
+/// output directory, named after the name recorded in its name section when
+/// present. A `manifest.json` file is written alongside them describing the
+/// remaining sections of the component and how each module is instantiated.
+/// The original component can be rebuilt with `wasm-tools component bundle`.
+#[derive(Parser)]
+pub struct UnbundleOpts {
+    #[clap(flatten)]
+    general: wasm_tools::GeneralOpts,
+
+    #[clap(flatten)]
+    input: wasm_tools::InputArg,
+
+    /// The directory to write the extracted modules and manifest to.
+    #[clap(long, value_name = "DIR")]
+    out_dir: PathBuf,
+}
+
+impl UnbundleOpts {
+    fn general_opts(&self) -> &wasm_tools::GeneralOpts {
+        &self.general
+    }
+
+    /// Executes the application.
+    fn run(self) -> Result<()> {
+        let wasm = self.input.parse_wasm()?;
+        let unbundled = wit_component::unbundle(&wasm)?;
+
+        let dir = &self.out_dir;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create directory: {dir:?}"))?;
+        for item in unbundled.items.iter() {
+            let path = dir.join(&item.file);
+            std::fs::write(&path, &item.bytes)
+                .with_context(|| format!("failed to write file: {path:?}"))?;
+            println!("Writing: {}", path.display());
+        }
+        let path = dir.join("manifest.json");
+        let manifest = serde_json::to_string_pretty(&unbundled.manifest)?;
+        std::fs::write(&path, manifest)
+            .with_context(|| format!("failed to write file: {path:?}"))?;
+        println!("Writing: {}", path.display());
+
+        Ok(())
+    }
+}
+
+/// Rebuild a component from the output of `wasm-tools component unbundle`.
+///
+/// The files referenced by the manifest are read relative to the directory
+/// containing the manifest, so individual modules may be modified before the
+/// component is rebuilt.
+#[derive(Parser)]
+pub struct BundleOpts {
+    #[clap(flatten)]
+    general: wasm_tools::GeneralOpts,
+
+    /// The `manifest.json` file written by `wasm-tools component unbundle`.
+    manifest: PathBuf,
+
+    #[clap(flatten)]
+    output: wasm_tools::OutputArg,
+
+    /// Print the output in the WebAssembly text format instead of binary.
+    #[clap(long, short = 't')]
+    wat: bool,
+}
+
+impl BundleOpts {
+    fn general_opts(&self) -> &wasm_tools::GeneralOpts {
+        &self.general
+    }
+
+    /// Executes the application.
+    fn run(self) -> Result<()> {
+        let path = &self.manifest;
+        let manifest = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read manifest: {path:?}"))?;
+        let manifest: wit_component::BundleManifest = serde_json::from_str(&manifest)
+            .with_context(|| format!("failed to parse manifest: {path:?}"))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+
+        let bytes = wit_component::bundle(&manifest, |file| {
+            let path = dir.join(file);
+            wat::parse_file(&path).map_err(|e| e.into())
+        })?;
+
+        self.output.output_wasm(&self.general, &bytes, self.wat)?;
+
+        Ok(())
+    }
+}
+
+/// Remove imports from a component by implementing them with stubs.
+///
+/// Each interface named with `--interface` is implemented by a generated
+/// component which is composed with the input, producing a component which no
+/// longer imports that interface. Functions of stubbed interfaces trap when
+/// called unless a constant result is given for them with `--result`.
+#[derive(Parser)]
+pub struct StubOpts {
+    #[clap(flatten)]
+    general: wasm_tools::GeneralOpts,
+
+    #[clap(flatten)]
+    input: wasm_tools::InputArg,
+
+    #[clap(flatten)]
+    output: wasm_tools::OutputArg,
+
+    /// An imported interface to stub, for example `wasi:sockets/tcp@0.2.0`
+    /// (may be repeated).
+    #[clap(long = "interface", value_name = "NAME", required = true)]
+    interfaces: Vec<String>,
+
+    /// Make a stubbed function return a constant rather than trap (may be
+    /// repeated).
+    ///
+    /// `FUNC` is the function's name prefixed with its interface, for example
+    /// `wasi:cli/environment@0.2.0#get-arguments`, and `VALUE` is its result
+    /// in the WAVE format, for example `["a", "b"]`. Functions with multiple
+    /// results take a tuple of them, and functions without results take `()`
+    /// to return without trapping.
+    #[clap(long = "result", value_name = "FUNC=VALUE", value_parser = parse_func_value)]
+    results: Vec<(String, String)>,
+
+    /// Skip validation of the output component.
+    #[clap(long)]
+    skip_validation: bool,
+
+    /// Print the output in the WebAssembly text format instead of binary.
+    #[clap(long, short = 't')]
+    wat: bool,
+}
+
+fn parse_func_value(s: &str) -> Result<(String, String)> {
+    match s.split_once('=') {
+        Some((func, value)) => Ok((func.to_string(), value.to_string())),
+        None => bail!("expected `FUNC=VALUE` but found `{s}`"),
+    }
+}
+
+impl StubOpts {
+    fn general_opts(&self) -> &wasm_tools::GeneralOpts {
+        &self.general
+    }
+
+    /// Executes the application.
+    fn run(self) -> Result<()> {
+        let wasm = self.input.parse_wasm()?;
+        let mut stubber = wit_component::ImportStubber::default().validate(!self.skip_validation);
+        for name in self.interfaces.iter() {
+            stubber = stubber.interface(name);
+        }
+        for (func, value) in self.results.iter() {
+            stubber = stubber.result(func, value);
+        }
+        let bytes = stubber
+            .encode(&wasm)
+            .context("failed to stub imports of component")?;
+
+        self.output.output_wasm(&self.general, &bytes, self.wat)?;
+
+        Ok(())
+    }
+}
+
+/// Generate a component implementing a WIT world, for use as a test double.
+///
+/// Every function exported by the world traps when called by default. This
+/// can be changed for all functions with `--behavior` or for individual
+/// functions with `--trap`, `--zero` and `--result`. Functions are named as
+/// they are exported from a core module, for example `run` for a function
+/// exported by the world itself or `wasi:cli/run@0.2.0#run` for a function of
+/// an exported interface.
+#[derive(Parser)]
+pub struct DummyOpts {
+    #[clap(flatten)]
+    general: wasm_tools::GeneralOpts,
+
+    #[clap(flatten)]
+    resolve: WitResolve,
+
+    /// The world to implement, which must be specified if the WIT source
+    /// contains more than one.
+    #[clap(short, long)]
+    world: Option<String>,
+
+    #[clap(flatten)]
+    output: wasm_tools::OutputArg,
+
+    /// The behavior of functions not otherwise configured: `trap` when
+    /// called, or return `zero` values.
+    #[clap(long, value_name = "trap|zero", default_value = "trap")]
+    behavior: wit_component::DummyBehavior,
+
+    /// Make a function trap when called (may be repeated).
+    #[clap(long = "trap", value_name = "FUNC")]
+    traps: Vec<String>,
+
+    /// Make a function return zero values: zero numbers, empty strings and
+    /// lists, `none` options and the first case of variants, enums and
+    /// results (may be repeated).
+    #[clap(long = "zero", value_name = "FUNC")]
+    zeros: Vec<String>,
+
+    /// Make a function return a constant (may be repeated).
+    ///
+    /// `VALUE` is the function's result in the WAVE format, for example
+    /// `["a", "b"]`. Functions with multiple results take a tuple of them, and
+    /// functions without results take `()`.
+    #[clap(long = "result", value_name = "FUNC=VALUE", value_parser = parse_func_value)]
+    results: Vec<(String, String)>,
+
+    /// Skip validation of the output component.
+    #[clap(long)]
+    skip_validation: bool,
+
+    /// Print the output in the WebAssembly text format instead of binary.
+    #[clap(long, short = 't')]
+    wat: bool,
+}
+
+impl DummyOpts {
+    fn general_opts(&self) -> &wasm_tools::GeneralOpts {
+        &self.general
+    }
+
+    /// Executes the application.
+    fn run(self) -> Result<()> {
+        use wit_component::DummyBehavior;
+
+        let (resolve, pkg_ids) = self.resolve.load()?;
+        let world = resolve.select_world(&pkg_ids, self.world.as_deref())?;
+
+        let mut dummy = wit_component::DummyComponent::default()
+            .validate(!self.skip_validation)
+            .behavior(self.behavior);
+        for func in self.traps {
+            dummy = dummy.export(func, DummyBehavior::Trap);
+        }
+        for func in self.zeros {
+            dummy = dummy.export(func, DummyBehavior::Zero);
+        }
+        for (func, value) in self.results {
+            dummy = dummy.export(func, DummyBehavior::Value(value));
+        }
+        let bytes = dummy
+            .encode(&resolve, world)
+            .context("failed to generate dummy component")?;
+
+        self.output.output_wasm(&self.general, &bytes, self.wat)?;
+
+        Ok(())
+    }
+}
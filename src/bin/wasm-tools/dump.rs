@@ -414,12 +414,7 @@ impl<'a> Dump<'a> {
                     self.section(s, "canonical function", |me, end, f| {
                         let (name, col) = match &f {
                             CanonicalFunction::Lift { .. } => ("func", &mut i.funcs),
-                            CanonicalFunction::Lower { .. }
-                            | CanonicalFunction::ResourceNew { .. }
-                            | CanonicalFunction::ResourceDrop { .. }
-                            | CanonicalFunction::ResourceRep { .. } => {
-                                ("core func", &mut i.core_funcs)
-                            }
+                            _ => ("core func", &mut i.core_funcs),
                         };
 
                         write!(me.state, "[{} {}] {:?}", name, inc(col), f)?;
@@ -485,6 +480,17 @@ impl<'a> Dump<'a> {
                                 })
                             })?;
                         }
+                        KnownCustom::CodeMetadata(iter) => {
+                            self.print_iter(iter, |me, _pos, item| {
+                                write!(me.state, "func: {}", item.func)?;
+                                me.print(item.annotations.range().start)?;
+
+                                me.print_iter(item.annotations, |me, pos, item| {
+                                    write!(me.state, "{item:?}")?;
+                                    me.print(pos)
+                                })
+                            })?;
+                        }
                         KnownCustom::CoreDump(s) => {
                             write!(self.state, "name: {}", s.name)?;
                             self.print(c.range().end)?;
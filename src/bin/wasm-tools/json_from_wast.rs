@@ -1,6 +1,7 @@
 use anyhow::{bail, Context, Result};
 use clap::Parser;
 use std::path::{Path, PathBuf};
+use wast::component::WastVal;
 use wast::core::{AbstractHeapType, HeapType, NanPattern, V128Const, V128Pattern, WastRetCore};
 use wast::lexer::Lexer;
 use wast::parser::{self, ParseBuffer};
@@ -113,7 +114,7 @@ impl Opts {
             serde_json::to_string(&builder.ret)?
         };
         self.output
-            .output(&self.general, wasm_tools::Output::Json(&json))?;
+            .output(&self.general, wasm_tools::Output::Str(&json))?;
         Ok(())
     }
 }
@@ -333,7 +334,12 @@ impl<'a> JsonBuilder<'a> {
         for arg in args {
             let arg = match arg {
                 WastArg::Core(core) => core,
-                WastArg::Component(_) => bail!("component support not implemented yet"),
+                WastArg::Component(val) => {
+                    ret.push(json::Const::Component {
+                        value: self.component_val(val),
+                    });
+                    continue;
+                }
             };
             let val = match arg {
                 I32(i) => json::Const::I32 {
@@ -390,7 +396,9 @@ impl<'a> JsonBuilder<'a> {
         for r in rets {
             let r = match r {
                 WastRet::Core(core) => self.core_ret(core)?,
-                WastRet::Component(_) => bail!("component support not implemented yet"),
+                WastRet::Component(val) => json::Const::Component {
+                    value: self.component_val(val),
+                },
             };
             ret.push(r);
         }
@@ -471,6 +479,74 @@ impl<'a> JsonBuilder<'a> {
         })
     }
 
+    fn component_val(&self, val: WastVal<'a>) -> json::ComponentConst<'a> {
+        use json::ComponentConst;
+
+        let boxed = |v: Option<Box<WastVal<'a>>>| v.map(|v| Box::new(self.component_val(*v)));
+        match val {
+            WastVal::Bool(b) => ComponentConst::Bool { value: b },
+            WastVal::U8(i) => ComponentConst::U8 {
+                value: i.to_string(),
+            },
+            WastVal::S8(i) => ComponentConst::S8 {
+                value: i.to_string(),
+            },
+            WastVal::U16(i) => ComponentConst::U16 {
+                value: i.to_string(),
+            },
+            WastVal::S16(i) => ComponentConst::S16 {
+                value: i.to_string(),
+            },
+            WastVal::U32(i) => ComponentConst::U32 {
+                value: i.to_string(),
+            },
+            WastVal::S32(i) => ComponentConst::S32 {
+                value: i.to_string(),
+            },
+            WastVal::U64(i) => ComponentConst::U64 {
+                value: i.to_string(),
+            },
+            WastVal::S64(i) => ComponentConst::S64 {
+                value: i.to_string(),
+            },
+            WastVal::F32(f) => ComponentConst::F32 {
+                value: f32_to_string(f),
+            },
+            WastVal::F64(f) => ComponentConst::F64 {
+                value: f64_to_string(f),
+            },
+            WastVal::Char(c) => ComponentConst::Char { value: c },
+            WastVal::String(s) => ComponentConst::String { value: s },
+            WastVal::List(vals) => ComponentConst::List {
+                values: vals.into_iter().map(|v| self.component_val(v)).collect(),
+            },
+            WastVal::Record(fields) => ComponentConst::Record {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, v)| json::ComponentField {
+                        name,
+                        value: self.component_val(v),
+                    })
+                    .collect(),
+            },
+            WastVal::Tuple(vals) => ComponentConst::Tuple {
+                values: vals.into_iter().map(|v| self.component_val(v)).collect(),
+            },
+            WastVal::Variant(case, payload) => ComponentConst::Variant {
+                case,
+                value: boxed(payload),
+            },
+            WastVal::Enum(case) => ComponentConst::Enum { case },
+            WastVal::Option(None) => ComponentConst::OptionNone,
+            WastVal::Option(Some(v)) => ComponentConst::OptionSome {
+                value: Box::new(self.component_val(*v)),
+            },
+            WastVal::Result(Ok(v)) => ComponentConst::ResultOk { value: boxed(v) },
+            WastVal::Result(Err(v)) => ComponentConst::ResultErr { value: boxed(v) },
+            WastVal::Flags(flags) => ComponentConst::Flags { flags },
+        }
+    }
+
     fn print_i8(&self, i: i8) -> String {
         i.to_string()
     }
@@ -686,5 +762,96 @@ mod json {
 
         // any null reference, type doesn't matter
         RefNull,
+
+        // a component model value
+        Component {
+            value: ComponentConst<'a>,
+        },
+    }
+
+    #[derive(Serialize)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    pub enum ComponentConst<'a> {
+        Bool {
+            value: bool,
+        },
+        U8 {
+            value: String,
+        },
+        S8 {
+            value: String,
+        },
+        U16 {
+            value: String,
+        },
+        S16 {
+            value: String,
+        },
+        U32 {
+            value: String,
+        },
+        S32 {
+            value: String,
+        },
+        U64 {
+            value: String,
+        },
+        S64 {
+            value: String,
+        },
+        F32 {
+            value: String,
+        },
+        F64 {
+            value: String,
+        },
+        Char {
+            value: char,
+        },
+        String {
+            value: &'a str,
+        },
+        List {
+            values: Vec<ComponentConst<'a>>,
+        },
+        Record {
+            fields: Vec<ComponentField<'a>>,
+        },
+        Tuple {
+            values: Vec<ComponentConst<'a>>,
+        },
+        Variant {
+            case: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            value: Option<Box<ComponentConst<'a>>>,
+        },
+        Enum {
+            case: &'a str,
+        },
+        #[serde(rename = "option.none")]
+        OptionNone,
+        #[serde(rename = "option.some")]
+        OptionSome {
+            value: Box<ComponentConst<'a>>,
+        },
+        #[serde(rename = "result.ok")]
+        ResultOk {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            value: Option<Box<ComponentConst<'a>>>,
+        },
+        #[serde(rename = "result.err")]
+        ResultErr {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            value: Option<Box<ComponentConst<'a>>>,
+        },
+        Flags {
+            flags: Vec<&'a str>,
+        },
+    }
+
+    #[derive(Serialize)]
+    pub struct ComponentField<'a> {
+        pub name: &'a str,
+        pub value: ComponentConst<'a>,
     }
 }
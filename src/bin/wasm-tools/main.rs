@@ -74,6 +74,8 @@ subcommands! {
     (addr2line, "addr2line")
     (completion, "completion")
     (json_from_wast, "json-from-wast")
+    (wast_from_json, "wast-from-json")
+    (to_json, "to-json")
 }
 
 // when all features are disabled then `WasmTools` is an empty enum so suppress
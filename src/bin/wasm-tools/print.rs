@@ -1,5 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
+#[cfg(feature = "addr2line")]
+use std::collections::{BTreeMap, HashMap};
 
 /// Print the textual form of a WebAssembly binary.
 #[derive(Parser)]
@@ -25,6 +27,24 @@ pub struct Opts {
     /// doesn't previously have a name.
     #[clap(long)]
     name_unnamed: bool,
+
+    /// Print the module as a standalone HTML document instead of plain text.
+    ///
+    /// References to items such as functions, globals, types, and labels are
+    /// linked to their definitions, each line is annotated with its binary
+    /// offset, and large literals such as data segments are collapsed.
+    #[clap(long)]
+    html: bool,
+
+    /// Interleave the source locations of instructions, as described by DWARF
+    /// debugging information, as comments in the output.
+    ///
+    /// A `;; file:line` comment is printed whenever the source location
+    /// changes. If the source file is present locally then the corresponding
+    /// line of source is printed as well.
+    #[cfg(feature = "addr2line")]
+    #[clap(long)]
+    source_lines: bool,
 }
 
 impl Opts {
@@ -39,9 +59,69 @@ impl Opts {
         config.print_offsets(self.print_offsets);
         config.print_skeleton(self.skeleton);
         config.name_unnamed(self.name_unnamed);
+        #[cfg(feature = "addr2line")]
+        if self.source_lines {
+            config.source_annotations(source_lines(&wasm)?);
+        }
+        if self.html {
+            let mut html = wasmprinter::PrintHtml::new(self.io.output_writer()?);
+            config.print(&wasm, &mut html)?;
+            html.finish()?;
+            return Ok(());
+        }
         self.io.output(wasm_tools::Output::Wat {
             wasm: &wasm,
             config,
         })
     }
 }
+
+/// Builds a function mapping binary offsets to their `file:line` source
+/// location, followed by the source line itself if the file can be read.
+#[cfg(feature = "addr2line")]
+fn source_lines(wasm: &[u8]) -> Result<impl Fn(usize) -> Option<String>> {
+    use anyhow::Context;
+    use wasm_tools::addr2line::Module;
+
+    // Map of the start of each range of code to the end of the range and its
+    // annotation.
+    let mut ranges = BTreeMap::new();
+    let mut files = HashMap::new();
+    for module in Module::parse_all(wasm).context("failed to parse custom sections")? {
+        let code_start = match module.code_start {
+            Some(start) => start,
+            None => continue,
+        };
+        let cx = module.context()?;
+        for (start, len, location) in cx.find_location_range(0, module.range.end - code_start)? {
+            let (file, line) = match (location.file, location.line) {
+                (Some(file), Some(line)) => (file, line),
+                _ => continue,
+            };
+            let mut annotation = format!("{file}:{line}");
+            let contents = files
+                .entry(file.to_string())
+                .or_insert_with(|| std::fs::read_to_string(file).ok());
+            let source = contents.as_ref().and_then(|contents| {
+                let line = usize::try_from(line).ok()?.checked_sub(1)?;
+                contents.lines().nth(line)
+            });
+            if let Some(source) = source {
+                annotation.push('\n');
+                annotation.push_str(source.trim_end());
+            }
+            let start = code_start + start;
+            ranges.insert(start, (start + len, annotation));
+        }
+    }
+
+    Ok(move |offset: usize| {
+        let offset = offset as u64;
+        let (_, (end, annotation)) = ranges.range(..=offset).next_back()?;
+        if offset < *end {
+            Some(annotation.clone())
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,1180 @@
+use anyhow::{bail, Result};
+use serde_json::{json, Map, Value};
+use wasmparser::*;
+
+/// Convert a WebAssembly binary to a JSON description of its structure.
+///
+/// The output is a JSON object describing the module or component, decoded
+/// with the same readers as the rest of `wasm-tools`, to make binaries easy to
+/// inspect from other languages without a wasm parser of their own.
+///
+/// Every module or component is an object with a `kind` of `"module"` or
+/// `"component"`, its `offset` and `end` in the binary, its `version`, and its
+/// `sections` in binary order. Each section is an object with a `section` name,
+/// such as `"type"` or `"code"`, along with its `offset` and `size`. Sections
+/// which are a vector of items have an `items` array where each item is an
+/// object with its own `offset`. Nested modules and components are found in
+/// `"module"` and `"component"` sections under the `module` and `component`
+/// keys respectively.
+///
+/// Function bodies in the `"code"` section have `locals` and `operators`, and
+/// constant expressions are an array of operators. Each operator is an object
+/// with its `offset`, an `op` name, and one key per immediate. The `op` name is
+/// the name of the corresponding `wasmparser::VisitOperator` method without
+/// its `visit_` prefix, for example `i32_add` or `local_get`.
+///
+/// Value types are either a string such as `"i32"` or an object describing a
+/// reference type. 64-bit integers, including the bits of `f64` constants, are
+/// encoded as decimal strings to avoid a loss of precision, and floating-point
+/// constants are encoded as their bit patterns.
+#[derive(clap::Parser)]
+pub struct Opts {
+    #[clap(flatten)]
+    io: wasm_tools::InputOutput,
+
+    /// Pretty-print the JSON output.
+    #[clap(long)]
+    pretty: bool,
+}
+
+impl Opts {
+    pub fn general_opts(&self) -> &wasm_tools::GeneralOpts {
+        self.io.general_opts()
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let wasm = self.io.parse_input_wasm()?;
+        let value = to_json(&wasm)?;
+        let json = if self.pretty {
+            serde_json::to_string_pretty(&value)?
+        } else {
+            serde_json::to_string(&value)?
+        };
+        self.io.output(wasm_tools::Output::Str(&json))
+    }
+}
+
+fn to_json(wasm: &[u8]) -> Result<Value> {
+    // Stack of modules and components currently being decoded, the last of
+    // which is the innermost.
+    let mut stack: Vec<Map<String, Value>> = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        if let Payload::Version {
+            num,
+            encoding,
+            range,
+        } = &payload
+        {
+            let kind = match encoding {
+                Encoding::Module => "module",
+                Encoding::Component => "component",
+            };
+            stack.push(object(json!({
+                "kind": kind,
+                "offset": range.start,
+                "version": num,
+                "sections": [],
+            })));
+            continue;
+        }
+
+        let sections = match stack.last_mut() {
+            Some(cur) => cur["sections"].as_array_mut().unwrap(),
+            None => bail!("payload found outside of a module or component"),
+        };
+        let value = match payload {
+            Payload::Version { .. } => unreachable!(),
+            Payload::End(offset) => {
+                let mut done = stack.pop().unwrap();
+                done.insert("end".to_string(), offset.into());
+                let kind = done["kind"].as_str().unwrap().to_string();
+                match stack.last_mut() {
+                    Some(parent) => {
+                        let sections = parent["sections"].as_array_mut().unwrap();
+                        let section = sections.last_mut().unwrap().as_object_mut().unwrap();
+                        section.insert(kind, Value::Object(done));
+                        continue;
+                    }
+                    None => return Ok(Value::Object(done)),
+                }
+            }
+
+            Payload::TypeSection(s) => section("type", s, rec_group)?,
+            Payload::ImportSection(s) => section("import", s, |i| {
+                Ok(json!({
+                    "module": i.module,
+                    "name": i.name,
+                    "type": type_ref(&i.ty),
+                }))
+            })?,
+            Payload::FunctionSection(s) => section("function", s, |ty| Ok(json!({ "type": ty })))?,
+            Payload::TableSection(s) => section("table", s, |t| {
+                let init = match t.init {
+                    TableInit::RefNull => Value::Null,
+                    TableInit::Expr(e) => const_expr(&e)?,
+                };
+                Ok(json!({
+                    "type": table_type(&t.ty),
+                    "init": init,
+                }))
+            })?,
+            Payload::MemorySection(s) => {
+                section("memory", s, |m| Ok(json!({ "type": memory_type(&m) })))?
+            }
+            Payload::TagSection(s) => section("tag", s, |t| Ok(json!({ "type": tag_type(&t) })))?,
+            Payload::GlobalSection(s) => section("global", s, |g| {
+                Ok(json!({
+                    "type": global_type(&g.ty),
+                    "init": const_expr(&g.init_expr)?,
+                }))
+            })?,
+            Payload::ExportSection(s) => section("export", s, |e| {
+                Ok(json!({
+                    "name": e.name,
+                    "kind": external_kind(e.kind),
+                    "index": e.index,
+                }))
+            })?,
+            Payload::StartSection { func, range } => json!({
+                "section": "start",
+                "offset": range.start,
+                "size": range.len(),
+                "func": func,
+            }),
+            Payload::ElementSection(s) => section("element", s, element)?,
+            Payload::DataCountSection { count, range } => json!({
+                "section": "data_count",
+                "offset": range.start,
+                "size": range.len(),
+                "count": count,
+            }),
+            Payload::DataSection(s) => section("data", s, data)?,
+            Payload::CodeSectionStart { count, range, .. } => json!({
+                "section": "code",
+                "offset": range.start,
+                "size": range.len(),
+                "count": count,
+                "items": [],
+            }),
+            Payload::CodeSectionEntry(body) => {
+                let code = sections.last_mut().unwrap()["items"]
+                    .as_array_mut()
+                    .unwrap();
+                code.push(function_body(&body)?);
+                continue;
+            }
+
+            Payload::ModuleSection {
+                unchecked_range: range,
+                ..
+            } => json!({
+                "section": "module",
+                "offset": range.start,
+                "size": range.len(),
+            }),
+            Payload::ComponentSection {
+                unchecked_range: range,
+                ..
+            } => json!({
+                "section": "component",
+                "offset": range.start,
+                "size": range.len(),
+            }),
+            Payload::InstanceSection(s) => section("core_instance", s, instance)?,
+            Payload::CoreTypeSection(s) => section("core_type", s, |t| core_type(&t))?,
+            Payload::ComponentInstanceSection(s) => section("instance", s, component_instance)?,
+            Payload::ComponentAliasSection(s) => section("alias", s, |a| Ok(alias(&a)))?,
+            Payload::ComponentTypeSection(s) => section("type", s, |t| component_type(&t))?,
+            Payload::ComponentCanonicalSection(s) => {
+                section("canonical", s, |f| Ok(canonical_function(&f)))?
+            }
+            Payload::ComponentStartSection { start, range } => json!({
+                "section": "start",
+                "offset": range.start,
+                "size": range.len(),
+                "func": start.func_index,
+                "arguments": start.arguments,
+                "results": start.results,
+            }),
+            Payload::ComponentImportSection(s) => section("import", s, |i| {
+                Ok(json!({
+                    "name": i.name.0,
+                    "type": component_type_ref(&i.ty),
+                }))
+            })?,
+            Payload::ComponentExportSection(s) => section("export", s, |e| {
+                Ok(json!({
+                    "name": e.name.0,
+                    "kind": e.kind.desc(),
+                    "index": e.index,
+                    "type": e.ty.as_ref().map(component_type_ref),
+                }))
+            })?,
+
+            Payload::CustomSection(c) => json!({
+                "section": "custom",
+                "offset": c.range().start,
+                "size": c.range().len(),
+                "name": c.name(),
+                "data_offset": c.data_offset(),
+                "data_size": c.data().len(),
+            }),
+            Payload::UnknownSection { id, range, .. } => json!({
+                "section": "unknown",
+                "offset": range.start,
+                "size": range.len(),
+                "id": id,
+            }),
+        };
+        sections.push(value);
+    }
+    bail!("unexpected end of input")
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+/// Converts a section which is a vector of items, adding an `offset` to each
+/// item produced by `item`.
+fn section<'a, T>(
+    name: &str,
+    reader: SectionLimited<'a, T>,
+    item: impl Fn(T) -> Result<Value>,
+) -> Result<Value>
+where
+    T: FromReader<'a>,
+{
+    let range = reader.range();
+    let mut items = Vec::new();
+    for entry in reader.into_iter_with_offsets() {
+        let (offset, entry) = entry?;
+        let mut map = Map::new();
+        map.insert("offset".to_string(), offset.into());
+        map.extend(object(item(entry)?));
+        items.push(Value::Object(map));
+    }
+    Ok(json!({
+        "section": name,
+        "offset": range.start,
+        "size": range.len(),
+        "items": items,
+    }))
+}
+
+fn rec_group(group: RecGroup) -> Result<Value> {
+    let explicit = group.is_explicit_rec_group();
+    let types = group
+        .into_types_and_offsets()
+        .map(|(offset, ty)| {
+            let mut map = Map::new();
+            map.insert("offset".to_string(), offset.into());
+            map.extend(object(sub_type(&ty)));
+            Value::Object(map)
+        })
+        .collect::<Vec<_>>();
+    Ok(json!({
+        "rec": explicit,
+        "types": types,
+    }))
+}
+
+fn sub_type(ty: &SubType) -> Value {
+    let supertype = ty.supertype_idx.map(|idx| unpacked_index(idx.unpack()));
+    let mut map = object(json!({
+        "final": ty.is_final,
+        "supertype": supertype,
+    }));
+    map.extend(object(composite_type(&ty.composite_type)));
+    Value::Object(map)
+}
+
+fn composite_type(ty: &CompositeType) -> Value {
+    match ty {
+        CompositeType::Func(f) => json!({
+            "kind": "func",
+            "params": f.params().iter().map(val_type).collect::<Vec<_>>(),
+            "results": f.results().iter().map(val_type).collect::<Vec<_>>(),
+        }),
+        CompositeType::Array(a) => json!({
+            "kind": "array",
+            "field": field_type(&a.0),
+        }),
+        CompositeType::Struct(s) => json!({
+            "kind": "struct",
+            "fields": s.fields.iter().map(field_type).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn field_type(ty: &FieldType) -> Value {
+    let storage = match ty.element_type {
+        StorageType::I8 => json!("i8"),
+        StorageType::I16 => json!("i16"),
+        StorageType::Val(ty) => val_type(&ty),
+    };
+    json!({
+        "type": storage,
+        "mutable": ty.mutable,
+    })
+}
+
+fn val_type(ty: &ValType) -> Value {
+    match ty {
+        ValType::I32 => json!("i32"),
+        ValType::I64 => json!("i64"),
+        ValType::F32 => json!("f32"),
+        ValType::F64 => json!("f64"),
+        ValType::V128 => json!("v128"),
+        ValType::Ref(r) => ref_type(r),
+    }
+}
+
+fn ref_type(ty: &RefType) -> Value {
+    json!({
+        "nullable": ty.is_nullable(),
+        "heap": heap_type(&ty.heap_type()),
+    })
+}
+
+fn heap_type(ty: &HeapType) -> Value {
+    match ty {
+        HeapType::Abstract { shared, ty } => {
+            let name = match ty {
+                AbstractHeapType::Func => "func",
+                AbstractHeapType::Extern => "extern",
+                AbstractHeapType::Any => "any",
+                AbstractHeapType::None => "none",
+                AbstractHeapType::NoExtern => "noextern",
+                AbstractHeapType::NoFunc => "nofunc",
+                AbstractHeapType::Eq => "eq",
+                AbstractHeapType::Struct => "struct",
+                AbstractHeapType::Array => "array",
+                AbstractHeapType::I31 => "i31",
+                AbstractHeapType::Exn => "exn",
+                AbstractHeapType::NoExn => "noexn",
+            };
+            json!({
+                "abstract": name,
+                "shared": shared,
+            })
+        }
+        HeapType::Concrete(idx) => unpacked_index(*idx),
+    }
+}
+
+fn unpacked_index(idx: UnpackedIndex) -> Value {
+    match idx {
+        UnpackedIndex::Module(i) => json!({ "module": i }),
+        UnpackedIndex::RecGroup(i) => json!({ "rec_group": i }),
+        // Type identifiers are only created during validation, which this
+        // conversion doesn't perform.
+        #[allow(unreachable_patterns)]
+        _ => unreachable!(),
+    }
+}
+
+fn table_type(ty: &TableType) -> Value {
+    json!({
+        "element_type": ref_type(&ty.element_type),
+        "table64": ty.table64,
+        "initial": ty.initial.to_string(),
+        "maximum": ty.maximum.map(|m| m.to_string()),
+    })
+}
+
+fn memory_type(ty: &MemoryType) -> Value {
+    json!({
+        "memory64": ty.memory64,
+        "shared": ty.shared,
+        "initial": ty.initial.to_string(),
+        "maximum": ty.maximum.map(|m| m.to_string()),
+        "page_size_log2": ty.page_size_log2,
+    })
+}
+
+fn global_type(ty: &GlobalType) -> Value {
+    json!({
+        "content_type": val_type(&ty.content_type),
+        "mutable": ty.mutable,
+        "shared": ty.shared,
+    })
+}
+
+fn tag_type(ty: &TagType) -> Value {
+    let kind = match ty.kind {
+        TagKind::Exception => "exception",
+    };
+    json!({
+        "kind": kind,
+        "func_type": ty.func_type_idx,
+    })
+}
+
+fn type_ref(ty: &TypeRef) -> Value {
+    match ty {
+        TypeRef::Func(i) => json!({ "kind": "func", "type": i }),
+        TypeRef::Table(t) => json!({ "kind": "table", "type": table_type(t) }),
+        TypeRef::Memory(m) => json!({ "kind": "memory", "type": memory_type(m) }),
+        TypeRef::Global(g) => json!({ "kind": "global", "type": global_type(g) }),
+        TypeRef::Tag(t) => json!({ "kind": "tag", "type": tag_type(t) }),
+    }
+}
+
+fn external_kind(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func => "func",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
+
+fn element(elem: Element<'_>) -> Result<Value> {
+    let mode = match elem.kind {
+        ElementKind::Passive => json!({ "kind": "passive" }),
+        ElementKind::Declared => json!({ "kind": "declared" }),
+        ElementKind::Active {
+            table_index,
+            offset_expr,
+        } => json!({
+            "kind": "active",
+            "table": table_index.unwrap_or(0),
+            "offset_expr": const_expr(&offset_expr)?,
+        }),
+    };
+    let items = match elem.items {
+        ElementItems::Functions(funcs) => json!({
+            "kind": "functions",
+            "functions": funcs.into_iter().collect::<wasmparser::Result<Vec<_>>>()?,
+        }),
+        ElementItems::Expressions(ty, exprs) => {
+            let exprs = exprs
+                .into_iter()
+                .map(|e| const_expr(&e?))
+                .collect::<Result<Vec<_>>>()?;
+            json!({
+                "kind": "expressions",
+                "type": ref_type(&ty),
+                "expressions": exprs,
+            })
+        }
+    };
+    Ok(json!({
+        "mode": mode,
+        "items": items,
+    }))
+}
+
+fn data(data: Data<'_>) -> Result<Value> {
+    let mode = match data.kind {
+        DataKind::Passive => json!({ "kind": "passive" }),
+        DataKind::Active {
+            memory_index,
+            offset_expr,
+        } => json!({
+            "kind": "active",
+            "memory": memory_index,
+            "offset_expr": const_expr(&offset_expr)?,
+        }),
+    };
+    let data_offset = data.range.end - data.data.len();
+    Ok(json!({
+        "mode": mode,
+        "data_offset": data_offset,
+        "data_size": data.data.len(),
+    }))
+}
+
+fn function_body(body: &FunctionBody<'_>) -> Result<Value> {
+    let mut locals = Vec::new();
+    for local in body.get_locals_reader()?.into_iter() {
+        let (count, ty) = local?;
+        locals.push(json!({
+            "count": count,
+            "type": val_type(&ty),
+        }));
+    }
+    Ok(json!({
+        "offset": body.range().start,
+        "size": body.range().len(),
+        "locals": locals,
+        "operators": operators(body.get_operators_reader()?)?,
+    }))
+}
+
+fn const_expr(expr: &ConstExpr<'_>) -> Result<Value> {
+    operators(expr.get_operators_reader())
+}
+
+fn operators(mut reader: OperatorsReader<'_>) -> Result<Value> {
+    let mut ops = Vec::new();
+    while !reader.eof() {
+        let offset = reader.original_position();
+        let mut op = Map::new();
+        op.insert("offset".to_string(), offset.into());
+        op.extend(object(reader.visit_operator(&mut OperatorToJson)??));
+        ops.push(Value::Object(op));
+    }
+    Ok(Value::Array(ops))
+}
+
+struct OperatorToJson;
+
+macro_rules! define_visit {
+    ($(@$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident)*) => {
+        $(
+            fn $visit(&mut self $($(,$arg: $argty)*)?) -> Result<Value> {
+                let mut op = Map::new();
+                op.insert("op".to_string(), stringify!($visit)["visit_".len()..].into());
+                $(
+                    $(
+                        op.insert(stringify!($arg).to_string(), $arg.to_json()?);
+                    )*
+                )?
+                Ok(Value::Object(op))
+            }
+        )*
+    };
+}
+
+impl<'a> VisitOperator<'a> for OperatorToJson {
+    type Output = Result<Value>;
+
+    for_each_operator!(define_visit);
+}
+
+/// Conversion of operator immediates to JSON.
+trait ToJson {
+    fn to_json(&self) -> Result<Value>;
+}
+
+macro_rules! to_json_via_into {
+    ($($ty:ty)*) => ($(
+        impl ToJson for $ty {
+            fn to_json(&self) -> Result<Value> {
+                Ok((*self).into())
+            }
+        }
+    )*)
+}
+
+to_json_via_into!(u8 u32 i32);
+
+impl ToJson for i64 {
+    fn to_json(&self) -> Result<Value> {
+        Ok(self.to_string().into())
+    }
+}
+
+impl ToJson for Ieee32 {
+    fn to_json(&self) -> Result<Value> {
+        Ok(self.bits().into())
+    }
+}
+
+impl ToJson for Ieee64 {
+    fn to_json(&self) -> Result<Value> {
+        Ok(self.bits().to_string().into())
+    }
+}
+
+impl ToJson for V128 {
+    fn to_json(&self) -> Result<Value> {
+        Ok(self.i128().to_string().into())
+    }
+}
+
+impl ToJson for [u8; 16] {
+    fn to_json(&self) -> Result<Value> {
+        Ok(self.to_vec().into())
+    }
+}
+
+impl ToJson for MemArg {
+    fn to_json(&self) -> Result<Value> {
+        Ok(json!({
+            "align": self.align,
+            "max_align": self.max_align,
+            "offset": self.offset.to_string(),
+            "memory": self.memory,
+        }))
+    }
+}
+
+impl ToJson for BlockType {
+    fn to_json(&self) -> Result<Value> {
+        Ok(match self {
+            BlockType::Empty => json!({ "kind": "empty" }),
+            BlockType::Type(ty) => json!({ "kind": "result", "result": val_type(ty) }),
+            BlockType::FuncType(idx) => json!({ "kind": "func_type", "type": idx }),
+        })
+    }
+}
+
+impl ToJson for BrTable<'_> {
+    fn to_json(&self) -> Result<Value> {
+        Ok(json!({
+            "targets": self.targets().collect::<wasmparser::Result<Vec<_>>>()?,
+            "default": self.default(),
+        }))
+    }
+}
+
+impl ToJson for TryTable {
+    fn to_json(&self) -> Result<Value> {
+        let catches = self
+            .catches
+            .iter()
+            .map(|catch| match *catch {
+                Catch::One { tag, label } => json!({ "kind": "catch", "tag": tag, "label": label }),
+                Catch::OneRef { tag, label } => {
+                    json!({ "kind": "catch_ref", "tag": tag, "label": label })
+                }
+                Catch::All { label } => json!({ "kind": "catch_all", "label": label }),
+                Catch::AllRef { label } => json!({ "kind": "catch_all_ref", "label": label }),
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({
+            "blockty": self.ty.to_json()?,
+            "catches": catches,
+        }))
+    }
+}
+
+impl ToJson for ValType {
+    fn to_json(&self) -> Result<Value> {
+        Ok(val_type(self))
+    }
+}
+
+impl ToJson for RefType {
+    fn to_json(&self) -> Result<Value> {
+        Ok(ref_type(self))
+    }
+}
+
+impl ToJson for HeapType {
+    fn to_json(&self) -> Result<Value> {
+        Ok(heap_type(self))
+    }
+}
+
+impl ToJson for Ordering {
+    fn to_json(&self) -> Result<Value> {
+        Ok(match self {
+            Ordering::AcqRel => "acq_rel".into(),
+            Ordering::SeqCst => "seq_cst".into(),
+        })
+    }
+}
+
+fn instance(instance: Instance<'_>) -> Result<Value> {
+    Ok(match instance {
+        Instance::Instantiate { module_index, args } => {
+            let args = args
+                .iter()
+                .map(|arg| {
+                    let kind = match arg.kind {
+                        InstantiationArgKind::Instance => "instance",
+                    };
+                    json!({
+                        "name": arg.name,
+                        "kind": kind,
+                        "index": arg.index,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "kind": "instantiate",
+                "module": module_index,
+                "args": args,
+            })
+        }
+        Instance::FromExports(exports) => {
+            let exports = exports
+                .iter()
+                .map(|e| {
+                    json!({
+                        "name": e.name,
+                        "kind": external_kind(e.kind),
+                        "index": e.index,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "kind": "from_exports",
+                "exports": exports,
+            })
+        }
+    })
+}
+
+fn component_instance(instance: ComponentInstance<'_>) -> Result<Value> {
+    Ok(match instance {
+        ComponentInstance::Instantiate {
+            component_index,
+            args,
+        } => {
+            let args = args
+                .iter()
+                .map(|arg| {
+                    json!({
+                        "name": arg.name,
+                        "kind": arg.kind.desc(),
+                        "index": arg.index,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "kind": "instantiate",
+                "component": component_index,
+                "args": args,
+            })
+        }
+        ComponentInstance::FromExports(exports) => {
+            let exports = exports
+                .iter()
+                .map(|e| {
+                    json!({
+                        "name": e.name.0,
+                        "kind": e.kind.desc(),
+                        "index": e.index,
+                        "type": e.ty.as_ref().map(component_type_ref),
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "kind": "from_exports",
+                "exports": exports,
+            })
+        }
+    })
+}
+
+fn alias(alias: &ComponentAlias<'_>) -> Value {
+    match *alias {
+        ComponentAlias::InstanceExport {
+            kind,
+            instance_index,
+            name,
+        } => json!({
+            "kind": "instance_export",
+            "item_kind": kind.desc(),
+            "instance": instance_index,
+            "name": name,
+        }),
+        ComponentAlias::CoreInstanceExport {
+            kind,
+            instance_index,
+            name,
+        } => json!({
+            "kind": "core_instance_export",
+            "item_kind": external_kind(kind),
+            "instance": instance_index,
+            "name": name,
+        }),
+        ComponentAlias::Outer { kind, count, index } => {
+            let kind = match kind {
+                ComponentOuterAliasKind::CoreModule => "core_module",
+                ComponentOuterAliasKind::CoreType => "core_type",
+                ComponentOuterAliasKind::Type => "type",
+                ComponentOuterAliasKind::Component => "component",
+            };
+            json!({
+                "kind": "outer",
+                "item_kind": kind,
+                "count": count,
+                "index": index,
+            })
+        }
+    }
+}
+
+fn core_type(ty: &CoreType<'_>) -> Result<Value> {
+    Ok(match ty {
+        CoreType::Sub(ty) => sub_type(ty),
+        CoreType::Module(decls) => {
+            let decls = decls
+                .iter()
+                .map(|decl| match decl {
+                    ModuleTypeDeclaration::Type(ty) => json!({
+                        "kind": "type",
+                        "type": sub_type(ty),
+                    }),
+                    ModuleTypeDeclaration::Export { name, ty } => json!({
+                        "kind": "export",
+                        "name": name,
+                        "type": type_ref(ty),
+                    }),
+                    ModuleTypeDeclaration::OuterAlias { kind, count, index } => {
+                        let kind = match kind {
+                            OuterAliasKind::Type => "type",
+                        };
+                        json!({
+                            "kind": "outer_alias",
+                            "item_kind": kind,
+                            "count": count,
+                            "index": index,
+                        })
+                    }
+                    ModuleTypeDeclaration::Import(i) => json!({
+                        "kind": "import",
+                        "module": i.module,
+                        "name": i.name,
+                        "type": type_ref(&i.ty),
+                    }),
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "kind": "module",
+                "declarations": decls,
+            })
+        }
+    })
+}
+
+fn component_type(ty: &ComponentType<'_>) -> Result<Value> {
+    Ok(match ty {
+        ComponentType::Defined(ty) => component_defined_type(ty),
+        ComponentType::Func(ty) => {
+            let params = ty
+                .params
+                .iter()
+                .map(|(name, ty)| json!({ "name": name, "type": component_val_type(ty) }))
+                .collect::<Vec<_>>();
+            let results = match &ty.results {
+                ComponentFuncResult::Unnamed(ty) => json!({
+                    "kind": "unnamed",
+                    "type": component_val_type(ty),
+                }),
+                ComponentFuncResult::Named(results) => json!({
+                    "kind": "named",
+                    "results": results
+                        .iter()
+                        .map(|(name, ty)| json!({ "name": name, "type": component_val_type(ty) }))
+                        .collect::<Vec<_>>(),
+                }),
+            };
+            json!({
+                "kind": "func",
+                "params": params,
+                "results": results,
+            })
+        }
+        ComponentType::Component(decls) => {
+            let decls = decls
+                .iter()
+                .map(|decl| {
+                    Ok(match decl {
+                        ComponentTypeDeclaration::CoreType(ty) => json!({
+                            "kind": "core_type",
+                            "type": core_type(ty)?,
+                        }),
+                        ComponentTypeDeclaration::Type(ty) => json!({
+                            "kind": "type",
+                            "type": component_type(ty)?,
+                        }),
+                        ComponentTypeDeclaration::Alias(a) => json!({
+                            "kind": "alias",
+                            "alias": alias(a),
+                        }),
+                        ComponentTypeDeclaration::Export { name, ty } => json!({
+                            "kind": "export",
+                            "name": name.0,
+                            "type": component_type_ref(ty),
+                        }),
+                        ComponentTypeDeclaration::Import(i) => json!({
+                            "kind": "import",
+                            "name": i.name.0,
+                            "type": component_type_ref(&i.ty),
+                        }),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            json!({
+                "kind": "component",
+                "declarations": decls,
+            })
+        }
+        ComponentType::Instance(decls) => {
+            let decls = decls
+                .iter()
+                .map(|decl| {
+                    Ok(match decl {
+                        InstanceTypeDeclaration::CoreType(ty) => json!({
+                            "kind": "core_type",
+                            "type": core_type(ty)?,
+                        }),
+                        InstanceTypeDeclaration::Type(ty) => json!({
+                            "kind": "type",
+                            "type": component_type(ty)?,
+                        }),
+                        InstanceTypeDeclaration::Alias(a) => json!({
+                            "kind": "alias",
+                            "alias": alias(a),
+                        }),
+                        InstanceTypeDeclaration::Export { name, ty } => json!({
+                            "kind": "export",
+                            "name": name.0,
+                            "type": component_type_ref(ty),
+                        }),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            json!({
+                "kind": "instance",
+                "declarations": decls,
+            })
+        }
+        ComponentType::Resource { rep, dtor } => json!({
+            "kind": "resource",
+            "rep": val_type(rep),
+            "dtor": dtor,
+        }),
+    })
+}
+
+fn component_defined_type(ty: &ComponentDefinedType<'_>) -> Value {
+    let named = |items: &[(&str, ComponentValType)]| {
+        items
+            .iter()
+            .map(|(name, ty)| json!({ "name": name, "type": component_val_type(ty) }))
+            .collect::<Vec<_>>()
+    };
+    match ty {
+        ComponentDefinedType::Primitive(ty) => json!({
+            "kind": "primitive",
+            "type": ty.to_string(),
+        }),
+        ComponentDefinedType::Record(fields) => json!({
+            "kind": "record",
+            "fields": named(fields),
+        }),
+        ComponentDefinedType::Variant(cases) => json!({
+            "kind": "variant",
+            "cases": cases
+                .iter()
+                .map(|case| json!({
+                    "name": case.name,
+                    "type": case.ty.as_ref().map(component_val_type),
+                    "refines": case.refines,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        ComponentDefinedType::List(ty) => json!({
+            "kind": "list",
+            "type": component_val_type(ty),
+        }),
+        ComponentDefinedType::Tuple(types) => json!({
+            "kind": "tuple",
+            "types": types.iter().map(component_val_type).collect::<Vec<_>>(),
+        }),
+        ComponentDefinedType::Flags(names) => json!({
+            "kind": "flags",
+            "names": names,
+        }),
+        ComponentDefinedType::Enum(names) => json!({
+            "kind": "enum",
+            "names": names,
+        }),
+        ComponentDefinedType::Option(ty) => json!({
+            "kind": "option",
+            "type": component_val_type(ty),
+        }),
+        ComponentDefinedType::Result { ok, err } => json!({
+            "kind": "result",
+            "ok": ok.as_ref().map(component_val_type),
+            "err": err.as_ref().map(component_val_type),
+        }),
+        ComponentDefinedType::Own(idx) => json!({
+            "kind": "own",
+            "type": idx,
+        }),
+        ComponentDefinedType::Borrow(idx) => json!({
+            "kind": "borrow",
+            "type": idx,
+        }),
+        ComponentDefinedType::Future(ty) => json!({
+            "kind": "future",
+            "type": ty.as_ref().map(component_val_type),
+        }),
+        ComponentDefinedType::Stream(ty) => json!({
+            "kind": "stream",
+            "type": ty.as_ref().map(component_val_type),
+        }),
+        ComponentDefinedType::ErrorContext => json!({ "kind": "error_context" }),
+    }
+}
+
+fn component_val_type(ty: &ComponentValType) -> Value {
+    match ty {
+        ComponentValType::Primitive(ty) => ty.to_string().into(),
+        ComponentValType::Type(idx) => json!({ "type": idx }),
+    }
+}
+
+fn component_type_ref(ty: &ComponentTypeRef) -> Value {
+    match *ty {
+        ComponentTypeRef::Module(idx) => json!({ "kind": "module", "type": idx }),
+        ComponentTypeRef::Func(idx) => json!({ "kind": "func", "type": idx }),
+        ComponentTypeRef::Value(ty) => json!({ "kind": "value", "type": component_val_type(&ty) }),
+        ComponentTypeRef::Type(bounds) => {
+            let bounds = match bounds {
+                TypeBounds::Eq(idx) => json!({ "kind": "eq", "type": idx }),
+                TypeBounds::SubResource => json!({ "kind": "sub_resource" }),
+            };
+            json!({ "kind": "type", "bounds": bounds })
+        }
+        ComponentTypeRef::Instance(idx) => json!({ "kind": "instance", "type": idx }),
+        ComponentTypeRef::Component(idx) => json!({ "kind": "component", "type": idx }),
+    }
+}
+
+fn canonical_function(func: &CanonicalFunction) -> Value {
+    let options = |options: &[CanonicalOption]| {
+        options
+            .iter()
+            .map(|option| match *option {
+                CanonicalOption::UTF8 => json!({ "kind": "utf8" }),
+                CanonicalOption::UTF16 => json!({ "kind": "utf16" }),
+                CanonicalOption::CompactUTF16 => json!({ "kind": "compact_utf16" }),
+                CanonicalOption::Memory(idx) => json!({ "kind": "memory", "index": idx }),
+                CanonicalOption::Realloc(idx) => json!({ "kind": "realloc", "index": idx }),
+                CanonicalOption::PostReturn(idx) => {
+                    json!({ "kind": "post_return", "index": idx })
+                }
+                CanonicalOption::Async => json!({ "kind": "async" }),
+                CanonicalOption::Callback(idx) => json!({ "kind": "callback", "index": idx }),
+            })
+            .collect::<Vec<_>>()
+    };
+    match func {
+        CanonicalFunction::Lift {
+            core_func_index,
+            type_index,
+            options: opts,
+        } => json!({
+            "kind": "lift",
+            "core_func": core_func_index,
+            "type": type_index,
+            "options": options(opts),
+        }),
+        CanonicalFunction::Lower {
+            func_index,
+            options: opts,
+        } => json!({
+            "kind": "lower",
+            "func": func_index,
+            "options": options(opts),
+        }),
+        CanonicalFunction::ResourceNew { resource } => json!({
+            "kind": "resource_new",
+            "resource": resource,
+        }),
+        CanonicalFunction::ResourceDrop { resource } => json!({
+            "kind": "resource_drop",
+            "resource": resource,
+        }),
+        CanonicalFunction::ResourceRep { resource } => json!({
+            "kind": "resource_rep",
+            "resource": resource,
+        }),
+        CanonicalFunction::TaskBackpressure => json!({ "kind": "task_backpressure" }),
+        CanonicalFunction::TaskReturn {
+            result,
+            options: opts,
+        } => json!({
+            "kind": "task_return",
+            "result": result.as_ref().map(component_val_type),
+            "options": options(opts),
+        }),
+        CanonicalFunction::TaskWait { async_, memory } => json!({
+            "kind": "task_wait",
+            "async": async_,
+            "memory": memory,
+        }),
+        CanonicalFunction::TaskPoll { async_, memory } => json!({
+            "kind": "task_poll",
+            "async": async_,
+            "memory": memory,
+        }),
+        CanonicalFunction::TaskYield { async_ } => json!({
+            "kind": "task_yield",
+            "async": async_,
+        }),
+        CanonicalFunction::SubtaskDrop => json!({ "kind": "subtask_drop" }),
+        CanonicalFunction::StreamNew { ty } => json!({ "kind": "stream_new", "type": ty }),
+        CanonicalFunction::StreamRead { ty, options: opts } => json!({
+            "kind": "stream_read",
+            "type": ty,
+            "options": options(opts),
+        }),
+        CanonicalFunction::StreamWrite { ty, options: opts } => json!({
+            "kind": "stream_write",
+            "type": ty,
+            "options": options(opts),
+        }),
+        CanonicalFunction::StreamCancelRead { ty, async_ } => json!({
+            "kind": "stream_cancel_read",
+            "type": ty,
+            "async": async_,
+        }),
+        CanonicalFunction::StreamCancelWrite { ty, async_ } => json!({
+            "kind": "stream_cancel_write",
+            "type": ty,
+            "async": async_,
+        }),
+        CanonicalFunction::StreamCloseReadable { ty } => json!({
+            "kind": "stream_close_readable",
+            "type": ty,
+        }),
+        CanonicalFunction::StreamCloseWritable { ty } => json!({
+            "kind": "stream_close_writable",
+            "type": ty,
+        }),
+        CanonicalFunction::FutureNew { ty } => json!({ "kind": "future_new", "type": ty }),
+        CanonicalFunction::FutureRead { ty, options: opts } => json!({
+            "kind": "future_read",
+            "type": ty,
+            "options": options(opts),
+        }),
+        CanonicalFunction::FutureWrite { ty, options: opts } => json!({
+            "kind": "future_write",
+            "type": ty,
+            "options": options(opts),
+        }),
+        CanonicalFunction::FutureCancelRead { ty, async_ } => json!({
+            "kind": "future_cancel_read",
+            "type": ty,
+            "async": async_,
+        }),
+        CanonicalFunction::FutureCancelWrite { ty, async_ } => json!({
+            "kind": "future_cancel_write",
+            "type": ty,
+            "async": async_,
+        }),
+        CanonicalFunction::FutureCloseReadable { ty } => json!({
+            "kind": "future_close_readable",
+            "type": ty,
+        }),
+        CanonicalFunction::FutureCloseWritable { ty } => json!({
+            "kind": "future_close_writable",
+            "type": ty,
+        }),
+        CanonicalFunction::ErrorContextNew { options: opts } => json!({
+            "kind": "error_context_new",
+            "options": options(opts),
+        }),
+        CanonicalFunction::ErrorContextDebugMessage { options: opts } => json!({
+            "kind": "error_context_debug_message",
+            "options": options(opts),
+        }),
+        CanonicalFunction::ErrorContextDrop => json!({ "kind": "error_context_drop" }),
+    }
+}
@@ -114,6 +114,11 @@ fn parse_features(arg: &str) -> Result<WasmFeatures> {
             "component-model-values",
             WasmFeatures::COMPONENT_MODEL_VALUES,
         ),
+        ("component-model-async", WasmFeatures::COMPONENT_MODEL_ASYNC),
+        (
+            "component-model-memory64",
+            WasmFeatures::COMPONENT_MODEL_MEMORY64,
+        ),
         ("multi-memory", WasmFeatures::MULTI_MEMORY),
         ("exception-handling", WasmFeatures::EXCEPTIONS),
         ("memory64", WasmFeatures::MEMORY64),
@@ -0,0 +1,773 @@
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Convert a `*.json` WebAssembly spec test back into a `*.wast` file.
+///
+/// This subcommand is the inverse of `json-from-wast`. It reads the JSON
+/// command list, as produced by `json-from-wast` or WABT's `wast2json`, along
+/// with the `*.wasm` and `*.wat` files that it references and emits a single
+/// `*.wast` script containing every directive.
+///
+/// Binary modules and components are printed through `wasmprinter` when the
+/// printed text assembles back to exactly the same bytes, and otherwise they
+/// are emitted as `(module binary ...)`. Modules in `assert_malformed` are
+/// always emitted in their original binary or quoted form.
+///
+/// Referenced files are resolved relative to the directory of the JSON file
+/// unless `--wasm-dir` is given. This command will print the `*.wast` script to
+/// stdout unless the `-o` flag is given.
+#[derive(Parser)]
+pub struct Opts {
+    #[clap(flatten)]
+    general: wasm_tools::GeneralOpts,
+
+    #[clap(flatten)]
+    output: wasm_tools::OutputArg,
+
+    /// Where to find binary and text WebAssembly files referenced by tests.
+    ///
+    /// Defaults to the directory that contains the input JSON file.
+    #[clap(long)]
+    wasm_dir: Option<PathBuf>,
+
+    /// Input `*.json` file that will be parsed and converted to `*.wast`.
+    json: PathBuf,
+}
+
+impl Opts {
+    pub fn general_opts(&self) -> &wasm_tools::GeneralOpts {
+        &self.general
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let contents = std::fs::read_to_string(&self.json)
+            .with_context(|| format!("failed to read input json file: {:?}", self.json))?;
+        let wast: json::Wast = serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse input json file: {:?}", self.json))?;
+
+        let dir = match &self.wasm_dir {
+            Some(dir) => dir.clone(),
+            None => self
+                .json
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_default(),
+        };
+        let mut builder = WastBuilder {
+            dir: &dir,
+            dst: String::new(),
+        };
+        for (i, command) in wast.commands.iter().enumerate() {
+            if i > 0 {
+                builder.dst.push('\n');
+            }
+            builder
+                .command(command, 0)
+                .with_context(|| format!("failed to convert command on line {}", command.line()))?;
+        }
+
+        self.output
+            .output(&self.general, wasm_tools::Output::Str(&builder.dst))?;
+        Ok(())
+    }
+}
+
+struct WastBuilder<'a> {
+    dir: &'a Path,
+    dst: String,
+}
+
+impl WastBuilder<'_> {
+    fn command(&mut self, command: &json::Command, indent: usize) -> Result<()> {
+        use json::Command::*;
+
+        let pad = " ".repeat(indent);
+        match command {
+            Module { name, filename, .. } => {
+                let module = self.module(filename, None, name.as_deref(), false)?;
+                writeln!(self.dst, "{}", indented(&module, &pad, false))?;
+            }
+            AssertMalformed {
+                filename,
+                text,
+                module_type,
+                ..
+            } => self.assert_module("assert_malformed", filename, module_type, text, true, &pad)?,
+            AssertInvalid {
+                filename,
+                text,
+                module_type,
+                ..
+            } => self.assert_module("assert_invalid", filename, module_type, text, false, &pad)?,
+            AssertUnlinkable {
+                filename,
+                text,
+                module_type,
+                ..
+            } => self.assert_module(
+                "assert_unlinkable",
+                filename,
+                module_type,
+                text,
+                false,
+                &pad,
+            )?,
+            AssertUninstantiable {
+                filename,
+                text,
+                module_type,
+                ..
+            } => self.assert_module("assert_trap", filename, module_type, text, false, &pad)?,
+            Register { name, as_, .. } => {
+                write!(self.dst, "{pad}(register {}", string(as_))?;
+                if let Some(name) = name {
+                    write!(self.dst, " {}", id(name))?;
+                }
+                self.dst.push_str(")\n");
+            }
+            Action { action, .. } => {
+                writeln!(self.dst, "{pad}{}", self.action(action)?)?;
+            }
+            AssertReturn {
+                action, expected, ..
+            } => {
+                write!(self.dst, "{pad}(assert_return {}", self.action(action)?)?;
+                for expected in expected {
+                    write!(self.dst, " {}", self.constant(expected)?)?;
+                }
+                self.dst.push_str(")\n");
+            }
+            AssertTrap { action, text, .. } => {
+                writeln!(
+                    self.dst,
+                    "{pad}(assert_trap {} {})",
+                    self.action(action)?,
+                    string(text)
+                )?;
+            }
+            AssertExhaustion { action, text, .. } => {
+                writeln!(
+                    self.dst,
+                    "{pad}(assert_exhaustion {} {})",
+                    self.action(action)?,
+                    string(text)
+                )?;
+            }
+            AssertException { action, .. } => {
+                writeln!(self.dst, "{pad}(assert_exception {})", self.action(action)?)?;
+            }
+            Thread {
+                name,
+                shared_module,
+                commands,
+                ..
+            } => {
+                write!(self.dst, "{pad}(thread {}", id(name))?;
+                if let Some(module) = shared_module {
+                    write!(self.dst, " (shared (module {}))", id(module))?;
+                }
+                self.dst.push('\n');
+                for command in commands {
+                    self.command(command, indent + 2)?;
+                }
+                writeln!(self.dst, "{pad})")?;
+            }
+            Wait { thread, .. } => {
+                writeln!(self.dst, "{pad}(wait {})", id(thread))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn assert_module(
+        &mut self,
+        directive: &str,
+        filename: &str,
+        module_type: &str,
+        text: &str,
+        malformed: bool,
+        pad: &str,
+    ) -> Result<()> {
+        let module = self.module(filename, Some(module_type), None, malformed)?;
+        writeln!(self.dst, "{pad}({directive}")?;
+        writeln!(self.dst, "{}", indented(&module, pad, true))?;
+        writeln!(self.dst, "{pad}  {}", string(text))?;
+        writeln!(self.dst, "{pad})")?;
+        Ok(())
+    }
+
+    /// Renders the module or component in `filename` as text.
+    ///
+    /// Binary files are printed with `wasmprinter` unless `binary_only` is set
+    /// or the printed text doesn't assemble back to the original bytes, in
+    /// which case a `(module binary ...)` form is produced.
+    fn module(
+        &self,
+        filename: &str,
+        module_type: Option<&str>,
+        name: Option<&str>,
+        binary_only: bool,
+    ) -> Result<String> {
+        let path = self.dir.join(filename);
+        let module_type = match module_type {
+            Some(ty) => ty,
+            None => match path.extension().and_then(|s| s.to_str()) {
+                Some("wat") => "text",
+                _ => "binary",
+            },
+        };
+        let name = name.map(|n| format!(" {}", id(n))).unwrap_or_default();
+
+        match module_type {
+            "text" => {
+                let contents = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read {path:?}"))?;
+                // Quoted components are emitted by `json-from-wast` with a
+                // `(component ...)` wrapper around their quoted source, and
+                // each quoted string is followed by a space.
+                let (kind, source) = match contents
+                    .strip_prefix("(component")
+                    .and_then(|s| s.strip_suffix(')'))
+                {
+                    Some(source) => ("component", source),
+                    None => ("module", &contents[..]),
+                };
+                let source = source.strip_suffix(' ').unwrap_or(source);
+                Ok(format!("({kind} quote {})", string(source)))
+            }
+            "binary" => {
+                let bytes =
+                    std::fs::read(&path).with_context(|| format!("failed to read {path:?}"))?;
+                let kind = if wasmparser::Parser::is_component(&bytes) {
+                    "component"
+                } else {
+                    "module"
+                };
+                if !binary_only {
+                    if let Some(text) = print_exact(&bytes, kind, &name) {
+                        return Ok(text);
+                    }
+                }
+                let mut ret = format!("({kind}{name} binary");
+                for chunk in bytes.chunks(16) {
+                    write!(ret, "\n  {}", bytes_string(chunk))?;
+                }
+                ret.push(')');
+                Ok(ret)
+            }
+            other => bail!("unknown module type `{other}`"),
+        }
+    }
+
+    fn action(&self, action: &json::Action) -> Result<String> {
+        let (kind, module, field) = match action {
+            json::Action::Invoke { module, field, .. } => ("invoke", module, field),
+            json::Action::Get { module, field } => ("get", module, field),
+        };
+        let mut ret = format!("({kind}");
+        if let Some(module) = module {
+            write!(ret, " {}", id(module))?;
+        }
+        write!(ret, " {}", string(field))?;
+        if let json::Action::Invoke { args, .. } = action {
+            for arg in args {
+                write!(ret, " {}", self.constant(arg)?)?;
+            }
+        }
+        ret.push(')');
+        Ok(ret)
+    }
+
+    fn constant(&self, val: &json::Const) -> Result<String> {
+        use json::Const::*;
+
+        Ok(match val {
+            I32 { value } => format!("(i32.const {value})"),
+            I64 { value } => format!("(i64.const {value})"),
+            F32 { value } => format!("(f32.const {})", f32_pattern(value)?),
+            F64 { value } => format!("(f64.const {})", f64_pattern(value)?),
+            V128 { lane_type, value } => {
+                let (shape, lane): (_, fn(&str) -> Result<String>) = match lane_type.as_str() {
+                    "i8" => ("i8x16", |s| Ok(s.to_string())),
+                    "i16" => ("i16x8", |s| Ok(s.to_string())),
+                    "i32" => ("i32x4", |s| Ok(s.to_string())),
+                    "i64" => ("i64x2", |s| Ok(s.to_string())),
+                    "f32" => ("f32x4", f32_pattern),
+                    "f64" => ("f64x2", f64_pattern),
+                    other => bail!("unknown v128 lane type `{other}`"),
+                };
+                let mut ret = format!("(v128.const {shape}");
+                for value in value {
+                    write!(ret, " {}", lane(value)?)?;
+                }
+                ret.push(')');
+                ret
+            }
+            FuncRef { value: None } => "(ref.func)".to_string(),
+            FuncRef { value: Some(v) } if v == "null" => "(ref.null func)".to_string(),
+            FuncRef { value: Some(_) } => bail!("unsupported `funcref` value"),
+            ExternRef { value } if value == "null" => "(ref.null extern)".to_string(),
+            ExternRef { value } => format!("(ref.extern {value})"),
+            AnyRef { value: None } => "(ref.any)".to_string(),
+            AnyRef { value: Some(v) } if v == "null" => "(ref.null any)".to_string(),
+            AnyRef { value: Some(v) } => format!("(ref.host {v})"),
+            ExnRef { value: Some(v) } if v == "null" => "(ref.null exn)".to_string(),
+            ExnRef { .. } => bail!("unsupported `exnref` value"),
+            EqRef => "(ref.eq)".to_string(),
+            ArrayRef => "(ref.array)".to_string(),
+            StructRef => "(ref.struct)".to_string(),
+            I31Ref => "(ref.i31)".to_string(),
+            NullRef => "(ref.null none)".to_string(),
+            NullFuncRef => "(ref.null nofunc)".to_string(),
+            NullExternRef => "(ref.null noextern)".to_string(),
+            RefNull => "(ref.null)".to_string(),
+            Either { values } => {
+                let mut ret = "(either".to_string();
+                for value in values {
+                    write!(ret, " {}", self.constant(value)?)?;
+                }
+                ret.push(')');
+                ret
+            }
+            Component { value } => format!("({})", self.component_val(value)?),
+        })
+    }
+
+    /// Renders a component value, without its surrounding parentheses.
+    fn component_val(&self, val: &json::ComponentConst) -> Result<String> {
+        use json::ComponentConst as C;
+
+        let payload = |val: &Option<Box<json::ComponentConst>>| -> Result<String> {
+            Ok(match val {
+                Some(v) => format!(" ({})", self.component_val(v)?),
+                None => String::new(),
+            })
+        };
+        let list = |kind: &str, vals: &[json::ComponentConst]| -> Result<String> {
+            let mut ret = format!("{kind}.const");
+            for val in vals {
+                write!(ret, " ({})", self.component_val(val)?)?;
+            }
+            Ok(ret)
+        };
+        Ok(match val {
+            C::Bool { value } => format!("bool.const {value}"),
+            C::U8 { value } => format!("u8.const {value}"),
+            C::S8 { value } => format!("s8.const {value}"),
+            C::U16 { value } => format!("u16.const {value}"),
+            C::S16 { value } => format!("s16.const {value}"),
+            C::U32 { value } => format!("u32.const {value}"),
+            C::S32 { value } => format!("s32.const {value}"),
+            C::U64 { value } => format!("u64.const {value}"),
+            C::S64 { value } => format!("s64.const {value}"),
+            C::F32 { value } => format!("f32.const {}", f32_pattern(value)?),
+            C::F64 { value } => format!("f64.const {}", f64_pattern(value)?),
+            C::Char { value } => format!("char.const {}", string(&value.to_string())),
+            C::String { value } => format!("str.const {}", string(value)),
+            C::List { values } => list("list", values)?,
+            C::Tuple { values } => list("tuple", values)?,
+            C::Record { fields } => {
+                let mut ret = "record.const".to_string();
+                for field in fields {
+                    write!(
+                        ret,
+                        " (field {} {})",
+                        string(&field.name),
+                        self.component_val(&field.value)?
+                    )?;
+                }
+                ret
+            }
+            C::Variant { case, value } => {
+                format!("variant.const {}{}", string(case), payload(value)?)
+            }
+            C::Enum { case } => format!("enum.const {}", string(case)),
+            C::OptionNone => "option.none".to_string(),
+            C::OptionSome { value } => format!("option.some ({})", self.component_val(value)?),
+            C::ResultOk { value } => format!("result.ok{}", payload(value)?),
+            C::ResultErr { value } => format!("result.err{}", payload(value)?),
+            C::Flags { flags } => {
+                let mut ret = "flags.const".to_string();
+                for flag in flags {
+                    write!(ret, " {}", string(flag))?;
+                }
+                ret
+            }
+        })
+    }
+}
+
+/// Attempts to print `bytes` with `wasmprinter`, returning the text only if it
+/// assembles back to precisely the same bytes.
+fn print_exact(bytes: &[u8], kind: &str, name: &str) -> Option<String> {
+    let mut text = wasmprinter::print_bytes(bytes).ok()?;
+
+    // Replace whatever identifier `wasmprinter` chose for the module, if any,
+    // with the name that the script refers to it with. If that loses a name
+    // from the name section then the bytes won't match below.
+    let prefix = format!("({kind}");
+    let rest = text.strip_prefix(&prefix)?;
+    let rest = match rest.strip_prefix(" $\"") {
+        Some(rest) => {
+            let mut escaped = false;
+            let end = rest.find(|c| {
+                let done = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                done
+            })?;
+            &rest[end + 1..]
+        }
+        None => match rest.strip_prefix(" $") {
+            Some(rest) => rest.trim_start_matches(|c: char| !c.is_whitespace() && c != ')'),
+            None => rest,
+        },
+    };
+    text = format!("{prefix}{name}{rest}");
+
+    match wat::parse_str(&text) {
+        Ok(reassembled) if reassembled == bytes => Some(text.trim_end().to_string()),
+        _ => None,
+    }
+}
+
+/// Indents every line of `text` by `pad`, plus two more spaces if `nested`.
+fn indented(text: &str, pad: &str, nested: bool) -> String {
+    let extra = if nested { "  " } else { "" };
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{pad}{extra}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn id(name: &str) -> String {
+    // `wast2json` includes the leading `$` in names while `json-from-wast`
+    // does not, so handle both.
+    let name = name.strip_prefix('$').unwrap_or(name);
+    if !name.is_empty()
+        && name.chars().all(|c| {
+            c.is_ascii_graphic()
+                && !matches!(c, '"' | ',' | ';' | '[' | ']' | '{' | '}' | '(' | ')')
+        })
+    {
+        format!("${name}")
+    } else {
+        format!("${}", string(name))
+    }
+}
+
+fn string(s: &str) -> String {
+    let mut ret = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\t' => ret.push_str("\\t"),
+            c if c.is_control() => ret.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+fn bytes_string(bytes: &[u8]) -> String {
+    let mut ret = String::from("\"");
+    for byte in bytes {
+        if byte.is_ascii_graphic() && *byte != b'"' && *byte != b'\\' || *byte == b' ' {
+            ret.push(*byte as char);
+        } else {
+            ret.push_str(&format!("\\{byte:02x}"));
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+fn f32_pattern(s: &str) -> Result<String> {
+    if s.starts_with("nan:") {
+        return Ok(s.to_string());
+    }
+    let bits = s
+        .parse::<u32>()
+        .with_context(|| format!("invalid f32 bit pattern `{s}`"))?;
+    let sign = if bits >> 31 != 0 { "-" } else { "" };
+    let mantissa = bits & 0x7f_ffff;
+    Ok(match (bits >> 23) & 0xff {
+        0xff if mantissa == 0 => format!("{sign}inf"),
+        0xff => format!("{sign}nan:{mantissa:#x}"),
+        _ => format!("{:?}", f32::from_bits(bits)),
+    })
+}
+
+fn f64_pattern(s: &str) -> Result<String> {
+    if s.starts_with("nan:") {
+        return Ok(s.to_string());
+    }
+    let bits = s
+        .parse::<u64>()
+        .with_context(|| format!("invalid f64 bit pattern `{s}`"))?;
+    let sign = if bits >> 63 != 0 { "-" } else { "" };
+    let mantissa = bits & 0xf_ffff_ffff_ffff;
+    Ok(match (bits >> 52) & 0x7ff {
+        0x7ff if mantissa == 0 => format!("{sign}inf"),
+        0x7ff => format!("{sign}nan:{mantissa:#x}"),
+        _ => format!("{:?}", f64::from_bits(bits)),
+    })
+}
+
+mod json {
+    use serde_derive::Deserialize;
+
+    #[derive(Deserialize)]
+    pub struct Wast {
+        pub commands: Vec<Command>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum Command {
+        Module {
+            line: u32,
+            name: Option<String>,
+            filename: String,
+        },
+        AssertMalformed {
+            line: u32,
+            filename: String,
+            text: String,
+            module_type: String,
+        },
+        AssertInvalid {
+            line: u32,
+            filename: String,
+            text: String,
+            module_type: String,
+        },
+        Register {
+            line: u32,
+            name: Option<String>,
+            #[serde(rename = "as")]
+            as_: String,
+        },
+        AssertUnlinkable {
+            line: u32,
+            filename: String,
+            text: String,
+            module_type: String,
+        },
+        AssertReturn {
+            line: u32,
+            action: Action,
+            expected: Vec<Const>,
+        },
+        Action {
+            line: u32,
+            action: Action,
+        },
+        AssertTrap {
+            line: u32,
+            action: Action,
+            text: String,
+        },
+        AssertExhaustion {
+            line: u32,
+            action: Action,
+            text: String,
+        },
+        AssertException {
+            line: u32,
+            action: Action,
+        },
+        AssertUninstantiable {
+            line: u32,
+            filename: String,
+            text: String,
+            module_type: String,
+        },
+        Thread {
+            line: u32,
+            name: String,
+            shared_module: Option<String>,
+            commands: Vec<Command>,
+        },
+        Wait {
+            line: u32,
+            thread: String,
+        },
+    }
+
+    impl Command {
+        pub fn line(&self) -> u32 {
+            use Command::*;
+
+            match self {
+                Module { line, .. }
+                | AssertMalformed { line, .. }
+                | AssertInvalid { line, .. }
+                | Register { line, .. }
+                | AssertUnlinkable { line, .. }
+                | AssertReturn { line, .. }
+                | Action { line, .. }
+                | AssertTrap { line, .. }
+                | AssertExhaustion { line, .. }
+                | AssertException { line, .. }
+                | AssertUninstantiable { line, .. }
+                | Thread { line, .. }
+                | Wait { line, .. } => *line,
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum Action {
+        Invoke {
+            module: Option<String>,
+            field: String,
+            args: Vec<Const>,
+        },
+        Get {
+            module: Option<String>,
+            field: String,
+        },
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    pub enum Const {
+        I32 {
+            value: String,
+        },
+        I64 {
+            value: String,
+        },
+        F32 {
+            value: String,
+        },
+        F64 {
+            value: String,
+        },
+        FuncRef {
+            value: Option<String>,
+        },
+        ExternRef {
+            value: String,
+        },
+        AnyRef {
+            value: Option<String>,
+        },
+        V128 {
+            lane_type: String,
+            value: Vec<String>,
+        },
+        Either {
+            values: Vec<Const>,
+        },
+        EqRef,
+        ArrayRef,
+        StructRef,
+        I31Ref,
+        NullRef,
+        NullFuncRef,
+        NullExternRef,
+        ExnRef {
+            value: Option<String>,
+        },
+        RefNull,
+        Component {
+            value: ComponentConst,
+        },
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    pub enum ComponentConst {
+        Bool {
+            value: bool,
+        },
+        U8 {
+            value: String,
+        },
+        S8 {
+            value: String,
+        },
+        U16 {
+            value: String,
+        },
+        S16 {
+            value: String,
+        },
+        U32 {
+            value: String,
+        },
+        S32 {
+            value: String,
+        },
+        U64 {
+            value: String,
+        },
+        S64 {
+            value: String,
+        },
+        F32 {
+            value: String,
+        },
+        F64 {
+            value: String,
+        },
+        Char {
+            value: char,
+        },
+        String {
+            value: String,
+        },
+        List {
+            values: Vec<ComponentConst>,
+        },
+        Record {
+            fields: Vec<ComponentField>,
+        },
+        Tuple {
+            values: Vec<ComponentConst>,
+        },
+        Variant {
+            case: String,
+            value: Option<Box<ComponentConst>>,
+        },
+        Enum {
+            case: String,
+        },
+        #[serde(rename = "option.none")]
+        OptionNone,
+        #[serde(rename = "option.some")]
+        OptionSome {
+            value: Box<ComponentConst>,
+        },
+        #[serde(rename = "result.ok")]
+        ResultOk {
+            value: Option<Box<ComponentConst>>,
+        },
+        #[serde(rename = "result.err")]
+        ResultErr {
+            value: Option<Box<ComponentConst>>,
+        },
+        Flags {
+            flags: Vec<String>,
+        },
+    }
+
+    #[derive(Deserialize)]
+    pub struct ComponentField {
+        pub name: String,
+        pub value: ComponentConst,
+    }
+}
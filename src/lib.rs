@@ -7,6 +7,9 @@ use std::io::{BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use termcolor::{Ansi, ColorChoice, NoColor, StandardStream, WriteColor};
 
+#[cfg(feature = "addr2line")]
+pub mod addr2line;
+
 #[derive(clap::Parser)]
 pub struct GeneralOpts {
     /// Use verbose output (-v info, -vv debug, -vvv trace).
@@ -103,7 +106,8 @@ pub enum Output<'a> {
         wasm: &'a [u8],
         config: wasmprinter::Config,
     },
-    Json(&'a str),
+    /// Text, such as JSON or a `*.wast` script, written out as-is.
+    Str(&'a str),
 }
 
 impl InputOutput {
@@ -182,7 +186,7 @@ impl OutputArg {
                 }
                 Ok(())
             }
-            Output::Json(s) => self.output_str(s),
+            Output::Str(s) => self.output_str(s),
             #[cfg(feature = "component")]
             Output::Wit {
                 resolve,
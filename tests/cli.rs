@@ -15,6 +15,15 @@
 //! additionally contains `*.stdout` and `*.stderr` files to assert the output
 //! of the subcommand. Files are not present if the stdout/stderr are empty.
 //!
+//! A test may have several directives, which are executed in order and whose
+//! outputs are concatenated. Occurrences of `%tmpdir` in arguments are
+//! replaced with the path to an empty temporary directory shared by all
+//! commands of the test, and the path is replaced with `%tmpdir` again in
+//! their output, for example:
+//!
+//!     ;; RUN: component unbundle % --out-dir %tmpdir
+//!     ;; RUN: component bundle %tmpdir/manifest.json -t
+//!
 //! This also supports a limited form of piping along the lines of:
 //!
 //!     ;; RUN: strip % | objdump
@@ -22,10 +31,15 @@
 //! where a `|` will execute the first subcommand and pipe its stdout into the
 //! stdin of the next command.
 //!
+//! Every `*.wat` and `*.wit` file must have a `RUN` or `FAIL` directive, except
+//! for those within a `fixtures` directory, which hold inputs shared by tests,
+//! or within a `deps` directory, which hold the dependencies of WIT packages
+//! used as inputs.
+//!
 //! Use `BLESS=1` in the environment to auto-update expectation files. Be sure
 //! to look at the diff!
 
-use anyhow::{anyhow, bail, Context, Result};
+use anyhow::{bail, Context, Result};
 use libtest_mimic::{Arguments, Trial};
 use pretty_assertions::StrComparison;
 use std::env;
@@ -64,47 +78,80 @@ fn wasm_tools_exe() -> Command {
 
 fn run_test(test: &Path, bless: bool) -> Result<()> {
     let contents = std::fs::read_to_string(test)?;
-    let (line, should_fail) = contents
-        .lines()
-        .filter_map(|l| {
-            let run = l.strip_prefix(";; RUN: ").or(l.strip_prefix("// RUN: "));
-            let fail = l.strip_prefix(";; FAIL: ").or(l.strip_prefix("// FAIL: "));
-            run.map(|l| (l, false)).or(fail.map(|l| (l, true)))
-        })
-        .next()
-        .ok_or_else(|| anyhow!("no line found with `;; RUN: ` directive"))?;
+    let directives = directives(&contents);
+    if directives.is_empty() {
+        bail!("no line found with `;; RUN: ` directive");
+    }
 
-    let mut cmd = wasm_tools_exe();
-    let mut stdin = None;
-    for arg in line.split_whitespace() {
-        if arg == "|" {
-            let output = execute(&mut cmd, stdin.as_deref(), false)?;
-            stdin = Some(output.stdout);
-            cmd = wasm_tools_exe();
-        } else if arg == "%" {
-            cmd.arg(test);
-        } else {
-            cmd.arg(arg);
-        }
+    let tmpdir = tempfile::TempDir::new()?;
+    let tmpdir = tmpdir.path().to_str().unwrap();
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    for (line, should_fail) in directives {
+        let output = run_directive(test, line, should_fail, tmpdir)?;
+        stdout.extend(replace_tmpdir(output.stdout, tmpdir));
+        stderr.extend(replace_tmpdir(output.stderr, tmpdir));
     }
 
-    let output = execute(&mut cmd, stdin.as_deref(), should_fail)?;
     let extension = test.extension().unwrap().to_str().unwrap();
     assert_output(
         bless,
-        &output.stdout,
+        &stdout,
         &test.with_extension(&format!("{extension}.stdout")),
     )
     .context("failed to check stdout expectation (auto-update with BLESS=1)")?;
     assert_output(
         bless,
-        &output.stderr,
+        &stderr,
         &test.with_extension(&format!("{extension}.stderr")),
     )
     .context("failed to check stderr expectation (auto-update with BLESS=1)")?;
     Ok(())
 }
 
+/// Executes the commands of a single `RUN` or `FAIL` directive, piping the
+/// output of each command into the next.
+fn run_directive(test: &Path, line: &str, should_fail: bool, tmpdir: &str) -> Result<Output> {
+    let mut cmd = wasm_tools_exe();
+    let mut stdin = None;
+    for arg in line.split_whitespace() {
+        if arg == "|" {
+            let output = execute(&mut cmd, stdin.as_deref(), false)?;
+            stdin = Some(output.stdout);
+            cmd = wasm_tools_exe();
+        } else if arg == "%" {
+            cmd.arg(test);
+        } else if arg.contains("%tmpdir") {
+            cmd.arg(arg.replace("%tmpdir", tmpdir));
+        } else {
+            cmd.arg(arg);
+        }
+    }
+    execute(&mut cmd, stdin.as_deref(), should_fail)
+}
+
+/// Returns the commands of the `RUN` and `FAIL` directives in `contents`, in
+/// order, and whether each is expected to fail.
+fn directives(contents: &str) -> Vec<(&str, bool)> {
+    contents
+        .lines()
+        .filter_map(|l| {
+            let run = l.strip_prefix(";; RUN: ").or(l.strip_prefix("// RUN: "));
+            let fail = l.strip_prefix(";; FAIL: ").or(l.strip_prefix("// FAIL: "));
+            run.map(|l| (l, false)).or(fail.map(|l| (l, true)))
+        })
+        .collect()
+}
+
+/// Replaces occurrences of the path `tmpdir` in textual `output` with
+/// `%tmpdir` so that expectations don't depend on where it's located.
+fn replace_tmpdir(output: Vec<u8>, tmpdir: &str) -> Vec<u8> {
+    match String::from_utf8(output) {
+        Ok(output) => output.replace(tmpdir, "%tmpdir").into_bytes(),
+        Err(e) => e.into_bytes(),
+    }
+}
+
 fn execute(cmd: &mut Command, stdin: Option<&[u8]>, should_fail: bool) -> Result<Output> {
     cmd.stdin(Stdio::piped());
     cmd.stdout(Stdio::piped());
@@ -180,7 +227,9 @@ fn find_tests(path: &Path, tests: &mut Vec<PathBuf>) {
     for f in path.read_dir().unwrap() {
         let f = f.unwrap();
         if f.file_type().unwrap().is_dir() {
-            find_tests(&f.path(), tests);
+            if f.file_name() != "fixtures" && f.file_name() != "deps" {
+                find_tests(&f.path(), tests);
+            }
             continue;
         }
         match f.path().extension().and_then(|s| s.to_str()) {
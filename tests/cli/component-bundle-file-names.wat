@@ -0,0 +1,17 @@
+;; RUN: component unbundle % --out-dir %tmpdir
+;; RUN: component bundle %tmpdir/manifest.json -t
+;;
+;; The second module named `x` would be written to `x-2.wasm`, which is already
+;; taken by the first module, so each module still gets a file of its own.
+
+(component
+  (core module $a (@name "x-2")
+    (func (export "a"))
+  )
+  (core module $b (@name "x")
+    (func (export "b"))
+  )
+  (core module $c (@name "x")
+    (func (export "c"))
+  )
+)
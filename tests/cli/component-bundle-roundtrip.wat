@@ -0,0 +1,22 @@
+;; RUN: component unbundle % --out-dir %tmpdir
+;; RUN: component bundle %tmpdir/manifest.json -t
+;;
+;; Splits this component into its core modules and then rebuilds it, which
+;; should produce the same component again.
+
+(component
+  (core module $libc
+    (memory (export "memory") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      unreachable)
+  )
+  (core module $main
+    (import "libc" "memory" (memory 1))
+    (func (export "run") (result i32)
+      i32.const 42)
+  )
+  (core instance $libc (instantiate $libc))
+  (core instance $main (instantiate $main (with "libc" (instance $libc))))
+  (func (export "run") (result u32)
+    (canon lift (core func $main "run")))
+)
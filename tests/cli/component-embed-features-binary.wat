@@ -0,0 +1,68 @@
+;; RUN: component embed --dummy --features bar % | component wit
+
+(component
+  (type (;0;)
+    (component
+      (type (;0;)
+        (instance
+          (type (;0;) (record (field "x" u32)))
+          (export (;1;) "r" (type (eq 0)))
+          (type (;2;) (func (param "x" 1)))
+          (export (;0;) "f" (func (type 2)))
+          (type (;3;) (func))
+          (export (;1;) "g" (func (type 3)))
+          (export (;2;) "h" (func (type 3)))
+        )
+      )
+      (export (;0;) "a:b/i@1.0.0" (instance (type 0)))
+    )
+  )
+  (export (;1;) "i" (type 0))
+  (type (;2;)
+    (component
+      (type (;0;)
+        (instance
+          (type (;0;) (func))
+          (export (;0;) "k" (func (type 0)))
+        )
+      )
+      (export (;0;) "a:b/j@1.0.0" (instance (type 0)))
+    )
+  )
+  (export (;3;) "j" (type 2))
+  (type (;4;)
+    (component
+      (type (;0;)
+        (component
+          (type (;0;)
+            (instance
+              (type (;0;) (record (field "x" u32)))
+              (export (;1;) "r" (type (eq 0)))
+              (type (;2;) (func (param "x" 1)))
+              (export (;0;) "f" (func (type 2)))
+              (type (;3;) (func))
+              (export (;1;) "g" (func (type 3)))
+              (export (;2;) "h" (func (type 3)))
+            )
+          )
+          (import "a:b/i@1.0.0" (instance (;0;) (type 0)))
+          (type (;1;)
+            (instance
+              (type (;0;) (func))
+              (export (;0;) "k" (func (type 0)))
+            )
+          )
+          (import "a:b/j@1.0.0" (instance (;1;) (type 1)))
+          (type (;2;) (func))
+          (export (;0;) "run" (func (type 2)))
+        )
+      )
+      (export (;0;) "a:b/w@1.0.0" (component (type 0)))
+    )
+  )
+  (export (;5;) "w" (type 4))
+  (@custom "package-docs" "\01{\22docs\22:\22RUN: component wit % --wasm | component wit\22,\22worlds\22:{\22w\22:{\22funcs\22:{\22run\22:{\22stability\22:{\22unstable\22:{\22feature\22:\22foo\22}}}},\22interface_import_stability\22:{\22a:b/j@1.0.0\22:{\22unstable\22:{\22feature\22:\22bar\22}}}}},\22interfaces\22:{\22i\22:{\22funcs\22:{\22f\22:{\22stability\22:{\22unstable\22:{\22feature\22:\22foo\22}}},\22g\22:{\22stability\22:{\22unstable\22:{\22feature\22:\22bar\22}}}},\22types\22:{\22r\22:{\22stability\22:{\22unstable\22:{\22feature\22:\22foo\22}}}}},\22j\22:{\22stability\22:{\22unstable\22:{\22feature\22:\22bar\22}}}}}")
+  (@producers
+    (processed-by "wit-component" "0.211.1")
+  )
+)
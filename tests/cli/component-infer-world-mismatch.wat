@@ -0,0 +1,6 @@
+;; FAIL: component new % --infer-world --deps tests/cli/infer-world/deps
+
+(module
+  (import "b:host/log@1.0.0" "log" (func (param i32)))
+  (memory (export "memory") 1)
+)
@@ -0,0 +1,14 @@
+;; RUN: component new % --infer-world --deps tests/cli/infer-world/deps | component wit
+
+(module
+  (import "b:host/log@1.0.0" "log" (func (param i32 i32)))
+  (import "b:host/log@1.0.0" "stdout" (func (result i32)))
+  (import "a:io/streams" "[method]output-stream.write" (func (param i32 i32 i32)))
+  (import "a:io/streams" "[resource-drop]output-stream" (func (param i32)))
+  (import "$root" "now" (func (result i64)))
+  (memory (export "memory") 1)
+  (func (export "b:host/api@1.0.0#run") (param i32 i32) (result i32) unreachable)
+  (func (export "add") (param i32 i32) (result i32) unreachable)
+  (func (export "_initialize"))
+  (func (export "cabi_realloc") (param i32 i32 i32 i32) (result i32) unreachable)
+)
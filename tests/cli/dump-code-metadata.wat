@@ -0,0 +1,10 @@
+;; RUN: dump %
+
+(module
+  (func
+    (@metadata.code.test "a")
+    nop
+    (@metadata.code.test "bc")
+    nop
+  )
+)
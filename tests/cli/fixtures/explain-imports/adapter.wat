@@ -0,0 +1,10 @@
+(module
+  (import "foo:foo/host" "log" (func $log (param i32)))
+  (import "foo:foo/host" "read" (func $read (result i32)))
+  (func $write (export "write") (param i32)
+    (call $emit (local.get 0)))
+  (func $emit (param i32)
+    (call $log (local.get 0)))
+  (func (export "read") (result i32)
+    (call $read))
+)
@@ -0,0 +1,6 @@
+(module
+  (import "old" "write" (func $write (param i32)))
+  (memory (export "memory") 1)
+  (func (export "run")
+    (call $write (i32.const 1)))
+)
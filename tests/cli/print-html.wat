@@ -0,0 +1,23 @@
+;; RUN: print --html %
+
+(module
+  (type $t (func (param i32) (result i32)))
+  (global $g (mut i32) (i32.const 0))
+  (memory 1)
+  (func $f (type $t) (param $x i32) (result i32)
+    block $exit
+      local.get $x
+      br_if $exit
+      global.get $g
+      call $f
+      drop
+    end
+    local.get 0)
+  (func (export "run")
+    loop
+      i32.const 1
+      br_if 0
+    end)
+  (data (i32.const 0) "hello")
+  (data (i32.const 16) "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef<&>")
+)
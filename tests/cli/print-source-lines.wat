@@ -0,0 +1,11 @@
+;; RUN: print --source-lines %
+
+(module
+  (func (export "add_one") (param i32) (result i32)
+    local.get 0
+    i32.const 1
+    i32.add)
+  (@custom ".debug_abbrev" "\01\11\00\03\08\10\17\11\01\12\06\00\00\00")
+  (@custom ".debug_info" "\31\00\00\00\04\00\00\00\00\00\04\01\74\65\73\74\73\2f\63\6c\69\2f\73\6f\75\72\63\65\2d\6c\69\6e\65\73\2f\61\64\64\2e\63\00\00\00\00\00\00\00\00\00\09\00\00\00")
+  (@custom ".debug_line" "\56\00\00\00\04\00\34\00\00\00\01\01\01\fb\0e\0d\00\01\01\01\01\00\00\00\01\00\00\01\00\74\65\73\74\73\2f\63\6c\69\2f\73\6f\75\72\63\65\2d\6c\69\6e\65\73\2f\61\64\64\2e\63\00\00\00\00\00\00\05\02\03\00\00\00\03\01\01\02\02\03\01\01\02\02\01\02\01\03\01\01\02\01\00\01\01")
+)
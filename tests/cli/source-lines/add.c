@@ -0,0 +1,4 @@
+int add_one(int x) {
+  int y = x;
+  return y + 1;
+}
@@ -0,0 +1,12 @@
+;; RUN: to-json --pretty %
+
+(component
+  (type $ty (func (param "x" string) (result (list u8))))
+  (import "f" (func $f (type $ty)))
+  (core module $m
+    (func (export "run") i32.const 0 drop)
+  )
+  (core instance $i (instantiate $m))
+  (alias core export $i "run" (core func $run))
+  (func (export "run") (canon lift (core func $run)))
+)
@@ -0,0 +1,22 @@
+;; RUN: to-json --pretty %
+
+(module
+  (type $t (func (param i32) (result i32)))
+  (import "env" "g" (global $imported i32))
+  (table 1 funcref)
+  (memory 1 2)
+  (global $g (mut i64) (i64.const 9007199254740993))
+  (func $f (type $t) (param $x i32) (result i32)
+    (local f64)
+    block (result i32)
+      local.get $x
+      br_table 0 0
+    end
+    f64.const 1.5
+    local.set 1
+    i32.load offset=4
+  )
+  (export "f" (func $f))
+  (elem (i32.const 0) func $f)
+  (data (i32.const 8) "hi")
+)
@@ -0,0 +1 @@
+;; RUN: wast-from-json tests/cli/wast-from-json/component.json
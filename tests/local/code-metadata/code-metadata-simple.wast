@@ -0,0 +1,36 @@
+(module
+  (func $main (result i32)
+    (local i32)
+    (@metadata.code.test "hello")
+    i32.const 0
+    local.set 0
+    (@metadata.code.test "\00\01")
+    (@metadata.code.other "a" "b")
+    local.get 0
+    (@metadata.code.branch_hint "\01")
+    if
+      (@metadata.code.test "")
+      nop
+    end
+    local.get 0)
+)
+
+(module
+  (import "" "" (func))
+
+  (func
+    (@metadata.code.test "first")
+    nop
+  )
+  (func
+    (@metadata.code.test "second")
+    (if (i32.const 0) (then (@metadata.code.test "nested") nop))
+  )
+)
+
+(assert_malformed
+  (module quote
+    "(func (@metadata.code.test 1) nop)"
+  )
+  "expected a string"
+)
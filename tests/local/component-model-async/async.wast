@@ -0,0 +1,205 @@
+;; future, stream and error-context types
+(component
+  (type $f1 (future))
+  (type $f2 (future u32))
+  (type $s1 (stream))
+  (type $s2 (stream string))
+  (type $e error-context)
+  (type (func (param "x" $f2) (param "y" $s2) (result $e)))
+  (type (list (stream (future (list u8)))))
+)
+
+;; async lift with and without a callback
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32) unreachable)
+    (func (export "callback") (param i32 i32 i32 i32) (result i32) unreachable)
+    (func (export "stackful") (param i32 i32))
+    (func (export "with-callback") (param i32 i32) (result i32) unreachable)
+  )
+  (core instance $i (instantiate $m))
+  (func (export "a") (param "x" string)
+    (canon lift (core func $i "stackful") async
+      (memory $i "memory") (realloc (func $i "realloc")))
+  )
+  (func (export "b") (param "x" string) (result u32)
+    (canon lift (core func $i "with-callback") async (callback (func $i "callback"))
+      (memory $i "memory") (realloc (func $i "realloc")))
+  )
+)
+
+;; async lower
+(component
+  (import "f" (func $f (param "x" u32) (result string)))
+  (import "g" (func $g (param "a" u64) (param "b" u64) (param "c" u64)
+    (param "d" u64) (param "e" u64)))
+  (core module $libc (memory (export "memory") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32) unreachable))
+  (core instance $libc (instantiate $libc))
+  (core func $f (canon lower (func $f) async
+    (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (core func $g (canon lower (func $g) async (memory $libc "memory")))
+  (core module $m
+    (import "" "f" (func (param i32 i32) (result i32)))
+    (import "" "g" (func (param i32) (result i32)))
+  )
+  (core instance (instantiate $m
+    (with "" (instance (export "f" (func $f)) (export "g" (func $g))))))
+)
+
+;; task and subtask intrinsics
+(component
+  (core module $libc (memory (export "memory") 1))
+  (core instance $libc (instantiate $libc))
+  (core func $backpressure (canon task.backpressure))
+  (core func $return0 (canon task.return))
+  (core func $return1 (canon task.return (result u32)))
+  (core func $return2 (canon task.return (result string) (memory $libc "memory")))
+  (core func $wait (canon task.wait async (memory $libc "memory")))
+  (core func $poll (canon task.poll (memory $libc "memory")))
+  (core func $yield (canon task.yield async))
+  (core func $drop (canon subtask.drop))
+  (core module $m
+    (import "" "backpressure" (func (param i32)))
+    (import "" "return0" (func))
+    (import "" "return1" (func (param i32)))
+    (import "" "return2" (func (param i32 i32)))
+    (import "" "wait" (func (param i32) (result i32)))
+    (import "" "poll" (func (param i32) (result i32)))
+    (import "" "yield" (func))
+    (import "" "drop" (func (param i32)))
+  )
+  (core instance (instantiate $m
+    (with "" (instance
+      (export "backpressure" (func $backpressure))
+      (export "return0" (func $return0))
+      (export "return1" (func $return1))
+      (export "return2" (func $return2))
+      (export "wait" (func $wait))
+      (export "poll" (func $poll))
+      (export "yield" (func $yield))
+      (export "drop" (func $drop))
+    ))
+  ))
+)
+
+;; stream, future and error-context intrinsics
+(component
+  (core module $libc (memory (export "memory") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32) unreachable))
+  (core instance $libc (instantiate $libc))
+  (type $s (stream string))
+  (type $f (future u32))
+  (core func $stream.new (canon stream.new $s))
+  (core func $stream.read (canon stream.read $s async
+    (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (core func $stream.write (canon stream.write $s (memory $libc "memory")))
+  (core func $stream.cancel-read (canon stream.cancel-read $s async))
+  (core func $stream.cancel-write (canon stream.cancel-write $s))
+  (core func $stream.close-readable (canon stream.close-readable $s))
+  (core func $stream.close-writable (canon stream.close-writable $s))
+  (core func $future.new (canon future.new $f))
+  (core func $future.read (canon future.read $f (memory $libc "memory")))
+  (core func $future.write (canon future.write $f (memory $libc "memory")))
+  (core func $future.cancel-read (canon future.cancel-read $f))
+  (core func $future.cancel-write (canon future.cancel-write $f async))
+  (core func $future.close-readable (canon future.close-readable $f))
+  (core func $future.close-writable (canon future.close-writable $f))
+  (core func $error-context.new (canon error-context.new (memory $libc "memory")))
+  (core func $error-context.debug-message (canon error-context.debug-message
+    string-encoding=utf16 (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (core func $error-context.drop (canon error-context.drop))
+  (core module $m
+    (import "" "stream.new" (func (result i64)))
+    (import "" "stream.read" (func (param i32 i32 i32) (result i32)))
+    (import "" "stream.write" (func (param i32 i32 i32) (result i32)))
+    (import "" "stream.cancel-read" (func (param i32) (result i32)))
+    (import "" "stream.close-writable" (func (param i32)))
+    (import "" "future.new" (func (result i64)))
+    (import "" "future.read" (func (param i32 i32) (result i32)))
+    (import "" "error-context.new" (func (param i32 i32) (result i32)))
+    (import "" "error-context.debug-message" (func (param i32 i32)))
+    (import "" "error-context.drop" (func (param i32)))
+  )
+  (core instance (instantiate $m
+    (with "" (instance
+      (export "stream.new" (func $stream.new))
+      (export "stream.read" (func $stream.read))
+      (export "stream.write" (func $stream.write))
+      (export "stream.cancel-read" (func $stream.cancel-read))
+      (export "stream.close-writable" (func $stream.close-writable))
+      (export "future.new" (func $future.new))
+      (export "future.read" (func $future.read))
+      (export "error-context.new" (func $error-context.new))
+      (export "error-context.debug-message" (func $error-context.debug-message))
+      (export "error-context.drop" (func $error-context.drop))
+    ))
+  ))
+)
+
+(assert_invalid
+  (component
+    (core module $m
+      (func (export "f") (param i32 i32 i32 i32) (result i32) unreachable)
+      (func (export "g")))
+    (core instance $i (instantiate $m))
+    (func (canon lift (core func $i "g") (callback (func $i "f"))))
+  )
+  "canonical option `callback` requires the `async` option")
+
+(assert_invalid
+  (component
+    (core module $m
+      (func (export "cb") (param i32) (result i32) unreachable)
+      (func (export "g") (result i32) unreachable))
+    (core instance $i (instantiate $m))
+    (func (canon lift (core func $i "g") async (callback (func $i "cb"))))
+  )
+  "canonical option `callback` uses a core function with an incorrect signature")
+
+(assert_invalid
+  (component
+    (core module $m
+      (func (export "f") (result i32) unreachable)
+      (func (export "post") (param i32)))
+    (core instance $i (instantiate $m))
+    (func (result u32)
+      (canon lift (core func $i "f") async (post-return (func $i "post"))))
+  )
+  "canonical option `post-return` cannot be used with the `async` option")
+
+(assert_invalid
+  (component
+    (import "f" (func $f))
+    (core module $m (func (export "cb") (param i32 i32 i32 i32) (result i32) unreachable))
+    (core instance $i (instantiate $m))
+    (core func (canon lower (func $f) async (callback (func $i "cb"))))
+  )
+  "canonical option `callback` cannot be specified for lowerings")
+
+(assert_invalid
+  (component
+    (type $f (future))
+    (core func (canon stream.new $f))
+  )
+  "`stream.new` requires a stream type")
+
+(assert_invalid
+  (component
+    (type $s (stream u8))
+    (core func (canon stream.read $s))
+  )
+  "canonical option `memory` is required")
+
+(assert_invalid
+  (component
+    (core func (canon task.return (result string)))
+  )
+  "canonical option `memory` is required")
+
+(assert_invalid
+  (component
+    (core func (canon error-context.new async))
+  )
+  "canonical option `async` cannot be used with `error-context.new`")
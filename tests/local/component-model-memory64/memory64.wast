@@ -0,0 +1,88 @@
+;; The i64 canonical ABI for 64-bit memories requires the
+;; `component-model-memory64` feature, which this test enables.
+
+(assert_invalid
+  (component
+    (core module $A
+      (import "" "" (memory 1)))
+    (core module $B
+      (memory (export "") i64 1))
+    (core instance $b (instantiate $B))
+    (core instance $a (instantiate $A (with "" (instance $b))))
+  )
+  "mismatch in index type used for memories")
+
+(assert_invalid
+  (component
+    (core module $A
+      (import "" "" (memory i64 1)))
+    (core module $B
+      (memory (export "") 1))
+    (core instance $b (instantiate $B))
+    (core instance $a (instantiate $A (with "" (instance $b))))
+  )
+  "mismatch in index type used for memories")
+
+;; pointers and lengths are `i64` values when the `memory` option refers to a
+;; 64-bit memory
+(component
+  (import "log" (func $log (param "msg" string)))
+  (import "get" (func $get (result (list u8))))
+  (core module $m
+    (memory (export "memory") i64 1)
+    (func (export "realloc") (param i64 i64 i32 i64) (result i64) unreachable)
+    (func (export "run") (param i64 i64) (result i64) unreachable)
+  )
+  (core instance $i (instantiate $m))
+  (core func (canon lower (func $log) (memory $i "memory") string-encoding=utf8))
+  (core func (canon lower (func $get) (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "run") (param "args" (list string)) (result string)
+    (canon lift (core func $i "run") (memory $i "memory") (realloc (func $i "realloc")))
+  )
+)
+
+(assert_invalid
+  (component
+    (type $t (func (param "args" (list string)) (result string)))
+    (core module $m
+      (memory (export "memory") i64 1)
+      (func (export "realloc") (param i64 i64 i32 i64) (result i64) unreachable)
+      (func (export "run") (param i32 i32) (result i32) unreachable)
+    )
+    (core instance $i (instantiate $m))
+    (func (type $t)
+      (canon lift (core func $i "run") (memory $i "memory") (realloc (func $i "realloc")))
+    )
+  )
+  "lowered parameter types `[I64, I64]` do not match parameter types `[I32, I32]`")
+
+(assert_invalid
+  (component
+    (type $t (func (param "args" (list string))))
+    (core module $m
+      (memory (export "memory") i64 1)
+      (func (export "realloc") (param i32 i32 i32 i32) (result i32) unreachable)
+      (func (export "run") (param i64 i64) unreachable)
+    )
+    (core instance $i (instantiate $m))
+    (func (type $t)
+      (canon lift (core func $i "run") (realloc (func $i "realloc")) (memory $i "memory"))
+    )
+  )
+  "canonical option `realloc` uses a core function with an incorrect signature")
+
+;; the alignment passed to `realloc` is an `i32` even for 64-bit memories
+(assert_invalid
+  (component
+    (type $t (func (param "args" (list string))))
+    (core module $m
+      (memory (export "memory") i64 1)
+      (func (export "realloc") (param i64 i64 i64 i64) (result i64) unreachable)
+      (func (export "run") (param i64 i64) unreachable)
+    )
+    (core instance $i (instantiate $m))
+    (func (type $t)
+      (canon lift (core func $i "run") (memory $i "memory") (realloc (func $i "realloc")))
+    )
+  )
+  "canonical option `realloc` uses a core function with an incorrect signature")
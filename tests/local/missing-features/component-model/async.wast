@@ -0,0 +1,7 @@
+(assert_invalid
+  (component (type (stream u8)))
+  "the component model async feature is required for stream types")
+
+(assert_invalid
+  (component (core func (canon task.backpressure)))
+  "the component model async feature is required for `task.backpressure`")
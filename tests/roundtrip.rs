@@ -567,6 +567,69 @@ impl TestState {
         }
         self.snapshot("json", path.as_ref(), &stdout)
             .context("failed to validate the `json-from-wast` snapshot")?;
+        self.test_wast_from_json(path.as_ref(), &stdout, td.path())
+            .context("failed to round-trip through `wast-from-json`")?;
+        Ok(())
+    }
+
+    /// Converts the JSON produced by `json-from-wast` back into a `*.wast`
+    /// script and asserts that converting that script to JSON again yields the
+    /// same commands and module files, modulo line numbers.
+    fn test_wast_from_json(&self, path: &Path, json: &str, wasm_dir: &Path) -> Result<()> {
+        let stem = path.file_stem().unwrap();
+        let json_path = wasm_dir.join(stem).with_extension("json");
+        std::fs::write(&json_path, json)?;
+
+        let td = tempfile::TempDir::new()?;
+        let wast_path = td.path().join(stem).with_extension("wast");
+        let mut cmd = self.wasm_tools();
+        cmd.arg("wast-from-json")
+            .arg(&json_path)
+            .arg("-o")
+            .arg(&wast_path);
+        let output = cmd.output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("failed to run {cmd:?}\nstderr: {stderr}");
+        }
+
+        let mut cmd = self.wasm_tools();
+        cmd.arg("json-from-wast")
+            .arg(&wast_path)
+            .arg("--pretty")
+            .arg("--wasm-dir")
+            .arg(td.path());
+        let output = cmd.output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("failed to run {cmd:?}\nstderr: {stderr}");
+        }
+
+        let strip = |json: &str| {
+            json.lines()
+                .filter(|l| !l.contains("\"line\":") && !l.contains("\"source_filename\":"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let roundtrip = String::from_utf8_lossy(&output.stdout);
+        if strip(json) != strip(&roundtrip) {
+            bail!(
+                "commands differ after round-trip:\n{}",
+                pretty_assertions::StrComparison::new(&strip(json), &strip(&roundtrip))
+            );
+        }
+
+        for entry in wasm_dir.read_dir()? {
+            let entry = entry?;
+            if entry.path() == json_path {
+                continue;
+            }
+            let expected = std::fs::read(entry.path())?;
+            let actual = std::fs::read(td.path().join(entry.file_name()))?;
+            if expected != actual {
+                bail!("file {:?} differs after round-trip", entry.file_name());
+            }
+        }
         Ok(())
     }
 
@@ -578,7 +641,9 @@ impl TestState {
         let mut features = WasmFeatures::all()
             & !WasmFeatures::SHARED_EVERYTHING_THREADS
             & !WasmFeatures::COMPONENT_MODEL
-            & !WasmFeatures::COMPONENT_MODEL_NESTED_NAMES;
+            & !WasmFeatures::COMPONENT_MODEL_NESTED_NAMES
+            & !WasmFeatures::COMPONENT_MODEL_ASYNC
+            & !WasmFeatures::COMPONENT_MODEL_MEMORY64;
         for part in test.iter().filter_map(|t| t.to_str()) {
             match part {
                 "testsuite" => {
@@ -606,6 +671,14 @@ impl TestState {
                 "tail-call" => features.insert(WasmFeatures::TAIL_CALL),
                 "memory64" => features.insert(WasmFeatures::MEMORY64),
                 "component-model" => features.insert(WasmFeatures::COMPONENT_MODEL),
+                "component-model-async" => {
+                    features.insert(WasmFeatures::COMPONENT_MODEL);
+                    features.insert(WasmFeatures::COMPONENT_MODEL_ASYNC);
+                }
+                "component-model-memory64" => {
+                    features.insert(WasmFeatures::COMPONENT_MODEL);
+                    features.insert(WasmFeatures::COMPONENT_MODEL_MEMORY64);
+                }
                 "shared-everything-threads" => {
                     features.insert(WasmFeatures::COMPONENT_MODEL);
                     features.insert(WasmFeatures::SHARED_EVERYTHING_THREADS);